@@ -0,0 +1,83 @@
+use akton_arn::*;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+const INPUT: &str = "arn:akton:hr:acme:root/department_a/team1/42/x";
+
+fn parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    group.bench_function("parse", |b| {
+        b.iter(|| ArnParser::new(black_box(INPUT)).parse())
+    });
+    group.bench_function("parse_owned", |b| {
+        b.iter(|| {
+            ArnParser::new(black_box(INPUT))
+                .parse()
+                .map(Arn::into_owned)
+        })
+    });
+    group.bench_function("extract_account", |b| {
+        b.iter(|| extract_account(black_box(INPUT)))
+    });
+    #[cfg(feature = "arena")]
+    group.bench_function("arena", |b| {
+        let mut arena = ArnArena::with_capacity(64 * 1024);
+        b.iter(|| {
+            if arena.allocated_bytes() > 1 << 20 {
+                arena.reset();
+            }
+            arena.parse(black_box(INPUT)).is_ok()
+        })
+    });
+    group.finish();
+}
+
+fn build(c: &mut Criterion) {
+    c.bench_function("build", |b| {
+        b.iter(|| {
+            ArnBuilder::new()
+                .with::<Domain>(black_box("akton"))
+                .and_then(|b| b.with::<Category>("hr"))
+                .and_then(|b| b.with::<Account>("acme"))
+                .and_then(|b| b.with::<Root>("root"))
+                .and_then(|b| b.with::<Part>("department_a"))
+                .and_then(|b| b.with::<Part>("team1"))
+                .and_then(|b| b.build())
+        })
+    });
+}
+
+fn display(c: &mut Criterion) {
+    let arn = ArnParser::new(INPUT).parse().unwrap();
+    c.bench_function("display", |b| b.iter(|| black_box(&arn).to_string()));
+}
+
+fn matching(c: &mut Criterion) {
+    let arn = ArnParser::new(INPUT).parse().unwrap();
+    let hit: ArnPattern = "arn:akton:hr:*:root/**".parse().unwrap();
+    let miss: ArnPattern = "arn:akton:it:*:root/**".parse().unwrap();
+    let set = ArnMatcherSet::new([hit.clone(), miss.clone()]);
+
+    let mut group = c.benchmark_group("match");
+    group.bench_function("pattern_hit", |b| b.iter(|| hit.matches(black_box(&arn))));
+    group.bench_function("pattern_miss", |b| b.iter(|| miss.matches(black_box(&arn))));
+    group.bench_function("matcher_set", |b| b.iter(|| set.matches(black_box(&arn))));
+    group.finish();
+}
+
+fn hierarchy(c: &mut Criterion) {
+    let arn = ArnParser::new(INPUT).parse().unwrap();
+    let parent = arn.parent().unwrap();
+
+    let mut group = c.benchmark_group("hierarchy");
+    group.bench_function("parent", |b| b.iter(|| black_box(&arn).parent()));
+    group.bench_function("ancestors", |b| {
+        b.iter(|| black_box(&arn).ancestors().count())
+    });
+    group.bench_function("is_child_of", |b| {
+        b.iter(|| black_box(&arn).is_child_of(&parent))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, parse, build, display, matching, hierarchy);
+criterion_main!(benches);
@@ -0,0 +1,72 @@
+#ifndef AKTON_ARN_H
+#define AKTON_ARN_H
+
+/* Generated by cbindgen from src/ffi.rs; do not edit by hand. */
+
+#include <stddef.h>
+#include <stdint.h>
+
+// An Arn owned by C code.
+typedef struct AktonArn AktonArn;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Parses `input`, returning `NULL` and setting `error` if it is not a valid Arn.
+//
+// # Safety
+//
+// `input` must be a NUL-terminated string, and `error` either `NULL` or valid for writes.
+AktonArn *arn_parse(const char *input, char **error);
+
+// Builds an Arn from its components, minting the root's id, then appending `parts_len`
+// parts. Returns `NULL` and sets `error` if a component is invalid.
+//
+// # Safety
+//
+// Every string must be NUL-terminated, `parts` must point to `parts_len` strings (or may be
+// `NULL` when `parts_len` is 0), and `error` must be `NULL` or valid for writes.
+AktonArn *arn_build(const char *domain,
+                    const char *category,
+                    const char *account,
+                    const char *root,
+                    const char *const *parts,
+                    size_t parts_len,
+                    char **error);
+
+// Returns the canonical form of `arn`, to be released with [`arn_string_free`].
+//
+// # Safety
+//
+// `arn` must have been returned by this API and not yet freed.
+char *arn_to_string(const AktonArn *arn);
+
+// Returns 1 if `arn` matches `pattern`, 0 if it does not, or -1 and sets `error` if the
+// pattern is invalid.
+//
+// # Safety
+//
+// `pattern` must be a NUL-terminated string, `arn` must have been returned by this API and
+// not yet freed, and `error` must be `NULL` or valid for writes.
+int arn_match(const char *pattern, const AktonArn *arn, char **error);
+
+// Releases an Arn returned by this API. Does nothing given `NULL`.
+//
+// # Safety
+//
+// `arn` must have been returned by this API and not already freed.
+void arn_free(AktonArn *arn);
+
+// Releases a string returned by this API. Does nothing given `NULL`.
+//
+// # Safety
+//
+// `string` must have been returned by this API and not already freed.
+void arn_string_free(char *string);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* AKTON_ARN_H */
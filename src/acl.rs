@@ -0,0 +1,161 @@
+use crate::model::Arn;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+/// Permissions granted to principals of type `P` at nodes of the Arn hierarchy.
+///
+/// A grant made at an Arn applies to it and everything below it, so allowing `read` at
+/// `.../root/teams` allows it at `.../root/teams/eng`. A deny applies the same way, and
+/// wherever it applies it overrides every allow for the same principal and permission, even
+/// one made further down.
+/// Grants are keyed by position in the hierarchy: the version and extensions of the Arns
+/// passed in are ignored.
+///
+/// ```
+/// use akton_arn::{Acl, ArnParser};
+///
+/// let arn = |s: &str| ArnParser::new(s).parse().unwrap().into_owned();
+/// let mut acl = Acl::new();
+/// acl.allow("alice", "read", &arn("arn:akton:hr:acme:root/teams"));
+/// acl.deny("alice", "read", &arn("arn:akton:hr:acme:root/teams/payroll"));
+///
+/// assert!(acl.check(&"alice", "read", &arn("arn:akton:hr:acme:root/teams/eng")));
+/// assert!(!acl.check(&"alice", "read", &arn("arn:akton:hr:acme:root/teams/payroll/2024")));
+/// assert!(!acl.check(&"alice", "write", &arn("arn:akton:hr:acme:root/teams/eng")));
+/// assert!(!acl.check(&"bob", "read", &arn("arn:akton:hr:acme:root/teams/eng")));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Acl<P> {
+    nodes: BTreeMap<Arn<'static>, HashMap<P, HashMap<String, Effect>>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Effect {
+    Allow,
+    Deny,
+}
+
+impl<P> Default for Acl<P> {
+    fn default() -> Self {
+        Acl {
+            nodes: BTreeMap::new(),
+        }
+    }
+}
+
+impl<P: Eq + Hash> Acl<P> {
+    /// Creates an empty ACL, which denies everything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allows `principal` `permission` at `arn` and below, replacing a deny made at `arn`.
+    pub fn allow(&mut self, principal: P, permission: impl Into<String>, arn: &Arn) {
+        self.grant(principal, permission.into(), arn, Effect::Allow);
+    }
+
+    /// Denies `principal` `permission` at `arn` and below, replacing an allow made at `arn`
+    /// and overriding any that would otherwise apply there.
+    pub fn deny(&mut self, principal: P, permission: impl Into<String>, arn: &Arn) {
+        self.grant(principal, permission.into(), arn, Effect::Deny);
+    }
+
+    /// Removes the allow or deny of `permission` made for `principal` at exactly `arn`,
+    /// returning `true` if there was one.
+    pub fn revoke(&mut self, principal: &P, permission: &str, arn: &Arn) -> bool {
+        let key = node_key(arn, arn.depth());
+        let Some(node) = self.nodes.get_mut(&key) else {
+            return false;
+        };
+        let Some(permissions) = node.get_mut(principal) else {
+            return false;
+        };
+        let removed = permissions.remove(permission).is_some();
+        if permissions.is_empty() {
+            node.remove(principal);
+        }
+        if node.is_empty() {
+            self.nodes.remove(&key);
+        }
+        removed
+    }
+
+    /// Returns `true` if `principal` is allowed `permission` at `arn`, by a grant at `arn`
+    /// or one of its ancestors, and denied it at none of them.
+    pub fn check(&self, principal: &P, permission: &str, arn: &Arn) -> bool {
+        let mut allowed = false;
+        for depth in 0..=arn.depth() {
+            let effect = self
+                .nodes
+                .get(&node_key(arn, depth))
+                .and_then(|node| node.get(principal))
+                .and_then(|permissions| permissions.get(permission));
+            match effect {
+                Some(Effect::Deny) => return false,
+                Some(Effect::Allow) => allowed = true,
+                None => {}
+            }
+        }
+        allowed
+    }
+
+    fn grant(&mut self, principal: P, permission: String, arn: &Arn, effect: Effect) {
+        self.nodes
+            .entry(node_key(arn, arn.depth()))
+            .or_default()
+            .entry(principal)
+            .or_default()
+            .insert(permission, effect);
+    }
+}
+
+/// Returns the ancestor of `arn` at `depth` as stored in an [`Acl`].
+fn node_key(arn: &Arn, depth: usize) -> Arn<'static> {
+    arn.truncated(depth).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArnParser;
+
+    fn arn(s: &str) -> Arn<'static> {
+        ArnParser::new(s).parse().unwrap().into_owned()
+    }
+
+    #[test]
+    fn test_inheritance_and_deny_overrides() {
+        let mut acl = Acl::new();
+        acl.allow(1, "read", &arn("arn:akton:hr:acme:root"));
+        acl.allow(1, "read", &arn("arn:akton:hr:acme:root/secret/open"));
+        acl.deny(1, "read", &arn("arn:akton:hr:acme:root/secret@v3"));
+
+        assert!(acl.check(&1, "read", &arn("arn:akton:hr:acme:root/users@v2")));
+        assert!(!acl.check(&1, "read", &arn("arn:akton:hr:acme:root/secret")));
+        assert!(!acl.check(&1, "read", &arn("arn:akton:hr:acme:root/secret/open")));
+        assert!(!acl.check(&1, "read", &arn("arn:akton:hr:beta:root")));
+        assert!(!acl.check(&2, "read", &arn("arn:akton:hr:acme:root")));
+
+        assert!(acl.revoke(&1, "read", &arn("arn:akton:hr:acme:root/secret")));
+        assert!(!acl.revoke(&1, "read", &arn("arn:akton:hr:acme:root/secret")));
+        assert!(acl.check(&1, "read", &arn("arn:akton:hr:acme:root/secret/open")));
+
+        acl.allow(1, "read", &arn("arn:akton:hr:acme:root/users"));
+        acl.deny(1, "read", &arn("arn:akton:hr:acme:root/users"));
+        assert!(!acl.check(&1, "read", &arn("arn:akton:hr:acme:root/users")));
+        acl.allow(1, "read", &arn("arn:akton:hr:acme:root/users"));
+        assert!(acl.check(&1, "read", &arn("arn:akton:hr:acme:root/users")));
+    }
+
+    #[test]
+    fn test_revoke_prunes_empty_nodes() {
+        let mut acl = Acl::new();
+        let team = arn("arn:akton:hr:acme:root/team");
+        acl.allow("alice", "read", &team);
+        acl.allow("alice", "write", &team);
+        assert!(acl.revoke(&"alice", "read", &team));
+        assert_eq!(acl.nodes.len(), 1);
+        assert!(acl.revoke(&"alice", "write", &team));
+        assert!(acl.nodes.is_empty());
+    }
+}
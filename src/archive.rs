@@ -0,0 +1,364 @@
+//! `rkyv` support, enabled with the `rkyv` feature.
+//!
+//! Arns and their components implement `Archive` and `Serialize`, so they can be fields of
+//! archived structs. An archived Arn is an [`ArchivedArn`], which can be printed, compared
+//! with other Arns and matched against an [`ArnPattern`] straight from the archive bytes, for
+//! example in a memory-mapped event log, without deserializing it first.
+
+use crate::model::{Account, Arn, Category, Domain, Extensions, Part, Parts, Root};
+use crate::pattern::ArnPattern;
+use rkyv::bytecheck::CheckBytes;
+use rkyv::ser::{ScratchSpace, Serializer};
+use rkyv::string::{ArchivedString, StringResolver};
+use rkyv::vec::{ArchivedVec, VecResolver};
+use rkyv::{out_field, Archive, Archived, Deserialize, Fallible, Resolver, Serialize};
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Archives each component as its text.
+macro_rules! archive_as_str {
+    ($($type:ident),+) => {$(
+        impl Archive for $type<'_> {
+            type Archived = ArchivedString;
+            type Resolver = StringResolver;
+
+            unsafe fn resolve(&self, pos: usize, resolver: StringResolver, out: *mut ArchivedString) {
+                ArchivedString::resolve_from_str(self.as_str(), pos, resolver, out);
+            }
+        }
+
+        impl<S: Serializer + ?Sized> Serialize<S> for $type<'_> {
+            fn serialize(&self, serializer: &mut S) -> Result<StringResolver, S::Error> {
+                ArchivedString::serialize_from_str(self.as_str(), serializer)
+            }
+        }
+
+        impl<D: Fallible + ?Sized> Deserialize<$type<'static>, D> for ArchivedString {
+            fn deserialize(&self, _: &mut D) -> Result<$type<'static>, D::Error> {
+                Ok($type::from(Cow::Owned(self.as_str().to_string())))
+            }
+        }
+    )+};
+}
+
+archive_as_str!(Domain, Category, Account, Root, Part);
+
+/// An [`Arn`] in an rkyv archive, read in place.
+///
+/// Equality and ordering agree with [`Arn`]'s, both between archived Arns and between an
+/// archived Arn and an `Arn`, and `Display` writes the canonical form.
+///
+/// ```
+/// use akton_arn::{ArchivedArn, Arn, ArnParser, ArnPattern};
+///
+/// let arn = ArnParser::new("arn:akton:hr:acme:root/teams/a").parse().unwrap();
+/// let bytes = rkyv::to_bytes::<_, 256>(&arn).unwrap();
+/// let archived = rkyv::check_archived_root::<Arn>(&bytes).unwrap();
+/// assert_eq!(*archived, arn);
+/// let pattern: ArnPattern = "arn:akton:hr:*:*/teams/**".parse().unwrap();
+/// assert!(archived.matches(&pattern));
+/// ```
+#[derive(CheckBytes)]
+#[check_bytes(crate = "rkyv::bytecheck")]
+#[repr(C)]
+pub struct ArchivedArn {
+    domain: ArchivedString,
+    category: ArchivedString,
+    account: ArchivedString,
+    root: ArchivedString,
+    parts: ArchivedVec<ArchivedString>,
+    version: Archived<Option<u64>>,
+    extensions: ArchivedVec<(ArchivedString, ArchivedString)>,
+}
+
+/// The resolver for an archived [`Arn`].
+pub struct ArnResolver {
+    domain: StringResolver,
+    category: StringResolver,
+    account: StringResolver,
+    root: StringResolver,
+    parts: VecResolver,
+    version: Resolver<Option<u64>>,
+    extensions: VecResolver,
+}
+
+impl Archive for Arn<'_> {
+    type Archived = ArchivedArn;
+    type Resolver = ArnResolver;
+
+    unsafe fn resolve(&self, pos: usize, resolver: ArnResolver, out: *mut ArchivedArn) {
+        let (fp, fo) = out_field!(out.domain);
+        self.domain.resolve(pos + fp, resolver.domain, fo);
+        let (fp, fo) = out_field!(out.category);
+        self.category.resolve(pos + fp, resolver.category, fo);
+        let (fp, fo) = out_field!(out.account);
+        self.account.resolve(pos + fp, resolver.account, fo);
+        let (fp, fo) = out_field!(out.root);
+        self.root.resolve(pos + fp, resolver.root, fo);
+        let (fp, fo) = out_field!(out.parts);
+        ArchivedVec::resolve_from_slice(&self.parts.0, pos + fp, resolver.parts, fo);
+        let (fp, fo) = out_field!(out.version);
+        self.version.resolve(pos + fp, resolver.version, fo);
+        let (fp, fo) = out_field!(out.extensions);
+        self.extensions.0.resolve(pos + fp, resolver.extensions, fo);
+    }
+}
+
+impl<S: ScratchSpace + Serializer + ?Sized> Serialize<S> for Arn<'_> {
+    fn serialize(&self, serializer: &mut S) -> Result<ArnResolver, S::Error> {
+        Ok(ArnResolver {
+            domain: self.domain.serialize(serializer)?,
+            category: self.category.serialize(serializer)?,
+            account: self.account.serialize(serializer)?,
+            root: self.root.serialize(serializer)?,
+            parts: ArchivedVec::serialize_from_slice(&self.parts.0, serializer)?,
+            version: self.version.serialize(serializer)?,
+            extensions: self.extensions.0.serialize(serializer)?,
+        })
+    }
+}
+
+impl<D: Fallible + ?Sized> Deserialize<Arn<'static>, D> for ArchivedArn {
+    /// Rebuilds the Arn without checking its components again, as they were checked when the
+    /// Arn was first made.
+    fn deserialize(&self, deserializer: &mut D) -> Result<Arn<'static>, D::Error> {
+        let parts = self
+            .parts
+            .iter()
+            .map(|part| part.deserialize(deserializer))
+            .collect::<Result<Parts, _>>()?;
+        let mut arn = Arn::new(
+            self.domain.deserialize(deserializer)?,
+            self.category.deserialize(deserializer)?,
+            self.account.deserialize(deserializer)?,
+            self.root.deserialize(deserializer)?,
+            parts,
+        );
+        arn.version = self.version();
+        arn.extensions = Extensions(
+            self.extensions()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+        );
+        Ok(arn)
+    }
+}
+
+impl ArchivedArn {
+    /// Returns the domain.
+    pub fn domain(&self) -> &str {
+        &self.domain
+    }
+
+    /// Returns the category.
+    pub fn category(&self) -> &str {
+        &self.category
+    }
+
+    /// Returns the account.
+    pub fn account(&self) -> &str {
+        &self.account
+    }
+
+    /// Returns the root.
+    pub fn root(&self) -> &str {
+        &self.root
+    }
+
+    /// Returns the parts after the root, nearest the root first.
+    pub fn parts(&self) -> impl ExactSizeIterator<Item = &str> {
+        self.parts.iter().map(ArchivedString::as_str)
+    }
+
+    /// Returns the version, if the Arn is versioned.
+    pub fn version(&self) -> Option<u64> {
+        self.version.as_ref().copied()
+    }
+
+    /// Returns the extensions in order.
+    pub fn extensions(&self) -> impl ExactSizeIterator<Item = (&str, &str)> {
+        self.extensions
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    /// Returns `true` if the archived Arn falls within `pattern`.
+    pub fn matches(&self, pattern: &ArnPattern) -> bool {
+        pattern.matches_components(
+            [self.domain(), self.category(), self.account(), self.root()],
+            self.parts.as_slice(),
+        )
+    }
+
+    fn identity(&self) -> Identity<'_> {
+        Identity {
+            components: [self.domain(), self.category(), self.account(), self.root()],
+            parts: self.parts().collect(),
+            version: self.version(),
+            extensions: self.extensions().collect(),
+        }
+    }
+}
+
+/// What equality and ordering compare, borrowed from an Arn in either form.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct Identity<'s> {
+    components: [&'s str; 4],
+    parts: Vec<&'s str>,
+    version: Option<u64>,
+    extensions: Vec<(&'s str, &'s str)>,
+}
+
+impl<'s> From<&'s Arn<'_>> for Identity<'s> {
+    fn from(arn: &'s Arn<'_>) -> Self {
+        Identity {
+            components: [
+                arn.domain.as_str(),
+                arn.category.as_str(),
+                arn.account.as_str(),
+                arn.root.as_str(),
+            ],
+            parts: arn.parts.iter().map(Part::as_str).collect(),
+            version: arn.version,
+            extensions: arn.extensions.iter().collect(),
+        }
+    }
+}
+
+impl PartialEq for ArchivedArn {
+    fn eq(&self, other: &Self) -> bool {
+        self.identity() == other.identity()
+    }
+}
+
+impl Eq for ArchivedArn {}
+
+impl PartialOrd for ArchivedArn {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ArchivedArn {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.identity().cmp(&other.identity())
+    }
+}
+
+impl PartialEq<Arn<'_>> for ArchivedArn {
+    fn eq(&self, other: &Arn<'_>) -> bool {
+        self.identity() == Identity::from(other)
+    }
+}
+
+impl PartialEq<ArchivedArn> for Arn<'_> {
+    fn eq(&self, other: &ArchivedArn) -> bool {
+        other == self
+    }
+}
+
+impl PartialOrd<Arn<'_>> for ArchivedArn {
+    fn partial_cmp(&self, other: &Arn<'_>) -> Option<Ordering> {
+        Some(self.identity().cmp(&Identity::from(other)))
+    }
+}
+
+impl fmt::Display for ArchivedArn {
+    /// Writes the canonical form, as `Display` for [`Arn`] does.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "arn:{}:{}:{}:{}",
+            self.domain(),
+            self.category(),
+            self.account(),
+            self.root()
+        )?;
+        for part in self.parts() {
+            write!(f, "/{part}")?;
+        }
+        if let Some(version) = self.version() {
+            write!(f, "@v{version}")?;
+        }
+        for (i, (key, value)) in self.extensions().enumerate() {
+            write!(f, "{}{key}={value}", if i == 0 { '?' } else { '&' })?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for ArchivedArn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ArchivedArn")
+            .field(&self.to_string())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArnParser;
+    use rkyv::Infallible;
+
+    fn arn(s: &str) -> Arn<'static> {
+        ArnParser::new(s.to_string()).parse().unwrap()
+    }
+
+    #[test]
+    fn test_archived_arns_read_in_place() {
+        let original = arn("arn:akton:hr:acme:root/teams/a@v2?region=eu&tier=gold");
+        let bytes = rkyv::to_bytes::<_, 256>(&original).unwrap();
+        let archived = rkyv::check_archived_root::<Arn>(&bytes).unwrap();
+        assert_eq!(archived.to_string(), original.to_string());
+        assert_eq!(archived.parts().collect::<Vec<_>>(), ["teams", "a"]);
+        assert_eq!(archived.version(), Some(2));
+        assert_eq!(*archived, original);
+        assert_ne!(*archived, original.clone().with_version(3));
+
+        let restored: Arn = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(restored, original);
+        assert_eq!(restored.to_string(), original.to_string());
+    }
+
+    #[test]
+    fn test_archived_arns_compare_and_match_like_arns() {
+        let arns = [
+            arn("arn:akton:hr:acme:root"),
+            arn("arn:akton:hr:acme:root/teams"),
+            arn("arn:akton:hr:acme:root/teams@v1"),
+            arn("arn:akton:hr:acme:root/teams?k=v"),
+            arn("arn:akton:it:acme:root"),
+        ];
+        let archives: Vec<_> = arns
+            .iter()
+            .map(|arn| rkyv::to_bytes::<_, 256>(arn).unwrap())
+            .collect();
+        let archived: Vec<&ArchivedArn> = archives
+            .iter()
+            .map(|bytes| rkyv::check_archived_root::<Arn>(bytes).unwrap())
+            .collect();
+        for (i, a) in archived.iter().enumerate() {
+            for (j, b) in archived.iter().enumerate() {
+                assert_eq!(a.cmp(b), arns[i].cmp(&arns[j]), "{a} vs {b}");
+                assert_eq!(a.partial_cmp(&&arns[j]), Some(arns[i].cmp(&arns[j])));
+            }
+        }
+
+        let pattern: ArnPattern = "arn:akton:hr:*:*/teams/**".parse().unwrap();
+        for (arn, archived) in arns.iter().zip(&archived) {
+            assert_eq!(archived.matches(&pattern), pattern.matches(arn));
+        }
+    }
+
+    #[test]
+    fn test_corrupt_archives_are_rejected() {
+        let bytes = rkyv::to_bytes::<_, 256>(&arn("arn:akton:hr:acme:root")).unwrap();
+        let mut corrupt = bytes.to_vec();
+        corrupt.truncate(corrupt.len() / 2);
+        let mut aligned = rkyv::AlignedVec::new();
+        aligned.extend_from_slice(&corrupt);
+        assert!(rkyv::check_archived_root::<Arn>(&aligned).is_err());
+    }
+}
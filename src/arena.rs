@@ -0,0 +1,197 @@
+use crate::errors::ArnError;
+use crate::model::{Account, Arn, Category, Domain, Parts, Root, SharedStr};
+use crate::parser::ArnParser;
+use bumpalo::Bump;
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::fmt;
+
+/// An Arn whose components all borrow from an [`ArnArena`], freed with the arena.
+pub type ArnRef<'arena> = Arn<'arena>;
+
+/// A bump allocator holding the text of many Arns, freed all at once.
+///
+/// Request handlers that parse thousands of Arns and drop them together pay for one heap
+/// allocation per component they keep with [`Arn::into_owned`], and one free for each later.
+/// An arena instead copies each input into large blocks it owns, and the [`ArnRef`]s it hands
+/// out borrow from those blocks, components normalization rewrote included. Dropping the
+/// arena, or [resetting](ArnArena::reset) it for the next request, releases everything in a
+/// handful of frees. The borrow checker keeps any `ArnRef` from outliving its arena.
+///
+/// Extensions and annotations are rare and stay on the heap.
+///
+/// ```
+/// use akton_arn::ArnArena;
+///
+/// let mut arena = ArnArena::new();
+/// let lines = ["arn:akton:hr:acme:root/a", "arn:akton:hr:acme:root/b"];
+/// let arns = lines
+///     .iter()
+///     .map(|line| arena.parse(line))
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+/// assert_eq!(arns[1].parts().len(), 1);
+/// assert_eq!(arena.len(), 2);
+///
+/// drop(arns);
+/// arena.reset();
+/// assert!(arena.is_empty());
+/// ```
+#[derive(Default)]
+pub struct ArnArena {
+    bump: Bump,
+    len: Cell<usize>,
+}
+
+impl ArnArena {
+    /// Creates an empty arena, which allocates its first block on first use.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an arena whose first block holds at least `bytes` bytes of text.
+    pub fn with_capacity(bytes: usize) -> Self {
+        ArnArena {
+            bump: Bump::with_capacity(bytes),
+            len: Cell::new(0),
+        }
+    }
+
+    /// Parses `input` with default settings into an Arn borrowing from the arena.
+    ///
+    /// The input is copied into the arena first, so it need not outlive the result.
+    pub fn parse(&self, input: &str) -> Result<ArnRef<'_>, ArnError> {
+        let arn = ArnParser::new(&*self.bump.alloc_str(input)).parse()?;
+        Ok(self.settle(arn, |text| match text {
+            SharedStr::Borrowed(Cow::Borrowed(text)) => SharedStr::borrowed(text),
+            text => SharedStr::borrowed(self.bump.alloc_str(&text)),
+        }))
+    }
+
+    /// Copies the text of `arn` into the arena, for Arns parsed with custom settings or
+    /// built elsewhere.
+    pub fn alloc(&self, arn: &Arn) -> ArnRef<'_> {
+        self.settle(arn.clone(), |text| {
+            SharedStr::borrowed(self.bump.alloc_str(&text))
+        })
+    }
+
+    /// Returns the number of Arns allocated since the arena was created or reset.
+    pub fn len(&self) -> usize {
+        self.len.get()
+    }
+
+    /// Returns `true` if no Arns were allocated since the arena was created or reset.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the bytes of memory the arena has reserved, including unused space in its
+    /// current block.
+    pub fn allocated_bytes(&self) -> usize {
+        self.bump.allocated_bytes()
+    }
+
+    /// Frees every Arn at once, keeping the largest block for reuse.
+    ///
+    /// Takes `&mut self`, so no [`ArnRef`] into the arena can still be alive.
+    pub fn reset(&mut self) {
+        self.bump.reset();
+        self.len.set(0);
+    }
+
+    /// Rebuilds `arn` with every component's text passed through `rehome`.
+    fn settle<'r, 'a>(
+        &'r self,
+        arn: Arn<'a>,
+        rehome: impl Fn(SharedStr<'a>) -> SharedStr<'r>,
+    ) -> ArnRef<'r> {
+        self.len.set(self.len.get() + 1);
+        let parts: Parts = arn
+            .parts
+            .iter()
+            .map(|part| part.rehomed(rehome(part.0.clone())))
+            .collect();
+        Arn {
+            domain: Domain(rehome(arn.domain.0)),
+            category: Category(rehome(arn.category.0)),
+            account: Account(rehome(arn.account.0)),
+            root: Root(rehome(arn.root.0)),
+            parts,
+            annotations: arn.annotations,
+            version: arn.version,
+            extensions: arn.extensions,
+            scheme: arn.scheme,
+        }
+    }
+}
+
+impl fmt::Debug for ArnArena {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArnArena")
+            .field("len", &self.len())
+            .field("allocated_bytes", &self.allocated_bytes())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ArnBuilder, CaseFold};
+
+    /// Returns `true` if `address` lies inside one of the arena's blocks.
+    fn in_arena(arena: &mut ArnArena, address: usize) -> bool {
+        arena.bump.iter_allocated_chunks().any(|chunk| {
+            let start = chunk.as_ptr() as usize;
+            (start..start + chunk.len()).contains(&address)
+        })
+    }
+
+    #[test]
+    fn test_components_borrow_from_the_arena() {
+        let mut arena = ArnArena::new();
+        let addresses: Vec<usize> = {
+            let folded = ArnParser::new("arn:AKTON:hr:acme:root/Team/42")
+                .with_case_fold(CaseFold::all())
+                .parse()
+                .unwrap();
+            let copied = arena.alloc(&folded);
+            drop(folded);
+            let parsed = arena
+                .parse(&String::from("arn:akton:hr:acme:root/team/42@v2"))
+                .unwrap();
+            assert_eq!(copied.to_string(), "arn:akton:hr:acme:root/team/42");
+            assert_eq!(copied.parts().last().unwrap().as_u64(), Some(42));
+            assert_eq!(parsed.version(), Some(2));
+            [
+                copied.domain().as_str(),
+                copied.parts().first().unwrap().as_str(),
+                parsed.account().as_str(),
+                parsed.parts().last().unwrap().as_str(),
+            ]
+            .map(|text| text.as_ptr() as usize)
+            .to_vec()
+        };
+        assert_eq!(arena.len(), 2);
+        assert!(addresses
+            .into_iter()
+            .all(|address| in_arena(&mut arena, address)));
+    }
+
+    #[test]
+    fn test_alloc_copies_built_arns() {
+        let arena = ArnArena::with_capacity(1024);
+        let built = ArnBuilder::new()
+            .with::<Domain>("akton")
+            .and_then(|b| b.with::<Category>("hr"))
+            .and_then(|b| b.with::<Account>("acme"))
+            .and_then(|b| b.with::<Root>("root"))
+            .and_then(|b| b.build())
+            .unwrap();
+        let copy = arena.alloc(&built);
+        assert_eq!(copy, built);
+        assert!(arena.allocated_bytes() >= 1024);
+        assert!(arena.parse("arn:akton").is_err());
+    }
+}
@@ -0,0 +1,316 @@
+use crate::errors::ArnError;
+use crate::model::Arn;
+use crate::parser::ArnParser;
+use crate::registry::ArnRegistry;
+use std::collections::BTreeMap;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+mod file;
+#[cfg(feature = "redis")]
+mod redis;
+#[cfg(feature = "sled")]
+mod sled;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+pub use self::file::FileBackend;
+#[cfg(feature = "redis")]
+pub use self::redis::RedisBackend;
+#[cfg(feature = "sled")]
+pub use self::sled::SledBackend;
+#[cfg(feature = "sqlite")]
+pub use self::sqlite::SqliteBackend;
+
+/// A change observed through [`RegistryBackend::watch`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BackendEvent {
+    /// The Arn was stored with the given value.
+    Put(Arn<'static>, Vec<u8>),
+    /// The Arn was removed.
+    Removed(Arn<'static>),
+}
+
+/// Persistent storage for registry entries, keyed by Arn.
+///
+/// Values are opaque bytes. A [`NamespaceGuard`](crate::NamespaceGuard) writes every claim
+/// through to its backend, while an [`ArnRegistry`] keeps its entries in memory and only
+/// copies them to and from a backend when told to, with [`ArnRegistry::save_snapshot`] and
+/// [`ArnRegistry::load_snapshot`]. The crate provides [`MemoryBackend`] and [`FileBackend`], plus sled, SQLite
+/// and Redis backends behind features; implement the trait to plug in any other store.
+///
+/// Entries are keyed by the canonical string form of the Arn, and prefixes passed to
+/// [`scan_prefix`](RegistryBackend::scan_prefix) and [`watch`](RegistryBackend::watch) match
+/// against that form, so `"arn:akton:hr:acme:"` selects every Arn of one account.
+pub trait RegistryBackend: Send + Sync {
+    /// Returns the value stored for the Arn, if any.
+    fn get(&self, arn: &Arn) -> Result<Option<Vec<u8>>, ArnError>;
+
+    /// Stores a value for the Arn, replacing any previous one.
+    fn put(&self, arn: &Arn, value: &[u8]) -> Result<(), ArnError>;
+
+    /// Removes the Arn, returning `true` if it was stored.
+    fn remove(&self, arn: &Arn) -> Result<bool, ArnError>;
+
+    /// Returns every stored entry whose canonical form starts with `prefix`, in lexical order.
+    fn scan_prefix(&self, prefix: &str) -> Result<Vec<(Arn<'static>, Vec<u8>)>, ArnError>;
+
+    /// Subscribes to changes of Arns whose canonical form starts with `prefix`.
+    ///
+    /// Events are delivered until the receiver is dropped. Which writers are observed depends
+    /// on the backend; see each implementation.
+    fn watch(&self, prefix: &str) -> Result<Receiver<BackendEvent>, ArnError>;
+}
+
+/// Shares one backend between several owners of the same kind of entry, such as the
+/// [`NamespaceGuard`](crate::NamespaceGuard)s of several services enforcing one set of claims.
+///
+/// Entries are keyed by the Arn alone, so owners storing different kinds of values, such as
+/// an [`ArnRegistry`] and a guard, each need a backend of their own.
+impl<B: RegistryBackend + ?Sized> RegistryBackend for Arc<B> {
+    fn get(&self, arn: &Arn) -> Result<Option<Vec<u8>>, ArnError> {
+        (**self).get(arn)
+    }
+
+    fn put(&self, arn: &Arn, value: &[u8]) -> Result<(), ArnError> {
+        (**self).put(arn, value)
+    }
+
+    fn remove(&self, arn: &Arn) -> Result<bool, ArnError> {
+        (**self).remove(arn)
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> Result<Vec<(Arn<'static>, Vec<u8>)>, ArnError> {
+        (**self).scan_prefix(prefix)
+    }
+
+    fn watch(&self, prefix: &str) -> Result<Receiver<BackendEvent>, ArnError> {
+        (**self).watch(prefix)
+    }
+}
+
+/// Parses a key read back from a backend.
+pub(crate) fn parse_key(key: &str) -> Result<Arn<'static>, ArnError> {
+    ArnParser::new(key.to_string()).parse()
+}
+
+/// A [`RegistryBackend`] held in process memory, for tests and single-process deployments.
+///
+/// Watchers observe every write made through the same backend.
+#[derive(Debug, Default)]
+pub struct MemoryBackend {
+    entries: Mutex<BTreeMap<String, Vec<u8>>>,
+    watchers: Watchers,
+}
+
+impl MemoryBackend {
+    /// Creates an empty backend.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl RegistryBackend for MemoryBackend {
+    fn get(&self, arn: &Arn) -> Result<Option<Vec<u8>>, ArnError> {
+        Ok(self.entries.lock().unwrap().get(&arn.to_string()).cloned())
+    }
+
+    fn put(&self, arn: &Arn, value: &[u8]) -> Result<(), ArnError> {
+        let key = arn.to_string();
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(key.clone(), value.to_vec());
+        self.watchers.notify(&key, || {
+            BackendEvent::Put(arn.clone().into_owned(), value.to_vec())
+        });
+        Ok(())
+    }
+
+    fn remove(&self, arn: &Arn) -> Result<bool, ArnError> {
+        let key = arn.to_string();
+        let removed = self.entries.lock().unwrap().remove(&key).is_some();
+        if removed {
+            self.watchers
+                .notify(&key, || BackendEvent::Removed(arn.clone().into_owned()));
+        }
+        Ok(removed)
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> Result<Vec<(Arn<'static>, Vec<u8>)>, ArnError> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .range(prefix.to_string()..)
+            .take_while(|(key, _)| key.starts_with(prefix))
+            .map(|(key, value)| Ok((parse_key(key)?, value.clone())))
+            .collect()
+    }
+
+    fn watch(&self, prefix: &str) -> Result<Receiver<BackendEvent>, ArnError> {
+        Ok(self.watchers.subscribe(prefix))
+    }
+}
+
+/// In-process subscribers to writes under a key prefix, shared by backends without native
+/// change feeds and by [`ArnRegistry::subscribe`].
+#[derive(Debug)]
+pub(crate) struct Watchers<E = BackendEvent>(Mutex<Vec<(String, Sender<E>)>>);
+
+impl<E> Default for Watchers<E> {
+    fn default() -> Self {
+        Watchers(Mutex::new(Vec::new()))
+    }
+}
+
+impl<E> Watchers<E> {
+    pub(crate) fn subscribe(&self, prefix: &str) -> Receiver<E> {
+        let (sender, receiver) = channel();
+        self.0.lock().unwrap().push((prefix.to_string(), sender));
+        receiver
+    }
+
+    /// Sends the event to every watcher of `key`, dropping watchers whose receiver is gone.
+    pub(crate) fn notify(&self, key: &str, event: impl Fn() -> E) {
+        self.0.lock().unwrap().retain(|(prefix, sender)| {
+            !key.starts_with(prefix.as_str()) || sender.send(event()).is_ok()
+        });
+    }
+}
+
+impl<M> ArnRegistry<M> {
+    /// Creates a registry holding every entry under `prefix` in a backend, decoding values
+    /// with `decode`.
+    ///
+    /// The registry is a copy: it does not write back to the backend as it changes. Call
+    /// [`save_snapshot`](ArnRegistry::save_snapshot) to persist it again.
+    pub fn load_snapshot(
+        backend: &dyn RegistryBackend,
+        prefix: &str,
+        decode: impl Fn(&[u8]) -> Result<M, ArnError>,
+    ) -> Result<Self, ArnError> {
+        let mut registry = ArnRegistry::new();
+        for (arn, value) in backend.scan_prefix(prefix)? {
+            registry.register(arn, decode(&value)?)?;
+        }
+        Ok(registry)
+    }
+
+    /// Writes every entry to a backend, encoding metadata with `encode`.
+    ///
+    /// Persistence is manual: only the entries present at the call are written, so changes
+    /// made afterwards are lost unless this is called again. Entries already in the backend
+    /// but absent from the registry are left untouched.
+    pub fn save_snapshot(
+        &self,
+        backend: &dyn RegistryBackend,
+        encode: impl Fn(&M) -> Vec<u8>,
+    ) -> Result<(), ArnError> {
+        for (arn, metadata) in self.iter() {
+            backend.put(arn, &encode(metadata))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arn(s: &str) -> Arn<'static> {
+        ArnParser::new(s.to_string()).parse().unwrap()
+    }
+
+    /// Checks the behaviour every backend must share.
+    pub(crate) fn exercise(backend: &dyn RegistryBackend) {
+        let team = arn("arn:akton:hr:acme:root/team");
+        let other = arn("arn:akton:hr:other:root/team");
+        let events = backend.watch("arn:akton:hr:acme:").unwrap();
+
+        backend.put(&team, b"one").unwrap();
+        backend.put(&other, b"two").unwrap();
+        assert_eq!(backend.get(&team).unwrap(), Some(b"one".to_vec()));
+        assert_eq!(
+            backend.scan_prefix("arn:akton:hr:acme:").unwrap(),
+            vec![(team.clone(), b"one".to_vec())]
+        );
+        assert_eq!(backend.scan_prefix("arn:akton:").unwrap().len(), 2);
+        assert!(backend.remove(&team).unwrap());
+        assert!(!backend.remove(&team).unwrap());
+        assert_eq!(backend.get(&team).unwrap(), None);
+
+        let timeout = std::time::Duration::from_secs(5);
+        assert_eq!(
+            events.recv_timeout(timeout).unwrap(),
+            BackendEvent::Put(team.clone(), b"one".to_vec())
+        );
+        assert_eq!(
+            events.recv_timeout(timeout).unwrap(),
+            BackendEvent::Removed(team)
+        );
+    }
+
+    #[test]
+    fn test_memory_backend() {
+        exercise(&MemoryBackend::new());
+        exercise(&Arc::new(MemoryBackend::new()));
+    }
+
+    #[test]
+    fn test_registry_survives_round_trip_through_backend() {
+        let backend = MemoryBackend::new();
+        let mut registry = ArnRegistry::new();
+        registry
+            .register(arn("arn:akton:hr:acme:root/a"), "a".to_string())
+            .unwrap();
+        registry
+            .register(arn("arn:akton:hr:acme:root/b"), "b".to_string())
+            .unwrap();
+        registry
+            .save_snapshot(&backend, |m| m.as_bytes().to_vec())
+            .unwrap();
+
+        let restored = ArnRegistry::load_snapshot(&backend, "arn:", |bytes| {
+            String::from_utf8(bytes.to_vec()).map_err(|e| ArnError::Backend(e.to_string()))
+        })
+        .unwrap();
+        assert_eq!(restored.len(), 2);
+        assert_eq!(
+            restored.get(&arn("arn:akton:hr:acme:root/b")),
+            Some(&"b".to_string())
+        );
+    }
+
+    #[test]
+    fn test_saved_registry_survives_reopening_the_backend() {
+        let path = std::env::temp_dir().join(format!("akton-arn-{}.arns", uuid::Uuid::new_v4()));
+        let team = arn("arn:akton:hr:acme:root/team");
+        let mut registry = ArnRegistry::new();
+        registry.register(team.clone(), "team".to_string()).unwrap();
+        let backend = FileBackend::open(&path).unwrap();
+        registry
+            .save_snapshot(&backend, |m| m.as_bytes().to_vec())
+            .unwrap();
+        // Later writes stay in memory until the registry is saved again.
+        let later = arn("arn:akton:hr:acme:root/later");
+        registry
+            .register(later.clone(), "later".to_string())
+            .unwrap();
+        drop(backend);
+
+        let decode = |bytes: &[u8]| {
+            String::from_utf8(bytes.to_vec()).map_err(|e| ArnError::Backend(e.to_string()))
+        };
+        let reopened = FileBackend::open(&path).unwrap();
+        let restored = ArnRegistry::load_snapshot(&reopened, "arn:", decode).unwrap();
+        assert_eq!(restored.get(&team), Some(&"team".to_string()));
+        assert!(!restored.contains(&later));
+
+        registry
+            .save_snapshot(&reopened, |m| m.as_bytes().to_vec())
+            .unwrap();
+        let restored =
+            ArnRegistry::load_snapshot(&FileBackend::open(&path).unwrap(), "arn:", decode).unwrap();
+        assert_eq!(restored.get(&later), Some(&"later".to_string()));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
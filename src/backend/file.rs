@@ -0,0 +1,214 @@
+use super::{parse_key, BackendEvent, RegistryBackend, Watchers};
+use crate::errors::ArnError;
+use crate::model::Arn;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, Write as _};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Receiver;
+use std::sync::Mutex;
+
+/// A [`RegistryBackend`] persisting entries in a single text file, for small deployments and
+/// tools that need state to survive restarts without a database.
+///
+/// The file holds one entry per line, the hex-encoded value then the Arn, in lexical order.
+/// Entries are read once when the backend is opened and kept in memory; each write replaces
+/// the file through a temporary file and a rename, so a crash leaves either the old or the
+/// new contents. Only one backend should have a file open at a time, and watchers observe
+/// writes made through the same backend only.
+///
+/// ```
+/// use akton_arn::{ArnParser, FileBackend, RegistryBackend};
+///
+/// let path = std::env::temp_dir().join(format!("akton-arn-doc-{}.arns", std::process::id()));
+/// let team = ArnParser::new("arn:akton:hr:acme:root/team").parse().unwrap();
+/// FileBackend::open(&path).unwrap().put(&team, b"team").unwrap();
+/// let reopened = FileBackend::open(&path).unwrap();
+/// assert_eq!(reopened.get(&team).unwrap(), Some(b"team".to_vec()));
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct FileBackend {
+    path: PathBuf,
+    entries: Mutex<BTreeMap<String, Vec<u8>>>,
+    watchers: Watchers,
+}
+
+impl FileBackend {
+    /// Opens the file at `path`, which is created on the first write if it does not exist.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, ArnError> {
+        let path = path.as_ref().to_path_buf();
+        let entries = match fs::read_to_string(&path) {
+            Ok(contents) => decode(&contents)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(backend_error(e)),
+        };
+        Ok(FileBackend {
+            path,
+            entries: Mutex::new(entries),
+            watchers: Watchers::default(),
+        })
+    }
+
+    /// Returns the path of the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Replaces the file with `entries`.
+    fn flush(&self, entries: &BTreeMap<String, Vec<u8>>) -> Result<(), ArnError> {
+        let mut temp = self.path.clone().into_os_string();
+        temp.push(".tmp");
+        let mut file = fs::File::create(&temp).map_err(backend_error)?;
+        file.write_all(encode(entries).as_bytes())
+            .and_then(|()| file.sync_all())
+            .map_err(backend_error)?;
+        fs::rename(&temp, &self.path).map_err(backend_error)
+    }
+}
+
+fn backend_error(e: io::Error) -> ArnError {
+    ArnError::Backend(e.to_string())
+}
+
+fn encode(entries: &BTreeMap<String, Vec<u8>>) -> String {
+    let mut contents = String::new();
+    for (key, value) in entries {
+        for byte in value {
+            let _ = write!(contents, "{byte:02x}");
+        }
+        let _ = writeln!(contents, " {key}");
+    }
+    contents
+}
+
+fn decode(contents: &str) -> Result<BTreeMap<String, Vec<u8>>, ArnError> {
+    contents
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            let malformed = || ArnError::Backend(format!("malformed entry on line {}", index + 1));
+            let (value, key) = line.split_once(' ').ok_or_else(malformed)?;
+            if value.len() % 2 != 0 {
+                return Err(malformed());
+            }
+            let value = (0..value.len())
+                .step_by(2)
+                .map(|i| {
+                    value
+                        .get(i..i + 2)
+                        .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                })
+                .collect::<Option<Vec<u8>>>()
+                .ok_or_else(malformed)?;
+            parse_key(key)?;
+            Ok((key.to_string(), value))
+        })
+        .collect()
+}
+
+impl RegistryBackend for FileBackend {
+    fn get(&self, arn: &Arn) -> Result<Option<Vec<u8>>, ArnError> {
+        Ok(self.entries.lock().unwrap().get(&arn.to_string()).cloned())
+    }
+
+    fn put(&self, arn: &Arn, value: &[u8]) -> Result<(), ArnError> {
+        let key = arn.to_string();
+        {
+            let mut entries = self.entries.lock().unwrap();
+            let previous = entries.insert(key.clone(), value.to_vec());
+            if let Err(e) = self.flush(&entries) {
+                match previous {
+                    Some(previous) => entries.insert(key, previous),
+                    None => entries.remove(&key),
+                };
+                return Err(e);
+            }
+        }
+        self.watchers.notify(&key, || {
+            BackendEvent::Put(arn.clone().into_owned(), value.to_vec())
+        });
+        Ok(())
+    }
+
+    fn remove(&self, arn: &Arn) -> Result<bool, ArnError> {
+        let key = arn.to_string();
+        {
+            let mut entries = self.entries.lock().unwrap();
+            let Some(previous) = entries.remove(&key) else {
+                return Ok(false);
+            };
+            if let Err(e) = self.flush(&entries) {
+                entries.insert(key, previous);
+                return Err(e);
+            }
+        }
+        self.watchers
+            .notify(&key, || BackendEvent::Removed(arn.clone().into_owned()));
+        Ok(true)
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> Result<Vec<(Arn<'static>, Vec<u8>)>, ArnError> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .range(prefix.to_string()..)
+            .take_while(|(key, _)| key.starts_with(prefix))
+            .map(|(key, value)| Ok((parse_key(key)?, value.clone())))
+            .collect()
+    }
+
+    fn watch(&self, prefix: &str) -> Result<Receiver<BackendEvent>, ArnError> {
+        Ok(self.watchers.subscribe(prefix))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArnParser;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "akton-arn-file-backend-{name}-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_file_backend() {
+        let path = temp_path("exercise");
+        super::super::tests::exercise(&FileBackend::open(&path).unwrap());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_entries_survive_reopening() {
+        let path = temp_path("reopen");
+        let arn = ArnParser::new("arn:akton:hr:acme:root/a").parse().unwrap();
+        let child = ArnParser::new("arn:akton:hr:acme:root/a/b")
+            .parse()
+            .unwrap();
+        let backend = FileBackend::open(&path).unwrap();
+        backend.put(&arn, &[0, 0xff, b' ', b'\n']).unwrap();
+        backend.put(&child, &[]).unwrap();
+        drop(backend);
+
+        let reopened = FileBackend::open(&path).unwrap();
+        assert_eq!(
+            reopened.get(&arn).unwrap(),
+            Some(vec![0, 0xff, b' ', b'\n'])
+        );
+        assert_eq!(reopened.scan_prefix("arn:").unwrap().len(), 2);
+        fs::remove_file(&path).unwrap();
+
+        fs::write(&path, "0g arn:akton:hr:acme:root\n").unwrap();
+        assert_eq!(
+            FileBackend::open(&path).unwrap_err(),
+            ArnError::Backend("malformed entry on line 1".to_string())
+        );
+        fs::remove_file(path).unwrap();
+    }
+}
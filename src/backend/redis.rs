@@ -0,0 +1,197 @@
+use super::{parse_key, BackendEvent, RegistryBackend};
+use crate::errors::ArnError;
+use crate::model::Arn;
+use ::redis::{Client, Commands, Connection};
+use std::sync::mpsc::{channel, sync_channel, Receiver};
+use std::sync::Mutex;
+
+/// A [`RegistryBackend`] storing entries as Redis strings.
+///
+/// Keys are the canonical Arn prefixed with a namespace. Every write is also published on the
+/// `<namespace>events` channel, so watchers observe writes made through any `RedisBackend`
+/// sharing the namespace, but not writes made to the keys directly.
+pub struct RedisBackend {
+    client: Client,
+    connection: Mutex<Connection>,
+    namespace: String,
+}
+
+impl RedisBackend {
+    /// Connects to the server at `url`, storing keys under `namespace`.
+    pub fn connect(url: &str, namespace: impl Into<String>) -> Result<Self, ArnError> {
+        let client = Client::open(url).map_err(backend_error)?;
+        let connection = client.get_connection().map_err(backend_error)?;
+        Ok(RedisBackend {
+            client,
+            connection: Mutex::new(connection),
+            namespace: namespace.into(),
+        })
+    }
+
+    fn key(&self, arn: &Arn) -> String {
+        format!("{}{}", self.namespace, arn)
+    }
+
+    fn channel(&self) -> String {
+        format!("{}events", self.namespace)
+    }
+
+    fn publish(&self, connection: &mut Connection, message: Vec<u8>) -> Result<(), ArnError> {
+        connection
+            .publish::<_, _, ()>(self.channel(), message)
+            .map_err(backend_error)
+    }
+}
+
+fn backend_error(e: ::redis::RedisError) -> ArnError {
+    ArnError::Backend(e.to_string())
+}
+
+/// Escapes glob metacharacters so a prefix matches literally in `SCAN MATCH`.
+fn escape_glob(prefix: &str) -> String {
+    let mut escaped = String::with_capacity(prefix.len());
+    for c in prefix.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Event messages are a tag byte (`P` or `R`), the Arn length as a big-endian `u32`, the Arn,
+/// and for puts the value.
+fn encode_event(tag: u8, arn: &Arn, value: &[u8]) -> Vec<u8> {
+    let arn = arn.to_string();
+    let mut message = Vec::with_capacity(5 + arn.len() + value.len());
+    message.push(tag);
+    message.extend_from_slice(&(arn.len() as u32).to_be_bytes());
+    message.extend_from_slice(arn.as_bytes());
+    message.extend_from_slice(value);
+    message
+}
+
+fn decode_event(message: &[u8]) -> Option<BackendEvent> {
+    let (&tag, rest) = message.split_first()?;
+    let len = u32::from_be_bytes(rest.get(..4)?.try_into().ok()?) as usize;
+    let arn = parse_key(std::str::from_utf8(rest.get(4..4 + len)?).ok()?).ok()?;
+    let value = &rest[4 + len..];
+    match tag {
+        b'P' => Some(BackendEvent::Put(arn, value.to_vec())),
+        b'R' => Some(BackendEvent::Removed(arn)),
+        _ => None,
+    }
+}
+
+impl RegistryBackend for RedisBackend {
+    fn get(&self, arn: &Arn) -> Result<Option<Vec<u8>>, ArnError> {
+        let mut connection = self.connection.lock().unwrap();
+        connection.get(self.key(arn)).map_err(backend_error)
+    }
+
+    fn put(&self, arn: &Arn, value: &[u8]) -> Result<(), ArnError> {
+        let mut connection = self.connection.lock().unwrap();
+        connection
+            .set::<_, _, ()>(self.key(arn), value)
+            .map_err(backend_error)?;
+        self.publish(&mut connection, encode_event(b'P', arn, value))
+    }
+
+    fn remove(&self, arn: &Arn) -> Result<bool, ArnError> {
+        let mut connection = self.connection.lock().unwrap();
+        let removed: u64 = connection.del(self.key(arn)).map_err(backend_error)?;
+        if removed > 0 {
+            self.publish(&mut connection, encode_event(b'R', arn, &[]))?;
+        }
+        Ok(removed > 0)
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> Result<Vec<(Arn<'static>, Vec<u8>)>, ArnError> {
+        let mut connection = self.connection.lock().unwrap();
+        let pattern = format!("{}*", escape_glob(&format!("{}{prefix}", self.namespace)));
+        let mut keys: Vec<String> = connection
+            .scan_match::<_, String>(pattern)
+            .map_err(backend_error)?
+            .collect();
+        keys.sort();
+        let mut entries = Vec::with_capacity(keys.len());
+        for key in keys {
+            // A key may be removed between the scan and the read.
+            let value: Option<Vec<u8>> = connection.get(&key).map_err(backend_error)?;
+            if let Some(value) = value {
+                entries.push((parse_key(&key[self.namespace.len()..])?, value));
+            }
+        }
+        Ok(entries)
+    }
+
+    fn watch(&self, prefix: &str) -> Result<Receiver<BackendEvent>, ArnError> {
+        let mut connection = self.client.get_connection().map_err(backend_error)?;
+        let channel_name = self.channel();
+        let prefix = prefix.to_string();
+        let (sender, receiver) = channel();
+        let (subscribed, confirmation) = sync_channel(1);
+        // The subscription must live on the `PubSub` that reads it: dropping a `PubSub`
+        // unsubscribes its connection.
+        std::thread::spawn(move || {
+            let mut pubsub = connection.as_pubsub();
+            if let Err(e) = pubsub.subscribe(&channel_name) {
+                let _ = subscribed.send(Err(backend_error(e)));
+                return;
+            }
+            let _ = subscribed.send(Ok(()));
+            while let Ok(message) = pubsub.get_message() {
+                let Some(event) = decode_event(message.get_payload_bytes()) else {
+                    continue;
+                };
+                let arn = match &event {
+                    BackendEvent::Put(arn, _) | BackendEvent::Removed(arn) => arn.to_string(),
+                };
+                if arn.starts_with(&prefix) && sender.send(event).is_err() {
+                    break;
+                }
+            }
+        });
+        // Wait for the subscription so no write made after `watch` returns is missed.
+        confirmation
+            .recv()
+            .map_err(|_| ArnError::Backend("redis watcher exited before subscribing".into()))??;
+        Ok(receiver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArnParser;
+
+    #[test]
+    fn test_event_encoding_round_trip() {
+        let arn = ArnParser::new("arn:akton:hr:acme:root/team")
+            .parse()
+            .unwrap();
+        assert_eq!(
+            decode_event(&encode_event(b'P', &arn, b"value")),
+            Some(BackendEvent::Put(arn.clone(), b"value".to_vec()))
+        );
+        assert_eq!(
+            decode_event(&encode_event(b'R', &arn, &[])),
+            Some(BackendEvent::Removed(arn))
+        );
+        assert_eq!(decode_event(b"P\0\0\0\x09short"), None);
+    }
+
+    #[test]
+    fn test_escape_glob() {
+        assert_eq!(escape_glob("arn:a*b?[c]\\"), "arn:a\\*b\\?\\[c\\]\\\\");
+    }
+
+    /// Runs against the server in `AKTON_ARN_REDIS_URL`, for example `redis://127.0.0.1/`.
+    #[test]
+    #[ignore = "needs a Redis server"]
+    fn test_redis_backend() {
+        let url = std::env::var("AKTON_ARN_REDIS_URL").expect("AKTON_ARN_REDIS_URL is not set");
+        let namespace = format!("akton-arn-test-{}:", uuid::Uuid::new_v4());
+        super::super::tests::exercise(&RedisBackend::connect(&url, namespace).unwrap());
+    }
+}
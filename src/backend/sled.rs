@@ -0,0 +1,102 @@
+use super::{parse_key, BackendEvent, RegistryBackend};
+use crate::errors::ArnError;
+use crate::model::Arn;
+use std::path::Path;
+use std::sync::mpsc::{channel, Receiver};
+
+/// A [`RegistryBackend`] persisting entries in an embedded [`sled`] database.
+///
+/// Watchers use sled's native change feed and observe every write to the tree, including
+/// writes made through other handles of the same database.
+#[derive(Debug, Clone)]
+pub struct SledBackend {
+    tree: ::sled::Tree,
+}
+
+impl SledBackend {
+    /// Opens, or creates, a database at `path` and stores entries in its default tree.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, ArnError> {
+        let db = ::sled::open(path).map_err(backend_error)?;
+        Ok(SledBackend {
+            tree: (*db).clone(),
+        })
+    }
+
+    /// Stores entries in an existing tree, for sharing a database with other data.
+    pub fn from_tree(tree: ::sled::Tree) -> Self {
+        SledBackend { tree }
+    }
+}
+
+fn backend_error(e: ::sled::Error) -> ArnError {
+    ArnError::Backend(e.to_string())
+}
+
+fn parse_entry(key: &[u8]) -> Result<Arn<'static>, ArnError> {
+    parse_key(std::str::from_utf8(key).map_err(|e| ArnError::Backend(e.to_string()))?)
+}
+
+impl RegistryBackend for SledBackend {
+    fn get(&self, arn: &Arn) -> Result<Option<Vec<u8>>, ArnError> {
+        let value = self.tree.get(arn.to_string()).map_err(backend_error)?;
+        Ok(value.map(|value| value.to_vec()))
+    }
+
+    fn put(&self, arn: &Arn, value: &[u8]) -> Result<(), ArnError> {
+        self.tree
+            .insert(arn.to_string(), value)
+            .map_err(backend_error)?;
+        Ok(())
+    }
+
+    fn remove(&self, arn: &Arn) -> Result<bool, ArnError> {
+        let removed = self.tree.remove(arn.to_string()).map_err(backend_error)?;
+        Ok(removed.is_some())
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> Result<Vec<(Arn<'static>, Vec<u8>)>, ArnError> {
+        self.tree
+            .scan_prefix(prefix)
+            .map(|entry| {
+                let (key, value) = entry.map_err(backend_error)?;
+                Ok((parse_entry(&key)?, value.to_vec()))
+            })
+            .collect()
+    }
+
+    fn watch(&self, prefix: &str) -> Result<Receiver<BackendEvent>, ArnError> {
+        let subscriber = self.tree.watch_prefix(prefix);
+        let (sender, receiver) = channel();
+        // The thread exits on the first event after the receiver is dropped, or when the
+        // database closes.
+        std::thread::spawn(move || {
+            for event in subscriber {
+                let event = match event {
+                    ::sled::Event::Insert { key, value } => match parse_entry(&key) {
+                        Ok(arn) => BackendEvent::Put(arn, value.to_vec()),
+                        Err(_) => continue,
+                    },
+                    ::sled::Event::Remove { key } => match parse_entry(&key) {
+                        Ok(arn) => BackendEvent::Removed(arn),
+                        Err(_) => continue,
+                    },
+                };
+                if sender.send(event).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(receiver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sled_backend() {
+        let db = ::sled::Config::new().temporary(true).open().unwrap();
+        super::super::tests::exercise(&SledBackend::from_tree((*db).clone()));
+    }
+}
@@ -0,0 +1,191 @@
+use super::{parse_key, BackendEvent, RegistryBackend, Watchers};
+use crate::errors::ArnError;
+use crate::model::Arn;
+use ::rusqlite::{params, Connection, OptionalExtension};
+use std::path::Path;
+use std::sync::mpsc::Receiver;
+use std::sync::Mutex;
+
+/// A [`RegistryBackend`] persisting entries in a SQLite database.
+///
+/// Entries live in a table, `arn_registry` unless another is given, created if missing, with
+/// the canonical Arn as the text primary key and the value as a blob. SQLite has no change
+/// feed, so watchers observe writes made through the same backend only.
+#[derive(Debug)]
+pub struct SqliteBackend {
+    connection: Mutex<Connection>,
+    table: String,
+    watchers: Watchers,
+}
+
+impl SqliteBackend {
+    /// Opens, or creates, the database at `path`.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, ArnError> {
+        let connection = Connection::open(path).map_err(backend_error)?;
+        Self::from_connection(connection, DEFAULT_TABLE)
+    }
+
+    /// Opens a private database held in memory, for tests.
+    pub fn open_in_memory() -> Result<Self, ArnError> {
+        let connection = Connection::open_in_memory().map_err(backend_error)?;
+        Self::from_connection(connection, DEFAULT_TABLE)
+    }
+
+    /// Stores entries in `table` through an existing connection, for sharing a database with
+    /// other data.
+    ///
+    /// Fails if `table` is not a plain identifier of ASCII letters, digits and `_`.
+    pub fn from_connection(connection: Connection, table: &str) -> Result<Self, ArnError> {
+        let plain = table.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && table.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !plain {
+            return Err(ArnError::Backend(format!(
+                "{table:?} is not a plain SQLite table name"
+            )));
+        }
+        connection
+            .execute(
+                &format!(
+                    "CREATE TABLE IF NOT EXISTS {table} (arn TEXT PRIMARY KEY, value BLOB NOT NULL)"
+                ),
+                [],
+            )
+            .map_err(backend_error)?;
+        Ok(SqliteBackend {
+            connection: Mutex::new(connection),
+            table: table.to_string(),
+            watchers: Watchers::default(),
+        })
+    }
+}
+
+const DEFAULT_TABLE: &str = "arn_registry";
+
+fn backend_error(e: ::rusqlite::Error) -> ArnError {
+    ArnError::Backend(e.to_string())
+}
+
+impl RegistryBackend for SqliteBackend {
+    fn get(&self, arn: &Arn) -> Result<Option<Vec<u8>>, ArnError> {
+        self.connection
+            .lock()
+            .unwrap()
+            .query_row(
+                &format!("SELECT value FROM {} WHERE arn = ?1", self.table),
+                [arn.to_string()],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(backend_error)
+    }
+
+    fn put(&self, arn: &Arn, value: &[u8]) -> Result<(), ArnError> {
+        let key = arn.to_string();
+        self.connection
+            .lock()
+            .unwrap()
+            .execute(
+                &format!(
+                    "INSERT INTO {} (arn, value) VALUES (?1, ?2)
+                     ON CONFLICT (arn) DO UPDATE SET value = excluded.value",
+                    self.table
+                ),
+                params![key, value],
+            )
+            .map_err(backend_error)?;
+        self.watchers.notify(&key, || {
+            BackendEvent::Put(arn.clone().into_owned(), value.to_vec())
+        });
+        Ok(())
+    }
+
+    fn remove(&self, arn: &Arn) -> Result<bool, ArnError> {
+        let key = arn.to_string();
+        let removed = self
+            .connection
+            .lock()
+            .unwrap()
+            .execute(
+                &format!("DELETE FROM {} WHERE arn = ?1", self.table),
+                [&key],
+            )
+            .map_err(backend_error)?
+            > 0;
+        if removed {
+            self.watchers
+                .notify(&key, || BackendEvent::Removed(arn.clone().into_owned()));
+        }
+        Ok(removed)
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> Result<Vec<(Arn<'static>, Vec<u8>)>, ArnError> {
+        let connection = self.connection.lock().unwrap();
+        // `substr` rather than `LIKE`, which treats `%` and `_` in the prefix as wildcards.
+        let mut statement = connection
+            .prepare(&format!(
+                "SELECT arn, value FROM {} WHERE substr(arn, 1, length(?1)) = ?1 ORDER BY arn",
+                self.table
+            ))
+            .map_err(backend_error)?;
+        let rows = statement
+            .query_map([prefix], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, Vec<u8>>(1)?))
+            })
+            .map_err(backend_error)?;
+        rows.map(|row| {
+            let (key, value) = row.map_err(backend_error)?;
+            Ok((parse_key(&key)?, value))
+        })
+        .collect()
+    }
+
+    fn watch(&self, prefix: &str) -> Result<Receiver<BackendEvent>, ArnError> {
+        Ok(self.watchers.subscribe(prefix))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sqlite_backend() {
+        super::super::tests::exercise(&SqliteBackend::open_in_memory().unwrap());
+    }
+
+    #[test]
+    fn test_sqlite_backend_persists() {
+        let path = std::env::temp_dir().join(format!("akton-arn-{}.sqlite", uuid::Uuid::new_v4()));
+        let team = parse_key("arn:akton:hr:acme:root/team_%").unwrap();
+        SqliteBackend::open(&path)
+            .unwrap()
+            .put(&team, b"team")
+            .unwrap();
+        let reopened = SqliteBackend::open(&path).unwrap();
+        assert_eq!(reopened.get(&team).unwrap(), Some(b"team".to_vec()));
+        assert_eq!(
+            reopened
+                .scan_prefix("arn:akton:hr:acme:root/team_")
+                .unwrap(),
+            vec![(team, b"team".to_vec())]
+        );
+        assert!(reopened
+            .scan_prefix("arn:akton:hr:acme:root/teamx")
+            .unwrap()
+            .is_empty());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_tables_keep_entries_apart() {
+        let path = std::env::temp_dir().join(format!("akton-arn-{}.sqlite", uuid::Uuid::new_v4()));
+        let open = |table| SqliteBackend::from_connection(Connection::open(&path).unwrap(), table);
+        let (registry, claims) = (open("arn_registry").unwrap(), open("arn_claims").unwrap());
+        let team = parse_key("arn:akton:hr:acme:root/team").unwrap();
+        registry.put(&team, b"metadata").unwrap();
+        assert_eq!(claims.get(&team).unwrap(), None);
+        assert!(open("claims; DROP TABLE arn_registry").is_err());
+        assert_eq!(registry.get(&team).unwrap(), Some(b"metadata".to_vec()));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,276 @@
+//! `arn`, a command-line tool for inspecting, building and matching Arns.
+//!
+//! Built with the `cli` feature. Commands that take Arns read them from their arguments or,
+//! when none are given, one per line from standard input, so the tool fits in pipelines:
+//!
+//! ```text
+//! $ arn build --domain akton --category hr --account acme --root payroll --part 2024
+//! $ grep -o 'arn:[^ ]*' audit.log | arn match 'arn:akton:hr:*:*/**'
+//! $ arn convert --to json arn:akton:hr:acme:root/teams/a
+//! ```
+
+use akton_arn::{
+    Account, Arn, ArnBuilder, ArnFormat, ArnParser, ArnPattern, Category, Domain, Root, RootKind,
+};
+use clap::{Parser, Subcommand, ValueEnum};
+use std::io::{self, BufRead};
+use std::process::ExitCode;
+
+/// Inspect, build and match Akton Resource Names.
+#[derive(Parser)]
+#[command(name = "arn", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Check that Arns parse and print them in canonical form.
+    Parse {
+        /// Arns to parse; read from standard input if none are given.
+        arns: Vec<String>,
+    },
+    /// Build an Arn from its components, minting the root's id.
+    Build {
+        #[arg(long)]
+        domain: String,
+        #[arg(long)]
+        category: String,
+        #[arg(long)]
+        account: String,
+        /// The root's name; a generated id is appended to it.
+        #[arg(long)]
+        root: String,
+        /// A part to add after the root; repeat for deeper paths.
+        #[arg(long = "part")]
+        parts: Vec<String>,
+    },
+    /// Describe each component of Arns.
+    Explain {
+        /// Arns to explain; read from standard input if none are given.
+        arns: Vec<String>,
+    },
+    /// Print the Arns that match a pattern, failing if none do.
+    Match {
+        /// The pattern, with `*` for any one component or part and `**` for any number of parts.
+        pattern: ArnPattern,
+        /// Arns to test; read from standard input if none are given.
+        arns: Vec<String>,
+    },
+    /// Describe how to turn one Arn into another.
+    Diff {
+        /// The Arn before and after; read from standard input if not given.
+        #[arg(num_args = 0..=2)]
+        arns: Vec<String>,
+    },
+    /// Write Arns in another notation.
+    Convert {
+        #[arg(long, value_enum)]
+        to: Notation,
+        /// Arns to convert; read from standard input if none are given.
+        arns: Vec<String>,
+    },
+}
+
+/// Notations `convert` writes.
+#[derive(Clone, Copy, ValueEnum)]
+enum Notation {
+    /// `arn:<domain>:<category>::<account>:<root>/<parts>`, the AWS ARN layout with the domain as
+    /// partition, the category as service and no region.
+    AwsArn,
+    /// `urn:<domain>:<category>:<account>:<root>/<parts>`.
+    Urn,
+    /// A JSON object with one field per component.
+    Json,
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse().command) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(message) => {
+            eprintln!("error: {message}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Runs `command`, returning whether it succeeded or why it could not run.
+fn run(command: Command) -> Result<bool, String> {
+    match command {
+        Command::Parse { arns } => each_arn(arns, |arn| {
+            println!("{arn}");
+            Ok(())
+        }),
+        Command::Build {
+            domain,
+            category,
+            account,
+            root,
+            parts,
+        } => {
+            let mut saved = ArnBuilder::new()
+                .with::<Domain>(domain)
+                .and_then(|b| b.with::<Category>(category))
+                .and_then(|b| b.with::<Account>(account))
+                .and_then(|b| b.with::<Root>(root))
+                .map_err(|e| e.to_string())?
+                .into_parts();
+            // Each part changes the builder's type, so add them all at once by resuming.
+            saved.parts = parts;
+            let arn = ArnBuilder::new()
+                .resume(saved)
+                .and_then(|builder| builder.build())
+                .map_err(|e| e.to_string())?;
+            println!("{arn}");
+            Ok(true)
+        }
+        Command::Explain { arns } => each_arn(arns, |arn| {
+            explain(&arn);
+            Ok(())
+        }),
+        Command::Match { pattern, arns } => {
+            let mut matched = false;
+            let ok = each_arn(arns, |arn| {
+                if pattern.matches(&arn) {
+                    matched = true;
+                    println!("{arn}");
+                }
+                Ok(())
+            })?;
+            Ok(ok && matched)
+        }
+        Command::Diff { arns } => {
+            let arns = if arns.is_empty() {
+                stdin_lines()?
+            } else {
+                arns
+            };
+            let [before, after] = <[String; 2]>::try_from(arns)
+                .map_err(|arns| format!("diff needs two Arns, got {}", arns.len()))?;
+            let before = parse(&before)?;
+            let after = parse(&after)?;
+            println!("{}", before.diff(&after));
+            Ok(true)
+        }
+        Command::Convert { to, arns } => each_arn(arns, |arn| {
+            println!("{}", convert(&arn, to)?);
+            Ok(())
+        }),
+    }
+}
+
+/// Calls `f` on each Arn in `arns`, or in standard input if `arns` is empty, reporting the ones
+/// that fail to parse or convert. Returns whether every Arn succeeded.
+fn each_arn(
+    arns: Vec<String>,
+    mut f: impl FnMut(Arn<'static>) -> Result<(), String>,
+) -> Result<bool, String> {
+    let arns = if arns.is_empty() {
+        stdin_lines()?
+    } else {
+        arns
+    };
+    let mut ok = true;
+    for input in arns {
+        if let Err(message) = parse(&input).and_then(&mut f) {
+            eprintln!("error: {input}: {message}");
+            ok = false;
+        }
+    }
+    Ok(ok)
+}
+
+/// Reads the non-blank lines of standard input, trimmed.
+fn stdin_lines() -> Result<Vec<String>, String> {
+    let mut lines = Vec::new();
+    for line in io::stdin().lock().lines() {
+        let line = line.map_err(|e| format!("reading standard input: {e}"))?;
+        let line = line.trim();
+        if !line.is_empty() {
+            lines.push(line.to_string());
+        }
+    }
+    Ok(lines)
+}
+
+fn parse(input: &str) -> Result<Arn<'static>, String> {
+    ArnParser::new(input.to_string())
+        .parse()
+        .map_err(|e| e.to_string())
+}
+
+fn explain(arn: &Arn) {
+    println!("{arn}");
+    println!("  scheme:     {}", arn.scheme());
+    println!("  domain:     {}", arn.domain());
+    println!("  category:   {}", arn.category());
+    println!("  account:    {}", arn.account());
+    match arn.root().kind() {
+        RootKind::Named => println!("  root:       {} (named)", arn.root()),
+        RootKind::Generated => match arn.age() {
+            Some(age) => println!(
+                "  root:       {} (generated for {}, {}s ago)",
+                arn.root(),
+                arn.root().name(),
+                age.as_secs()
+            ),
+            None => println!(
+                "  root:       {} (generated for {})",
+                arn.root(),
+                arn.root().name()
+            ),
+        },
+    }
+    for (depth, part) in arn.parts().iter().enumerate() {
+        println!("  part {}:     {part}", depth + 1);
+    }
+    if let Some(version) = arn.version() {
+        println!("  version:    {version}");
+    }
+    for (key, value) in arn.extensions().iter() {
+        println!("  extension:  {key}={value}");
+    }
+}
+
+fn convert(arn: &Arn, to: Notation) -> Result<String, String> {
+    match to {
+        Notation::AwsArn => {
+            let mut resource = arn.root().to_string();
+            for part in arn.parts().iter() {
+                resource.push('/');
+                resource.push_str(part.as_str());
+            }
+            Ok(format!(
+                "arn:{}:{}::{}:{resource}",
+                arn.domain(),
+                arn.category(),
+                arn.account()
+            ))
+        }
+        Notation::Urn => ArnFormat::new()
+            .with_scheme("urn")
+            .and_then(|format| arn.to_string_with(&format))
+            .map_err(|e| e.to_string()),
+        Notation::Json => {
+            let parts: Vec<&str> = arn.parts().iter().map(|part| part.as_str()).collect();
+            let extensions: serde_json::Map<String, serde_json::Value> = arn
+                .extensions()
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.into()))
+                .collect();
+            Ok(serde_json::json!({
+                "arn": arn.to_string(),
+                "domain": arn.domain().as_str(),
+                "category": arn.category().as_str(),
+                "account": arn.account().as_str(),
+                "root": arn.root().as_str(),
+                "parts": parts,
+                "version": arn.version(),
+                "extensions": extensions,
+            })
+            .to_string())
+        }
+    }
+}
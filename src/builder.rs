@@ -1,8 +1,17 @@
+use crate::case_fold::CaseFold;
 use crate::errors::ArnError;
+use crate::id_generator::{IdGenerator, IdScope, RootConfig};
+use crate::limits::ArnLimits;
 use crate::model::{Account, Arn, Category, Domain, Part, Parts};
-use crate::traits::ArnComponent;
-use crate::Root;
+use crate::namespace_guard::NamespaceGuard;
+use crate::parser::ComponentRules;
+use crate::profile::ArnProfile;
+use crate::time_source::{SystemClock, TimeSource};
+use crate::traits::{run_validators, ArnComponent, ArnValidator};
+use crate::validation_mode::ValidationMode;
+use crate::{ComponentKind, Root};
 use std::borrow::Cow;
+use std::sync::Arc;
 
 /// A builder for constructing Arn instances using a state-driven approach with type safety.
 pub struct ArnBuilder<'a, State> {
@@ -21,6 +30,65 @@ impl<'a> ArnBuilder<'a, ()> {
     }
 }
 
+/// Implementation of `ArnBuilder` for a builder that has no components yet.
+impl<'a> ArnBuilder<'a, Domain<'a>> {
+    /// Adds the domain, category and account of `profile`, leaving the root to be added next.
+    ///
+    /// The components are checked like any others, so set validators and other settings
+    /// first.
+    pub fn with_defaults(self, profile: &ArnProfile) -> Result<ArnBuilder<'a, Root<'a>>, ArnError> {
+        let builder = self
+            .builder
+            .add_part(Domain::prefix(), profile.domain().as_cow())?
+            .add_part(Category::prefix(), profile.category().as_cow())?
+            .add_part(Account::prefix(), profile.account().as_cow())?;
+        Ok(builder.into_state())
+    }
+
+    /// Continues building from `parts` saved by [`ArnBuilder::into_parts`], returning the builder
+    /// in the state its components reached.
+    ///
+    /// Configure the builder first: every component is checked again against its validators,
+    /// limits, validation mode and case folding, so parts read back from storage cannot skip a
+    /// check. The saved root is restored as it was rather than minted again. Fails if a
+    /// component is missing before one that is present, such as an account without a
+    /// category.
+    ///
+    /// ```
+    /// use akton_arn::{Account, ArnBuilder, Category, Domain, ResumedArnBuilder};
+    ///
+    /// // First step of a wizard: the user picks a domain and category.
+    /// let saved = ArnBuilder::new()
+    ///     .with::<Domain>("akton").unwrap()
+    ///     .with::<Category>("hr").unwrap()
+    ///     .into_parts();
+    /// assert_eq!(saved.account, None);
+    ///
+    /// // A later step, possibly in another process, picks up where the first left off.
+    /// let ResumedArnBuilder::Account(builder) = ArnBuilder::new().resume(saved).unwrap() else {
+    ///     panic!("the account comes next");
+    /// };
+    /// let builder = builder.with::<Account>("acme").unwrap();
+    /// assert_eq!(builder.peek_account().unwrap().as_str(), "acme");
+    /// ```
+    pub fn resume(self, parts: ArnBuilderParts) -> Result<ResumedArnBuilder<'a>, ArnError> {
+        let builder = self.builder.resume(parts)?;
+        Ok(if builder.domain.is_none() {
+            ResumedArnBuilder::Domain(builder.into_state())
+        } else if builder.category.is_none() {
+            ResumedArnBuilder::Category(builder.into_state())
+        } else if builder.account.is_none() {
+            ResumedArnBuilder::Account(builder.into_state())
+        } else if builder.root.is_none() {
+            ResumedArnBuilder::Root(builder.into_state())
+        } else if builder.parts.0.is_empty() {
+            ResumedArnBuilder::Part(builder.into_state())
+        } else {
+            ResumedArnBuilder::Parts(builder.into_state())
+        })
+    }
+}
+
 /// Implementation of `ArnBuilder` for `Part` states, allowing for building the final Arn.
 impl<'a> ArnBuilder<'a, Part<'a>> {
     /// Finalizes the building process and constructs the Arn.
@@ -37,6 +105,121 @@ impl<'a> ArnBuilder<'a, Parts<'a>> {
     }
 }
 
+/// Implementation of `ArnBuilder` available in every state.
+impl<'a, State> ArnBuilder<'a, State> {
+    /// Registers a validator that is run against each component as it is added.
+    ///
+    /// Validators only see components added after they are registered, so they are
+    /// typically attached straight after [`ArnBuilder::new`].
+    pub fn with_validator(mut self, validator: impl ArnValidator + 'static) -> Self {
+        self.builder.validators.push(Arc::new(validator));
+        self
+    }
+
+    /// Mints the root id from `time_source` instead of the wall clock.
+    ///
+    /// Like validators, it only affects a root added after it is set.
+    pub fn with_time_source(mut self, time_source: impl TimeSource + 'static) -> Self {
+        self.builder.id_generator = Some(Arc::new(time_source));
+        self
+    }
+
+    /// Mints the root id with `generator` instead of a UUIDv7 from the wall clock.
+    ///
+    /// The generator sees the domain, category and account added before the root. Like
+    /// [`with_time_source`](ArnBuilder::with_time_source), which it replaces, it only affects a
+    /// root added after it is set.
+    pub fn with_id_generator(mut self, generator: impl IdGenerator + 'static) -> Self {
+        self.builder.id_generator = Some(Arc::new(generator));
+        self
+    }
+
+    /// Applies `limits` instead of [`ArnLimits::default`].
+    ///
+    /// Components added before it is set are not checked again, but the length of the whole
+    /// Arn is checked against it when the Arn is built.
+    pub fn with_limits(mut self, limits: ArnLimits) -> Self {
+        self.builder.rules.limits = limits;
+        self
+    }
+
+    /// Checks and normalizes component characters as `mode` says instead of
+    /// [`ValidationMode::Permissive`].
+    ///
+    /// Like validators, it only affects components added after it is set.
+    pub fn with_validation_mode(mut self, mode: ValidationMode) -> Self {
+        self.builder.rules.mode = mode;
+        self
+    }
+
+    /// Lowercases the components `fold` selects.
+    ///
+    /// Like validators, it only affects components added after it is set.
+    pub fn with_case_fold(mut self, fold: CaseFold) -> Self {
+        self.builder.rules.case_fold = fold;
+        self
+    }
+
+    /// Claims the built Arn's name with `guard`, so building a name that is already taken
+    /// fails with [`ArnError::AlreadyRegistered`].
+    ///
+    /// The name is claimed only once the Arn is otherwise complete and valid.
+    pub fn with_namespace_guard(mut self, guard: Arc<NamespaceGuard>) -> Self {
+        self.builder.guard = Some(guard);
+        self
+    }
+
+    /// Joins the root's name and generated id as `config` says instead of as a TypeID.
+    ///
+    /// Like [`with_id_generator`](ArnBuilder::with_id_generator), it only affects a root added
+    /// after it is set.
+    pub fn with_root_config(mut self, config: RootConfig) -> Self {
+        self.builder.root_config = config;
+        self
+    }
+
+    /// Returns the domain, if it has been added.
+    pub fn peek_domain(&self) -> Option<&Domain<'a>> {
+        self.builder.domain.as_ref()
+    }
+
+    /// Returns the category, if it has been added.
+    pub fn peek_category(&self) -> Option<&Category<'a>> {
+        self.builder.category.as_ref()
+    }
+
+    /// Returns the account, if it has been added.
+    pub fn peek_account(&self) -> Option<&Account<'a>> {
+        self.builder.account.as_ref()
+    }
+
+    /// Returns the root, if it has been added, with any generated id already minted.
+    pub fn peek_root(&self) -> Option<&Root<'a>> {
+        self.builder.root.as_ref()
+    }
+
+    /// Returns the parts added so far.
+    pub fn peek_parts(&self) -> &Parts<'a> {
+        &self.builder.parts
+    }
+
+    /// Detaches the components added so far, to be stored and later passed to
+    /// [`ArnBuilder::resume`].
+    ///
+    /// Only the components are kept. Validators, limits and the other settings belong to the
+    /// code doing the building and are set again on the builder that resumes.
+    pub fn into_parts(self) -> ArnBuilderParts {
+        let builder = self.builder;
+        ArnBuilderParts {
+            domain: builder.domain.map(String::from),
+            category: builder.category.map(String::from),
+            account: builder.account.map(String::from),
+            root: builder.root.map(String::from),
+            parts: builder.parts.0.into_iter().map(String::from).collect(),
+        }
+    }
+}
+
 /// Generic implementation of `ArnBuilder` for all states that can transition to another state.
 impl<'a, T: ArnComponent<'a>> ArnBuilder<'a, T> {
     /// Adds a new part to the Arn, transitioning to the next appropriate state.
@@ -54,6 +237,60 @@ impl<'a, T: ArnComponent<'a>> ArnBuilder<'a, T> {
     }
 }
 
+/// The components of a partly built Arn, detached from the [`ArnBuilder`] that collected them.
+///
+/// Workflows that assemble an Arn across several user interactions or async steps save the
+/// parts from [`ArnBuilder::into_parts`] between steps, serialized with the `serde` feature if
+/// need be, and continue with [`ArnBuilder::resume`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ArnBuilderParts {
+    /// The domain, if it has been added.
+    pub domain: Option<String>,
+    /// The category, if it has been added.
+    pub category: Option<String>,
+    /// The account, if it has been added.
+    pub account: Option<String>,
+    /// The root as it was minted, if it has been added.
+    pub root: Option<String>,
+    /// The parts added after the root, in order.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub parts: Vec<String>,
+}
+
+/// An [`ArnBuilder`] resumed by [`ArnBuilder::resume`], in the state named by the component it
+/// expects next.
+pub enum ResumedArnBuilder<'a> {
+    /// Nothing has been added; the domain comes next.
+    Domain(ArnBuilder<'a, Domain<'a>>),
+    /// The category comes next.
+    Category(ArnBuilder<'a, Category<'a>>),
+    /// The account comes next.
+    Account(ArnBuilder<'a, Account<'a>>),
+    /// The root comes next.
+    Root(ArnBuilder<'a, Root<'a>>),
+    /// The root has been added; the Arn can be built or given its first part.
+    Part(ArnBuilder<'a, Part<'a>>),
+    /// Parts have been added; the Arn can be built or given more parts.
+    Parts(ArnBuilder<'a, Parts<'a>>),
+}
+
+impl<'a> ResumedArnBuilder<'a> {
+    /// Builds the Arn if every required component has been added.
+    ///
+    /// Fails with [`ArnError::MissingPart`] naming the first component still missing.
+    pub fn build(self) -> Result<Arn<'a>, ArnError> {
+        match self {
+            ResumedArnBuilder::Domain(builder) => builder.builder.build(),
+            ResumedArnBuilder::Category(builder) => builder.builder.build(),
+            ResumedArnBuilder::Account(builder) => builder.builder.build(),
+            ResumedArnBuilder::Root(builder) => builder.builder.build(),
+            ResumedArnBuilder::Part(builder) => builder.build(),
+            ResumedArnBuilder::Parts(builder) => builder.build(),
+        }
+    }
+}
+
 /// Represents a private, internal structure for building the Arn.
 struct PrivateArnBuilder<'a> {
     domain: Option<Domain<'a>>,
@@ -61,6 +298,11 @@ struct PrivateArnBuilder<'a> {
     account: Option<Account<'a>>,
     root: Option<Root<'a>>,
     parts: Parts<'a>,
+    validators: Vec<Arc<dyn ArnValidator>>,
+    id_generator: Option<Arc<dyn IdGenerator>>,
+    root_config: RootConfig,
+    rules: ComponentRules,
+    guard: Option<Arc<NamespaceGuard>>,
 }
 
 impl<'a> PrivateArnBuilder<'a> {
@@ -72,34 +314,121 @@ impl<'a> PrivateArnBuilder<'a> {
             account: None,
             root: None,
             parts: Parts::new(Vec::new()),
+            validators: Vec::new(),
+            id_generator: None,
+            root_config: RootConfig::default(),
+            rules: ComponentRules::default(),
+            guard: None,
         }
     }
 
     fn add_part(mut self, prefix: &'static str, part: Cow<'a, str>) -> Result<Self, ArnError> {
         match prefix {
             p if p == Domain::prefix() => {
-                self.domain = Some(Domain::new(part)?);
+                let domain = Domain::new(self.rules.fit_component(ComponentKind::Domain, part)?)?;
+                self.validate(ComponentKind::Domain, domain.as_str())?;
+                self.domain = Some(domain);
             }
             "" => {
                 if self.domain.is_some() && self.category.is_none() {
-                    self.category = Some(Category::new(part));
+                    let category =
+                        Category::new(self.rules.fit_component(ComponentKind::Category, part)?);
+                    self.validate(ComponentKind::Category, category.as_str())?;
+                    self.category = Some(category);
                 } else if self.category.is_some() && self.account.is_none() {
-                    self.account = Some(Account::new(part));
+                    let account =
+                        Account::new(self.rules.fit_component(ComponentKind::Account, part)?);
+                    self.validate(ComponentKind::Account, account.as_str())?;
+                    self.account = Some(account);
                 } else if self.account.is_some() && self.root.is_none() {
-                    self.root = Some(Root::new(part)?);
+                    let root = match &self.id_generator {
+                        None if self.root_config == RootConfig::default() => Root::new(part)?,
+                        generator => {
+                            let scope = IdScope {
+                                domain: self.domain.as_ref().map_or("", |d| d.as_str()),
+                                category: self.category.as_ref().map_or("", |c| c.as_str()),
+                                account: self.account.as_ref().map_or("", |a| a.as_str()),
+                                name: &part,
+                            };
+                            Root::generate_with_config(
+                                &scope,
+                                generator.as_deref().unwrap_or(&SystemClock),
+                                &self.root_config,
+                            )?
+                        }
+                    };
+                    let root = Root(self.rules.fit(ComponentKind::Root, root.into())?.into());
+                    self.validate(ComponentKind::Root, root.as_str())?;
+                    self.root = Some(root);
                 } else {
                     // add the first part
-                    self.parts = self.parts.add_part(Part::new(part)?);
+                    let part = Part::new(self.rules.fit(ComponentKind::Part, part)?)?;
+                    self.validate(ComponentKind::Part, part.as_str())?;
+                    self.parts = self.parts.add_part(part);
                 }
             }
             ":" => {
-                self.parts = self.parts.add_part(Part::new(part)?);
+                let part = Part::new(self.rules.fit(ComponentKind::Part, part)?)?;
+                self.validate(ComponentKind::Part, part.as_str())?;
+                self.parts = self.parts.add_part(part);
             }
             _ => return Err(ArnError::InvalidPrefix(prefix.to_string())),
         }
         Ok(self)
     }
 
+    /// Restores a root saved by [`ArnBuilder::into_parts`] as it was, without minting a new id.
+    fn restore_root(mut self, root: String) -> Result<Self, ArnError> {
+        let root = Root::named(self.rules.fit(ComponentKind::Root, root.into())?)?;
+        self.validate(ComponentKind::Root, root.as_str())?;
+        self.root = Some(root);
+        Ok(self)
+    }
+
+    /// Adds the saved components in order, checking each one as it is added.
+    fn resume(mut self, saved: ArnBuilderParts) -> Result<Self, ArnError> {
+        let mut missing = None;
+        let components = [
+            ("domain", saved.domain),
+            ("category", saved.category),
+            ("account", saved.account),
+            ("root", saved.root),
+        ];
+        for (name, value) in components {
+            match (value, missing) {
+                (None, _) => {
+                    missing.get_or_insert(name);
+                }
+                (Some(_), Some(missing)) => return Err(ArnError::MissingPart(missing.to_string())),
+                (Some(root), None) if name == "root" => self = self.restore_root(root)?,
+                (Some(value), None) if name == "domain" => {
+                    self = self.add_part(Domain::prefix(), value.into())?
+                }
+                (Some(value), None) => self = self.add_part("", value.into())?,
+            }
+        }
+        if let (Some(missing), false) = (missing, saved.parts.is_empty()) {
+            return Err(ArnError::MissingPart(missing.to_string()));
+        }
+        for part in saved.parts {
+            self = self.add_part(Parts::prefix(), part.into())?;
+        }
+        Ok(self)
+    }
+
+    /// Wraps the builder in the typed state its components have reached.
+    fn into_state<State>(self) -> ArnBuilder<'a, State> {
+        ArnBuilder {
+            builder: self,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Runs the registered validators against a freshly constructed component.
+    fn validate(&self, kind: ComponentKind, value: &str) -> Result<(), ArnError> {
+        run_validators(&self.validators, kind, value)
+    }
+
     /// Finalizes and builds the Arn.
     fn build(self) -> Result<Arn<'a>, ArnError> {
         let domain = self
@@ -113,7 +442,12 @@ impl<'a> PrivateArnBuilder<'a> {
             .ok_or(ArnError::MissingPart("account".to_string()))?;
         let root = self.root.ok_or(ArnError::MissingPart("root".to_string()))?;
 
-        Ok(Arn::new(domain, category, account, root, self.parts))
+        let arn = Arn::new(domain, category, account, root, self.parts);
+        self.rules.limits.check_len(&arn)?;
+        if let Some(guard) = &self.guard {
+            guard.claim(&arn)?;
+        }
+        Ok(arn)
     }
 }
 
@@ -188,4 +522,153 @@ mod tests {
             .starts_with("arn:custom:service:account123:resource"));
         Ok(())
     }
+
+    #[test]
+    fn test_arn_builder_with_validator() -> anyhow::Result<()> {
+        let twelve_digits = |kind: ComponentKind, value: &str| match kind {
+            ComponentKind::Account
+                if value.len() != 12 || !value.chars().all(|c| c.is_ascii_digit()) =>
+            {
+                Err("account must be a 12-digit number".to_string())
+            }
+            _ => Ok(()),
+        };
+
+        let arn = ArnBuilder::new()
+            .with_validator(twelve_digits)
+            .with::<Domain>("akton")?
+            .with::<Category>("hr")?
+            .with::<Account>("123456789012")?
+            .with::<Root>("root")?
+            .build();
+        assert!(arn.is_ok());
+
+        let result = ArnBuilder::new()
+            .with_validator(twelve_digits)
+            .with::<Domain>("akton")?
+            .with::<Category>("hr")?
+            .with::<Account>("company123");
+        assert_eq!(
+            result.err(),
+            Some(ArnError::ValidationFailure(
+                ComponentKind::Account,
+                "account must be a 12-digit number".to_string()
+            ))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_arn_builder_with_root_config() -> anyhow::Result<()> {
+        let arn = ArnBuilder::new()
+            .with_root_config(RootConfig::new().with_separator('-')?)
+            .with::<Domain>("akton")?
+            .with::<Category>("hr")?
+            .with::<Account>("acme")?
+            .with::<Root>("payroll")?
+            .build()?;
+        let (name, id) = arn.root.as_str().split_once('-').unwrap();
+        assert_eq!((name, id.len()), ("payroll", 26));
+        Ok(())
+    }
+
+    #[test]
+    fn test_arn_builder_resumes_from_parts() -> anyhow::Result<()> {
+        let builder = ArnBuilder::new()
+            .with::<Domain>("akton")?
+            .with::<Category>("hr")?
+            .with::<Account>("acme")?
+            .with::<Root>("payroll")?;
+        let root = builder.peek_root().unwrap().clone();
+        assert_eq!(builder.peek_domain().unwrap().as_str(), "akton");
+        let saved = builder.with::<Part>("2024")?.into_parts();
+        assert_eq!(saved.root.as_deref(), Some(root.as_str()));
+
+        let ResumedArnBuilder::Parts(resumed) = ArnBuilder::new().resume(saved.clone())? else {
+            panic!("expected the parts state");
+        };
+        let arn = resumed.with::<Parts>("q1")?.build()?;
+        assert_eq!(arn.root, root);
+        assert_eq!(arn.parts.to_string(), "2024/q1");
+        assert!(ArnBuilder::new().resume(saved.clone())?.build().is_ok());
+
+        let early = ArnBuilderParts {
+            domain: Some("akton".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            ArnBuilder::new().resume(early.clone())?,
+            ResumedArnBuilder::Category(_)
+        ));
+        assert_eq!(
+            ArnBuilder::new().resume(early)?.build().err(),
+            Some(ArnError::MissingPart("category".to_string()))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_arn_builder_rechecks_resumed_parts() {
+        let gap = ArnBuilderParts {
+            domain: Some("akton".to_string()),
+            account: Some("acme".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            ArnBuilder::new().resume(gap).err(),
+            Some(ArnError::MissingPart("category".to_string()))
+        );
+
+        let saved = ArnBuilderParts {
+            domain: Some("akton".to_string()),
+            category: Some("hr".to_string()),
+            account: Some("company123".to_string()),
+            ..Default::default()
+        };
+        let digits_only = |kind: ComponentKind, value: &str| match kind {
+            ComponentKind::Account if !value.chars().all(|c| c.is_ascii_digit()) => {
+                Err("account must be numeric".to_string())
+            }
+            _ => Ok(()),
+        };
+        assert!(matches!(
+            ArnBuilder::new().with_validator(digits_only).resume(saved),
+            Err(ArnError::ValidationFailure(ComponentKind::Account, _))
+        ));
+    }
+
+    #[test]
+    fn test_arn_builder_rejects_separators_the_string_form_cannot_hold() -> anyhow::Result<()> {
+        let domain = || ArnBuilder::new().with::<Domain>("akton");
+        assert!(matches!(
+            domain()?.with::<Category>("hr:payroll"),
+            Err(ArnError::ParseFailure("Category", _))
+        ));
+        assert!(matches!(
+            domain()?
+                .with::<Category>("hr")?
+                .with::<Account>("acme:root"),
+            Err(ArnError::ParseFailure("Account", _))
+        ));
+        assert!(ArnBuilder::new().with::<Domain>("akton:hr").is_err());
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_arn_builder_parts_serde_round_trip() -> anyhow::Result<()> {
+        let saved = ArnBuilder::new()
+            .with::<Domain>("akton")?
+            .with::<Category>("hr")?
+            .into_parts();
+        let json = serde_json::to_string(&saved)?;
+        assert_eq!(
+            json,
+            r#"{"domain":"akton","category":"hr","account":null,"root":null,"parts":[]}"#
+        );
+        let restored: ArnBuilderParts =
+            serde_json::from_str(r#"{"domain":"akton","category":"hr"}"#)?;
+        assert_eq!(restored, saved);
+        Ok(())
+    }
 }
@@ -0,0 +1,203 @@
+//! Column-oriented construction of many Arns at once.
+//!
+//! [`build_columns`] takes parallel columns, as read from Arrow or Parquet, and materializes
+//! one Arn per row. Components borrow from the columns instead of being copied, each distinct
+//! value is validated once no matter how many rows repeat it, and every invalid row is
+//! reported together instead of stopping at the first.
+
+use crate::errors::ArnError;
+use crate::model::{Account, Arn, Category, ComponentKind, Domain, Part, Parts, Root};
+use std::collections::HashSet;
+
+/// A row that could not be turned into an Arn.
+#[derive(Debug, PartialEq)]
+pub struct RowFailure {
+    /// Index of the row in the input columns.
+    pub row: usize,
+    /// Why the row was rejected.
+    pub error: ArnError,
+}
+
+/// Error returned by [`build_columns`].
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum BulkError {
+    /// A column's length differs from the domain column's.
+    #[error("{column} column has {found} rows, expected {expected}")]
+    LengthMismatch {
+        /// The column with the wrong length.
+        column: ComponentKind,
+        /// Number of rows in the domain column.
+        expected: usize,
+        /// Number of rows in the offending column.
+        found: usize,
+    },
+    /// Some rows were invalid; they are listed in row order.
+    #[error("{} rows are invalid", .0.len())]
+    InvalidRows(Vec<RowFailure>),
+}
+
+/// Builds one Arn per row from parallel columns.
+///
+/// `parts` holds each row's parts joined with `/`, with an empty string for an Arn without
+/// parts. Roots are taken verbatim, as [`ArnParser`](crate::ArnParser) does. The result
+/// borrows from the columns.
+///
+/// ```
+/// use akton_arn::bulk::build_columns;
+///
+/// let arns = build_columns(
+///     &["akton", "akton"],
+///     &["hr", "hr"],
+///     &["acme", "acme"],
+///     &["root", "root"],
+///     &["team/a", ""],
+/// )
+/// .unwrap();
+/// assert_eq!(arns[0].to_string(), "arn:akton:hr:acme:root/team/a");
+/// assert_eq!(arns[1].to_string(), "arn:akton:hr:acme:root");
+/// ```
+pub fn build_columns<'a>(
+    domains: &[&'a str],
+    categories: &[&'a str],
+    accounts: &[&'a str],
+    roots: &[&'a str],
+    parts: &[&'a str],
+) -> Result<Vec<Arn<'a>>, BulkError> {
+    let expected = domains.len();
+    for (column, found) in [
+        (ComponentKind::Category, categories.len()),
+        (ComponentKind::Account, accounts.len()),
+        (ComponentKind::Root, roots.len()),
+        (ComponentKind::Part, parts.len()),
+    ] {
+        if found != expected {
+            return Err(BulkError::LengthMismatch {
+                column,
+                expected,
+                found,
+            });
+        }
+    }
+
+    // Values known to be valid; invalid ones are rare and simply re-validated per row so each
+    // failure carries its own error.
+    let mut valid_domains: HashSet<&str> = HashSet::new();
+    let mut valid_parts: HashSet<&str> = HashSet::new();
+    let mut arns = Vec::with_capacity(expected);
+    let mut failures = Vec::new();
+
+    for row in 0..expected {
+        match build_row(
+            [domains[row], categories[row], accounts[row], roots[row]],
+            parts[row],
+            &mut valid_domains,
+            &mut valid_parts,
+        ) {
+            Ok(arn) => arns.push(arn),
+            Err(error) => failures.push(RowFailure { row, error }),
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(arns)
+    } else {
+        Err(BulkError::InvalidRows(failures))
+    }
+}
+
+fn build_row<'a>(
+    [domain, category, account, root]: [&'a str; 4],
+    parts: &'a str,
+    valid_domains: &mut HashSet<&'a str>,
+    valid_parts: &mut HashSet<&'a str>,
+) -> Result<Arn<'a>, ArnError> {
+    let domain = if valid_domains.contains(domain) {
+        Domain(domain.into())
+    } else {
+        let checked = Domain::new(domain)?;
+        valid_domains.insert(domain);
+        checked
+    };
+    let parts = if parts.is_empty() {
+        Vec::new()
+    } else {
+        parts
+            .split('/')
+            .map(|part| {
+                if valid_parts.contains(part) {
+                    Ok(Part::from_shared(part.into()))
+                } else {
+                    let checked = Part::new(part)?;
+                    valid_parts.insert(part);
+                    Ok(checked)
+                }
+            })
+            .collect::<Result<_, ArnError>>()?
+    };
+    Ok(Arn::new(
+        domain,
+        Category::new(category),
+        Account::new(account),
+        Root(root.into()),
+        Parts::new(parts),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::SharedStr;
+    use crate::ArnParser;
+    use std::borrow::Cow;
+
+    #[test]
+    fn test_columns_match_parsed_arns() {
+        let domains = ["akton"; 3];
+        let arns = build_columns(
+            &domains,
+            &["hr", "hr", "it"],
+            &["acme", "acme", "globex"],
+            &["root", "root", "base"],
+            &["team/a", "team/b", ""],
+        )
+        .unwrap();
+        for arn in &arns {
+            assert_eq!(ArnParser::new(arn.to_string()).parse().unwrap(), *arn);
+        }
+        assert_eq!(arns[2].to_string(), "arn:akton:it:globex:base");
+        assert!(matches!(
+            arns[0].domain.0,
+            SharedStr::Borrowed(Cow::Borrowed(_))
+        ));
+    }
+
+    #[test]
+    fn test_every_invalid_row_is_reported() {
+        let result = build_columns(
+            &["akton", "", "akton", "akton"],
+            &["hr"; 4],
+            &["acme"; 4],
+            &["root"; 4],
+            &["ok", "ok", "bad//part", "ok"],
+        );
+        let Err(BulkError::InvalidRows(failures)) = result else {
+            panic!("expected invalid rows, got {result:?}");
+        };
+        assert_eq!(
+            failures.iter().map(|f| f.row).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+    }
+
+    #[test]
+    fn test_length_mismatch() {
+        assert_eq!(
+            build_columns(&["a", "b"], &["c", "c"], &["d"], &["r", "r"], &["", ""]),
+            Err(BulkError::LengthMismatch {
+                column: ComponentKind::Account,
+                expected: 2,
+                found: 1
+            })
+        );
+    }
+}
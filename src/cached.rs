@@ -0,0 +1,147 @@
+use crate::model::Arn;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::sync::{Arc, OnceLock};
+
+/// An Arn that renders its canonical string once and reuses it.
+///
+/// Formatting an Arn walks every component, which shows up when the same Arn is logged or
+/// sent thousands of times a second. A `CachedArn` renders on first use and then hands out
+/// the stored string; clones share it. It dereferences to the [`Arn`], but only immutably:
+/// the Arn's in-place mutators, such as [`Arn::push_part`], know nothing of the stored string,
+/// so the Arn can only be changed through [`CachedArn::modify`], which drops it.
+///
+/// ```
+/// use akton_arn::{ArnParser, CachedArn};
+///
+/// let mut arn = CachedArn::new(ArnParser::new("arn:akton:hr:acme:root").parse().unwrap());
+/// assert_eq!(arn.as_str(), "arn:akton:hr:acme:root");
+/// arn.modify(|arn| arn.push_part("teams").unwrap());
+/// assert_eq!(arn.to_string(), "arn:akton:hr:acme:root/teams");
+/// ```
+#[derive(Clone)]
+pub struct CachedArn<'a> {
+    arn: Arn<'a>,
+    rendered: OnceLock<Arc<str>>,
+}
+
+impl<'a> CachedArn<'a> {
+    /// Wraps `arn`, rendering it on first use.
+    pub fn new(arn: Arn<'a>) -> Self {
+        CachedArn {
+            arn,
+            rendered: OnceLock::new(),
+        }
+    }
+
+    /// Returns the canonical string, rendering it if this is the first use.
+    pub fn as_str(&self) -> &str {
+        self.rendered()
+    }
+
+    /// Returns the canonical string as a shared `Arc<str>`, without copying it.
+    pub fn to_arc_str(&self) -> Arc<str> {
+        self.rendered().clone()
+    }
+
+    /// Changes the Arn through `f`, rendering it again on next use.
+    pub fn modify<R>(&mut self, f: impl FnOnce(&mut Arn<'a>) -> R) -> R {
+        self.rendered = OnceLock::new();
+        f(&mut self.arn)
+    }
+
+    /// Returns the wrapped Arn.
+    pub fn into_inner(self) -> Arn<'a> {
+        self.arn
+    }
+
+    fn rendered(&self) -> &Arc<str> {
+        self.rendered.get_or_init(|| self.arn.to_string().into())
+    }
+}
+
+impl<'a> From<Arn<'a>> for CachedArn<'a> {
+    fn from(arn: Arn<'a>) -> Self {
+        CachedArn::new(arn)
+    }
+}
+
+impl<'a> Deref for CachedArn<'a> {
+    type Target = Arn<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.arn
+    }
+}
+
+impl AsRef<str> for CachedArn<'_> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for CachedArn<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for CachedArn<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CachedArn").field(&self.arn).finish()
+    }
+}
+
+impl PartialEq for CachedArn<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.arn == other.arn
+    }
+}
+
+impl Eq for CachedArn<'_> {}
+
+impl Hash for CachedArn<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.arn.hash(state)
+    }
+}
+
+impl PartialOrd for CachedArn<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for CachedArn<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.arn.cmp(&other.arn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArnParser;
+
+    #[test]
+    fn test_rendering_is_shared_until_modified() {
+        let arn = CachedArn::new(ArnParser::new("arn:akton:hr:acme:root").parse().unwrap());
+        let first = arn.to_arc_str();
+        let clone = arn.clone();
+        assert!(Arc::ptr_eq(&first, &clone.to_arc_str()));
+        assert_eq!(format!("{arn}"), "arn:akton:hr:acme:root");
+
+        let mut modified = clone;
+        let version = modified.modify(|arn| {
+            *arn = arn.clone().with_version(2);
+            arn.version()
+        });
+        assert_eq!(version, Some(2));
+        assert_eq!(modified.as_str(), "arn:akton:hr:acme:root@v2");
+        assert_eq!(&*first, "arn:akton:hr:acme:root");
+        assert_ne!(modified, arn);
+        assert_eq!(modified.into_inner().version(), Some(2));
+    }
+}
@@ -0,0 +1,273 @@
+//! Capability tokens, enabled with the `signing` feature.
+
+use crate::errors::ArnError;
+use crate::model::Arn;
+use crate::parser::ArnParser;
+use crate::short_id::{decode, encode};
+use crate::time_source::{SystemClock, TimeSource};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Keeps capability MACs distinct from [`SignedArn`](crate::SignedArn) MACs made with the
+/// same key.
+const MAC_CONTEXT: &[u8] = b"akton-arn capability\0";
+
+/// A signed grant of permissions over an Arn and everything below it, until an expiry time.
+///
+/// A service holding the key mints a capability and hands its string form to a downstream
+/// service, which checks it with the same key through [`Capability::parse`]. The token reads
+/// `<arn>:<permission>,<permission>:<expiry>:<signature>`, with the expiry in Unix seconds
+/// and the HMAC-SHA256 signature Crockford base32 encoded. A `Capability` is only ever
+/// minted or parsed with a matching signature, so [`Capability::authorizes`] can trust it.
+///
+/// ```
+/// use akton_arn::{ArnParser, Capability};
+/// use std::time::{Duration, SystemTime};
+///
+/// let key = b"capability-key";
+/// let teams = ArnParser::new("arn:akton:hr:acme:root/teams").parse().unwrap();
+/// let expiry = SystemTime::now() + Duration::from_secs(300);
+/// let token = Capability::mint(&teams, ["read"], expiry, key).unwrap().to_string();
+///
+/// let capability = Capability::parse(&token, key).unwrap();
+/// let eng = ArnParser::new("arn:akton:hr:acme:root/teams/eng").parse().unwrap();
+/// assert!(capability.authorizes(&eng, "read"));
+/// assert!(!capability.authorizes(&eng, "write"));
+/// assert!(!capability.authorizes(&eng.parent().unwrap().parent().unwrap(), "read"));
+/// assert!(Capability::parse(&token, b"other-key").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capability {
+    root: Arn<'static>,
+    permissions: Vec<String>,
+    expires_at: u64,
+    signature: [u8; 32],
+}
+
+impl Capability {
+    /// Grants `permissions` over `root` and its descendants until `expiry`, signed with `key`.
+    ///
+    /// The expiry is kept to the second, rounded down. Fails if a permission is empty or
+    /// contains `,` or `:`, which would make the token ambiguous.
+    pub fn mint<P: Into<String>>(
+        root: &Arn,
+        permissions: impl IntoIterator<Item = P>,
+        expiry: SystemTime,
+        key: &[u8],
+    ) -> Result<Capability, ArnError> {
+        let mut permissions = permissions
+            .into_iter()
+            .map(|permission| check_permission(permission.into()))
+            .collect::<Result<Vec<_>, _>>()?;
+        permissions.sort();
+        permissions.dedup();
+        let root = root.clone().into_owned();
+        let expires_at = expiry
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let signature = mac(&root, &permissions, expires_at, key)
+            .finalize()
+            .into_bytes()
+            .into();
+        Ok(Capability {
+            root,
+            permissions,
+            expires_at,
+            signature,
+        })
+    }
+
+    /// Reads a token written by `Display`, failing with [`ArnError::InvalidSignature`] unless
+    /// it was signed with `key`.
+    ///
+    /// An expired capability still parses; [`Capability::authorizes`] rejects it.
+    pub fn parse(token: &str, key: &[u8]) -> Result<Capability, ArnError> {
+        let mut fields = token.rsplitn(4, ':');
+        let (Some(signature), Some(expires_at), Some(permissions), Some(root)) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            return Err(ArnError::InvalidFormat);
+        };
+        let signature: [u8; 32] = decode(signature)?.try_into().map_err(|bytes: Vec<u8>| {
+            ArnError::DecodeFailure(format!("signature is {} bytes, expected 32", bytes.len()))
+        })?;
+        let expires_at = expires_at
+            .parse()
+            .map_err(|_| ArnError::DecodeFailure(format!("invalid expiry {expires_at:?}")))?;
+        let permissions: Vec<String> = match permissions {
+            "" => Vec::new(),
+            permissions => permissions.split(',').map(str::to_owned).collect(),
+        };
+        let root = ArnParser::new(root.to_string()).parse()?;
+        mac(&root, &permissions, expires_at, key)
+            .verify_slice(&signature)
+            .map_err(|_| ArnError::InvalidSignature)?;
+        Ok(Capability {
+            root,
+            permissions,
+            expires_at,
+            signature,
+        })
+    }
+
+    /// Returns `true` if the capability grants `permission` over `arn` and has not expired.
+    ///
+    /// `arn` is covered if it is the capability's root or one of its descendants; versions
+    /// and extensions are not compared.
+    pub fn authorizes(&self, arn: &Arn, permission: &str) -> bool {
+        self.authorizes_with_time_source(arn, permission, &SystemClock)
+    }
+
+    /// Like [`Capability::authorizes`], but checks the expiry at the current time of
+    /// `time_source`.
+    pub fn authorizes_with_time_source(
+        &self,
+        arn: &Arn,
+        permission: &str,
+        time_source: &dyn TimeSource,
+    ) -> bool {
+        time_source.now().as_secs() < self.expires_at
+            && self
+                .permissions
+                .binary_search_by(|granted| granted.as_str().cmp(permission))
+                .is_ok()
+            && arn.depth() >= self.root.depth()
+            && arn.truncated(self.root.depth()) == self.root.truncated(self.root.depth())
+    }
+
+    /// Returns the Arn at the top of the granted subtree.
+    pub fn root(&self) -> &Arn<'static> {
+        &self.root
+    }
+
+    /// Returns the granted permissions, sorted.
+    pub fn permissions(&self) -> &[String] {
+        &self.permissions
+    }
+
+    /// Returns when the capability stops authorizing anything.
+    pub fn expires_at(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.expires_at)
+    }
+}
+
+fn check_permission(permission: String) -> Result<String, ArnError> {
+    if permission.is_empty() || permission.contains([',', ':']) {
+        return Err(ArnError::ParseFailure(
+            "Permission",
+            format!("{permission:?} must be non-empty and must not contain ',' or ':'"),
+        ));
+    }
+    Ok(permission)
+}
+
+fn mac(root: &Arn, permissions: &[String], expires_at: u64, key: &[u8]) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(MAC_CONTEXT);
+    mac.update(&root.to_bytes());
+    mac.update(&expires_at.to_be_bytes());
+    mac.update(permissions.join(",").as_bytes());
+    mac
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}:{}",
+            self.root,
+            self.permissions.join(","),
+            self.expires_at,
+            encode(&self.signature)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SimulatedClock;
+
+    const KEY: &[u8] = b"test-capability-key";
+
+    fn arn(s: &str) -> Arn<'static> {
+        ArnParser::new(s).parse().unwrap().into_owned()
+    }
+
+    fn mint(permissions: &[&str]) -> Capability {
+        Capability::mint(
+            &arn("arn:akton:hr:acme:root/teams@v2"),
+            permissions.iter().copied(),
+            UNIX_EPOCH + Duration::from_secs(1_000),
+            KEY,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_token_round_trip() {
+        let capability = mint(&["write", "read", "write"]);
+        assert_eq!(capability.permissions(), ["read", "write"]);
+        let token = capability.to_string();
+        assert!(token.starts_with("arn:akton:hr:acme:root/teams@v2:read,write:1000:"));
+        assert_eq!(Capability::parse(&token, KEY).unwrap(), capability);
+        assert_eq!(
+            capability.expires_at(),
+            UNIX_EPOCH + Duration::from_secs(1_000)
+        );
+    }
+
+    #[test]
+    fn test_authorizes_subtree_until_expiry() {
+        let capability = mint(&["read"]);
+        let clock = SimulatedClock::new(Duration::from_secs(999));
+        let check = |s: &str, permission: &str| {
+            capability.authorizes_with_time_source(&arn(s), permission, &clock)
+        };
+        assert!(check("arn:akton:hr:acme:root/teams", "read"));
+        assert!(check("arn:akton:hr:acme:root/teams/eng/backend", "read"));
+        assert!(!check("arn:akton:hr:acme:root/teams/eng", "write"));
+        assert!(!check("arn:akton:hr:acme:root/teamsx", "read"));
+        assert!(!check("arn:akton:hr:acme:root", "read"));
+        assert!(!check("arn:akton:hr:beta:root/teams", "read"));
+        clock.advance(Duration::from_secs(1));
+        assert!(!check("arn:akton:hr:acme:root/teams", "read"));
+    }
+
+    #[test]
+    fn test_tampered_tokens_are_rejected() {
+        let token = mint(&["read"]).to_string();
+        for tampered in [
+            token.replacen("acme", "beta", 1),
+            token.replacen(":read:", ":read,write:", 1),
+            token.replacen(":1000:", ":9000:", 1),
+        ] {
+            assert_eq!(
+                Capability::parse(&tampered, KEY),
+                Err(ArnError::InvalidSignature),
+                "{tampered}"
+            );
+        }
+        assert_eq!(
+            Capability::parse(&token, b"other-key"),
+            Err(ArnError::InvalidSignature)
+        );
+        assert!(Capability::parse("read:1000:ABC", KEY).is_err());
+
+        let none = mint(&[]);
+        assert_eq!(Capability::parse(&none.to_string(), KEY).unwrap(), none);
+    }
+
+    #[test]
+    fn test_invalid_permissions_are_rejected() {
+        let root = arn("arn:akton:hr:acme:root");
+        for permission in ["", "a,b", "a:b"] {
+            assert!(Capability::mint(&root, [permission], UNIX_EPOCH, KEY).is_err());
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use crate::model::{Account, Arn, Category, Domain, Part, Root};
+use crate::ComponentKind;
+use std::borrow::Cow;
+
+/// Which components are compared and stored without regard to case.
+///
+/// Names typed by people arrive as `HR`, `Hr` and `hr`. Folding lowercases the chosen
+/// components, so all three become the same Arn: set a `CaseFold` on
+/// [`ArnParser`](crate::ArnParser) or [`ArnBuilder`](crate::ArnBuilder) to fold while reading,
+/// call [`Arn::fold_case`] on Arns you already hold, or set one on an
+/// [`ArnPattern`](crate::ArnPattern) to match without folding. Components left unfolded, such
+/// as parts naming case-sensitive resources, keep their case.
+///
+/// ```
+/// use akton_arn::{ArnParser, CaseFold, ComponentKind};
+///
+/// let fold = CaseFold::none()
+///     .fold(ComponentKind::Domain)
+///     .fold(ComponentKind::Category);
+/// let arn = ArnParser::new("arn:Akton:HR:acme:root/ReadMe").with_case_fold(fold).parse().unwrap();
+/// assert_eq!(arn.to_string(), "arn:akton:hr:acme:root/ReadMe");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct CaseFold {
+    /// Whether the domain, category, account, root and parts are folded, in that order.
+    folded: [bool; 5],
+}
+
+impl CaseFold {
+    /// Folds nothing, the default.
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    /// Folds every component.
+    pub fn all() -> Self {
+        CaseFold { folded: [true; 5] }
+    }
+
+    /// Also folds `kind`.
+    pub fn fold(mut self, kind: ComponentKind) -> Self {
+        self.folded[index(kind)] = true;
+        self
+    }
+
+    /// Keeps the case of `kind`.
+    pub fn preserve(mut self, kind: ComponentKind) -> Self {
+        self.folded[index(kind)] = false;
+        self
+    }
+
+    /// Returns `true` if `kind` is folded.
+    pub fn folds(&self, kind: ComponentKind) -> bool {
+        self.folded[index(kind)]
+    }
+
+    /// Returns `value` lowercased if `kind` is folded, borrowing it when nothing changes.
+    pub(crate) fn apply<'v>(&self, kind: ComponentKind, value: Cow<'v, str>) -> Cow<'v, str> {
+        match self.fold_str(kind, &value) {
+            Some(folded) => Cow::Owned(folded),
+            None => value,
+        }
+    }
+
+    /// Returns `true` if `a` and `b` are equal once `kind` is folded.
+    pub(crate) fn same(&self, kind: ComponentKind, a: &str, b: &str) -> bool {
+        if self.folds(kind) {
+            eq_ignore_case(a, b)
+        } else {
+            a == b
+        }
+    }
+
+    /// Returns the folded form of `value`, or `None` if folding leaves it unchanged.
+    ///
+    /// Folds char by char like [`eq_ignore_case`] and the pattern matchers, rather than with
+    /// `str::to_lowercase`, whose context-dependent rules (a final `Σ` becomes `ς`) would store
+    /// text that those comparisons never produce.
+    fn fold_str(&self, kind: ComponentKind, value: &str) -> Option<String> {
+        let changes = |c: char| c.to_lowercase().ne(std::iter::once(c));
+        (self.folds(kind) && value.chars().any(changes)).then(|| lowercase(value))
+    }
+}
+
+fn index(kind: ComponentKind) -> usize {
+    match kind {
+        ComponentKind::Domain => 0,
+        ComponentKind::Category => 1,
+        ComponentKind::Account => 2,
+        ComponentKind::Root => 3,
+        ComponentKind::Part => 4,
+    }
+}
+
+/// Lowercases `value` char by char, as [`CaseFold`] compares folded components.
+pub(crate) fn lowercase(value: &str) -> String {
+    value.chars().flat_map(char::to_lowercase).collect()
+}
+
+pub(crate) fn eq_ignore_case(a: &str, b: &str) -> bool {
+    a == b
+        || a.chars()
+            .flat_map(char::to_lowercase)
+            .eq(b.chars().flat_map(char::to_lowercase))
+}
+
+impl<'a> Arn<'a> {
+    /// Returns `true` if the Arns are equal ignoring the case of every component.
+    ///
+    /// The version and extensions must still match exactly.
+    pub fn eq_ignore_case(&self, other: &Arn) -> bool {
+        eq_ignore_case(self.domain.as_str(), other.domain.as_str())
+            && eq_ignore_case(self.category.as_str(), other.category.as_str())
+            && eq_ignore_case(self.account.as_str(), other.account.as_str())
+            && eq_ignore_case(self.root.as_str(), other.root.as_str())
+            && self.parts.0.len() == other.parts.0.len()
+            && self
+                .parts
+                .0
+                .iter()
+                .zip(other.parts.0.iter())
+                .all(|(a, b)| eq_ignore_case(a.as_str(), b.as_str()))
+            && self.version == other.version
+            && self.extensions == other.extensions
+    }
+
+    /// Lowercases the components `fold` selects, so differently cased input compares equal.
+    ///
+    /// Components that are already lowercase keep sharing their text.
+    pub fn fold_case(mut self, fold: &CaseFold) -> Arn<'a> {
+        if let Some(domain) = fold.fold_str(ComponentKind::Domain, self.domain.as_str()) {
+            self.domain = Domain(domain.into());
+        }
+        if let Some(category) = fold.fold_str(ComponentKind::Category, self.category.as_str()) {
+            self.category = Category(category.into());
+        }
+        if let Some(account) = fold.fold_str(ComponentKind::Account, self.account.as_str()) {
+            self.account = Account(account.into());
+        }
+        if let Some(root) = fold.fold_str(ComponentKind::Root, self.root.as_str()) {
+            self.root = Root(root.into());
+        }
+        for part in self.parts.0.iter_mut() {
+            if let Some(folded) = fold.fold_str(ComponentKind::Part, part.as_str()) {
+                *part = Part::from_shared(folded.into());
+            }
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ArnBuilder, ArnParser, ArnPattern};
+
+    fn arn(s: &str) -> Arn<'_> {
+        ArnParser::new(s).parse().unwrap()
+    }
+
+    #[test]
+    fn test_eq_ignore_case() {
+        let a = arn("arn:Akton:HR:Acme:Root/Teams/\u{c9}quipe");
+        let b = arn("arn:akton:hr:acme:root/teams/\u{e9}quipe");
+        assert_ne!(a, b);
+        assert!(a.eq_ignore_case(&b));
+        assert!(!a.eq_ignore_case(&arn("arn:akton:hr:acme:root/teams")));
+        assert!(!a.eq_ignore_case(&b.clone().with_version(1)));
+    }
+
+    #[test]
+    fn test_fold_case_per_component() {
+        let fold = CaseFold::all().preserve(ComponentKind::Part);
+        let folded = arn("arn:Akton:HR:Acme:Root/ReadMe").fold_case(&fold);
+        assert_eq!(folded.to_string(), "arn:akton:hr:acme:root/ReadMe");
+        assert_eq!(
+            arn("arn:a:b:c:d/E")
+                .fold_case(&CaseFold::none())
+                .to_string(),
+            "arn:a:b:c:d/E"
+        );
+
+        let built = ArnBuilder::new()
+            .with_case_fold(CaseFold::none().fold(ComponentKind::Account))
+            .with::<Domain>("Akton")
+            .unwrap()
+            .with::<Category>("hr")
+            .unwrap()
+            .with::<Account>("ACME")
+            .unwrap()
+            .with::<Root>("root")
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(
+            (built.domain.as_str(), built.account.as_str()),
+            ("Akton", "acme")
+        );
+    }
+
+    #[test]
+    fn test_folding_agrees_with_comparisons() {
+        let folded =
+            arn("arn:akton:hr:acme:root/\u{39f}\u{394}\u{39f}\u{3a3}").fold_case(&CaseFold::all());
+        assert_eq!(folded.parts.0[0].as_str(), "\u{3bf}\u{3b4}\u{3bf}\u{3c3}");
+        let pattern: ArnPattern = "arn:akton:hr:acme:root/\u{3bf}\u{3b4}\u{3bf}\u{3c3}"
+            .parse()
+            .unwrap();
+        assert!(pattern.matches(&folded));
+        assert!(pattern
+            .with_case_fold(CaseFold::all())
+            .matches(&arn("arn:akton:hr:acme:root/\u{39f}\u{394}\u{39f}\u{3a3}")));
+    }
+
+    #[test]
+    fn test_patterns_honor_case_fold() {
+        let pattern: ArnPattern = "arn:akton:hr:*:*/teams/**".parse().unwrap();
+        let shouted = arn("arn:AKTON:HR:acme:root/teams/a");
+        assert!(!pattern.matches(&shouted));
+        let folding = pattern.clone().with_case_fold(
+            CaseFold::none()
+                .fold(ComponentKind::Domain)
+                .fold(ComponentKind::Category),
+        );
+        assert!(folding.matches(&shouted));
+        assert!(!folding.matches(&arn("arn:AKTON:HR:acme:root/TEAMS/a")));
+        let everything = pattern.with_case_fold(CaseFold::all());
+        assert!(everything.matches(&arn("arn:AKTON:HR:acme:root/TEAMS/a")));
+    }
+}
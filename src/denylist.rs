@@ -0,0 +1,175 @@
+use crate::errors::ArnError;
+use crate::model::Root;
+use crate::traits::ArnValidator;
+use crate::ComponentKind;
+use std::collections::HashSet;
+
+/// Names the framework keeps for itself, denied by [`Denylist::reserved`].
+pub const RESERVED_WORDS: &[&str] = &["system", "internal", "reserved", "admin"];
+
+/// A validator rejecting components that are, or contain as a word, a denied name.
+///
+/// Register it with [`ArnParser::with_validator`](crate::ArnParser::with_validator) or
+/// [`ArnBuilder::with_validator`](crate::ArnBuilder::with_validator); a denied component
+/// fails with [`ArnError::ReservedName`] rather than a generic validation failure, so callers
+/// can tell a user the name is taken.
+///
+/// Names are compared without regard to ASCII case, against the whole component and each
+/// word in it, where words are split at `-`, `_`, `.`, `~` and `=`. So `internal` is denied
+/// in `internal`, `Internal-Tools` and `team=internal`, but not in `internals`. A generated
+/// root is checked by its name only. By default only roots and parts are checked, as those
+/// are what users choose.
+///
+/// ```
+/// use akton_arn::{ArnError, ArnParser, ComponentKind, Denylist};
+///
+/// let denylist = Denylist::reserved().deny(["legacy"]);
+/// let parse = |input| ArnParser::new(input).with_validator(denylist.clone()).parse();
+///
+/// assert!(parse("arn:akton:hr:acme:root/teams").is_ok());
+/// assert_eq!(
+///     parse("arn:akton:hr:acme:root/System").unwrap_err(),
+///     ArnError::ReservedName(ComponentKind::Part, "System".to_string())
+/// );
+/// assert!(parse("arn:akton:hr:acme:legacy-root").is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Denylist {
+    /// Denied names, lowercased.
+    words: HashSet<String>,
+    kinds: Vec<ComponentKind>,
+}
+
+impl Denylist {
+    /// Creates a denylist without names, checking roots and parts.
+    pub fn new() -> Self {
+        Denylist {
+            words: HashSet::new(),
+            kinds: vec![ComponentKind::Root, ComponentKind::Part],
+        }
+    }
+
+    /// Creates a denylist of the [`RESERVED_WORDS`].
+    pub fn reserved() -> Self {
+        Self::new().deny(RESERVED_WORDS.iter().copied())
+    }
+
+    /// Adds names to deny, such as a list of profanity.
+    pub fn deny<I>(mut self, words: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        self.words.extend(
+            words
+                .into_iter()
+                .map(|word| word.as_ref().to_ascii_lowercase()),
+        );
+        self
+    }
+
+    /// Checks only components of the given kinds instead of roots and parts.
+    pub fn for_kinds(mut self, kinds: impl IntoIterator<Item = ComponentKind>) -> Self {
+        self.kinds = kinds.into_iter().collect();
+        self
+    }
+
+    /// Returns `true` if `value`, or a word in it, is denied.
+    fn denies(&self, value: &str) -> bool {
+        std::iter::once(value)
+            .chain(value.split(['-', '_', '.', '~', '=']))
+            .any(|word| self.words.contains(&word.to_ascii_lowercase()))
+    }
+}
+
+impl Default for Denylist {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ArnValidator for Denylist {
+    fn validate(&self, kind: ComponentKind, value: &str) -> Result<(), String> {
+        self.check(kind, value).map_err(|error| error.to_string())
+    }
+
+    fn check(&self, kind: ComponentKind, value: &str) -> Result<(), ArnError> {
+        if !self.kinds.contains(&kind) || self.words.is_empty() {
+            return Ok(());
+        }
+        let root;
+        let name = if kind == ComponentKind::Root {
+            root = Root::new_unchecked(value);
+            root.name()
+        } else {
+            value
+        };
+        if self.denies(name) {
+            return Err(ArnError::ReservedName(kind, value.to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Account, ArnBuilder, ArnParser, Category, Domain, Part, Root};
+
+    #[test]
+    fn test_words_and_kinds() {
+        let denylist = Denylist::reserved().deny(["Heck"]);
+        for denied in [
+            "system",
+            "SYSTEM",
+            "internal-tools",
+            "team=admin",
+            "oh.heck",
+        ] {
+            assert!(
+                denylist.check(ComponentKind::Part, denied).is_err(),
+                "{denied}"
+            );
+        }
+        for allowed in ["systems", "subsystem", "teams", "heckle"] {
+            assert!(
+                denylist.check(ComponentKind::Part, allowed).is_ok(),
+                "{allowed}"
+            );
+        }
+        assert!(denylist.check(ComponentKind::Account, "system").is_ok());
+        let accounts = denylist.for_kinds([ComponentKind::Account]);
+        assert!(accounts.check(ComponentKind::Account, "system").is_err());
+        assert!(accounts.check(ComponentKind::Part, "system").is_ok());
+        assert!(Denylist::new().check(ComponentKind::Part, "system").is_ok());
+    }
+
+    #[test]
+    fn test_generated_roots_are_checked_by_name() {
+        let result = ArnBuilder::new()
+            .with_validator(Denylist::reserved())
+            .with::<Domain>("akton")
+            .and_then(|b| b.with::<Category>("hr"))
+            .and_then(|b| b.with::<Account>("acme"))
+            .and_then(|b| b.with::<Root>("system"));
+        assert!(matches!(
+            result,
+            Err(ArnError::ReservedName(ComponentKind::Root, _))
+        ));
+        let arn = ArnBuilder::new()
+            .with_validator(Denylist::reserved())
+            .with::<Domain>("akton")
+            .and_then(|b| b.with::<Category>("hr"))
+            .and_then(|b| b.with::<Account>("acme"))
+            .and_then(|b| b.with::<Root>("teams"))
+            .and_then(|b| b.with::<Part>("admin"));
+        assert!(matches!(
+            arn,
+            Err(ArnError::ReservedName(ComponentKind::Part, _))
+        ));
+        assert!(ArnParser::new("arn:akton:hr:acme:root/ok")
+            .with_validator(Denylist::reserved())
+            .parse()
+            .is_ok());
+    }
+}
@@ -0,0 +1,316 @@
+use crate::errors::ArnError;
+use crate::model::{Account, Arn, Category, Domain, Extensions, Part, Root};
+use crate::ComponentKind;
+use std::fmt;
+
+/// The difference between two Arns, as produced by [`Arn::diff`].
+///
+/// Parts are compared as a path: the parts both Arns share from the start are kept, the
+/// original's remaining parts are removed and the target's remaining parts are appended. This
+/// matches how resources move in a hierarchy and keeps audit entries short.
+///
+/// ```
+/// use akton_arn::ArnParser;
+///
+/// let before = ArnParser::new("arn:akton:hr:acme:root/teams").parse().unwrap();
+/// let after = ArnParser::new("arn:akton:hr:globex:root/teams/a/b").parse().unwrap();
+/// let diff = before.diff(&after);
+/// assert_eq!(
+///     diff.to_string(),
+///     "Account changed from acme to globex, parts appended [a, b]"
+/// );
+/// assert_eq!(diff.apply(&before).unwrap(), after);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ArnDiff {
+    /// Changes to the domain, category, account and root, in that order.
+    pub components: Vec<ComponentChange>,
+    /// Trailing parts of the original that the target does not share.
+    pub parts_removed: Vec<String>,
+    /// Parts of the target following the shared ones.
+    pub parts_appended: Vec<String>,
+    /// The version before and after, if it changed.
+    pub version: Option<(Option<u64>, Option<u64>)>,
+    /// The extensions before and after, if they changed.
+    pub extensions: Option<(Extensions, Extensions)>,
+}
+
+/// A change to one of the single-valued components of an Arn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentChange {
+    pub kind: ComponentKind,
+    pub from: String,
+    pub to: String,
+}
+
+impl Arn<'_> {
+    /// Describes how to turn this Arn into `other`.
+    ///
+    /// Annotations are not compared, since they are not part of an Arn's identity.
+    pub fn diff(&self, other: &Arn) -> ArnDiff {
+        let components = [
+            (
+                ComponentKind::Domain,
+                self.domain.as_str(),
+                other.domain.as_str(),
+            ),
+            (
+                ComponentKind::Category,
+                self.category.as_str(),
+                other.category.as_str(),
+            ),
+            (
+                ComponentKind::Account,
+                self.account.as_str(),
+                other.account.as_str(),
+            ),
+            (ComponentKind::Root, self.root.as_str(), other.root.as_str()),
+        ]
+        .into_iter()
+        .filter(|(_, from, to)| from != to)
+        .map(|(kind, from, to)| ComponentChange {
+            kind,
+            from: from.to_string(),
+            to: to.to_string(),
+        })
+        .collect();
+        let shared = self
+            .parts
+            .0
+            .iter()
+            .zip(&other.parts.0)
+            .take_while(|(a, b)| a == b)
+            .count();
+        let tail = |arn: &Arn| -> Vec<String> {
+            arn.parts.0[shared..]
+                .iter()
+                .map(|part| part.as_str().to_string())
+                .collect()
+        };
+        ArnDiff {
+            components,
+            parts_removed: tail(self),
+            parts_appended: tail(other),
+            version: (self.version != other.version).then_some((self.version, other.version)),
+            extensions: (self.extensions != other.extensions)
+                .then(|| (self.extensions.clone(), other.extensions.clone())),
+        }
+    }
+}
+
+impl ArnDiff {
+    /// Returns `true` if the two Arns were equal.
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+            && self.parts_removed.is_empty()
+            && self.parts_appended.is_empty()
+            && self.version.is_none()
+            && self.extensions.is_none()
+    }
+
+    /// Applies the diff to `arn`, which must match the Arn it was computed from.
+    ///
+    /// Only the components the diff changes are checked: a diff that renames an account
+    /// applies to any Arn under the old account, as long as it ends with the removed parts.
+    /// Fails with [`ArnError::PatchConflict`] if `arn` does not hold a value the diff
+    /// replaces, or with a validation error if a new value is not allowed.
+    pub fn apply(&self, arn: &Arn) -> Result<Arn<'static>, ArnError> {
+        let mut result = arn.clone().into_owned();
+        result.annotations = Default::default();
+        for change in &self.components {
+            let current = match change.kind {
+                ComponentKind::Domain => result.domain.as_str(),
+                ComponentKind::Category => result.category.as_str(),
+                ComponentKind::Account => result.account.as_str(),
+                ComponentKind::Root => result.root.as_str(),
+                ComponentKind::Part => {
+                    return Err(ArnError::PatchConflict(
+                        "parts are changed through parts_removed and parts_appended".to_string(),
+                    ))
+                }
+            };
+            if current != change.from {
+                return Err(conflict(change.kind.as_str(), &change.from, current));
+            }
+            let to = change.to.clone();
+            match change.kind {
+                ComponentKind::Domain => result.domain = Domain::new(to)?,
+                ComponentKind::Category => result.category = Category::new(to),
+                ComponentKind::Account => result.account = Account::new(to),
+                ComponentKind::Root => result.root = Root::named(to)?,
+                ComponentKind::Part => unreachable!("rejected above"),
+            }
+        }
+        let kept = result
+            .parts
+            .0
+            .len()
+            .checked_sub(self.parts_removed.len())
+            .filter(|&kept| {
+                result.parts.0[kept..]
+                    .iter()
+                    .map(Part::as_str)
+                    .eq(self.parts_removed.iter().map(String::as_str))
+            })
+            .ok_or_else(|| {
+                ArnError::PatchConflict(format!(
+                    "expected parts ending with [{}]",
+                    self.parts_removed.join(", ")
+                ))
+            })?;
+        while result.parts.0.len() > kept {
+            result.parts.0.pop();
+        }
+        for part in &self.parts_appended {
+            result.parts.0.push(Part::new(part.clone())?);
+        }
+        if let Some((from, to)) = self.version {
+            if result.version != from {
+                return Err(conflict(
+                    "Version",
+                    &VersionDisplay(from).to_string(),
+                    &VersionDisplay(result.version).to_string(),
+                ));
+            }
+            result.version = to;
+        }
+        if let Some((from, to)) = &self.extensions {
+            if result.extensions != *from {
+                return Err(conflict(
+                    "Extensions",
+                    &from.to_string(),
+                    &result.extensions.to_string(),
+                ));
+            }
+            result.extensions = to.clone();
+        }
+        Ok(result)
+    }
+}
+
+fn conflict(what: &str, expected: &str, found: &str) -> ArnError {
+    ArnError::PatchConflict(format!("expected {what} {expected:?}, found {found:?}"))
+}
+
+/// Renders an optional version as `v3` or `none`.
+struct VersionDisplay(Option<u64>);
+
+impl fmt::Display for VersionDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(version) => write!(f, "v{version}"),
+            None => f.write_str("none"),
+        }
+    }
+}
+
+impl fmt::Display for ArnDiff {
+    /// Describes the changes in words, for audit logs.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut changes = Vec::new();
+        for change in &self.components {
+            changes.push(format!(
+                "{} changed from {} to {}",
+                change.kind, change.from, change.to
+            ));
+        }
+        if !self.parts_removed.is_empty() {
+            changes.push(format!("parts removed [{}]", self.parts_removed.join(", ")));
+        }
+        if !self.parts_appended.is_empty() {
+            changes.push(format!(
+                "parts appended [{}]",
+                self.parts_appended.join(", ")
+            ));
+        }
+        if let Some((from, to)) = self.version {
+            changes.push(format!(
+                "version changed from {} to {}",
+                VersionDisplay(from),
+                VersionDisplay(to)
+            ));
+        }
+        if let Some((from, to)) = &self.extensions {
+            changes.push(format!("extensions changed from [{from}] to [{to}]"));
+        }
+        if changes.is_empty() {
+            f.write_str("no changes")
+        } else {
+            f.write_str(&changes.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArnParser;
+
+    fn arn(s: &str) -> Arn<'static> {
+        ArnParser::new(s.to_string()).parse().unwrap()
+    }
+
+    #[test]
+    fn test_diff_round_trips() {
+        let pairs = [
+            ("arn:akton:hr:acme:root/a/b", "arn:akton:hr:acme:root/a/b"),
+            ("arn:akton:hr:acme:root/a/b", "arn:akton:hr:acme:root/a/c/d"),
+            ("arn:akton:hr:acme:root/a/b", "arn:akton:hr:acme:root"),
+            (
+                "arn:akton:hr:acme:root",
+                "arn:other:it:globex:base/x@v2?k=v",
+            ),
+            (
+                "arn:akton:hr:acme:root/x@v1?k=v",
+                "arn:akton:hr:acme:root/x",
+            ),
+        ];
+        for (from, to) in pairs {
+            let (from, to) = (arn(from), arn(to));
+            let diff = from.diff(&to);
+            assert_eq!(diff.is_empty(), from == to);
+            assert_eq!(diff.apply(&from).unwrap(), to, "{from} -> {to}");
+        }
+    }
+
+    #[test]
+    fn test_diff_description() {
+        let diff = arn("arn:akton:hr:acme:root/a/b@v1").diff(&arn("arn:akton:it:acme:root/a/c"));
+        assert_eq!(
+            diff.to_string(),
+            "Category changed from hr to it, parts removed [b], parts appended [c], \
+             version changed from v1 to none"
+        );
+        assert_eq!(ArnDiff::default().to_string(), "no changes");
+    }
+
+    #[test]
+    fn test_apply_to_other_arns() {
+        let rename = arn("arn:akton:hr:acme:root").diff(&arn("arn:akton:hr:globex:root"));
+        assert_eq!(
+            rename.apply(&arn("arn:akton:hr:acme:other/a")).unwrap(),
+            arn("arn:akton:hr:globex:other/a")
+        );
+        assert!(matches!(
+            rename.apply(&arn("arn:akton:hr:initech:root")),
+            Err(ArnError::PatchConflict(_))
+        ));
+
+        let move_up = arn("arn:akton:hr:acme:root/a/b").diff(&arn("arn:akton:hr:acme:root/a"));
+        assert_eq!(
+            move_up.apply(&arn("arn:akton:hr:acme:root/x/b")).unwrap(),
+            arn("arn:akton:hr:acme:root/x")
+        );
+        assert!(move_up.apply(&arn("arn:akton:hr:acme:root/b/a")).is_err());
+        assert!(move_up.apply(&arn("arn:akton:hr:acme:root")).is_err());
+
+        for to in ["", "a/b", "a@v1", "a?k=v"] {
+            let mut reroot = arn("arn:akton:hr:acme:root").diff(&arn("arn:akton:hr:acme:other"));
+            reroot.components[0].to = to.to_string();
+            assert!(matches!(
+                reroot.apply(&arn("arn:akton:hr:acme:root")),
+                Err(ArnError::ParseFailure("Root", _))
+            ));
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! Differential testing between Arn parser implementations.
+//!
+//! [`compare`] runs two parsers over the same corpus and reports every input on which they
+//! disagree, along with a minimized counterexample. It is intended to de-risk parser rewrites
+//! by checking a new implementation against the existing [`ArnParser`](crate::ArnParser).
+
+use crate::errors::ArnError;
+use crate::model::Arn;
+
+/// The outcome of running a single parser over one input.
+pub type ParseOutcome = Result<Arn<'static>, ArnError>;
+
+/// An input on which two parsers produced different outcomes.
+#[derive(Debug, PartialEq)]
+pub struct Divergence {
+    /// The corpus entry that triggered the divergence.
+    pub input: String,
+    /// The smallest input derived from `input` that still diverges.
+    pub minimized: String,
+    /// The outcome of the first parser on the minimized input.
+    pub left: ParseOutcome,
+    /// The outcome of the second parser on the minimized input.
+    pub right: ParseOutcome,
+}
+
+/// Summary of a differential run.
+#[derive(Debug, PartialEq, Default)]
+pub struct DiffReport {
+    /// Number of corpus entries checked.
+    pub checked: usize,
+    /// All inputs on which the parsers disagreed.
+    pub divergences: Vec<Divergence>,
+}
+
+impl DiffReport {
+    /// Returns `true` when both parsers agreed on every input.
+    pub fn is_clean(&self) -> bool {
+        self.divergences.is_empty()
+    }
+}
+
+/// Runs both parsers over every entry in `corpus` and reports any behavioral divergence.
+///
+/// Two outcomes agree when both parsers return equal `Arn`s or equal errors. Each divergent
+/// input is shrunk by removing characters for as long as the parsers keep disagreeing.
+///
+/// # Example
+///
+/// ```
+/// use akton_arn::{difftest, ArnParser};
+///
+/// let legacy = |s: &str| ArnParser::new(s.to_string()).parse();
+/// let rewrite = |s: &str| ArnParser::new(s.to_string()).parse();
+/// let report = difftest::compare(legacy, rewrite, difftest::generate_corpus(7, 200));
+/// assert!(report.is_clean());
+/// ```
+pub fn compare<A, B, I, S>(parser_a: A, parser_b: B, corpus: I) -> DiffReport
+where
+    A: Fn(&str) -> ParseOutcome,
+    B: Fn(&str) -> ParseOutcome,
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut report = DiffReport::default();
+    for input in corpus {
+        let input = input.as_ref();
+        report.checked += 1;
+        if parser_a(input) == parser_b(input) {
+            continue;
+        }
+        let minimized = minimize(input, |candidate| {
+            parser_a(candidate) != parser_b(candidate)
+        });
+        report.divergences.push(Divergence {
+            input: input.to_string(),
+            left: parser_a(&minimized),
+            right: parser_b(&minimized),
+            minimized,
+        });
+    }
+    report
+}
+
+/// Shrinks `input` by removing ever smaller runs of characters while `diverges` holds.
+fn minimize(input: &str, diverges: impl Fn(&str) -> bool) -> String {
+    let mut current: Vec<char> = input.chars().collect();
+    let mut chunk = current.len().max(1);
+    while chunk > 0 {
+        let mut start = 0;
+        let mut shrunk = false;
+        while start < current.len() {
+            let end = (start + chunk).min(current.len());
+            let candidate: String = current[..start].iter().chain(&current[end..]).collect();
+            if diverges(&candidate) {
+                current = candidate.chars().collect();
+                shrunk = true;
+            } else {
+                start += chunk;
+            }
+        }
+        if !shrunk {
+            chunk /= 2;
+        }
+    }
+    current.into_iter().collect()
+}
+
+/// Generates a deterministic corpus of well-formed and adversarial Arn strings.
+///
+/// The same `seed` always yields the same corpus, so failures can be reproduced.
+pub fn generate_corpus(seed: u64, count: usize) -> Vec<String> {
+    let mut rng = XorShift(seed.max(1));
+    (0..count)
+        .map(|_| {
+            let arn = well_formed(&mut rng);
+            if rng.below(3) == 0 {
+                arn
+            } else {
+                mutate(&mut rng, arn)
+            }
+        })
+        .collect()
+}
+
+const SEGMENT_ALPHABET: &[char] = &[
+    'a', 'b', 'c', 'x', 'z', 'A', 'Z', '0', '9', '-', '_', '.', 'é', '∂',
+];
+const NOISE_ALPHABET: &[char] = &[':', '/', ' ', '\0', '\n', '\u{1b}', '%', '*', '?'];
+
+fn segment(rng: &mut XorShift) -> String {
+    let len = 1 + rng.below(8) as usize;
+    (0..len).map(|_| *rng.pick(SEGMENT_ALPHABET)).collect()
+}
+
+fn well_formed(rng: &mut XorShift) -> String {
+    let mut arn = format!(
+        "arn:{}:{}:{}:{}",
+        segment(rng),
+        segment(rng),
+        segment(rng),
+        segment(rng)
+    );
+    for _ in 0..rng.below(4) {
+        arn.push('/');
+        arn.push_str(&segment(rng));
+    }
+    arn
+}
+
+fn mutate(rng: &mut XorShift, arn: String) -> String {
+    let mut chars: Vec<char> = arn.chars().collect();
+    for _ in 0..=rng.below(3) {
+        let at = rng.below(chars.len() as u64 + 1) as usize;
+        match rng.below(4) {
+            0 if at < chars.len() => {
+                chars.remove(at);
+            }
+            1 => chars.insert(at, *rng.pick(NOISE_ALPHABET)),
+            2 => chars.truncate(at),
+            _ => chars.insert(at, *rng.pick(SEGMENT_ALPHABET)),
+        }
+    }
+    chars.into_iter().collect()
+}
+
+/// Small deterministic generator so corpora are reproducible without extra dependencies.
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next() % bound.max(1)
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArnParser;
+
+    fn legacy(s: &str) -> ParseOutcome {
+        ArnParser::new(s.to_string()).parse()
+    }
+
+    #[test]
+    fn test_identical_parsers_agree() {
+        let report = compare(legacy, legacy, generate_corpus(42, 500));
+        assert_eq!(report.checked, 500);
+        assert!(report.is_clean());
+    }
+
+    #[test]
+    fn test_divergence_is_reported_and_minimized() {
+        // A "rewrite" that wrongly rejects any input containing an uppercase letter.
+        let rewrite = |s: &str| {
+            if s.chars().any(|c| c.is_ascii_uppercase()) {
+                Err(ArnError::InvalidFormat)
+            } else {
+                legacy(s)
+            }
+        };
+        let report = compare(legacy, rewrite, ["arn:akton:hr:Company:root/team"]);
+        assert_eq!(report.divergences.len(), 1);
+        let divergence = &report.divergences[0];
+        assert_eq!(divergence.input, "arn:akton:hr:Company:root/team");
+        assert!(divergence.minimized.len() < divergence.input.len());
+        assert!(divergence.minimized.contains('C'));
+        assert_ne!(divergence.left, divergence.right);
+    }
+
+    #[test]
+    fn test_corpus_is_deterministic() {
+        assert_eq!(generate_corpus(1, 50), generate_corpus(1, 50));
+        assert_ne!(generate_corpus(1, 50), generate_corpus(2, 50));
+    }
+}
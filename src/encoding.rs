@@ -0,0 +1,539 @@
+use crate::errors::ArnError;
+use crate::limits::ArnLimits;
+use crate::model::{Account, Arn, Category, Domain, Extensions, Part, Parts, Root};
+use crate::parser::ComponentRules;
+use crate::ComponentKind;
+use std::borrow::Cow;
+
+/// Version byte written at the start of every encoding produced by [`Arn::to_bytes`].
+///
+/// Version 1 layout:
+///
+/// ```text
+/// version: u8 = 1
+/// domain, category, account, root: varint length + UTF-8 bytes (each)
+/// part count: varint
+/// parts: varint length + UTF-8 bytes (each)
+/// ```
+///
+/// Varints are unsigned LEB128. Arns with [`Extensions`] are written in the
+/// [`BINARY_FORMAT_VERSION_WITH_EXTENSIONS`] layout and versioned Arns in the
+/// [`BINARY_FORMAT_VERSION_WITH_VERSION`] layout instead.
+pub const BINARY_FORMAT_VERSION: u8 = 1;
+
+/// Version byte written by [`Arn::to_bytes`] for Arns that carry [`Extensions`].
+///
+/// Version 2 layout is the version 1 layout followed by:
+///
+/// ```text
+/// extension count: varint
+/// extensions: key, value: varint length + UTF-8 bytes (each)
+/// ```
+///
+/// Arns without extensions are still written as version 1, so existing encodings are
+/// unchanged. [`Arn::from_bytes`] accepts every version.
+pub const BINARY_FORMAT_VERSION_WITH_EXTENSIONS: u8 = 2;
+
+/// Version byte written by [`Arn::to_bytes`] for Arns with a [version](Arn::version).
+///
+/// Version 3 layout is the version 1 layout followed by:
+///
+/// ```text
+/// resource version: varint
+/// extension count: varint
+/// extensions: key, value: varint length + UTF-8 bytes (each)
+/// ```
+pub const BINARY_FORMAT_VERSION_WITH_VERSION: u8 = 3;
+
+impl<'a> Arn<'a> {
+    /// Encodes the Arn in the compact, versioned binary format described by [`BINARY_FORMAT_VERSION`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.encoded_len());
+        out.push(self.format_version());
+        for component in [
+            self.domain.as_str(),
+            self.category.as_str(),
+            self.account.as_str(),
+            self.root.as_str(),
+        ] {
+            write_str(&mut out, component);
+        }
+        write_varint(&mut out, self.parts.0.len() as u64);
+        for part in &self.parts.0 {
+            write_str(&mut out, part.as_str());
+        }
+        if let Some(version) = self.version {
+            write_varint(&mut out, version);
+        }
+        if self.format_version() != BINARY_FORMAT_VERSION {
+            write_varint(&mut out, self.extensions.len() as u64);
+            for (key, value) in self.extensions.iter() {
+                write_str(&mut out, key);
+                write_str(&mut out, value);
+            }
+        }
+        out
+    }
+
+    /// Returns the exact number of bytes [`Arn::to_bytes`] produces for this Arn.
+    pub fn encoded_len(&self) -> usize {
+        let str_len = |s: &str| varint_len(s.len() as u64) + s.len();
+        1 + str_len(self.domain.as_str())
+            + str_len(self.category.as_str())
+            + str_len(self.account.as_str())
+            + str_len(self.root.as_str())
+            + varint_len(self.parts.0.len() as u64)
+            + self
+                .parts
+                .0
+                .iter()
+                .map(|p| str_len(p.as_str()))
+                .sum::<usize>()
+            + self.version.map_or(0, varint_len)
+            + if self.format_version() == BINARY_FORMAT_VERSION {
+                0
+            } else {
+                varint_len(self.extensions.len() as u64)
+                    + self
+                        .extensions
+                        .iter()
+                        .map(|(key, value)| str_len(key) + str_len(value))
+                        .sum::<usize>()
+            }
+    }
+
+    /// Returns the oldest format version able to hold this Arn.
+    fn format_version(&self) -> u8 {
+        if self.version.is_some() {
+            BINARY_FORMAT_VERSION_WITH_VERSION
+        } else if !self.extensions.is_empty() {
+            BINARY_FORMAT_VERSION_WITH_EXTENSIONS
+        } else {
+            BINARY_FORMAT_VERSION
+        }
+    }
+
+    /// Decodes an Arn produced by [`Arn::to_bytes`], borrowing component strings from `bytes`.
+    ///
+    /// Components are validated exactly as [`ArnParser`](crate::ArnParser) validates the
+    /// string form under its default limits, so a decoded Arn displays as a string that parses
+    /// back to it. Trailing bytes are rejected.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Arn<'a>, ArnError> {
+        Arn::from_bytes_with_limits(bytes, ArnLimits::default())
+    }
+
+    /// Decodes an Arn as [`Arn::from_bytes`] does, applying `limits` instead of
+    /// [`ArnLimits::default`].
+    pub fn from_bytes_with_limits(bytes: &'a [u8], limits: ArnLimits) -> Result<Arn<'a>, ArnError> {
+        let mut reader = Reader { bytes, pos: 0 };
+        let version = reader.byte()?;
+        if !(BINARY_FORMAT_VERSION..=BINARY_FORMAT_VERSION_WITH_VERSION).contains(&version) {
+            return Err(ArnError::DecodeFailure(format!(
+                "unsupported format version {version}"
+            )));
+        }
+        let rules = ComponentRules {
+            limits,
+            ..ComponentRules::default()
+        };
+        let mut component = |kind| -> Result<Cow<'a, str>, ArnError> {
+            rules.fit_component(kind, reader.str()?.into())
+        };
+        let domain = Domain::new_unchecked(component(ComponentKind::Domain)?);
+        let category = Category::new(component(ComponentKind::Category)?);
+        let account = Account::new(component(ComponentKind::Account)?);
+        let root = Root(component(ComponentKind::Root)?.into());
+        let count = reader.varint()?;
+        // Every part takes at least two bytes, which bounds the allocation for hostile counts.
+        let mut parts = Vec::with_capacity((count as usize).min(reader.remaining() / 2));
+        for _ in 0..count {
+            let part = rules.fit_component(ComponentKind::Part, reader.str()?.into())?;
+            parts.push(Part::new_unchecked(part));
+        }
+        let resource_version = match version {
+            BINARY_FORMAT_VERSION_WITH_VERSION => Some(reader.varint()?),
+            _ => None,
+        };
+        let mut extensions = Extensions::new();
+        if version != BINARY_FORMAT_VERSION {
+            for _ in 0..reader.varint()? {
+                let (key, value) = (reader.str()?, reader.str()?);
+                if extensions.insert(key, value)?.is_some() {
+                    return Err(ArnError::DecodeFailure(format!(
+                        "duplicate extension {key:?}"
+                    )));
+                }
+            }
+        }
+        if reader.remaining() != 0 {
+            return Err(ArnError::DecodeFailure(format!(
+                "{} trailing bytes",
+                reader.remaining()
+            )));
+        }
+        let mut arn = Arn::new(domain, category, account, root, Parts::new(parts));
+        arn.version = resource_version;
+        arn.extensions = extensions;
+        rules.limits.check_len(&arn)?;
+        Ok(arn)
+    }
+}
+
+/// Opt-in serde format that writes an Arn in the binary format of [`Arn::to_bytes`].
+///
+/// The default `Serialize` implementation of [`Arn`] writes the canonical string. Binary
+/// formats such as postcard and bincode can instead store the versioned layout described by
+/// [`BINARY_FORMAT_VERSION`], as a byte string, with
+/// `#[serde(with = "akton_arn::binary")]`. The layout is frozen: bytes written by any release
+/// decode in every later one.
+///
+/// ```
+/// use akton_arn::{Arn, ArnParser};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Snapshot {
+///     #[serde(with = "akton_arn::binary")]
+///     owner: Arn<'static>,
+/// }
+///
+/// let owner = ArnParser::new("arn:akton:hr:acme:root/teams").parse().unwrap().into_owned();
+/// let bytes = postcard::to_allocvec(&Snapshot { owner: owner.clone() }).unwrap();
+/// let restored: Snapshot = postcard::from_bytes(&bytes).unwrap();
+/// assert_eq!(restored.owner, owner);
+/// ```
+#[cfg(feature = "serde")]
+pub mod binary {
+    use crate::Arn;
+    use serde::de::{Error, SeqAccess, Visitor};
+    use serde::{Deserializer, Serializer};
+    use std::fmt;
+
+    /// Serializes an Arn as the bytes of [`Arn::to_bytes`].
+    pub fn serialize<S: Serializer>(arn: &Arn<'_>, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&arn.to_bytes())
+    }
+
+    /// Deserializes an Arn from the bytes of [`Arn::to_bytes`].
+    pub fn deserialize<'de, 'a, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Arn<'a>, D::Error> {
+        deserializer.deserialize_bytes(BytesVisitor)
+    }
+
+    struct BytesVisitor;
+
+    impl<'de> Visitor<'de> for BytesVisitor {
+        type Value = Arn<'static>;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("an Arn in its binary format")
+        }
+
+        fn visit_bytes<E: Error>(self, bytes: &[u8]) -> Result<Self::Value, E> {
+            Arn::from_bytes(bytes)
+                .map(Arn::into_owned)
+                .map_err(E::custom)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+            while let Some(byte) = seq.next_element()? {
+                bytes.push(byte);
+            }
+            self.visit_bytes(&bytes)
+        }
+    }
+}
+
+/// Encodes an Arn as the bytes of [`Arn::to_bytes`], so its bincode layout is the frozen
+/// binary format rather than whatever the struct looks like today.
+#[cfg(feature = "bincode")]
+impl bincode::Encode for Arn<'_> {
+    fn encode<E: bincode::enc::Encoder>(
+        &self,
+        encoder: &mut E,
+    ) -> Result<(), bincode::error::EncodeError> {
+        self.to_bytes().encode(encoder)
+    }
+}
+
+#[cfg(feature = "bincode")]
+impl<Context> bincode::Decode<Context> for Arn<'static> {
+    fn decode<D: bincode::de::Decoder<Context = Context>>(
+        decoder: &mut D,
+    ) -> Result<Self, bincode::error::DecodeError> {
+        let bytes = Vec::<u8>::decode(decoder)?;
+        Arn::from_bytes(&bytes)
+            .map(Arn::into_owned)
+            .map_err(|e| bincode::error::DecodeError::OtherString(e.to_string()))
+    }
+}
+
+/// Decodes an Arn borrowing its components from the input, like [`Arn::from_bytes`].
+#[cfg(feature = "bincode")]
+impl<'de, Context> bincode::BorrowDecode<'de, Context> for Arn<'de> {
+    fn borrow_decode<D: bincode::de::BorrowDecoder<'de, Context = Context>>(
+        decoder: &mut D,
+    ) -> Result<Self, bincode::error::DecodeError> {
+        let bytes = <&'de [u8]>::borrow_decode(decoder)?;
+        Arn::from_bytes(bytes).map_err(|e| bincode::error::DecodeError::OtherString(e.to_string()))
+    }
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn varint_len(value: u64) -> usize {
+    (64 - value.max(1).leading_zeros() as usize).div_ceil(7)
+}
+
+fn write_str(out: &mut Vec<u8>, value: &str) {
+    write_varint(out, value.len() as u64);
+    out.extend_from_slice(value.as_bytes());
+}
+
+/// Cursor over an encoded Arn.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.pos
+    }
+
+    fn byte(&mut self) -> Result<u8, ArnError> {
+        let byte = *self
+            .bytes
+            .get(self.pos)
+            .ok_or_else(|| ArnError::DecodeFailure("unexpected end of input".to_string()))?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    /// Reads a varint in its shortest encoding, so every value has exactly one.
+    fn varint(&mut self) -> Result<u64, ArnError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            // The tenth byte holds only the top bit of a u64.
+            if shift == 63 && byte > 1 {
+                return Err(ArnError::DecodeFailure("varint overflows u64".to_string()));
+            }
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                if byte == 0 && shift > 0 {
+                    return Err(ArnError::DecodeFailure(
+                        "varint is not in its shortest form".to_string(),
+                    ));
+                }
+                return Ok(value);
+            }
+        }
+        Err(ArnError::DecodeFailure("varint is too long".to_string()))
+    }
+
+    fn str(&mut self) -> Result<&'a str, ArnError> {
+        let len = self.varint()?;
+        if len > self.remaining() as u64 {
+            return Err(ArnError::DecodeFailure(
+                "unexpected end of input".to_string(),
+            ));
+        }
+        let slice = &self.bytes[self.pos..self.pos + len as usize];
+        self.pos += len as usize;
+        std::str::from_utf8(slice).map_err(|e| ArnError::DecodeFailure(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArnParser;
+
+    #[test]
+    fn test_binary_round_trip() {
+        let arn = ArnParser::new("arn:akton-internal:hr:company123:root/departmentA/team1")
+            .parse()
+            .unwrap();
+        let bytes = arn.to_bytes();
+        assert_eq!(bytes.len(), arn.encoded_len());
+        assert!(bytes.len() < arn.to_string().len());
+        assert_eq!(Arn::from_bytes(&bytes).unwrap(), arn);
+    }
+
+    #[test]
+    fn test_binary_layout_is_frozen() {
+        let arn = ArnParser::new("arn:d:c:a:r/p").parse().unwrap();
+        assert_eq!(
+            arn.to_bytes(),
+            vec![1, 1, b'd', 1, b'c', 1, b'a', 1, b'r', 1, 1, b'p']
+        );
+    }
+
+    #[test]
+    fn test_binary_extensions_use_version_two() {
+        let arn = ArnParser::new("arn:d:c:a:r/p?v=3").parse().unwrap();
+        let bytes = arn.to_bytes();
+        assert_eq!(
+            bytes,
+            vec![2, 1, b'd', 1, b'c', 1, b'a', 1, b'r', 1, 1, b'p', 1, 1, b'v', 1, b'3']
+        );
+        assert_eq!(bytes.len(), arn.encoded_len());
+        assert_eq!(Arn::from_bytes(&bytes).unwrap(), arn);
+        let duplicate = [
+            2, 1, b'd', 1, b'c', 1, b'a', 1, b'r', 0, 2, 1, b'v', 0, 1, b'v', 0,
+        ];
+        assert!(Arn::from_bytes(&duplicate).is_err());
+    }
+
+    #[test]
+    fn test_binary_versions_use_version_three() {
+        let arn = ArnParser::new("arn:d:c:a:r@v300").parse().unwrap();
+        let bytes = arn.to_bytes();
+        assert_eq!(
+            bytes,
+            vec![3, 1, b'd', 1, b'c', 1, b'a', 1, b'r', 0, 0xac, 0x02, 0]
+        );
+        assert_eq!(bytes.len(), arn.encoded_len());
+        assert_eq!(Arn::from_bytes(&bytes).unwrap(), arn);
+        let arn = arn.with_extension("k", "v").unwrap();
+        assert_eq!(Arn::from_bytes(&arn.to_bytes()).unwrap(), arn);
+        assert_eq!(arn.to_bytes().len(), arn.encoded_len());
+    }
+
+    #[test]
+    fn test_binary_rejects_malformed_input() {
+        let bytes = ArnParser::new("arn:d:c:a:r/p").parse().unwrap().to_bytes();
+        assert!(Arn::from_bytes(&[]).is_err());
+        assert!(Arn::from_bytes(&[4]).is_err());
+        assert!(Arn::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(Arn::from_bytes(&trailing).is_err());
+        // An empty part is invalid just like in the string form.
+        assert_eq!(
+            Arn::from_bytes(&[1, 1, b'd', 1, b'c', 1, b'a', 1, b'r', 1, 0]),
+            Err(ArnError::ParseFailure(
+                "Part",
+                "cannot be empty".to_string()
+            ))
+        );
+        // A root holding a version suffix would not survive the string form.
+        assert!(matches!(
+            Arn::from_bytes(&[1, 1, b'd', 1, b'c', 1, b'a', 4, b'r', b'@', b'v', b'2', 0]),
+            Err(ArnError::ParseFailure("Root", _))
+        ));
+        // Overlong and overflowing varints would give one Arn several encodings.
+        for count in [&[0x80, 0x00][..], &[0x81, 0x80, 0x00]] {
+            let mut overlong = vec![1, 1, b'd', 1, b'c', 1, b'a', 1, b'r'];
+            overlong.extend_from_slice(count);
+            assert!(matches!(
+                Arn::from_bytes(&overlong),
+                Err(ArnError::DecodeFailure(_))
+            ));
+        }
+        let mut overflowing = vec![1, 1, b'd', 1, b'c', 1, b'a', 1, b'r'];
+        overflowing.extend_from_slice(&[0xff; 9]);
+        overflowing.push(0x02);
+        assert_eq!(
+            Arn::from_bytes(&overflowing),
+            Err(ArnError::DecodeFailure("varint overflows u64".to_string()))
+        );
+        // A separator inside a category or account would display as a different Arn.
+        assert!(matches!(
+            Arn::from_bytes(&[1, 1, b'd', 3, b'c', b':', b'x', 1, b'a', 1, b'r', 0]),
+            Err(ArnError::ParseFailure("Category", _))
+        ));
+        assert!(matches!(
+            Arn::from_bytes(&[1, 1, b'd', 1, b'c', 2, b':', b'a', 1, b'r', 0]),
+            Err(ArnError::ParseFailure("Account", _))
+        ));
+        // The parser's limits apply to decoded components too.
+        assert!(matches!(
+            Arn::from_bytes(&[1, 1, b'd', 1, b'c', 2, b'a', 0x1b, 1, b'r', 0]),
+            Err(ArnError::ValidationFailure(ComponentKind::Account, _))
+        ));
+        let long = Arn::new(
+            Domain::new("d").unwrap(),
+            Category::new("c".repeat(crate::MAX_COMPONENT_LEN + 1)),
+            Account::new("a"),
+            Root::named("r").unwrap(),
+            Parts::default(),
+        );
+        assert!(Arn::from_bytes(&long.to_bytes()).is_err());
+        // A huge part count must not allocate or loop before running out of input.
+        assert!(
+            Arn::from_bytes(&[1, 1, b'd', 1, b'c', 1, b'a', 1, b'r', 0xff, 0xff, 0xff, 0x7f])
+                .is_err()
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_postcard_layout_is_frozen() {
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct Snapshot {
+            #[serde(with = "crate::binary")]
+            owner: Arn<'static>,
+        }
+
+        let owner = ArnParser::new("arn:d:c:a:r/p@v1").parse().unwrap();
+        let bytes = postcard::to_allocvec(&Snapshot {
+            owner: owner.clone(),
+        })
+        .unwrap();
+        // The byte-string length, then the binary format unchanged.
+        assert_eq!(
+            bytes,
+            vec![14, 3, 1, b'd', 1, b'c', 1, b'a', 1, b'r', 1, 1, b'p', 1, 0]
+        );
+        let restored: Snapshot = postcard::from_bytes(&bytes).unwrap();
+        assert_eq!(restored.owner, owner);
+        assert!(postcard::from_bytes::<Snapshot>(&[2, 9, 9]).is_err());
+
+        let json = serde_json::to_string(&Snapshot { owner }).unwrap();
+        let restored: Snapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.owner.version(), Some(1));
+    }
+
+    #[cfg(feature = "bincode")]
+    #[test]
+    fn test_bincode_layout_is_frozen() {
+        let config = bincode::config::standard();
+        let arn = ArnParser::new("arn:d:c:a:r/p").parse().unwrap();
+        let bytes = bincode::encode_to_vec(&arn, config).unwrap();
+        assert_eq!(
+            bytes,
+            vec![12, 1, 1, b'd', 1, b'c', 1, b'a', 1, b'r', 1, 1, b'p']
+        );
+        let (decoded, read): (Arn<'static>, usize) =
+            bincode::decode_from_slice(&bytes, config).unwrap();
+        assert_eq!((decoded, read), (arn.clone(), bytes.len()));
+        let (borrowed, _): (Arn<'_>, usize) =
+            bincode::borrow_decode_from_slice(&bytes, config).unwrap();
+        assert_eq!(borrowed, arn);
+        assert!(bincode::decode_from_slice::<Arn<'static>, _>(&[2, 9, 9], config).is_err());
+    }
+
+    #[test]
+    fn test_varint_len_matches_encoding() {
+        for value in [0u64, 1, 127, 128, 16_383, 16_384, u64::MAX] {
+            let mut out = Vec::new();
+            write_varint(&mut out, value);
+            assert_eq!(out.len(), varint_len(value));
+            let mut reader = Reader {
+                bytes: &out,
+                pos: 0,
+            };
+            assert_eq!(reader.varint(), Ok(value));
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::{ComponentKind, SegmentRejection};
 use std::convert::Infallible;
 
 // Merged ArnBuilderError and ArnParseError into ArnError
@@ -25,6 +26,51 @@ pub enum ArnError {
     #[error("ARN has invalid format")]
     InvalidFormat,
 
+    #[error("Validation Error - {0} rejected: {1}")]
+    ValidationFailure(ComponentKind, String),
+
+    #[error("Lineage Error - {0}")]
+    BrokenLineage(String),
+
+    #[error("Registry Error - {0} is already registered")]
+    AlreadyRegistered(String),
+
+    #[error("Registry Error - {0} is not registered")]
+    NotRegistered(String),
+
+    #[error("Decoding Error - {0}")]
+    DecodeFailure(String),
+
+    #[error("Encoding Error - {0}")]
+    EncodeFailure(String),
+
+    #[error("Signature Error - signature does not match")]
+    InvalidSignature,
+
+    #[error("Backend Error - {0}")]
+    Backend(String),
+
+    #[error("User Segment Error - {0}")]
+    RejectedUserSegment(SegmentRejection),
+
+    #[error("Template Error - no value for placeholder {0}")]
+    MissingPlaceholder(String),
+
+    #[error("Template Error - {0}")]
+    TemplateMismatch(String),
+
+    #[error("Patch Error - {0}")]
+    PatchConflict(String),
+
+    #[error("Limit Error - Arn is {0} bytes, exceeding the limit of {1}")]
+    TooLong(usize, usize),
+
+    #[error("Quota Error - {0} has {1} units left, {2} requested")]
+    QuotaExceeded(String, u64, u64),
+
+    #[error("Reserved Name Error - {0} {1:?} is reserved")]
+    ReservedName(ComponentKind, String),
+
     // Converted the Infallible implementation to ArnError
     #[error("Infallible error")]
     InfallibleError,
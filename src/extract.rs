@@ -0,0 +1,137 @@
+//! Reading a single component out of an Arn string without building an [`Arn`](crate::Arn).
+
+use crate::errors::ArnError;
+use crate::format::ArnFormat;
+use crate::parser::{walk, ComponentRules};
+use crate::traits::ArnVisitor;
+use crate::ComponentKind;
+use std::borrow::Cow;
+use std::ops::ControlFlow;
+
+/// Returns the domain of `input`, checking the Arn only as far as the domain.
+///
+/// See [`extract_account`].
+pub fn extract_domain(input: &str) -> Result<Cow<'_, str>, ArnError> {
+    extract(input, ComponentKind::Domain)
+}
+
+/// Returns the category of `input`, checking the Arn only as far as the category.
+///
+/// See [`extract_account`].
+pub fn extract_category(input: &str) -> Result<Cow<'_, str>, ArnError> {
+    extract(input, ComponentKind::Category)
+}
+
+/// Returns the account of `input`, checking the Arn only as far as the account.
+///
+/// The component is checked and normalized as [`ArnParser::parse`](crate::ArnParser::parse)
+/// with default settings would, and borrows from `input` unless normalizing rewrote it. The
+/// string is scanned once and stops at the component, so what follows it is not checked: an
+/// input `parse` rejects for a bad part still yields its account. Use
+/// [`ArnParser::parse_with`](crate::ArnParser::parse_with) for custom settings.
+///
+/// ```
+/// use akton_arn::{extract_account, extract_root};
+///
+/// let line = "arn:akton:hr:acme:root/orders/42@v3";
+/// assert_eq!(extract_account(line).unwrap(), "acme");
+/// assert_eq!(extract_root(line).unwrap(), "root");
+/// assert!(extract_account("arn:akton:hr").is_err());
+/// ```
+pub fn extract_account(input: &str) -> Result<Cow<'_, str>, ArnError> {
+    extract(input, ComponentKind::Account)
+}
+
+/// Returns the root of `input`, checking the Arn only as far as the root.
+///
+/// See [`extract_account`].
+pub fn extract_root(input: &str) -> Result<Cow<'_, str>, ArnError> {
+    extract(input, ComponentKind::Root)
+}
+
+fn extract(input: &str, kind: ComponentKind) -> Result<Cow<'_, str>, ArnError> {
+    let mut pick = Pick { kind, found: None };
+    walk(
+        input,
+        &[],
+        &ArnFormat::DEFAULT,
+        &ComponentRules::default(),
+        &mut pick,
+    )?;
+    pick.found.ok_or(ArnError::InvalidFormat)
+}
+
+/// Keeps the first component of one kind and stops reading.
+struct Pick<'i> {
+    kind: ComponentKind,
+    found: Option<Cow<'i, str>>,
+}
+
+impl<'i> Pick<'i> {
+    fn pick(&mut self, kind: ComponentKind, value: Cow<'i, str>) -> ControlFlow<()> {
+        if kind != self.kind {
+            return ControlFlow::Continue(());
+        }
+        self.found = Some(value);
+        ControlFlow::Break(())
+    }
+}
+
+impl<'i> ArnVisitor<'i> for Pick<'i> {
+    fn visit_domain(&mut self, domain: Cow<'i, str>) -> ControlFlow<()> {
+        self.pick(ComponentKind::Domain, domain)
+    }
+
+    fn visit_category(&mut self, category: Cow<'i, str>) -> ControlFlow<()> {
+        self.pick(ComponentKind::Category, category)
+    }
+
+    fn visit_account(&mut self, account: Cow<'i, str>) -> ControlFlow<()> {
+        self.pick(ComponentKind::Account, account)
+    }
+
+    fn visit_root(&mut self, root: Cow<'i, str>) -> ControlFlow<()> {
+        self.pick(ComponentKind::Root, root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArnParser;
+
+    #[test]
+    fn test_agrees_with_parse() {
+        for input in [
+            "arn:akton:hr:acme:root",
+            "arn:akton:hr.payroll:acme.eu:root/a/b@v2?region=eu",
+            "eid:akton:hr:acme:root/a",
+        ] {
+            let arn = ArnParser::new(input).parse().unwrap();
+            assert_eq!(extract_domain(input).unwrap(), arn.domain().as_str());
+            assert_eq!(extract_category(input).unwrap(), arn.category().as_str());
+            assert_eq!(extract_account(input).unwrap(), arn.account().as_str());
+            assert_eq!(extract_root(input).unwrap(), arn.root().as_str());
+        }
+    }
+
+    #[test]
+    fn test_stops_at_the_component() {
+        let input = "arn:akton:hr:acme:root/bad:part";
+        assert!(ArnParser::new(input).parse().is_err());
+        let account = extract_account(input).unwrap();
+        assert!(matches!(account, Cow::Borrowed("acme")));
+        assert_eq!(extract_root(input).unwrap(), "root");
+        assert_eq!(
+            extract_domain("arn::hr:acme:root").unwrap_err().to_string(),
+            ArnParser::new("arn::hr:acme:root")
+                .parse()
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            extract_root("urn:akton:hr:acme:root"),
+            Err(ArnError::InvalidFormat)
+        );
+    }
+}
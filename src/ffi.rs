@@ -0,0 +1,310 @@
+//! C API, enabled with the `ffi` feature.
+//!
+//! Lets C and C++ programs parse, build, print and match Arns through the declarations in
+//! `include/akton_arn.h`, which cbindgen generates from this module using `cbindgen.toml`.
+//! Build a linkable library with
+//! `cargo rustc --release --features ffi --crate-type cdylib` (or `staticlib`).
+//!
+//! Arns cross the boundary as opaque [`AktonArn`] pointers. Every pointer and string this API
+//! returns is owned by the caller and must be released with [`arn_free`] or
+//! [`arn_string_free`]. Functions that can fail take an optional `char **error`, which
+//! receives a message the caller frees with [`arn_string_free`]; pass `NULL` to ignore it. A
+//! message already in `*error` is overwritten, not freed, so free it before reusing the
+//! variable.
+//!
+//! No panic unwinds into C: a function that panics returns its failure value (`NULL` or -1)
+//! and, where it takes `error`, sets it to the panic message.
+
+use crate::builder::ArnBuilder;
+use crate::errors::ArnError;
+use crate::model::{Account, Arn, Category, Domain, Root};
+use crate::parser::ArnParser;
+use crate::pattern::ArnPattern;
+use std::any::Any;
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+/// An Arn owned by C code.
+pub struct AktonArn(Arn<'static>);
+
+/// Parses `input`, returning `NULL` and setting `error` if it is not a valid Arn.
+///
+/// # Safety
+///
+/// `input` must be a NUL-terminated string, and `error` either `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn arn_parse(input: *const c_char, error: *mut *mut c_char) -> *mut AktonArn {
+    catch(error, ptr::null_mut(), || {
+        let arn =
+            read_str(input).and_then(|input| ArnParser::new(input).parse().map(Arn::into_owned));
+        into_raw(arn, error)
+    })
+}
+
+/// Builds an Arn from its components, minting the root's id, then appending `parts_len`
+/// parts. Returns `NULL` and sets `error` if a component is invalid.
+///
+/// # Safety
+///
+/// Every string must be NUL-terminated, `parts` must point to `parts_len` strings (or may be
+/// `NULL` when `parts_len` is 0), and `error` must be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn arn_build(
+    domain: *const c_char,
+    category: *const c_char,
+    account: *const c_char,
+    root: *const c_char,
+    parts: *const *const c_char,
+    parts_len: usize,
+    error: *mut *mut c_char,
+) -> *mut AktonArn {
+    let build = || {
+        let mut saved = ArnBuilder::new()
+            .with::<Domain>(read_str(domain)?)?
+            .with::<Category>(read_str(category)?)?
+            .with::<Account>(read_str(account)?)?
+            .with::<Root>(read_str(root)?)?
+            .into_parts();
+        for i in 0..parts_len {
+            saved.parts.push(read_str(*parts.add(i))?.to_string());
+        }
+        ArnBuilder::new()
+            .resume(saved)?
+            .build()
+            .map(Arn::into_owned)
+    };
+    catch(error, ptr::null_mut(), || into_raw(build(), error))
+}
+
+/// Returns the canonical form of `arn`, to be released with [`arn_string_free`].
+///
+/// # Safety
+///
+/// `arn` must have been returned by this API and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn arn_to_string(arn: *const AktonArn) -> *mut c_char {
+    catch(ptr::null_mut(), ptr::null_mut(), || match arn.as_ref() {
+        Some(arn) => into_c_string(arn.0.to_string()),
+        None => ptr::null_mut(),
+    })
+}
+
+/// Returns 1 if `arn` matches `pattern`, 0 if it does not, or -1 and sets `error` if the
+/// pattern is invalid.
+///
+/// # Safety
+///
+/// `pattern` must be a NUL-terminated string, `arn` must have been returned by this API and
+/// not yet freed, and `error` must be `NULL` or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn arn_match(
+    pattern: *const c_char,
+    arn: *const AktonArn,
+    error: *mut *mut c_char,
+) -> c_int {
+    catch(error, -1, || {
+        let Some(arn) = arn.as_ref() else {
+            set_error(error, null_argument());
+            return -1;
+        };
+        match read_str(pattern).and_then(|pattern| pattern.parse::<ArnPattern>()) {
+            Ok(pattern) => c_int::from(pattern.matches(&arn.0)),
+            Err(e) => {
+                set_error(error, e);
+                -1
+            }
+        }
+    })
+}
+
+/// Releases an Arn returned by this API. Does nothing given `NULL`.
+///
+/// # Safety
+///
+/// `arn` must have been returned by this API and not already freed.
+#[no_mangle]
+pub unsafe extern "C" fn arn_free(arn: *mut AktonArn) {
+    catch(ptr::null_mut(), (), || {
+        if !arn.is_null() {
+            drop(Box::from_raw(arn));
+        }
+    })
+}
+
+/// Releases a string returned by this API. Does nothing given `NULL`.
+///
+/// # Safety
+///
+/// `string` must have been returned by this API and not already freed.
+#[no_mangle]
+pub unsafe extern "C" fn arn_string_free(string: *mut c_char) {
+    catch(ptr::null_mut(), (), || {
+        if !string.is_null() {
+            drop(CString::from_raw(string));
+        }
+    })
+}
+
+/// Runs `f`, returning `failed` and reporting the panic through `error` if it panics, since
+/// unwinding across `extern "C"` is undefined behaviour.
+unsafe fn catch<T>(error: *mut *mut c_char, failed: T, f: impl FnOnce() -> T) -> T {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        set_message(
+            error,
+            format!("akton-arn panicked: {}", panic_message(&*payload)),
+        );
+        failed
+    })
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic")
+}
+
+unsafe fn read_str<'s>(ptr: *const c_char) -> Result<&'s str, ArnError> {
+    if ptr.is_null() {
+        return Err(null_argument());
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map_err(|e| ArnError::ParseFailure("C string", e.to_string()))
+}
+
+fn null_argument() -> ArnError {
+    ArnError::ParseFailure("C string", "argument is NULL".to_string())
+}
+
+unsafe fn into_raw(arn: Result<Arn<'static>, ArnError>, error: *mut *mut c_char) -> *mut AktonArn {
+    match arn {
+        Ok(arn) => Box::into_raw(Box::new(AktonArn(arn))),
+        Err(e) => {
+            set_error(error, e);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Stores the message for `e` in `*error`, overwriting without freeing whatever was there.
+unsafe fn set_error(error: *mut *mut c_char, e: ArnError) {
+    set_message(error, e.to_string());
+}
+
+unsafe fn set_message(error: *mut *mut c_char, message: String) {
+    if !error.is_null() {
+        *error = into_c_string(message);
+    }
+}
+
+/// Hands `s` to C, or returns `NULL` if it contains a NUL byte C could not represent.
+fn into_c_string(s: String) -> *mut c_char {
+    CString::new(s).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c(s: &str) -> CString {
+        CString::new(s).unwrap()
+    }
+
+    unsafe fn take_string(s: *mut c_char) -> String {
+        let owned = CStr::from_ptr(s).to_str().unwrap().to_string();
+        arn_string_free(s);
+        owned
+    }
+
+    #[test]
+    fn test_parse_print_and_match() {
+        unsafe {
+            let input = c("arn:akton:hr:acme:root/teams/a");
+            let arn = arn_parse(input.as_ptr(), ptr::null_mut());
+            assert!(!arn.is_null());
+            assert_eq!(
+                take_string(arn_to_string(arn)),
+                "arn:akton:hr:acme:root/teams/a"
+            );
+            let mut error = ptr::null_mut();
+            assert_eq!(
+                arn_match(c("arn:akton:*:*:*/**").as_ptr(), arn, &mut error),
+                1
+            );
+            assert_eq!(
+                arn_match(c("arn:akton:it:*:*").as_ptr(), arn, &mut error),
+                0
+            );
+            assert!(error.is_null());
+            assert_eq!(arn_match(c("nonsense").as_ptr(), arn, &mut error), -1);
+            assert_eq!(take_string(error), "ARN has invalid format");
+            arn_free(arn);
+        }
+    }
+
+    #[test]
+    fn test_errors_are_reported() {
+        unsafe {
+            let mut error = ptr::null_mut();
+            assert!(arn_parse(c("arn:akton").as_ptr(), &mut error).is_null());
+            assert_eq!(take_string(error), "ARN has invalid format");
+            assert!(arn_parse(ptr::null(), ptr::null_mut()).is_null());
+            assert!(arn_to_string(ptr::null()).is_null());
+            arn_free(ptr::null_mut());
+            arn_string_free(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn test_panics_do_not_cross_the_boundary() {
+        unsafe {
+            let mut error = ptr::null_mut();
+            assert_eq!(catch(&mut error, -1, || panic!("boom")), -1);
+            assert_eq!(take_string(error), "akton-arn panicked: boom");
+            let code = catch(ptr::null_mut(), -1, || panic!("{}", "formatted"));
+            assert_eq!(code, -1);
+            error = ptr::null_mut();
+            assert_eq!(catch(&mut error, 1, || 0), 0);
+            assert!(error.is_null());
+        }
+    }
+
+    #[test]
+    fn test_build() {
+        unsafe {
+            let (domain, category, account, root) = (c("akton"), c("hr"), c("acme"), c("payroll"));
+            let parts = [c("2024"), c("q1")];
+            let part_ptrs: Vec<*const c_char> = parts.iter().map(|p| p.as_ptr()).collect();
+            let arn = arn_build(
+                domain.as_ptr(),
+                category.as_ptr(),
+                account.as_ptr(),
+                root.as_ptr(),
+                part_ptrs.as_ptr(),
+                part_ptrs.len(),
+                ptr::null_mut(),
+            );
+            let printed = take_string(arn_to_string(arn));
+            assert!(printed.starts_with("arn:akton:hr:acme:payroll_"));
+            assert!(printed.ends_with("/2024/q1"));
+            arn_free(arn);
+
+            let mut error = ptr::null_mut();
+            let empty = c("");
+            let failed = arn_build(
+                empty.as_ptr(),
+                category.as_ptr(),
+                account.as_ptr(),
+                root.as_ptr(),
+                ptr::null(),
+                0,
+                &mut error,
+            );
+            assert!(failed.is_null());
+            assert!(take_string(error).contains("Domain"));
+        }
+    }
+}
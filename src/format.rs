@@ -0,0 +1,380 @@
+use crate::errors::ArnError;
+use crate::model::Arn;
+use std::borrow::Cow;
+use std::fmt;
+use std::io;
+
+/// The scheme written by `Display` and [`ArnFormat::DEFAULT`].
+pub(crate) const CANONICAL_SCHEME: &str = "arn";
+
+/// Schemes the canonical parser accepts: `arn` and the `ern` and `eid` schemes other Akton
+/// crates write.
+pub const KNOWN_SCHEMES: [&str; 3] = [CANONICAL_SCHEME, "ern", "eid"];
+
+/// The grammar an Arn is written in: its scheme and the separators between components and
+/// between the root and its parts.
+///
+/// [`ArnFormat::DEFAULT`] is the canonical `arn:domain:category:account:root/part` grammar
+/// that `Display` and [`ArnParser`](crate::ArnParser) use; a format with the `arn` scheme
+/// also reads the other [`KNOWN_SCHEMES`]. Other formats let the crate read and write the
+/// identifiers of legacy in-house systems while they are migrated. The version suffix (`@v3`)
+/// and extensions (`?k=v`) are written the same way in every format.
+///
+/// ```
+/// use akton_arn::{ArnFormat, ArnParser};
+///
+/// let legacy = ArnFormat::new()
+///     .with_scheme("res").unwrap()
+///     .with_component_separator('|').unwrap()
+///     .with_part_separator('.').unwrap();
+/// let arn = ArnParser::new("res|akton|hr|acme|root.teams.a")
+///     .with_format(legacy.clone())
+///     .parse()
+///     .unwrap();
+/// assert_eq!(arn.to_string(), "arn:akton:hr:acme:root/teams/a");
+/// assert_eq!(arn.to_string_with(&legacy).unwrap(), "res|akton|hr|acme|root.teams.a");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ArnFormat {
+    scheme: Cow<'static, str>,
+    component_separator: char,
+    part_separator: char,
+}
+
+impl Default for ArnFormat {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl ArnFormat {
+    /// The canonical grammar: scheme `arn`, components separated by `:`, parts by `/`.
+    pub const DEFAULT: ArnFormat = ArnFormat {
+        scheme: Cow::Borrowed(CANONICAL_SCHEME),
+        component_separator: ':',
+        part_separator: '/',
+    };
+
+    /// Creates the canonical format, to be adjusted with the `with_` methods.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Writes `scheme` in place of `arn`.
+    ///
+    /// Fails if `scheme` is empty or contains either separator, `@` or `?`.
+    pub fn with_scheme(mut self, scheme: impl Into<Cow<'static, str>>) -> Result<Self, ArnError> {
+        let scheme = scheme.into();
+        if scheme.is_empty()
+            || scheme.contains([self.component_separator, self.part_separator, '@', '?'])
+        {
+            return Err(ArnError::ParseFailure(
+                "ArnFormat",
+                format!("{scheme:?} is not a valid scheme"),
+            ));
+        }
+        self.scheme = scheme;
+        Ok(self)
+    }
+
+    /// Separates the scheme, domain, category, account and root with `separator`.
+    pub fn with_component_separator(mut self, separator: char) -> Result<Self, ArnError> {
+        self.check_separator(separator, self.part_separator)?;
+        self.component_separator = separator;
+        Ok(self)
+    }
+
+    /// Separates the root and each part with `separator`.
+    pub fn with_part_separator(mut self, separator: char) -> Result<Self, ArnError> {
+        self.check_separator(separator, self.component_separator)?;
+        self.part_separator = separator;
+        Ok(self)
+    }
+
+    /// Returns the scheme written before the domain.
+    pub fn scheme(&self) -> &str {
+        &self.scheme
+    }
+
+    /// Returns the separator between components.
+    pub fn component_separator(&self) -> char {
+        self.component_separator
+    }
+
+    /// Returns the separator between the root and each part.
+    pub fn part_separator(&self) -> char {
+        self.part_separator
+    }
+
+    /// Rejects separators that would make the grammar ambiguous.
+    fn check_separator(&self, separator: char, other: char) -> Result<(), ArnError> {
+        if separator == other
+            || separator.is_alphanumeric()
+            || separator.is_whitespace()
+            || ['@', '?', '&', '='].contains(&separator)
+            || self.scheme.contains(separator)
+        {
+            return Err(ArnError::ParseFailure(
+                "ArnFormat",
+                format!("{separator:?} cannot separate the components of an Arn"),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the scheme to record on an Arn written with `scheme`, or `None` if this format
+    /// does not read it.
+    pub(crate) fn detect_scheme(&self, scheme: &str) -> Option<Cow<'static, str>> {
+        if scheme == self.scheme {
+            return Some(self.scheme.clone());
+        }
+        if self.scheme != CANONICAL_SCHEME {
+            return None;
+        }
+        KNOWN_SCHEMES
+            .into_iter()
+            .find(|known| *known == scheme)
+            .map(Cow::Borrowed)
+    }
+
+    /// Splits text written like an Arn in this format, such as a template, route or migration
+    /// prefix, into the domain, category and account, then the root and each part.
+    ///
+    /// The text may stop after any component. Fails with [`ArnError::InvalidFormat`] unless
+    /// it starts with a scheme this format reads.
+    pub(crate) fn split_segments<'t>(&self, text: &'t str) -> Result<Vec<&'t str>, ArnError> {
+        let (scheme, rest) = text
+            .split_once(self.component_separator)
+            .ok_or(ArnError::InvalidFormat)?;
+        self.detect_scheme(scheme).ok_or(ArnError::InvalidFormat)?;
+        let mut segments: Vec<&str> = rest.splitn(4, self.component_separator).collect();
+        if segments.len() == 4 {
+            let resource = segments.pop().unwrap_or_default();
+            segments.extend(resource.split(self.part_separator));
+        }
+        Ok(segments)
+    }
+
+    /// Writes `arn` in this format without checking that it reads back.
+    pub(crate) fn write(&self, arn: &Arn, f: &mut impl fmt::Write) -> fmt::Result {
+        let c = self.component_separator;
+        write!(
+            f,
+            "{}{c}{}{c}{}{c}{}{c}{}",
+            self.scheme, arn.domain, arn.category, arn.account, arn.root
+        )?;
+        for part in arn.parts.0.iter() {
+            write!(f, "{}{part}", self.part_separator)?;
+        }
+        if let Some(version) = arn.version {
+            write!(f, "@v{version}")?;
+        }
+        if !arn.extensions.is_empty() {
+            write!(f, "?{}", arn.extensions)?;
+        }
+        Ok(())
+    }
+}
+
+impl Arn<'_> {
+    /// Writes the Arn in `format` rather than the canonical grammar `Display` uses.
+    ///
+    /// Fails if a component contains one of the format's separators, since the result
+    /// could not be parsed back into the same Arn.
+    pub fn to_string_with(&self, format: &ArnFormat) -> Result<String, ArnError> {
+        let separators = [format.component_separator, format.part_separator];
+        let components = [
+            ("Domain", self.domain.as_str()),
+            ("Category", self.category.as_str()),
+            ("Account", self.account.as_str()),
+            ("Root", self.root.as_str()),
+        ];
+        let parts = self.parts.0.iter().map(|part| ("Part", part.as_str()));
+        if let Some((kind, value)) = components
+            .into_iter()
+            .chain(parts)
+            .find(|(_, value)| value.contains(separators))
+        {
+            return Err(ArnError::ParseFailure(
+                kind,
+                format!("{value:?} contains a separator of the format"),
+            ));
+        }
+        let mut out = String::new();
+        format
+            .write(self, &mut out)
+            .expect("writing to a String cannot fail");
+        Ok(out)
+    }
+
+    /// Writes the Arn as `Display` does, into any `fmt::Write` such as a log line being built.
+    pub fn write_to(&self, out: &mut impl fmt::Write) -> fmt::Result {
+        ArnFormat::DEFAULT.write(self, out)
+    }
+
+    /// Writes the Arn as `Display` does, straight into a byte sink such as a socket or file.
+    pub fn write_to_io(&self, out: &mut impl io::Write) -> io::Result<()> {
+        write!(out, "{self}")
+    }
+
+    /// Writes the Arn as `Display` does into the front of `buf`, returning the bytes written.
+    ///
+    /// Fails with [`ArnError::TooLong`] if `buf` is too short, in which case its contents are
+    /// unspecified. [`Arn::rendered_len`] gives the size needed.
+    pub fn write_to_slice(&self, buf: &mut [u8]) -> Result<usize, ArnError> {
+        let mut writer = SliceWriter { buf, len: 0 };
+        match self.write_to(&mut writer) {
+            Ok(()) => Ok(writer.len),
+            Err(_) => Err(ArnError::TooLong(self.rendered_len(), writer.buf.len())),
+        }
+    }
+
+    /// Returns the number of bytes `Display` writes for this Arn, without allocating them.
+    pub fn rendered_len(&self) -> usize {
+        let mut counter = ByteCounter(0);
+        self.write_to(&mut counter).expect("counting cannot fail");
+        counter.0
+    }
+}
+
+/// Measures formatted output without allocating it.
+struct ByteCounter(usize);
+
+impl fmt::Write for ByteCounter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 += s.len();
+        Ok(())
+    }
+}
+
+/// Fills a caller's buffer, failing once it runs out of room.
+struct SliceWriter<'b> {
+    buf: &'b mut [u8],
+    len: usize,
+}
+
+impl fmt::Write for SliceWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        let dest = self.buf.get_mut(self.len..end).ok_or(fmt::Error)?;
+        dest.copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArnParser;
+
+    #[test]
+    fn test_custom_format_round_trips() {
+        let format = ArnFormat::new()
+            .with_scheme("urn")
+            .unwrap()
+            .with_component_separator('|')
+            .unwrap()
+            .with_part_separator('.')
+            .unwrap();
+        let arn = ArnParser::new("arn:akton:hr:acme:root/a/b@v2?k=v")
+            .parse()
+            .unwrap();
+        let legacy = arn.to_string_with(&format).unwrap();
+        assert_eq!(legacy, "urn|akton|hr|acme|root.a.b@v2?k=v");
+        let parsed = ArnParser::new(legacy)
+            .with_format(format.clone())
+            .parse()
+            .unwrap();
+        assert_eq!(parsed, arn);
+        assert_eq!(
+            arn.to_string_with(&ArnFormat::DEFAULT).unwrap(),
+            arn.to_string()
+        );
+        assert!(ArnParser::new(arn.to_string())
+            .with_format(format)
+            .parse()
+            .is_err());
+    }
+
+    #[test]
+    fn test_known_schemes_are_detected() {
+        let canonical = ArnParser::new("arn:akton:hr:acme:root/a").parse().unwrap();
+        for scheme in KNOWN_SCHEMES {
+            let arn = ArnParser::new(format!("{scheme}:akton:hr:acme:root/a"))
+                .parse()
+                .unwrap();
+            assert_eq!(arn.scheme(), scheme);
+            assert_eq!(arn, canonical);
+            assert_eq!(arn.to_string(), canonical.to_string());
+        }
+        assert!(ArnParser::new("urn:akton:hr:acme:root").parse().is_err());
+
+        let pipes = ArnFormat::new().with_component_separator('|').unwrap();
+        let arn = ArnParser::new("ern|akton|hr|acme|root")
+            .with_format(pipes)
+            .parse()
+            .unwrap();
+        assert_eq!(arn.scheme(), "ern");
+        let custom = ArnFormat::new().with_scheme("res").unwrap();
+        assert!(ArnParser::new("ern:akton:hr:acme:root")
+            .with_format(custom.clone())
+            .parse()
+            .is_err());
+        let arn = ArnParser::new("res:akton:hr:acme:root")
+            .with_format(custom)
+            .parse()
+            .unwrap();
+        assert_eq!(arn.scheme(), "res");
+    }
+
+    #[test]
+    fn test_ambiguous_formats_are_rejected() {
+        let format = ArnFormat::new();
+        assert!(format.clone().with_scheme("").is_err());
+        assert!(format.clone().with_scheme("a:b").is_err());
+        assert!(format.clone().with_component_separator('/').is_err());
+        assert!(format.clone().with_part_separator('x').is_err());
+        assert!(format.clone().with_part_separator('@').is_err());
+        assert!(format
+            .clone()
+            .with_scheme("a.b")
+            .unwrap()
+            .with_part_separator('.')
+            .is_err());
+
+        let dotted = format.with_part_separator('.').unwrap();
+        let arn = ArnParser::new("arn:akton:hr:acme:root/v1.2")
+            .parse()
+            .unwrap();
+        assert!(matches!(
+            arn.to_string_with(&dotted),
+            Err(ArnError::ParseFailure("Part", _))
+        ));
+    }
+
+    #[test]
+    fn test_writers_match_display() {
+        let arn = ArnParser::new("arn:akton:hr:acme:root/teams@v2?region=eu")
+            .parse()
+            .unwrap();
+        let expected = arn.to_string();
+        assert_eq!(arn.rendered_len(), expected.len());
+
+        let mut line = String::from("granted ");
+        arn.write_to(&mut line).unwrap();
+        assert_eq!(line, format!("granted {expected}"));
+
+        let mut frame = vec![0xff];
+        arn.write_to_io(&mut frame).unwrap();
+        assert_eq!(&frame[1..], expected.as_bytes());
+
+        let mut buf = [0u8; 64];
+        let len = arn.write_to_slice(&mut buf).unwrap();
+        assert_eq!(&buf[..len], expected.as_bytes());
+        assert_eq!(
+            arn.write_to_slice(&mut buf[..10]),
+            Err(ArnError::TooLong(expected.len(), 10))
+        );
+    }
+}
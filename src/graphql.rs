@@ -0,0 +1,71 @@
+//! `async-graphql` support, enabled with the `async-graphql` feature.
+//!
+//! Arns are exposed as a custom `Arn` scalar carried as its canonical string. Input is
+//! validated with [`ArnParser`], and malformed values are rejected with the parser's error
+//! message.
+
+use crate::model::Arn;
+use crate::parser::ArnParser;
+use async_graphql::{InputValueError, InputValueResult, Scalar, ScalarType, Value};
+
+/// The `Arn` GraphQL scalar. Parsed values are owned, so any lifetime can be produced.
+#[Scalar(name = "Arn")]
+impl<'a> ScalarType for Arn<'a> {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        match value {
+            Value::String(s) => ArnParser::new(s)
+                .parse()
+                .map_err(|e| InputValueError::custom(format!("invalid Arn: {e}"))),
+            other => Err(InputValueError::expected_type(other)),
+        }
+    }
+
+    fn is_valid(value: &Value) -> bool {
+        matches!(value, Value::String(_))
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_graphql::{EmptyMutation, EmptySubscription, Object, Schema};
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn parent(&self, arn: Arn<'static>) -> Option<Arn<'static>> {
+            arn.parent()
+        }
+    }
+
+    fn schema() -> Schema<Query, EmptyMutation, EmptySubscription> {
+        Schema::new(Query, EmptyMutation, EmptySubscription)
+    }
+
+    #[tokio::test]
+    async fn test_arn_scalar_round_trip() {
+        let response = schema()
+            .execute(r#"{ parent(arn: "arn:akton:hr:acme:root/team/alice") }"#)
+            .await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(
+            response.data.into_json().unwrap()["parent"],
+            "arn:akton:hr:acme:root/team"
+        );
+        assert!(schema().sdl().contains("scalar Arn"));
+    }
+
+    #[tokio::test]
+    async fn test_malformed_arn_is_rejected() {
+        let response = schema().execute(r#"{ parent(arn: "not-an-arn") }"#).await;
+        assert_eq!(response.errors.len(), 1);
+        assert!(response.errors[0].message.contains("invalid Arn"));
+        let response = schema().execute(r#"{ parent(arn: 42) }"#).await;
+        assert_eq!(response.errors.len(), 1);
+    }
+}
@@ -0,0 +1,682 @@
+use crate::errors::ArnError;
+use crate::model::{Arn, Part, Root, AKTON};
+use crate::time_source::{SystemClock, TimeSource};
+use std::sync::Mutex;
+use type_safe_id::{DynamicType, TypeSafeId};
+use uuid::{Builder, Uuid};
+
+/// What an [`IdGenerator`] knows about the root it is minting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdScope<'s> {
+    pub domain: &'s str,
+    pub category: &'s str,
+    pub account: &'s str,
+    /// The root's name, which becomes its TypeID prefix.
+    pub name: &'s str,
+}
+
+impl<'s> IdScope<'s> {
+    /// Creates a scope for a root named `name` outside of any Arn.
+    pub fn named(name: &'s str) -> Self {
+        IdScope {
+            domain: "",
+            category: "",
+            account: "",
+            name,
+        }
+    }
+
+    /// Returns the root's name, `akton` when unnamed, and its TypeID prefix.
+    fn prefix(&self) -> Result<(&'s str, DynamicType), ArnError> {
+        let name = if self.name.is_empty() {
+            AKTON
+        } else {
+            self.name
+        };
+        Ok((name, DynamicType::new(name)?))
+    }
+}
+
+/// Chooses the UUID embedded in each new root.
+///
+/// Every [`TimeSource`] is a generator minting UUIDv7 ids from its clock and random bytes, as
+/// [`Root::new`] does with the wall clock. [`MonotonicGenerator`] keeps ids strictly increasing
+/// when the clock stalls or goes backwards, [`Snowflake`](crate::Snowflake) embeds
+/// cluster-unique ids, and [`NamespaceGenerator`] derives the same id for the same resource. Install one with
+/// [`ArnBuilder::with_id_generator`](crate::ArnBuilder::with_id_generator) or call
+/// [`Root::generate`] directly.
+pub trait IdGenerator: Send + Sync {
+    /// Returns the UUID for a new root in `scope`.
+    fn generate(&self, scope: &IdScope<'_>) -> Uuid;
+
+    /// Returns how many of the least significant bits are the same in every UUID minted.
+    ///
+    /// [`RootSuffix::Truncated`] suffixes end just above these bits, so they keep bits that
+    /// tell ids apart. The default, 0, suits ids whose low bits are random.
+    fn fixed_low_bits(&self) -> u32 {
+        0
+    }
+}
+
+impl<T: TimeSource> IdGenerator for T {
+    fn generate(&self, _scope: &IdScope<'_>) -> Uuid {
+        let millis = self.now().as_millis() as u64;
+        Builder::from_unix_timestamp_millis(millis, &self.random_bytes()).into_uuid()
+    }
+}
+
+/// Mints UUIDv7 ids that strictly increase, even when the clock stalls or goes backwards.
+///
+/// While the clock reads no later than the last id, the generator keeps the last timestamp and
+/// counts up in the 74 bits UUIDv7 leaves to randomness, as RFC 9562 suggests. Each new
+/// millisecond starts from fresh random bytes with the top counter bit clear, leaving room to
+/// count. Clock and randomness both come from the wrapped [`TimeSource`], so a deterministic
+/// source yields the same ids on every run.
+///
+/// ```
+/// use akton_arn::{IdGenerator, IdScope, MonotonicGenerator, SimulatedClock};
+/// use std::time::Duration;
+///
+/// let generator = MonotonicGenerator::new(SimulatedClock::new(Duration::from_secs(1_700_000_000)));
+/// let scope = IdScope::named("order");
+/// let first = generator.generate(&scope);
+/// assert!(generator.generate(&scope) > first, "same millisecond, still increasing");
+/// ```
+#[derive(Debug, Default)]
+pub struct MonotonicGenerator<T = SystemClock> {
+    time_source: T,
+    /// The timestamp and counter of the last id minted.
+    last: Mutex<(u64, u128)>,
+}
+
+/// Bits of a UUIDv7 that are not timestamp, version or variant.
+const COUNTER_BITS: u32 = 74;
+
+impl<T: TimeSource> MonotonicGenerator<T> {
+    /// Creates a generator reading `time_source`.
+    pub fn new(time_source: T) -> Self {
+        MonotonicGenerator {
+            time_source,
+            last: Mutex::new((0, 0)),
+        }
+    }
+
+    fn fresh_counter(&self) -> u128 {
+        let mut bytes = [0; 16];
+        bytes[6..].copy_from_slice(&self.time_source.random_bytes());
+        u128::from_be_bytes(bytes) & ((1 << (COUNTER_BITS - 1)) - 1)
+    }
+}
+
+impl<T: TimeSource> IdGenerator for MonotonicGenerator<T> {
+    fn generate(&self, _scope: &IdScope<'_>) -> Uuid {
+        let now = self.time_source.now().as_millis() as u64;
+        let mut last = self.last.lock().unwrap();
+        let (last_millis, last_counter) = *last;
+        *last = if now > last_millis {
+            (now, self.fresh_counter())
+        } else if last_counter + 1 < 1 << COUNTER_BITS {
+            (last_millis, last_counter + 1)
+        } else {
+            (last_millis + 1, self.fresh_counter())
+        };
+        let (millis, counter) = *last;
+        Uuid::from_u128(
+            (u128::from(millis) << 80)
+                | (0x7 << 76)
+                | ((counter >> 62) << 64)
+                | (0b10 << 62)
+                | (counter & ((1 << 62) - 1)),
+        )
+    }
+}
+
+/// Namespace [`NamespaceGenerator::default`] derives root ids in: the UUIDv5 of the URL
+/// `https://github.com/GovCraft/akton-arn` in the RFC 9562 URL namespace.
+pub const ARN_NAMESPACE: Uuid = Uuid::from_u128(0x3d7f40bb_12f9_52a7_aea1_19caae74ffe1);
+
+/// Derives each root id as a UUIDv5 of its domain, category, account and name.
+///
+/// The same logical resource always gets the same root, in every service and on every run, so
+/// creating it twice is idempotent. The name hashed is `domain:category:account:name` within
+/// the generator's namespace; services that must not share ids use different namespaces.
+///
+/// ```
+/// use akton_arn::{ArnBuilder, Category, Account, Domain, NamespaceGenerator, Root};
+///
+/// let build = || {
+///     ArnBuilder::new()
+///         .with_id_generator(NamespaceGenerator::default())
+///         .with::<Domain>("akton").unwrap()
+///         .with::<Category>("hr").unwrap()
+///         .with::<Account>("acme").unwrap()
+///         .with::<Root>("payroll").unwrap()
+///         .build()
+///         .unwrap()
+/// };
+/// assert_eq!(build(), build());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NamespaceGenerator {
+    namespace: Uuid,
+}
+
+impl NamespaceGenerator {
+    /// Creates a generator deriving ids within `namespace`.
+    pub fn new(namespace: Uuid) -> Self {
+        NamespaceGenerator { namespace }
+    }
+}
+
+impl Default for NamespaceGenerator {
+    fn default() -> Self {
+        Self::new(ARN_NAMESPACE)
+    }
+}
+
+impl IdGenerator for NamespaceGenerator {
+    fn generate(&self, scope: &IdScope<'_>) -> Uuid {
+        let name = format!(
+            "{}:{}:{}:{}",
+            scope.domain, scope.category, scope.account, scope.name
+        );
+        Uuid::new_v5(&self.namespace, name.as_bytes())
+    }
+}
+
+/// Length of the base32 suffix of a TypeID.
+const SUFFIX_LEN: usize = 26;
+
+/// How much of the generated id a root keeps, set through [`RootConfig::with_suffix`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RootSuffix {
+    /// The whole 26-character id, so the root is a TypeID.
+    #[default]
+    Full,
+    /// Only `n` characters of the id, between 1 and 26, from its least significant end.
+    ///
+    /// The suffix holds the id's least significant bits that vary, as
+    /// [`IdGenerator::fixed_low_bits`] tells: the random ones for time-based ids, the sequence
+    /// and node for [`Snowflake`](crate::Snowflake) ids. Each character carries 5 bits, so
+    /// short suffixes collide sooner.
+    Truncated(usize),
+    /// No id at all: the root is its name, and two roots with the same name are equal.
+    Omitted,
+}
+
+/// How a generated id is joined to a root's name.
+///
+/// The default separator `_` with a [`RootSuffix::Full`] suffix yields TypeIDs such as
+/// `order_01h455vb4pex5vsknk084sn02q`, which [`Root::kind`] recognizes as generated and
+/// [`Root::created_at`] decodes. Any other configuration matches an existing naming
+/// convention at the cost of that decoding: such roots read back as
+/// [`RootKind::Named`](crate::RootKind::Named).
+///
+/// ```
+/// use akton_arn::{IdScope, Root, RootConfig, RootSuffix, SystemClock};
+///
+/// let config = RootConfig::new()
+///     .with_separator('-').unwrap()
+///     .with_suffix(RootSuffix::Truncated(8)).unwrap();
+/// let root = Root::generate_with_config(&IdScope::named("order"), &SystemClock, &config).unwrap();
+/// assert!(root.as_str().starts_with("order-"));
+/// assert_eq!(root.as_str().len(), "order-".len() + 8);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RootConfig {
+    separator: char,
+    suffix: RootSuffix,
+}
+
+impl Default for RootConfig {
+    fn default() -> Self {
+        RootConfig {
+            separator: '_',
+            suffix: RootSuffix::Full,
+        }
+    }
+}
+
+impl RootConfig {
+    /// Creates the default configuration, which mints TypeIDs.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Joins the name and the id with `separator`.
+    ///
+    /// Fails if `separator` is a letter, digit or whitespace, which would blur where the name
+    /// ends, or a character that delimits another part of an Arn.
+    pub fn with_separator(mut self, separator: char) -> Result<Self, ArnError> {
+        if separator.is_alphanumeric()
+            || separator.is_whitespace()
+            || [':', '/', '?', '@', '&', '='].contains(&separator)
+        {
+            return Err(ArnError::ParseFailure(
+                "RootConfig",
+                format!("{separator:?} cannot separate a root's name from its id"),
+            ));
+        }
+        self.separator = separator;
+        Ok(self)
+    }
+
+    /// Keeps `suffix` of the generated id.
+    ///
+    /// Fails if a [`RootSuffix::Truncated`] length is not between 1 and 26.
+    pub fn with_suffix(mut self, suffix: RootSuffix) -> Result<Self, ArnError> {
+        if let RootSuffix::Truncated(len) = suffix {
+            if !(1..=SUFFIX_LEN).contains(&len) {
+                return Err(ArnError::ParseFailure(
+                    "RootConfig",
+                    format!("suffix length {len} must be between 1 and {SUFFIX_LEN}"),
+                ));
+            }
+        }
+        self.suffix = suffix;
+        Ok(self)
+    }
+
+    /// Returns the separator between name and id.
+    pub fn separator(&self) -> char {
+        self.separator
+    }
+
+    /// Returns how much of the id is kept.
+    pub fn suffix(&self) -> RootSuffix {
+        self.suffix
+    }
+}
+
+impl Root<'static> {
+    /// Mints a root named after `scope` (or `akton` when unnamed) with an id from `generator`.
+    pub fn generate(scope: &IdScope<'_>, generator: &dyn IdGenerator) -> Result<Self, ArnError> {
+        Self::generate_with_config(scope, generator, &RootConfig::default())
+    }
+
+    /// Mints a root like [`Root::generate`], joining name and id as `config` says.
+    ///
+    /// The name follows TypeID prefix rules whatever the configuration, and the id is only
+    /// drawn from `generator` when the suffix keeps some of it.
+    pub fn generate_with_config(
+        scope: &IdScope<'_>,
+        generator: &dyn IdGenerator,
+        config: &RootConfig,
+    ) -> Result<Self, ArnError> {
+        let (name, prefix) = scope.prefix()?;
+        if config.suffix == RootSuffix::Omitted {
+            return Ok(Root(name.to_string().into()));
+        }
+        let uuid = generator.generate(scope);
+        Ok(Self::join(
+            name,
+            prefix,
+            uuid,
+            generator.fixed_low_bits(),
+            config,
+        ))
+    }
+
+    /// Joins `name` and the TypeID of `uuid` under `prefix` as `config` says; the suffix is
+    /// not [`RootSuffix::Omitted`].
+    fn join(
+        name: &str,
+        prefix: DynamicType,
+        uuid: Uuid,
+        fixed_low_bits: u32,
+        config: &RootConfig,
+    ) -> Self {
+        let keep = match config.suffix {
+            RootSuffix::Truncated(len) => len,
+            RootSuffix::Full | RootSuffix::Omitted => SUFFIX_LEN,
+        };
+        // Each character of the id encodes 5 bits; skip those encoding only fixed bits.
+        let fixed = (fixed_low_bits / 5) as usize;
+        let id = TypeSafeId::from_type_and_uuid(prefix, uuid).to_string();
+        let end = id.len() - fixed.min(SUFFIX_LEN - keep);
+        let suffix = &id[end - keep..end];
+        Root(format!("{name}{}{suffix}", config.separator).into())
+    }
+}
+
+impl<'a> Arn<'a> {
+    /// Mints `count` children of this Arn, each with one more part `<prefix>_<id>`, for
+    /// allocating names ahead of a bulk import.
+    ///
+    /// The ids come from one [`MonotonicGenerator`] over the wall clock, so they are unique
+    /// within the batch and the children are returned in ascending order, even when the
+    /// whole batch falls within one millisecond. The prefix follows TypeID rules and is
+    /// checked once for the batch; an empty prefix becomes `akton`.
+    ///
+    /// ```
+    /// use akton_arn::Arn;
+    ///
+    /// let imports = Arn::with_root("root").unwrap().add_part("imports").unwrap();
+    /// let batch = imports.generate_batch("order", 1_000).unwrap();
+    /// assert_eq!(batch.len(), 1_000);
+    /// assert!(batch.windows(2).all(|pair| pair[0] < pair[1]));
+    /// assert!(batch.iter().all(|arn| arn.is_child_of(&imports)));
+    /// assert!(batch[0].parts().last().unwrap().as_str().starts_with("order_"));
+    /// ```
+    pub fn generate_batch(&self, prefix: &str, count: usize) -> Result<Vec<Arn<'a>>, ArnError> {
+        self.generate_batch_with_time_source(prefix, count, SystemClock)
+    }
+
+    /// Like [`Arn::generate_batch`], but takes time and randomness from `time_source`, so
+    /// simulations mint the same batch on every run.
+    pub fn generate_batch_with_time_source(
+        &self,
+        prefix: &str,
+        count: usize,
+        time_source: impl TimeSource,
+    ) -> Result<Vec<Arn<'a>>, ArnError> {
+        let generator = MonotonicGenerator::new(time_source);
+        let scope = IdScope {
+            domain: self.domain.as_str(),
+            category: self.category.as_str(),
+            account: self.account.as_str(),
+            name: prefix,
+        };
+        let (_, prefix) = scope.prefix()?;
+        let mut batch = Vec::with_capacity(count);
+        for _ in 0..count {
+            let id = TypeSafeId::from_type_and_uuid(prefix.clone(), generator.generate(&scope));
+            let mut parts = self.parts.clone();
+            parts.0.push(Part::from_shared(id.to_string().into()));
+            batch.push(Arn::new(
+                self.domain.clone(),
+                self.category.clone(),
+                self.account.clone(),
+                self.root.clone(),
+                parts,
+            ));
+        }
+        Ok(batch)
+    }
+}
+
+/// Chooses the UUID embedded in each new root from a source that may need to wait, such as a
+/// coordination service handing out counter ranges to a Snowflake-like generator.
+///
+/// Every [`IdGenerator`] is an `AsyncIdGenerator` that never waits. Mint roots with
+/// [`Root::new_async`] or [`Root::generate_async`], which await the source instead of blocking
+/// the executor.
+///
+/// ```
+/// use akton_arn::{ArnError, AsyncIdGenerator, IdScope, Root};
+/// use akton_arn::Uuid;
+/// use std::sync::atomic::{AtomicU64, Ordering};
+///
+/// /// Numbers roots from blocks leased from a coordinator.
+/// struct Leased(AtomicU64);
+///
+/// async fn lease_block() -> Result<u64, ArnError> {
+///     Ok(1 << 20) // a request to the coordination service
+/// }
+///
+/// impl AsyncIdGenerator for Leased {
+///     async fn generate_async(&self, _scope: &IdScope<'_>) -> Result<Uuid, ArnError> {
+///         let block = lease_block().await?;
+///         Ok(Uuid::from_u64_pair(block, self.0.fetch_add(1, Ordering::Relaxed)))
+///     }
+/// }
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let root = Root::new_async("order", &Leased(AtomicU64::new(0))).await.unwrap();
+/// assert!(root.as_str().starts_with("order_"));
+/// # });
+/// ```
+#[cfg(feature = "tokio")]
+pub trait AsyncIdGenerator: Send + Sync {
+    /// Returns the UUID for a new root in `scope`, failing if the source cannot provide one.
+    fn generate_async(
+        &self,
+        scope: &IdScope<'_>,
+    ) -> impl std::future::Future<Output = Result<Uuid, ArnError>> + Send;
+
+    /// Returns how many of the least significant bits are the same in every UUID minted, as
+    /// [`IdGenerator::fixed_low_bits`] does.
+    fn fixed_low_bits(&self) -> u32 {
+        0
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<G: IdGenerator> AsyncIdGenerator for G {
+    async fn generate_async(&self, scope: &IdScope<'_>) -> Result<Uuid, ArnError> {
+        Ok(self.generate(scope))
+    }
+
+    fn fixed_low_bits(&self) -> u32 {
+        IdGenerator::fixed_low_bits(self)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl Root<'static> {
+    /// Mints a root named `value` (or `akton` when empty) with an id awaited from `generator`.
+    pub async fn new_async(
+        value: &str,
+        generator: &(impl AsyncIdGenerator + ?Sized),
+    ) -> Result<Self, ArnError> {
+        Self::generate_async(&IdScope::named(value), generator).await
+    }
+
+    /// Mints a root like [`Root::generate`], awaiting the id from `generator`.
+    pub async fn generate_async(
+        scope: &IdScope<'_>,
+        generator: &(impl AsyncIdGenerator + ?Sized),
+    ) -> Result<Self, ArnError> {
+        Self::generate_with_config_async(scope, generator, &RootConfig::default()).await
+    }
+
+    /// Mints a root like [`Root::generate_with_config`], awaiting the id from `generator`.
+    ///
+    /// The generator is not consulted when the configuration omits the id.
+    pub async fn generate_with_config_async(
+        scope: &IdScope<'_>,
+        generator: &(impl AsyncIdGenerator + ?Sized),
+        config: &RootConfig,
+    ) -> Result<Self, ArnError> {
+        let (name, prefix) = scope.prefix()?;
+        if config.suffix == RootSuffix::Omitted {
+            return Ok(Root(name.to_string().into()));
+        }
+        let uuid = generator.generate_async(scope).await?;
+        let fixed_low_bits = AsyncIdGenerator::fixed_low_bits(generator);
+        Ok(Self::join(name, prefix, uuid, fixed_low_bits, config))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SimulatedClock, SteppedClock};
+    use std::time::Duration;
+
+    #[test]
+    fn test_time_sources_generate_v7_ids() {
+        let clock = SteppedClock::new(Duration::from_millis(1_000), Duration::from_millis(1));
+        let scope = IdScope::named("x");
+        let uuid = clock.generate(&scope);
+        assert_eq!(uuid.get_version_num(), 7);
+        assert_eq!(uuid.get_timestamp().unwrap().to_unix(), (1, 0));
+        assert!(clock.generate(&scope) > uuid);
+    }
+
+    #[test]
+    fn test_monotonic_generator_survives_clock_skew() {
+        let start = Duration::from_secs(1_700_000_000);
+        let generator = MonotonicGenerator::new(SimulatedClock::new(start));
+        let scope = IdScope::named("x");
+        let mut previous = generator.generate(&scope);
+        for step in 0..100 {
+            if step == 50 {
+                generator.time_source.set(start - Duration::from_secs(60));
+            }
+            let uuid = generator.generate(&scope);
+            assert!(uuid > previous, "step {step}");
+            assert_eq!(uuid.get_version_num(), 7);
+            assert_eq!(uuid.get_variant(), uuid::Variant::RFC4122);
+            previous = uuid;
+        }
+        assert_eq!(
+            previous.get_timestamp().unwrap().to_unix().0,
+            start.as_secs()
+        );
+    }
+
+    #[test]
+    fn test_monotonic_generator_carries_into_next_millisecond() {
+        let generator = MonotonicGenerator::new(SimulatedClock::new(Duration::from_millis(5)));
+        *generator.last.lock().unwrap() = (5, (1 << COUNTER_BITS) - 1);
+        let uuid = generator.generate(&IdScope::named("x"));
+        assert_eq!(uuid.as_u128() >> 80, 6);
+    }
+
+    #[test]
+    fn test_namespace_generator_is_deterministic_per_resource() {
+        let generator = NamespaceGenerator::default();
+        let scope = IdScope {
+            domain: "akton",
+            category: "hr",
+            account: "acme",
+            name: "payroll",
+        };
+        let root = Root::generate(&scope, &generator).unwrap();
+        assert_eq!(root, Root::generate(&scope, &generator).unwrap());
+        assert_eq!(generator.generate(&scope).get_version_num(), 5);
+        assert_eq!(
+            generator.generate(&scope),
+            Uuid::new_v5(&ARN_NAMESPACE, b"akton:hr:acme:payroll")
+        );
+        let other_account = IdScope {
+            account: "globex",
+            ..scope
+        };
+        assert_ne!(root, Root::generate(&other_account, &generator).unwrap());
+        let other_namespace = NamespaceGenerator::new(Uuid::NAMESPACE_OID);
+        assert_ne!(root, Root::generate(&scope, &other_namespace).unwrap());
+        assert_eq!(
+            ARN_NAMESPACE,
+            Uuid::new_v5(
+                &Uuid::NAMESPACE_URL,
+                b"https://github.com/GovCraft/akton-arn"
+            )
+        );
+    }
+
+    #[test]
+    fn test_deterministic_sources_repeat_ids() {
+        let mint = || {
+            let generator =
+                MonotonicGenerator::new(SteppedClock::new(Duration::from_secs(1), Duration::ZERO));
+            (0..3)
+                .map(|_| Root::generate(&IdScope::named("order"), &generator).unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(mint(), mint());
+        assert!(mint()[0].as_str().starts_with("order_"));
+    }
+
+    #[test]
+    fn test_root_config() {
+        let clock = SteppedClock::new(Duration::from_secs(1), Duration::from_millis(1));
+        let scope = IdScope::named("order");
+        let typeid = Root::generate(&IdScope::named("order"), &clock).unwrap();
+        assert!(typeid.is_generated());
+
+        let config = RootConfig::new()
+            .with_separator('.')
+            .unwrap()
+            .with_suffix(RootSuffix::Truncated(6))
+            .unwrap();
+        let short = Root::generate_with_config(&scope, &clock, &config).unwrap();
+        let (name, suffix) = short.as_str().split_once('.').unwrap();
+        assert_eq!(name, "order");
+        assert_eq!(suffix.len(), 6);
+        assert!(!short.is_generated());
+        assert_ne!(
+            short,
+            Root::generate_with_config(&scope, &clock, &config).unwrap()
+        );
+
+        let bare = RootConfig::new().with_suffix(RootSuffix::Omitted).unwrap();
+        let root = Root::generate_with_config(&scope, &clock, &bare).unwrap();
+        assert_eq!(root, Root::named("order").unwrap());
+        assert!(Root::generate_with_config(&IdScope::named("Order"), &clock, &bare).is_err());
+
+        for separator in ['a', '7', ' ', ':', '/', '?', '@'] {
+            assert!(
+                RootConfig::new().with_separator(separator).is_err(),
+                "{separator:?}"
+            );
+        }
+        for len in [0, 27] {
+            assert!(RootConfig::new()
+                .with_suffix(RootSuffix::Truncated(len))
+                .is_err());
+        }
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_async_generation_matches_blocking() {
+        struct Unreachable;
+        impl AsyncIdGenerator for Unreachable {
+            async fn generate_async(&self, _scope: &IdScope<'_>) -> Result<Uuid, ArnError> {
+                Err(ArnError::IdGenerationFailure(
+                    "coordinator unreachable".to_string(),
+                ))
+            }
+        }
+
+        let clock = || SteppedClock::new(Duration::from_secs(1), Duration::from_millis(1));
+        let scope = IdScope::named("order");
+        assert_eq!(
+            Root::generate_async(&scope, &clock()).await.unwrap(),
+            Root::generate(&scope, &clock()).unwrap()
+        );
+        assert!(Root::new_async("", &clock())
+            .await
+            .unwrap()
+            .as_str()
+            .starts_with("akton_"));
+        assert!(matches!(
+            Root::new_async("order", &Unreachable).await,
+            Err(ArnError::IdGenerationFailure(_))
+        ));
+        let bare = RootConfig::new().with_suffix(RootSuffix::Omitted).unwrap();
+        assert_eq!(
+            Root::generate_with_config_async(&scope, &Unreachable, &bare)
+                .await
+                .unwrap(),
+            Root::named("order").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_generate_batch_is_unique_and_ordered() {
+        let parent = Arn::with_root("root").unwrap();
+        let stalled = || SteppedClock::new(Duration::from_secs(1), Duration::ZERO);
+        let batch = parent
+            .generate_batch_with_time_source("item", 500, stalled())
+            .unwrap();
+        assert_eq!(batch.len(), 500);
+        assert!(batch.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(batch
+            .iter()
+            .all(|arn| arn.parent().as_ref() == Some(&parent)));
+        assert_eq!(
+            batch,
+            parent
+                .generate_batch_with_time_source("item", 500, stalled())
+                .unwrap()
+        );
+        assert!(parent.generate_batch("item", 0).unwrap().is_empty());
+        assert!(parent.generate_batch("Item", 1).is_err());
+        assert!(parent.generate_batch("", 1).unwrap()[0]
+            .to_string()
+            .contains("/akton_"));
+    }
+}
@@ -0,0 +1,113 @@
+use crate::model::{Account, Arn, Category, Domain};
+use elsa::FrozenMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// A pool of component strings shared by many Arns.
+///
+/// Large collections of Arns usually repeat a handful of domains, categories and accounts.
+/// [`Arn::intern`] replaces those components with borrows of a single copy held here, so a
+/// million Arns under one account store the account once. Strings are never removed, so
+/// every Arn borrowing from the interner stays valid for as long as the interner lives.
+///
+/// ```
+/// use akton_arn::{ArnParser, Interner};
+///
+/// let interner = Interner::new();
+/// let a = ArnParser::new("arn:akton:hr:acme:root/a").parse().unwrap().intern(&interner);
+/// let b = ArnParser::new("arn:akton:hr:acme:root/b").parse().unwrap().intern(&interner);
+/// assert!(std::ptr::eq(a.account().as_str(), b.account().as_str()));
+/// assert_eq!(interner.len(), 3);
+/// ```
+#[derive(Default)]
+pub struct Interner {
+    strings: FrozenMap<Arc<str>, Arc<str>>,
+}
+
+impl Interner {
+    /// Creates an empty interner.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the pooled copy of `value`, adding it on first use.
+    pub fn intern(&self, value: &str) -> &str {
+        match self.strings.get(value) {
+            Some(pooled) => pooled,
+            None => {
+                let pooled: Arc<str> = Arc::from(value);
+                self.strings.insert(pooled.clone(), pooled)
+            }
+        }
+    }
+
+    /// Returns the number of distinct strings in the pool.
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl fmt::Debug for Interner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Interner")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+impl<'a> Arn<'a> {
+    /// Replaces the domain, category and account with borrows of the copies pooled in `interner`.
+    ///
+    /// Roots and parts are usually unique per Arn and are kept as they are. Interning only
+    /// needs a shared reference, so any number of interned Arns can borrow from one interner.
+    pub fn intern<'i>(self, interner: &'i Interner) -> Arn<'i>
+    where
+        'a: 'i,
+    {
+        Arn {
+            domain: Domain(interner.intern(self.domain.as_str()).into()),
+            category: Category(interner.intern(self.category.as_str()).into()),
+            account: Account(interner.intern(self.account.as_str()).into()),
+            ..self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArnParser;
+
+    #[test]
+    fn test_interned_components_share_storage() {
+        let interner = Interner::new();
+        let inputs: Vec<String> = (0..100)
+            .map(|i| format!("arn:akton:hr:acme:root/{i}"))
+            .collect();
+        let arns: Vec<Arn> = inputs
+            .iter()
+            .map(|input| ArnParser::new(input.as_str()).parse().unwrap())
+            .map(|arn| arn.intern(&interner))
+            .collect();
+        assert_eq!(interner.len(), 3);
+        assert!(arns
+            .iter()
+            .all(|arn| std::ptr::eq(arn.domain.as_str(), arns[0].domain.as_str())));
+        assert_eq!(arns[7].to_string(), "arn:akton:hr:acme:root/7");
+    }
+
+    #[test]
+    fn test_intern_preserves_equality() {
+        let interner = Interner::new();
+        let arn = ArnParser::new("arn:akton:hr:acme:root/a/b")
+            .parse()
+            .unwrap();
+        assert_eq!(arn.clone().intern(&interner), arn);
+        assert_eq!(interner.intern("hr"), "hr");
+        assert_eq!(interner.len(), 3);
+    }
+}
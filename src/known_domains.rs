@@ -0,0 +1,183 @@
+use crate::errors::ArnError;
+use crate::model::Domain;
+use crate::traits::ArnValidator;
+use crate::ComponentKind;
+
+/// The finite set of domains an application accepts, checked when it is declared.
+///
+/// Declare the set once with [`known_domains!`](crate::known_domains), which rejects empty,
+/// duplicate or malformed domains at compile time, then register it as a validator on
+/// [`ArnParser`](crate::ArnParser) or [`ArnBuilder`](crate::ArnBuilder) so an Arn under any
+/// other domain, such as a misspelt `acton-intenral`, fails to build.
+///
+/// ```
+/// use akton_arn::{known_domains, ArnParser};
+///
+/// known_domains! {
+///     /// Every domain this service writes.
+///     pub DOMAINS = ["akton", "akton-internal"];
+/// }
+///
+/// let parse = |input| ArnParser::new(input).with_validator(DOMAINS).parse();
+/// assert!(parse("arn:akton-internal:hr:acme:root").is_ok());
+/// assert!(parse("arn:acton-intenral:hr:acme:root").is_err());
+/// assert_eq!(DOMAINS.domain("akton").unwrap().as_str(), "akton");
+/// ```
+///
+/// A bad declaration does not compile:
+///
+/// ```compile_fail
+/// akton_arn::known_domains! { DOMAINS = ["akton", "akton"]; }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KnownDomains {
+    domains: &'static [&'static str],
+}
+
+impl KnownDomains {
+    /// Creates the set, panicking if a domain is empty, contains `:`, `/`, `?` or `@`, or is
+    /// listed twice.
+    ///
+    /// In a `const`, as [`known_domains!`](crate::known_domains) declares it, the panic is a
+    /// compile error.
+    pub const fn new(domains: &'static [&'static str]) -> Self {
+        assert!(!domains.is_empty(), "at least one domain must be known");
+        let mut i = 0;
+        while i < domains.len() {
+            let bytes = domains[i].as_bytes();
+            assert!(!bytes.is_empty(), "a known domain cannot be empty");
+            let mut j = 0;
+            while j < bytes.len() {
+                assert!(
+                    !matches!(bytes[j], b':' | b'/' | b'?' | b'@'),
+                    "a known domain cannot contain ':', '/', '?' or '@'"
+                );
+                j += 1;
+            }
+            let mut k = 0;
+            while k < i {
+                assert!(
+                    !const_eq(domains[k], domains[i]),
+                    "a known domain is listed twice"
+                );
+                k += 1;
+            }
+            i += 1;
+        }
+        KnownDomains { domains }
+    }
+
+    /// Returns the domains in the order declared.
+    pub const fn domains(&self) -> &'static [&'static str] {
+        self.domains
+    }
+
+    /// Returns `true` if `domain` is known.
+    pub fn contains(&self, domain: &str) -> bool {
+        self.domains.contains(&domain)
+    }
+
+    /// Returns the known domain named `name`, failing as the validator does if it is unknown.
+    pub fn domain(&self, name: &str) -> Result<Domain<'static>, ArnError> {
+        let known = self
+            .domains
+            .iter()
+            .find(|domain| **domain == name)
+            .ok_or_else(|| {
+                ArnError::ValidationFailure(ComponentKind::Domain, self.unknown(name))
+            })?;
+        Ok(Domain::new_unchecked(*known))
+    }
+
+    fn unknown(&self, domain: &str) -> String {
+        format!(
+            "unknown domain {domain:?}, expected one of {}",
+            self.domains.join(", ")
+        )
+    }
+}
+
+impl ArnValidator for KnownDomains {
+    fn validate(&self, kind: ComponentKind, value: &str) -> Result<(), String> {
+        if kind != ComponentKind::Domain || self.contains(value) {
+            return Ok(());
+        }
+        Err(self.unknown(value))
+    }
+}
+
+const fn const_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Declares a [`KnownDomains`] constant, failing to compile if a domain is invalid.
+///
+/// ```
+/// akton_arn::known_domains! {
+///     pub(crate) DOMAINS = ["akton", "akton-internal"];
+/// }
+/// assert_eq!(DOMAINS.domains().len(), 2);
+/// ```
+#[macro_export]
+macro_rules! known_domains {
+    ($(#[$meta:meta])* $vis:vis $name:ident = [$($domain:literal),+ $(,)?];) => {
+        $(#[$meta])*
+        $vis const $name: $crate::KnownDomains = $crate::KnownDomains::new(&[$($domain),+]);
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Account, ArnBuilder, Category, Root};
+
+    known_domains! {
+        DOMAINS = ["akton", "akton-internal",];
+    }
+
+    #[test]
+    fn test_unknown_domains_fail() {
+        assert!(DOMAINS.contains("akton-internal"));
+        assert!(!DOMAINS.contains("acton-intenral"));
+        assert_eq!(
+            DOMAINS.domain("acton-intenral").unwrap_err(),
+            ArnError::ValidationFailure(
+                ComponentKind::Domain,
+                "unknown domain \"acton-intenral\", expected one of akton, akton-internal"
+                    .to_string()
+            )
+        );
+        assert_eq!(DOMAINS.validate(ComponentKind::Category, "acton"), Ok(()));
+        let built = |domain: &str| {
+            ArnBuilder::new()
+                .with_validator(DOMAINS)
+                .with::<Domain>(domain.to_string())
+                .and_then(|b| b.with::<Category>("hr"))
+                .and_then(|b| b.with::<Account>("acme"))
+                .and_then(|b| b.with::<Root>("root"))
+                .and_then(|b| b.build())
+        };
+        assert!(built("akton").is_ok());
+        assert!(matches!(
+            built("acton"),
+            Err(ArnError::ValidationFailure(ComponentKind::Domain, _))
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot contain")]
+    fn test_malformed_domain_panics_outside_const() {
+        KnownDomains::new(&["ak:ton"]);
+    }
+}
@@ -16,17 +16,95 @@
 //! - `parser`: Module for parsing Arns.
 //! - `model`: Contains the models representing different parts of an Arn.
 //! - `traits`: Traits used across the crate for common functionality.
+//! - `bulk`: Column-oriented construction of many Arns from parallel slices.
+//! - `difftest`: Differential testing of parser implementations against each other.
+//! - `perf`: Allocation counters and the performance targets they check (requires the `perf` feature).
+//! - `testing`: Proptest strategies and `Arbitrary` implementations (requires the `testing` feature).
+//! - `testserver`: An embedded HTTP registry for integration tests (requires the `testserver` feature).
 //!
 
 #![allow(missing_docs)]
 
 extern crate core;
 
+mod acl;
+#[cfg(feature = "rkyv")]
+mod archive;
+#[cfg(feature = "arena")]
+mod arena;
+mod backend;
 mod builder;
+pub mod bulk;
+mod cached;
+#[cfg(feature = "signing")]
+mod capability;
+mod case_fold;
+mod denylist;
+mod diff;
+pub mod difftest;
+mod encoding;
 mod errors;
+mod extract;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod format;
+#[cfg(feature = "async-graphql")]
+mod graphql;
+mod id_generator;
+#[cfg(feature = "interner")]
+mod interner;
+mod known_domains;
+mod limits;
+mod ltree;
+mod matcher_set;
+mod metrics;
+mod migration;
 mod model;
+mod namespace_guard;
+#[cfg(feature = "obfuscation")]
+mod obfuscation;
 mod parser;
+mod part_glob;
+mod path;
+mod pattern;
+mod pattern_analysis;
+mod pattern_matcher;
+#[cfg(feature = "perf")]
+pub mod perf;
+mod pointer;
+mod profile;
+#[cfg(feature = "proto")]
+mod proto;
+mod quota;
+pub mod raw;
+mod registry;
+mod router;
+#[cfg(feature = "schemars")]
+mod schema;
+mod scratch;
+mod sharding;
+mod short_id;
+#[cfg(feature = "signing")]
+mod signing;
+mod snowflake;
+#[cfg(feature = "tracing")]
+mod span;
+#[cfg(feature = "sqlx")]
+mod sql;
+mod stable_hash;
+pub mod stream;
+mod template;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "testserver")]
+pub mod testserver;
+mod time_bucket;
+mod time_source;
 mod traits;
+mod tree;
+mod validation_mode;
+#[cfg(feature = "clap")]
+mod value_parser;
 
 pub mod prelude {
     //! The prelude module for `akton-arn`.
@@ -34,16 +112,86 @@ pub mod prelude {
     //! This module re-exports essential traits and structures for easy use by downstream consumers.
 
     pub use super::builder::ArnBuilder;
-    pub use super::model::{Account, Arn, Category, Domain, Part, Parts};
-    pub use super::parser::ArnParser;
-    pub use super::traits::ArnComponent;
+    pub use super::model::{
+        Account, Annotations, Arn, Category, ComponentKind, Domain, OwnedArn, Part, Parts,
+    };
+    pub use super::parser::{ArnParser, OwnedArnParser};
+    pub use super::registry::ArnRegistry;
+    pub use super::traits::{ArnComponent, ArnValidator};
 }
 
 // Re-exporting the public API under the root of the crate for direct access
+pub use acl::Acl;
+#[cfg(feature = "rkyv")]
+pub use archive::{ArchivedArn, ArnResolver};
+#[cfg(feature = "arena")]
+pub use arena::{ArnArena, ArnRef};
+pub use backend::*;
 pub use builder::*;
+pub use cached::CachedArn;
+#[cfg(feature = "signing")]
+pub use capability::Capability;
+pub use case_fold::CaseFold;
+pub use denylist::{Denylist, RESERVED_WORDS};
+pub use diff::{ArnDiff, ComponentChange};
+#[cfg(feature = "serde")]
+pub use encoding::binary;
+pub use encoding::{
+    BINARY_FORMAT_VERSION, BINARY_FORMAT_VERSION_WITH_EXTENSIONS,
+    BINARY_FORMAT_VERSION_WITH_VERSION,
+};
+pub use errors::ArnError;
+pub use extract::{extract_account, extract_category, extract_domain, extract_root};
+pub use format::{ArnFormat, KNOWN_SCHEMES};
+#[cfg(feature = "tokio")]
+pub use id_generator::AsyncIdGenerator;
+pub use id_generator::{
+    IdGenerator, IdScope, MonotonicGenerator, NamespaceGenerator, RootConfig, RootSuffix,
+    ARN_NAMESPACE,
+};
+#[cfg(feature = "interner")]
+pub use interner::Interner;
+pub use known_domains::KnownDomains;
+pub use limits::{ArnLimits, LengthPolicy, MAX_ARN_LEN, MAX_COMPONENT_LEN};
+pub use matcher_set::ArnMatcherSet;
+pub use metrics::{MetricLabelOptions, MAX_METRIC_LABEL_LEN, MAX_METRIC_LABEL_PARTS};
+pub use migration::Migration;
 pub use model::*;
+pub use namespace_guard::NamespaceGuard;
+#[cfg(feature = "obfuscation")]
+pub use obfuscation::Obfuscator;
 pub use parser::*;
+pub use path::ArnPath;
+pub use pattern::{ArnPattern, ArnPatternBuilder};
+pub use pattern_analysis::Shadowed;
+pub use pattern_matcher::ArnMatcher;
+pub use profile::ArnProfile;
+#[cfg(feature = "proto")]
+pub use proto::{ArnProto, ExtensionProto};
+pub use quota::QuotaLedger;
+pub use registry::*;
+pub use router::{ArnRouter, RouteMatch};
+pub use scratch::with_scratch;
+pub use sharding::{ShardKey, ShardRing};
+#[cfg(feature = "signing")]
+pub use signing::SignedArn;
+pub use snowflake::{Snowflake, SNOWFLAKE_EPOCH, SNOWFLAKE_NODES};
+#[cfg(feature = "tracing")]
+pub use span::{ArnSpanExt, ARN_SPAN_FIELDS, REDACTED};
+pub use stable_hash::{STABLE_HASH_KEY, STABLE_HASH_VERSION};
+pub use template::{ArnTemplate, TemplateContext};
+pub use time_bucket::Granularity;
+pub use time_source::{SimulatedClock, SteppedClock, SystemClock, TimeSource};
+#[cfg(feature = "tracing")]
+#[doc(hidden)]
+pub use tracing as __tracing;
 pub use traits::*;
+pub use tree::{ArnNode, ArnTree};
+/// The UUID type returned by [`IdGenerator`], re-exported so implementations need no direct dependency.
+pub use uuid::Uuid;
+pub use validation_mode::ValidationMode;
+#[cfg(feature = "clap")]
+pub use value_parser::{ArnPatternValueParser, ArnValueParser};
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,271 @@
+use crate::errors::ArnError;
+use crate::model::Arn;
+use crate::ComponentKind;
+use std::borrow::Cow;
+
+/// Longest Arn, in bytes as `Display` writes it, accepted by [`ArnLimits::default`].
+pub const MAX_ARN_LEN: usize = 2048;
+
+/// Longest component, in bytes, accepted by [`ArnLimits::default`].
+pub const MAX_COMPONENT_LEN: usize = 256;
+
+/// What [`ArnLimits`] does with a component longer than its limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LengthPolicy {
+    /// Fail with [`ArnError::ValidationFailure`].
+    #[default]
+    Reject,
+    /// Cut the component down to its limit, on a character boundary.
+    ///
+    /// Roots are never cut, since a shortened root names a different resource, and an Arn
+    /// still longer than [`ArnLimits::max_len`] once its components fit is rejected.
+    Truncate,
+}
+
+/// Size and character limits applied by [`ArnParser`](crate::ArnParser) and
+/// [`ArnBuilder`](crate::ArnBuilder), keeping Arns safe as database keys and HTTP headers.
+///
+/// By default every component is limited to [`MAX_COMPONENT_LEN`] bytes, the whole Arn to
+/// [`MAX_ARN_LEN`] bytes, and control characters are rejected. [`ArnLimits::unlimited`]
+/// switches every check off.
+///
+/// ```
+/// use akton_arn::{ArnLimits, ArnParser, ComponentKind, LengthPolicy};
+///
+/// let limits = ArnLimits::new()
+///     .max_component_len(ComponentKind::Part, 4)
+///     .policy(LengthPolicy::Truncate);
+/// let arn = ArnParser::new("arn:akton:hr:acme:root/quarterly")
+///     .with_limits(limits)
+///     .parse()
+///     .unwrap();
+/// assert_eq!(arn.to_string(), "arn:akton:hr:acme:root/quar");
+///
+/// let long = format!("arn:akton:hr:acme:root/{}", "a".repeat(300));
+/// assert!(ArnParser::new(long).parse().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArnLimits {
+    max_len: usize,
+    /// Limits for the domain, category, account, root and each part, in that order.
+    max_component_lens: [usize; 5],
+    policy: LengthPolicy,
+    reject_control: bool,
+}
+
+impl Default for ArnLimits {
+    fn default() -> Self {
+        Self {
+            max_len: MAX_ARN_LEN,
+            max_component_lens: [MAX_COMPONENT_LEN; 5],
+            policy: LengthPolicy::Reject,
+            reject_control: true,
+        }
+    }
+}
+
+impl ArnLimits {
+    /// Creates limits of [`MAX_COMPONENT_LEN`] per component and [`MAX_ARN_LEN`] overall.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates limits that accept everything, as Arns were checked before limits existed.
+    pub fn unlimited() -> Self {
+        Self {
+            max_len: usize::MAX,
+            max_component_lens: [usize::MAX; 5],
+            policy: LengthPolicy::Reject,
+            reject_control: false,
+        }
+    }
+
+    /// Sets the longest Arn, in bytes as `Display` writes it (default [`MAX_ARN_LEN`]).
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Sets the longest value, in bytes, of `kind` (default [`MAX_COMPONENT_LEN`]).
+    ///
+    /// The limit for [`ComponentKind::Part`] applies to each part on its own.
+    pub fn max_component_len(mut self, kind: ComponentKind, max_len: usize) -> Self {
+        self.max_component_lens[index(kind)] = max_len;
+        self
+    }
+
+    /// Sets what happens to a component over its limit (default [`LengthPolicy::Reject`]).
+    pub fn policy(mut self, policy: LengthPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Checks an Arn constructed without a parser or builder against the limits.
+    ///
+    /// Nothing is truncated: every violation is an error, whatever the policy.
+    pub fn check(&self, arn: &Arn) -> Result<(), ArnError> {
+        let components = [
+            (ComponentKind::Domain, arn.domain.as_str()),
+            (ComponentKind::Category, arn.category.as_str()),
+            (ComponentKind::Account, arn.account.as_str()),
+            (ComponentKind::Root, arn.root.as_str()),
+        ];
+        let parts = arn
+            .parts
+            .0
+            .iter()
+            .map(|part| (ComponentKind::Part, part.as_str()));
+        for (kind, value) in components.into_iter().chain(parts) {
+            self.check_chars(kind, value)?;
+            let max = self.max_component_lens[index(kind)];
+            if value.len() > max {
+                return Err(too_long(kind, value.len(), max));
+            }
+        }
+        self.check_len(arn)
+    }
+
+    /// Returns `value` if it is within the limit for `kind`, or cut down to it when the policy
+    /// allows.
+    pub(crate) fn fit<'v>(
+        &self,
+        kind: ComponentKind,
+        value: Cow<'v, str>,
+    ) -> Result<Cow<'v, str>, ArnError> {
+        self.check_chars(kind, &value)?;
+        let max = self.max_component_lens[index(kind)];
+        if value.len() <= max {
+            return Ok(value);
+        }
+        if self.policy == LengthPolicy::Reject || kind == ComponentKind::Root {
+            return Err(too_long(kind, value.len(), max));
+        }
+        let end = (0..=max)
+            .rev()
+            .find(|&end| value.is_char_boundary(end))
+            .unwrap_or(0);
+        Ok(match value {
+            Cow::Borrowed(value) => Cow::Borrowed(&value[..end]),
+            Cow::Owned(mut value) => {
+                value.truncate(end);
+                Cow::Owned(value)
+            }
+        })
+    }
+
+    /// Fails with [`ArnError::TooLong`] if `arn` is written longer than the overall limit.
+    pub(crate) fn check_len(&self, arn: &Arn) -> Result<(), ArnError> {
+        if self.max_len == usize::MAX {
+            return Ok(());
+        }
+        self.check_total_len(arn.rendered_len())
+    }
+
+    /// Fails with [`ArnError::TooLong`] if `len` is over the overall limit.
+    pub(crate) fn check_total_len(&self, len: usize) -> Result<(), ArnError> {
+        if len > self.max_len {
+            return Err(ArnError::TooLong(len, self.max_len));
+        }
+        Ok(())
+    }
+
+    fn check_chars(&self, kind: ComponentKind, value: &str) -> Result<(), ArnError> {
+        if self.reject_control && value.chars().any(char::is_control) {
+            return Err(ArnError::ValidationFailure(
+                kind,
+                "contains a control character".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn index(kind: ComponentKind) -> usize {
+    match kind {
+        ComponentKind::Domain => 0,
+        ComponentKind::Category => 1,
+        ComponentKind::Account => 2,
+        ComponentKind::Root => 3,
+        ComponentKind::Part => 4,
+    }
+}
+
+fn too_long(kind: ComponentKind, len: usize, max: usize) -> ArnError {
+    ArnError::ValidationFailure(kind, format!("{len} bytes exceeds the limit of {max}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Account, ArnBuilder, ArnParser, Category, Domain, Root};
+
+    #[test]
+    fn test_default_limits() {
+        let at_limit = format!("arn:akton:hr:acme:root/{}", "a".repeat(MAX_COMPONENT_LEN));
+        assert!(ArnParser::new(at_limit.as_str()).parse().is_ok());
+        assert_eq!(
+            ArnParser::new(format!("{at_limit}a")).parse().unwrap_err(),
+            ArnError::ValidationFailure(
+                ComponentKind::Part,
+                "257 bytes exceeds the limit of 256".to_string()
+            )
+        );
+        let deep = format!("arn:akton:hr:acme:root{}", "/abcdefghi".repeat(205));
+        assert_eq!(
+            ArnParser::new(deep.as_str()).parse().unwrap_err(),
+            ArnError::TooLong(deep.len(), MAX_ARN_LEN)
+        );
+        assert!(ArnParser::new(deep)
+            .with_limits(ArnLimits::unlimited())
+            .parse()
+            .is_ok());
+        assert!(matches!(
+            ArnParser::new("arn:akton:hr:ac\u{7}me:root").parse(),
+            Err(ArnError::ValidationFailure(ComponentKind::Account, _))
+        ));
+    }
+
+    #[test]
+    fn test_truncation_keeps_char_boundaries_and_roots() {
+        let limits = ArnLimits::new()
+            .max_component_len(ComponentKind::Category, 3)
+            .max_component_len(ComponentKind::Root, 4)
+            .policy(LengthPolicy::Truncate);
+        let arn = ArnParser::new("arn:akton:h\u{e9}r:acme:root")
+            .with_limits(limits)
+            .parse()
+            .unwrap();
+        assert_eq!(arn.category.as_str(), "h\u{e9}");
+        assert!(ArnParser::new("arn:akton:hr:acme:roots")
+            .with_limits(limits)
+            .parse()
+            .is_err());
+
+        let built = ArnBuilder::new()
+            .with_limits(limits.max_component_len(ComponentKind::Root, MAX_COMPONENT_LEN))
+            .with::<Domain>("akton")
+            .unwrap()
+            .with::<Category>("human-resources".to_string())
+            .unwrap()
+            .with::<Account>("acme")
+            .unwrap();
+        let arn = built.with::<Root>("root").unwrap().build().unwrap();
+        assert_eq!(arn.category.as_str(), "hum");
+    }
+
+    #[test]
+    fn test_check_built_arns() {
+        let arn = ArnParser::new("arn:akton:hr:acme:root/abc")
+            .parse()
+            .unwrap();
+        assert!(ArnLimits::new().check(&arn).is_ok());
+        let strict = ArnLimits::new()
+            .max_component_len(ComponentKind::Part, 2)
+            .policy(LengthPolicy::Truncate);
+        assert!(strict.check(&arn).is_err());
+        assert_eq!(
+            ArnLimits::new().max_len(10).check(&arn),
+            Err(ArnError::TooLong(26, 10))
+        );
+    }
+}
@@ -0,0 +1,192 @@
+use crate::errors::ArnError;
+use crate::model::{Account, Arn, Category, Domain, Part, Parts, Root};
+use crate::parser::ComponentRules;
+use crate::ComponentKind;
+use std::borrow::Cow;
+use std::fmt::Write;
+
+/// Longest label Postgres accepts in an `ltree` path.
+const MAX_LABEL_LEN: usize = 1000;
+
+impl Arn<'_> {
+    /// Converts the Arn to a Postgres `ltree` path, enabling ancestor and descendant queries.
+    ///
+    /// The path has one label per component: domain, category, account, root, then each part,
+    /// so `a.to_ltree()? @> b.to_ltree()?` holds exactly when `a` is `b` or one of its
+    /// ancestors. Labels only use `[A-Za-z0-9_]`, which every `ltree` version accepts:
+    /// ASCII letters and digits are kept, every other byte (including `_`) becomes `_` followed
+    /// by two upper-case hex digits, and an empty component becomes a lone `_`.
+    ///
+    /// [`Arn::from_ltree`] reverses the conversion exactly. The version and extensions
+    /// qualify a resource rather than place it in the hierarchy, so they are not part of the
+    /// path. Fails if an escaped label would exceed the 1000-character `ltree` limit.
+    pub fn to_ltree(&self) -> Result<String, ArnError> {
+        let components = [
+            (ComponentKind::Domain, self.domain.as_str()),
+            (ComponentKind::Category, self.category.as_str()),
+            (ComponentKind::Account, self.account.as_str()),
+            (ComponentKind::Root, self.root.as_str()),
+        ]
+        .into_iter()
+        .chain(
+            self.parts
+                .0
+                .iter()
+                .map(|p| (ComponentKind::Part, p.as_str())),
+        );
+
+        let mut path = String::new();
+        for (i, (kind, value)) in components.enumerate() {
+            if i > 0 {
+                path.push('.');
+            }
+            let start = path.len();
+            write_label(&mut path, value);
+            if path.len() - start > MAX_LABEL_LEN {
+                return Err(ArnError::ValidationFailure(
+                    kind,
+                    format!("ltree label exceeds {MAX_LABEL_LEN} characters"),
+                ));
+            }
+        }
+        Ok(path)
+    }
+
+    /// Recovers an Arn from a path produced by [`Arn::to_ltree`].
+    ///
+    /// Components are checked as [`ArnParser`](crate::ArnParser) checks the string form, so an
+    /// escaped separator in a label is an error rather than an Arn that displays differently.
+    pub fn from_ltree(path: &str) -> Result<Arn<'static>, ArnError> {
+        let mut labels = path.split('.').map(read_label);
+        let mut next = |name: &str| {
+            labels.next().unwrap_or_else(|| {
+                Err(ArnError::DecodeFailure(format!(
+                    "ltree path is missing the {name} label"
+                )))
+            })
+        };
+        let rules = ComponentRules::default();
+        let mut component =
+            |kind: ComponentKind, name| rules.fit_component(kind, Cow::Owned(next(name)?));
+        let domain = Domain::new_unchecked(component(ComponentKind::Domain, "domain")?);
+        let category = Category::new(component(ComponentKind::Category, "category")?);
+        let account = Account::new(component(ComponentKind::Account, "account")?);
+        let root = Root(component(ComponentKind::Root, "root")?.into());
+        let parts = labels
+            .map(|label| {
+                let part = rules.fit_component(ComponentKind::Part, Cow::Owned(label?))?;
+                Ok(Part::new_unchecked(part))
+            })
+            .collect::<Result<Vec<_>, ArnError>>()?;
+        let arn = Arn::new(domain, category, account, root, Parts::new(parts));
+        rules.limits.check_len(&arn)?;
+        Ok(arn)
+    }
+}
+
+fn write_label(out: &mut String, value: &str) {
+    if value.is_empty() {
+        out.push('_');
+        return;
+    }
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() {
+            out.push(byte as char);
+        } else {
+            write!(out, "_{byte:02X}").expect("formatting into a String cannot fail");
+        }
+    }
+}
+
+fn read_label(label: &str) -> Result<String, ArnError> {
+    if label == "_" {
+        return Ok(String::new());
+    }
+    let invalid = || ArnError::DecodeFailure(format!("invalid ltree label {label:?}"));
+    let mut bytes = Vec::with_capacity(label.len());
+    let mut input = label.bytes();
+    while let Some(byte) = input.next() {
+        match byte {
+            b'_' => {
+                let hex = [
+                    input.next().ok_or_else(invalid)?,
+                    input.next().ok_or_else(invalid)?,
+                ];
+                let hex = std::str::from_utf8(&hex).map_err(|_| invalid())?;
+                bytes.push(u8::from_str_radix(hex, 16).map_err(|_| invalid())?);
+            }
+            b if b.is_ascii_alphanumeric() => bytes.push(b),
+            _ => return Err(invalid()),
+        }
+    }
+    if bytes.is_empty() {
+        return Err(invalid());
+    }
+    String::from_utf8(bytes).map_err(|e| ArnError::DecodeFailure(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArnParser;
+
+    fn arn(s: &str) -> Arn<'static> {
+        ArnParser::new(s.to_string()).parse().unwrap()
+    }
+
+    #[test]
+    fn test_ltree_escaping() {
+        let arn = arn("arn:akton-internal:hr:acme:root_01/team.a/ü");
+        let path = arn.to_ltree().unwrap();
+        assert_eq!(path, "akton_2Dinternal.hr.acme.root_5F01.team_2Ea._C3_BC");
+        assert!(path
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.'));
+        assert_eq!(Arn::from_ltree(&path).unwrap(), arn);
+    }
+
+    #[test]
+    fn test_ltree_round_trip_and_prefixes() {
+        for s in [
+            "arn:d:c:a:r",
+            "arn:d::a:r/p",
+            "arn:akton:hr:acme:root/__/_5F/a b/%",
+        ] {
+            let arn = arn(s);
+            assert_eq!(Arn::from_ltree(&arn.to_ltree().unwrap()).unwrap(), arn);
+        }
+        let parent = arn("arn:akton:hr:acme:root/team").to_ltree().unwrap();
+        let child = arn("arn:akton:hr:acme:root/team/alice").to_ltree().unwrap();
+        assert!(child.starts_with(&format!("{parent}.")));
+    }
+
+    #[test]
+    fn test_ltree_rejects_invalid_input() {
+        let long = ArnParser::new(format!("arn:d:c:a:r/{}", "-".repeat(400)))
+            .with_limits(crate::ArnLimits::unlimited())
+            .parse()
+            .unwrap();
+        assert!(matches!(
+            long.to_ltree(),
+            Err(ArnError::ValidationFailure(ComponentKind::Part, _))
+        ));
+        assert!(Arn::from_ltree("d.c.a").is_err());
+        assert!(Arn::from_ltree("d.c.a.r._Z1").is_err());
+        assert!(Arn::from_ltree("d.c.a.r.x-y").is_err());
+        assert!(Arn::from_ltree("d.c.a.r.").is_err());
+        assert!(Arn::from_ltree("d.c.a.r_40v2").is_err());
+        // An escaped separator or control character is rejected as the parser rejects it.
+        assert!(matches!(
+            Arn::from_ltree("d.c_3Ax.a.r"),
+            Err(ArnError::ParseFailure("Category", _))
+        ));
+        assert!(matches!(
+            Arn::from_ltree("d.c.a_3Ab.r"),
+            Err(ArnError::ParseFailure("Account", _))
+        ));
+        assert!(matches!(
+            Arn::from_ltree("d.c.a_1B.r"),
+            Err(ArnError::ValidationFailure(ComponentKind::Account, _))
+        ));
+    }
+}
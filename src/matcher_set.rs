@@ -0,0 +1,356 @@
+use crate::case_fold::lowercase;
+use crate::model::{category_lineage, split_attr, Arn};
+use crate::part_glob::PartGlob;
+use crate::pattern::{ArnPattern, OrgTenant, PatternToken};
+use std::collections::HashMap;
+
+/// Many [`ArnPattern`]s compiled together, to find every one an Arn matches in a single pass.
+///
+/// The patterns are merged into one automaton keyed by segment, in the manner of
+/// Aho-Corasick: patterns sharing a prefix share its states, so matching walks the Arn once
+/// and its cost grows with the Arn's length and the number of states live at a time, not
+/// with the number of patterns. Rules are identified by their position in the list given to
+/// [`ArnMatcherSet::new`].
+///
+/// ```
+/// use akton_arn::{ArnMatcherSet, ArnParser, ArnPattern};
+///
+/// let rules: Vec<ArnPattern> = [
+///     "arn:akton:hr:*:root/**",
+///     "arn:akton:hr:acme:root/payroll/**",
+///     "arn:akton:ops:*:*",
+/// ]
+/// .iter()
+/// .map(|p| p.parse().unwrap())
+/// .collect();
+/// let set = ArnMatcherSet::new(rules);
+///
+/// let arn = ArnParser::new("arn:akton:hr:acme:root/payroll/2024").parse().unwrap();
+/// assert_eq!(set.matches(&arn), [0, 1]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ArnMatcherSet {
+    start: Node,
+    len: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    exact: HashMap<String, Node>,
+    /// Literals compared without regard to case, keyed lowercased.
+    folded: HashMap<String, Node>,
+    attrs: HashMap<String, Node>,
+    /// Attribute keys compared without regard to case, keyed lowercased.
+    folded_attrs: HashMap<String, Node>,
+    /// Part globs, whether they ignore case, and the states they lead to, tried in turn.
+    globs: Vec<(PartGlob, bool, Node)>,
+    /// Categories matched with everything within them, such as `hr` for `hr.**`.
+    subtrees: HashMap<String, Node>,
+    /// Category subtrees compared without regard to case, keyed lowercased.
+    folded_subtrees: HashMap<String, Node>,
+    /// Account org and tenant patterns, whether they ignore case, and their states.
+    org_tenants: Vec<(OrgTenant, bool, Node)>,
+    /// The state after `*` or a wildcard component.
+    one: Option<Box<Node>>,
+    /// The state after `**`, which loops on every part.
+    many: Option<Box<Node>>,
+    /// Set on the state a `**` leads to.
+    looping: bool,
+    /// Rules that match an Arn ending in this state.
+    accept: Vec<usize>,
+}
+
+impl ArnMatcherSet {
+    /// Compiles `patterns`, numbering the rules from zero in the order given.
+    pub fn new(patterns: impl IntoIterator<Item = ArnPattern>) -> Self {
+        let mut set = ArnMatcherSet::default();
+        for pattern in patterns {
+            set.push(&pattern);
+        }
+        set
+    }
+
+    /// Adds `pattern` as the next rule, returning its id.
+    pub fn push(&mut self, pattern: &ArnPattern) -> usize {
+        let id = self.len;
+        let mut node = &mut self.start;
+        for token in pattern.tokens() {
+            node = match token {
+                PatternToken::Exact(value, false) => {
+                    node.exact.entry(value.to_string()).or_default()
+                }
+                PatternToken::Exact(value, true) => {
+                    node.folded.entry(lowercase(value)).or_default()
+                }
+                PatternToken::Attr(key, false) => node.attrs.entry(key.to_string()).or_default(),
+                PatternToken::Attr(key, true) => {
+                    node.folded_attrs.entry(lowercase(key)).or_default()
+                }
+                PatternToken::Glob(glob, folded) => {
+                    let index = match node.globs.iter().position(|(other, other_folded, _)| {
+                        other == glob && *other_folded == folded
+                    }) {
+                        Some(index) => index,
+                        None => {
+                            node.globs.push((glob.clone(), folded, Node::default()));
+                            node.globs.len() - 1
+                        }
+                    };
+                    &mut node.globs[index].2
+                }
+                PatternToken::Subtree(category, false) => {
+                    node.subtrees.entry(category.to_string()).or_default()
+                }
+                PatternToken::Subtree(category, true) => {
+                    node.folded_subtrees.entry(lowercase(category)).or_default()
+                }
+                PatternToken::OrgTenant(org_tenant, folded) => {
+                    let index =
+                        match node
+                            .org_tenants
+                            .iter()
+                            .position(|(other, other_folded, _)| {
+                                other == org_tenant && *other_folded == folded
+                            }) {
+                            Some(index) => index,
+                            None => {
+                                node.org_tenants.push((
+                                    org_tenant.clone(),
+                                    folded,
+                                    Node::default(),
+                                ));
+                                node.org_tenants.len() - 1
+                            }
+                        };
+                    &mut node.org_tenants[index].2
+                }
+                PatternToken::One => node.one.get_or_insert_with(Box::default),
+                PatternToken::Many => node.many.get_or_insert_with(|| {
+                    Box::new(Node {
+                        looping: true,
+                        ..Node::default()
+                    })
+                }),
+            };
+        }
+        node.accept.push(id);
+        self.len += 1;
+        id
+    }
+
+    /// Returns the number of rules.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no rules.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the ids of every rule `arn` matches, in ascending order.
+    pub fn matches(&self, arn: &Arn) -> Vec<usize> {
+        let mut ids: Vec<usize> = self
+            .final_states(arn)
+            .into_iter()
+            .flat_map(|node| node.accept.iter().copied())
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    /// Returns `true` if `arn` matches any rule.
+    pub fn is_match(&self, arn: &Arn) -> bool {
+        self.final_states(arn)
+            .iter()
+            .any(|node| !node.accept.is_empty())
+    }
+
+    /// Runs the automaton over the segments of `arn`, returning the states it ends in.
+    fn final_states(&self, arn: &Arn) -> Vec<&Node> {
+        let leading = [
+            arn.domain.as_str(),
+            arn.category.as_str(),
+            arn.account.as_str(),
+            arn.root.as_str(),
+        ];
+        let mut states = Vec::new();
+        enter(&mut states, &self.start);
+        for (index, segment) in leading
+            .into_iter()
+            .chain(arn.parts.0.iter().map(|part| part.as_str()))
+            .enumerate()
+        {
+            let is_part = index >= leading.len();
+            let mut next = Vec::new();
+            let mut lowered = None;
+            let attr = split_attr(segment).map(|(key, _)| key);
+            let mut lowered_attr = None;
+            for node in states {
+                if node.looping && is_part {
+                    enter(&mut next, node);
+                }
+                if let Some(target) = node.exact.get(segment) {
+                    enter(&mut next, target);
+                }
+                if !node.folded.is_empty() {
+                    let key = lowered.get_or_insert_with(|| lowercase(segment));
+                    if let Some(target) = node.folded.get(key.as_str()) {
+                        enter(&mut next, target);
+                    }
+                }
+                if let Some(key) = attr.filter(|_| is_part) {
+                    if let Some(target) = node.attrs.get(key) {
+                        enter(&mut next, target);
+                    }
+                    if !node.folded_attrs.is_empty() {
+                        let key = lowered_attr.get_or_insert_with(|| lowercase(key));
+                        if let Some(target) = node.folded_attrs.get(key.as_str()) {
+                            enter(&mut next, target);
+                        }
+                    }
+                }
+                for (glob, folded, target) in node.globs.iter().filter(|_| is_part) {
+                    if glob.matches(segment, *folded) {
+                        enter(&mut next, target);
+                    }
+                }
+                if !node.subtrees.is_empty() {
+                    for category in category_lineage(segment) {
+                        if let Some(target) = node.subtrees.get(category) {
+                            enter(&mut next, target);
+                        }
+                    }
+                }
+                if !node.folded_subtrees.is_empty() {
+                    let key = lowered.get_or_insert_with(|| lowercase(segment));
+                    for category in category_lineage(key) {
+                        if let Some(target) = node.folded_subtrees.get(category) {
+                            enter(&mut next, target);
+                        }
+                    }
+                }
+                for (org_tenant, folded, target) in &node.org_tenants {
+                    if org_tenant.matches(segment, *folded) {
+                        enter(&mut next, target);
+                    }
+                }
+                if let Some(target) = &node.one {
+                    enter(&mut next, target);
+                }
+            }
+            if next.is_empty() {
+                return next;
+            }
+            states = next;
+        }
+        states
+    }
+}
+
+/// Adds `node` to `states`, along with the states its `**` edges reach without consuming
+/// a part, skipping any already present.
+fn enter<'n>(states: &mut Vec<&'n Node>, node: &'n Node) {
+    let mut node = Some(node);
+    while let Some(current) = node {
+        if states.iter().any(|state| std::ptr::eq(*state, current)) {
+            return;
+        }
+        states.push(current);
+        node = current.many.as_deref();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ArnParser, CaseFold, ComponentKind};
+
+    fn arn(s: &str) -> Arn<'static> {
+        ArnParser::new(s.to_string()).parse().unwrap()
+    }
+
+    #[test]
+    fn test_agrees_with_matching_each_pattern() {
+        let patterns: Vec<ArnPattern> = [
+            "arn:*:*:*:*/**",
+            "arn:akton:hr:acme:root",
+            "arn:akton:hr:*:root/teams/**",
+            "arn:akton:hr:acme:root/*/*",
+            "arn:akton:hr:acme:root/**/b",
+            "arn:akton:hr:acme:root/**/**/b/**",
+            "arn:akton:hr:acme:root/env=*/*",
+            "arn:akton:hr:acme:root/teams/a",
+            "arn:akton:hr:acme:root/!teams/*",
+            "arn:akton:hr:acme:root/{teams,b}/[a-c]",
+            "arn:akton:hr:acme:root/**/{teams,b}/[a-c]",
+            "arn:akton:ops:*:*",
+            "arn:akton:*:acme.*:*",
+            "arn:akton:*:*.eu:root/**",
+            "arn:*:*:*.*:*/**",
+            "arn:akton:hr.**:*:*",
+            "arn:akton:hr.payroll.**:acme:root/**",
+        ]
+        .iter()
+        .map(|p| p.parse().unwrap())
+        .collect();
+        let folded: Vec<ArnPattern> = patterns
+            .iter()
+            .map(|p| p.clone().with_case_fold(CaseFold::all()))
+            .collect();
+        let arns = [
+            "arn:akton:hr:acme:root",
+            "arn:akton:hr:acme:root/teams",
+            "arn:akton:hr:acme:root/teams/a",
+            "arn:akton:hr:acme:root/teams/a/b@v3",
+            "arn:akton:hr:acme:root/b/x/b",
+            "arn:akton:hr:acme:root/env=prod/x",
+            "arn:akton:hr:acme:root/ENV=prod/x",
+            "arn:akton:hr:ACME:root/Teams/A",
+            "arn:akton:ops:beta:root",
+            "arn:akton:hr:acme.eu:root",
+            "arn:akton:ops:ACME.EU:root/a",
+            "arn:akton:ops:beta.eu.west:root",
+            "arn:akton:hr.payroll:acme:root/a",
+            "arn:akton:HR.Payroll.uk:acme:root",
+            "arn:akton:hrx:acme:root",
+        ];
+        for rules in [&patterns, &folded] {
+            let set = ArnMatcherSet::new(rules.iter().cloned());
+            assert_eq!(set.len(), rules.len());
+            for a in arns {
+                let arn = arn(a);
+                let expected: Vec<usize> = (0..rules.len())
+                    .filter(|&id| rules[id].matches(&arn))
+                    .collect();
+                assert_eq!(set.matches(&arn), expected, "{a}");
+                assert_eq!(set.is_match(&arn), !expected.is_empty(), "{a}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_thousands_of_rules() {
+        let mut set = ArnMatcherSet::default();
+        for account in 0..5_000 {
+            let pattern: ArnPattern = format!("arn:akton:hr:acct{account}:root/**")
+                .parse()
+                .unwrap();
+            set.push(&pattern);
+        }
+        let folded = "arn:akton:hr:*:root/Docs/**"
+            .parse::<ArnPattern>()
+            .unwrap()
+            .with_case_fold(CaseFold::none().fold(ComponentKind::Part));
+        assert_eq!(set.push(&folded), 5_000);
+        assert_eq!(
+            set.matches(&arn("arn:akton:hr:acct42:root/docs/a")),
+            [42, 5_000]
+        );
+        assert!(!set.is_match(&arn("arn:akton:hr:other:root")));
+        assert!(ArnMatcherSet::default()
+            .matches(&arn("arn:akton:hr:acme:root"))
+            .is_empty());
+    }
+}
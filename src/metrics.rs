@@ -0,0 +1,184 @@
+use crate::model::Arn;
+
+/// Longest label value produced by [`Arn::to_metric_labels`] by default.
+pub const MAX_METRIC_LABEL_LEN: usize = 64;
+
+/// Number of leading parts kept in the `arn_parts` label by default.
+pub const MAX_METRIC_LABEL_PARTS: usize = 2;
+
+/// Replaces parts beyond the cap in the `arn_parts` label.
+const ELIDED_PARTS: &str = "*";
+
+/// Bounds applied by [`Arn::to_metric_labels_with`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetricLabelOptions {
+    max_len: usize,
+    max_parts: usize,
+}
+
+impl Default for MetricLabelOptions {
+    fn default() -> Self {
+        Self {
+            max_len: MAX_METRIC_LABEL_LEN,
+            max_parts: MAX_METRIC_LABEL_PARTS,
+        }
+    }
+}
+
+impl MetricLabelOptions {
+    /// Creates options with [`MAX_METRIC_LABEL_LEN`] and [`MAX_METRIC_LABEL_PARTS`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the longest value, in bytes, of any label (default [`MAX_METRIC_LABEL_LEN`]).
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Sets how many leading parts are kept before the rest collapse to `*` (default [`MAX_METRIC_LABEL_PARTS`]).
+    pub fn max_parts(mut self, max_parts: usize) -> Self {
+        self.max_parts = max_parts;
+        self
+    }
+}
+
+impl Arn<'_> {
+    /// Returns label pairs for metrics systems using [`MetricLabelOptions::default`].
+    ///
+    /// See [`Arn::to_metric_labels_with`].
+    pub fn to_metric_labels(&self) -> Vec<(&'static str, String)> {
+        self.to_metric_labels_with(&MetricLabelOptions::default())
+    }
+
+    /// Returns `arn_domain`, `arn_category`, `arn_account`, `arn_root` and `arn_parts` labels.
+    ///
+    /// Raw Arns make poor label values: every minted root is unique and deep part paths are
+    /// unbounded. The root label keeps only the root's type prefix (`order` for
+    /// `order_01h455vb4pex5vsknk084sn02q`), parts beyond `max_parts` collapse into a single `*`,
+    /// each component is restricted to `[A-Za-z0-9_.:-]` (other characters become `_`),
+    /// and every value is truncated to `max_len` bytes.
+    ///
+    /// ```
+    /// use akton_arn::ArnParser;
+    ///
+    /// let arn = ArnParser::new("arn:akton:hr:acme:order_01h455vb4pex5vsknk084sn02q/eu west/42/items")
+    ///     .parse()
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     arn.to_metric_labels(),
+    ///     vec![
+    ///         ("arn_domain", "akton".to_string()),
+    ///         ("arn_category", "hr".to_string()),
+    ///         ("arn_account", "acme".to_string()),
+    ///         ("arn_root", "order".to_string()),
+    ///         ("arn_parts", "eu_west/42/*".to_string()),
+    ///     ]
+    /// );
+    /// ```
+    pub fn to_metric_labels_with(
+        &self,
+        options: &MetricLabelOptions,
+    ) -> Vec<(&'static str, String)> {
+        let mut parts = self
+            .parts
+            .0
+            .iter()
+            .take(options.max_parts)
+            .map(|part| sanitize(part.as_str()))
+            .collect::<Vec<_>>();
+        if self.parts.0.len() > options.max_parts {
+            parts.push(ELIDED_PARTS.to_string());
+        }
+        let label = |value: String| truncate(value, options.max_len);
+        vec![
+            ("arn_domain", label(sanitize(self.domain.as_str()))),
+            ("arn_category", label(sanitize(self.category.as_str()))),
+            ("arn_account", label(sanitize(self.account.as_str()))),
+            ("arn_root", label(sanitize(root_type(self.root.as_str())))),
+            ("arn_parts", label(parts.join("/"))),
+        ]
+    }
+}
+
+/// Strips the 26-character type id suffix minted by [`crate::Root::new`], if present.
+fn root_type(root: &str) -> &str {
+    match root.rsplit_once('_') {
+        Some((prefix, suffix))
+            if suffix.len() == 26
+                && suffix
+                    .bytes()
+                    .all(|b| b.is_ascii_digit() || b.is_ascii_lowercase()) =>
+        {
+            prefix
+        }
+        _ => root,
+    }
+}
+
+fn sanitize(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | ':' | '-') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Truncates an ASCII value to `max_len` bytes.
+fn truncate(mut value: String, max_len: usize) -> String {
+    value.truncate(max_len.min(value.len()));
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Account, ArnBuilder, ArnParser, Category, Domain, Root};
+
+    #[test]
+    fn test_minted_roots_share_a_label() {
+        let build = || {
+            ArnBuilder::new()
+                .with::<Domain>("akton")
+                .unwrap()
+                .with::<Category>("hr")
+                .unwrap()
+                .with::<Account>("acme")
+                .unwrap()
+                .with::<Root>("order")
+                .unwrap()
+                .build()
+                .unwrap()
+        };
+        let (a, b) = (build(), build());
+        assert_ne!(a, b);
+        assert_eq!(a.to_metric_labels(), b.to_metric_labels());
+        assert_eq!(a.to_metric_labels()[3], ("arn_root", "order".to_string()));
+    }
+
+    #[test]
+    fn test_labels_are_sanitized_and_bounded() {
+        let arn = ArnParser::new("arn:ak ton:hr:äcme:root/a/b")
+            .parse()
+            .unwrap();
+        let labels = arn.to_metric_labels_with(&MetricLabelOptions::new().max_len(3).max_parts(0));
+        assert_eq!(
+            labels,
+            vec![
+                ("arn_domain", "ak_".to_string()),
+                ("arn_category", "hr".to_string()),
+                ("arn_account", "_cm".to_string()),
+                ("arn_root", "roo".to_string()),
+                ("arn_parts", "*".to_string()),
+            ]
+        );
+        let labels = arn.to_metric_labels();
+        assert_eq!(labels[4], ("arn_parts", "a/b".to_string()));
+    }
+}
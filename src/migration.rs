@@ -0,0 +1,277 @@
+use crate::errors::ArnError;
+use crate::format::ArnFormat;
+use crate::model::{Account, Arn, Category, Domain, Part, Root};
+
+/// Rewrites stored Arns when the hierarchy is reorganized.
+///
+/// Each rule maps an old prefix to a new one. A prefix is written like an Arn cut short after
+/// any component or part: `arn:akton`, `arn:akton:hr:acme`, or
+/// `arn:akton:hr:acme:root/payroll`. An Arn under the old prefix keeps everything after it and
+/// moves under the new prefix; when several rules match, the longest prefix wins. Versions,
+/// extensions and annotations are carried over, since the resource itself is unchanged.
+///
+/// A prefix that stops before the root can only be mapped to one of the same length, so a
+/// rule never leaves an Arn without a component. Prefixes that reach the root may differ in
+/// length, which lets a subtree become a category of its own:
+///
+/// ```
+/// use akton_arn::{ArnParser, Migration};
+///
+/// let migration = Migration::new()
+///     .map("arn:akton:hr", "arn:akton:people").unwrap()
+///     .map("arn:akton:hr:acme:root/payroll", "arn:akton:payroll:acme:root").unwrap();
+///
+/// let arn = ArnParser::new("arn:akton:hr:acme:root/payroll/2024@v2").parse().unwrap();
+/// let migrated = migration.migrate(&arn).unwrap();
+/// assert_eq!(migrated.to_string(), "arn:akton:payroll:acme:root/2024@v2");
+///
+/// let arn = ArnParser::new("arn:akton:hr:acme:root/teams").parse().unwrap();
+/// assert_eq!(migration.migrate(&arn).unwrap().to_string(), "arn:akton:people:acme:root/teams");
+/// ```
+///
+/// Prefixes are read in the canonical grammar unless [`Migration::with_format`] sets another.
+#[derive(Debug, Clone, Default)]
+pub struct Migration {
+    /// Rules ordered from the longest prefix to the shortest.
+    rules: Vec<Rule>,
+    /// The grammar prefixes are written in.
+    format: ArnFormat,
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    from: Vec<String>,
+    to: Vec<String>,
+}
+
+/// Number of segments up to and including the root.
+const RESOURCE_SEGMENTS: usize = 4;
+
+impl Migration {
+    /// Creates a migration without rules.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads the prefixes passed to [`Migration::map`] afterwards in `format`, for rules kept
+    /// alongside identifiers of a legacy system.
+    pub fn with_format(mut self, format: ArnFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Adds a rule moving Arns under `from` to `to`.
+    ///
+    /// Fails with [`ArnError::AlreadyRegistered`] if a rule for `from` exists, or if either
+    /// prefix is malformed or the new one holds an invalid component.
+    pub fn map(mut self, from: &str, to: &str) -> Result<Self, ArnError> {
+        let rule = Rule {
+            from: prefix_segments(from, &self.format)?,
+            to: prefix_segments(to, &self.format)?,
+        };
+        if (rule.from.len() < RESOURCE_SEGMENTS || rule.to.len() < RESOURCE_SEGMENTS)
+            && rule.from.len() != rule.to.len()
+        {
+            return Err(ArnError::ParseFailure(
+                "Migration",
+                format!("{from:?} and {to:?} must name the same components"),
+            ));
+        }
+        Domain::new(rule.to[0].as_str())?;
+        if let Some(root) = rule.to.get(RESOURCE_SEGMENTS - 1) {
+            Root::named(root.as_str())?;
+        }
+        for part in rule.to.iter().skip(RESOURCE_SEGMENTS) {
+            Part::new(part.as_str())?;
+        }
+        if self.rules.iter().any(|existing| existing.from == rule.from) {
+            return Err(ArnError::AlreadyRegistered(from.to_string()));
+        }
+        let index = self
+            .rules
+            .partition_point(|existing| existing.from.len() >= rule.from.len());
+        self.rules.insert(index, rule);
+        Ok(self)
+    }
+
+    /// Returns the number of rules.
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Returns the migrated Arn, or `None` if no rule applies.
+    pub fn migrate(&self, arn: &Arn) -> Option<Arn<'static>> {
+        let segments: Vec<&str> = [
+            arn.domain.as_str(),
+            arn.category.as_str(),
+            arn.account.as_str(),
+            arn.root.as_str(),
+        ]
+        .into_iter()
+        .chain(arn.parts.0.iter().map(Part::as_str))
+        .collect();
+        let rule = self.rules.iter().find(|rule| {
+            rule.from.len() <= segments.len()
+                && rule.from.iter().zip(&segments).all(|(a, b)| a == b)
+        })?;
+        let mut migrated: Vec<String> = rule.to.clone();
+        migrated.extend(
+            segments[rule.from.len()..]
+                .iter()
+                .map(|segment| segment.to_string()),
+        );
+        let mut migrated = migrated.into_iter();
+        let mut next = || migrated.next().expect("rules keep every component");
+        // Both sides of each rule were validated when it was added.
+        let domain = Domain::new(next()).ok()?;
+        let category = Category::new(next());
+        let account = Account::new(next());
+        let root = Root::named(next()).ok()?;
+        let parts = migrated.map(Part::new).collect::<Result<_, _>>().ok()?;
+        Some(Arn {
+            domain,
+            category,
+            account,
+            root,
+            parts,
+            ..arn.clone().into_owned()
+        })
+    }
+
+    /// Migrates every Arn, keeping those no rule applies to unchanged.
+    pub fn migrate_all<'a>(&self, arns: impl IntoIterator<Item = Arn<'a>>) -> Vec<Arn<'a>> {
+        arns.into_iter()
+            .map(|arn| self.migrate(&arn).unwrap_or(arn))
+            .collect()
+    }
+}
+
+/// Splits `arn:<domain>[:<category>[:<account>[:<root>[/<part>...]]]]`, written in `format`,
+/// into its segments.
+fn prefix_segments(prefix: &str, format: &ArnFormat) -> Result<Vec<String>, ArnError> {
+    let segments = format.split_segments(prefix)?;
+    if segments.iter().any(|segment| segment.contains(['@', '?'])) {
+        return Err(ArnError::ParseFailure(
+            "Migration",
+            format!("{prefix:?} must not have a version or extensions"),
+        ));
+    }
+    Ok(segments.into_iter().map(String::from).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArnParser;
+
+    fn arn(s: &str) -> Arn<'static> {
+        ArnParser::new(s.to_string()).parse().unwrap()
+    }
+
+    #[test]
+    fn test_longest_prefix_wins() {
+        let migration = Migration::new()
+            .map("arn:akton", "arn:platform")
+            .unwrap()
+            .map("arn:akton:hr:acme", "arn:akton:hr:globex")
+            .unwrap()
+            .map("arn:akton:hr:acme:root/a/b", "arn:akton:hr:acme:root/c")
+            .unwrap();
+        assert_eq!(migration.len(), 3);
+        let cases = [
+            ("arn:akton:it:acme:root", Some("arn:platform:it:acme:root")),
+            (
+                "arn:akton:hr:acme:root/x",
+                Some("arn:akton:hr:globex:root/x"),
+            ),
+            (
+                "arn:akton:hr:acme:root/a/b/d",
+                Some("arn:akton:hr:acme:root/c/d"),
+            ),
+            (
+                "arn:akton:hr:acme:root/a/bb",
+                Some("arn:akton:hr:globex:root/a/bb"),
+            ),
+            ("arn:other:hr:acme:root", None),
+            ("arn:aktonx:hr:acme:root", None),
+        ];
+        for (input, expected) in cases {
+            let migrated = migration.migrate(&arn(input));
+            assert_eq!(
+                migrated.map(|a| a.to_string()).as_deref(),
+                expected,
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_migrate_all_keeps_unmatched_and_qualifiers() {
+        let migration = Migration::new()
+            .map("arn:akton:hr:acme:root", "arn:akton:hr:acme:base/legacy")
+            .unwrap();
+        let arns = [
+            arn("arn:akton:hr:acme:root/a@v3?k=v").with_annotation("owner", "ops"),
+            arn("arn:akton:hr:acme:other/a"),
+        ];
+        let migrated = migration.migrate_all(arns.clone());
+        assert_eq!(migrated[0], arn("arn:akton:hr:acme:base/legacy/a@v3?k=v"));
+        assert_eq!(migrated[0].annotations().get("owner"), Some("ops"));
+        assert_eq!(migrated[1], arns[1]);
+    }
+
+    #[test]
+    fn test_prefixes_follow_the_format() {
+        let legacy = ArnFormat::new()
+            .with_scheme("res")
+            .unwrap()
+            .with_part_separator('.')
+            .unwrap();
+        let migration = Migration::new()
+            .with_format(legacy)
+            .map("res:akton:hr:acme:root.a", "res:akton:hr:acme:root.b.c")
+            .unwrap();
+        assert_eq!(
+            migration.migrate(&arn("arn:akton:hr:acme:root/a/x")),
+            Some(arn("arn:akton:hr:acme:root/b/c/x"))
+        );
+        assert!(migration.clone().map("arn:akton", "arn:other").is_err());
+        let ern = Migration::new()
+            .map("ern:akton:hr", "eid:akton:people")
+            .unwrap();
+        assert_eq!(
+            ern.migrate(&arn("arn:akton:hr:acme:root")),
+            Some(arn("arn:akton:people:acme:root"))
+        );
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        let migration = Migration::new()
+            .map("arn:akton:hr", "arn:akton:people")
+            .unwrap();
+        assert_eq!(
+            migration
+                .clone()
+                .map("arn:akton:hr", "arn:akton:staff")
+                .unwrap_err(),
+            ArnError::AlreadyRegistered("arn:akton:hr".to_string())
+        );
+        for (from, to) in [
+            ("akton:hr", "arn:akton:people"),
+            ("arn:akton:hr", "arn:akton"),
+            ("arn:akton:hr", "arn:akton:hr:acme:root"),
+            ("arn:akton:hr:acme:root", "arn::hr:acme:root"),
+            ("arn:akton:hr:acme:root", "arn:akton:hr:acme:root/a:b"),
+            ("arn:akton:hr:acme:root", "arn:akton:hr:acme:"),
+            ("arn:akton:hr:acme:root", "arn:akton:hr:acme:a:b"),
+            ("arn:akton:hr:acme:root@v1", "arn:akton:hr:acme:root"),
+        ] {
+            assert!(migration.clone().map(from, to).is_err(), "{from} -> {to}");
+        }
+    }
+}
@@ -1,15 +1,36 @@
+#[macro_use]
+mod shared_str;
 mod account;
+mod annotations;
 mod arn;
 mod category;
+mod component_kind;
 mod domain;
+mod extensions;
+mod hierarchy;
+mod lineage;
 mod part;
 mod parts;
 mod root;
+mod user_segment;
 
-pub use account::Account;
-pub use arn::Arn;
-pub use category::Category;
+pub(crate) use account::split_org_tenant;
+pub use account::{Account, StructuredAccounts, ORG_TENANT_SEPARATOR};
+#[cfg(feature = "serde")]
+pub use annotations::annotated;
+pub use annotations::Annotations;
+pub use arn::{Ancestors, Arn, OwnedArn};
+pub(crate) use category::category_lineage;
+pub use category::{Category, HierarchicalCategories, CATEGORY_SEPARATOR};
+pub use component_kind::ComponentKind;
 pub use domain::Domain;
+pub use extensions::Extensions;
+pub use hierarchy::ByHierarchy;
+pub use lineage::{ArnLineage, LineageRecord};
 pub use part::Part;
-pub use parts::Parts;
-pub use root::Root;
+pub(crate) use part::{check_part, split_attr};
+pub use parts::{Parts, PartsIndex, PartsIntoIter};
+pub(crate) use root::AKTON;
+pub use root::{Root, RootKind};
+pub(crate) use shared_str::SharedStr;
+pub use user_segment::{SegmentRejection, MAX_USER_SEGMENT_LEN};
@@ -1,26 +1,115 @@
-use derive_more::{AsRef, From, Into};
+use crate::errors::ArnError;
+use crate::model::SharedStr;
+use crate::parser::ComponentRules;
+use crate::traits::ArnValidator;
+use crate::ComponentKind;
 use std::borrow::Cow;
 use std::fmt;
+
+/// Separates the org from the tenant in a structured account such as `acme.eu`.
+pub const ORG_TENANT_SEPARATOR: char = '.';
+
 /// Represents an account identifier in the Arn system.
+///
+/// An account may be structured as `<org>.<tenant>`, with exactly one
+/// [`ORG_TENANT_SEPARATOR`]; [`Account::org`] and [`Account::tenant`] read the two halves,
+/// and patterns can match either with `acme.*` or `*.eu` (see [`ArnPattern`](crate::ArnPattern)).
+///
+/// ```
+/// use akton_arn::Account;
+///
+/// let account = Account::structured("acme", "eu").unwrap();
+/// assert_eq!(account.as_str(), "acme.eu");
+/// assert_eq!((account.org(), account.tenant()), (Some("acme"), Some("eu")));
+/// assert_eq!(Account::new("acme").org(), None);
+/// ```
+#[derive(Eq, Debug, PartialEq, PartialOrd, Ord, Clone, Hash)]
+pub struct Account<'a>(pub(crate) SharedStr<'a>);
 
-#[derive(AsRef, From, Into, Eq, Debug, PartialEq, Clone, Hash)]
-pub struct Account<'a>(pub(crate) Cow<'a, str>);
+impl_shared_str_conversions!(Account);
 
 impl<'a> Account<'a> {
     pub fn as_str(&self) -> &str {
         &self.0
     }
     pub fn new(value: impl Into<Cow<'a, str>>) -> Self {
-        Account(value.into())
+        Account(value.into().into())
     }
     pub fn into_owned(self) -> Account<'static> {
-        Account(Cow::Owned(self.0.into_owned()))
+        Account(self.0.into_owned())
+    }
+
+    /// Creates the structured account `<org>.<tenant>`.
+    ///
+    /// Fails if either half is empty or contains the separator, or if the account is one
+    /// [`ArnParser`](crate::ArnParser) would reject, such as one holding `:`.
+    pub fn structured(org: &str, tenant: &str) -> Result<Account<'static>, ArnError> {
+        for (half, value) in [("org", org), ("tenant", tenant)] {
+            if value.is_empty() || value.contains(ORG_TENANT_SEPARATOR) {
+                return Err(ArnError::ValidationFailure(
+                    ComponentKind::Account,
+                    format!("{half} {value:?} must be non-empty and must not contain '.'"),
+                ));
+            }
+        }
+        let account = format!("{org}{ORG_TENANT_SEPARATOR}{tenant}");
+        let account =
+            ComponentRules::default().fit_component(ComponentKind::Account, account.into())?;
+        Ok(Account::new(account))
+    }
+
+    /// Returns the org and tenant of a structured account, or `None` if the account is not
+    /// two non-empty halves around one separator.
+    pub fn org_tenant(&self) -> Option<(&str, &str)> {
+        split_org_tenant(&self.0)
+    }
+
+    /// Returns the org of a structured account.
+    pub fn org(&self) -> Option<&str> {
+        self.org_tenant().map(|(org, _)| org)
+    }
+
+    /// Returns the tenant of a structured account.
+    pub fn tenant(&self) -> Option<&str> {
+        self.org_tenant().map(|(_, tenant)| tenant)
+    }
+}
+
+/// Splits an account's text as [`Account::org_tenant`] does.
+pub(crate) fn split_org_tenant(account: &str) -> Option<(&str, &str)> {
+    account
+        .split_once(ORG_TENANT_SEPARATOR)
+        .filter(|(org, tenant)| {
+            !org.is_empty() && !tenant.is_empty() && !tenant.contains(ORG_TENANT_SEPARATOR)
+        })
+}
+
+/// A validator requiring every account to be structured as `<org>.<tenant>`.
+///
+/// ```
+/// use akton_arn::{ArnParser, StructuredAccounts};
+///
+/// let parse = |input| ArnParser::new(input).with_validator(StructuredAccounts).parse();
+/// assert!(parse("arn:akton:hr:acme.eu:root").is_ok());
+/// assert!(parse("arn:akton:hr:acme:root").is_err());
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StructuredAccounts;
+
+impl ArnValidator for StructuredAccounts {
+    fn validate(&self, kind: ComponentKind, value: &str) -> Result<(), String> {
+        if kind != ComponentKind::Account || split_org_tenant(value).is_some() {
+            return Ok(());
+        }
+        Err(format!(
+            "{value:?} is not structured as <org>{ORG_TENANT_SEPARATOR}<tenant>"
+        ))
     }
 }
 
 impl<'a> Default for Account<'a> {
     fn default() -> Self {
-        Account(Cow::Borrowed("account"))
+        Account(SharedStr::borrowed("account"))
     }
 }
 
@@ -34,12 +123,12 @@ impl<'a> std::str::FromStr for Account<'a> {
     type Err = std::convert::Infallible;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Account(Cow::Owned(s.to_owned())))
+        Ok(Account(s.to_owned().into()))
     }
 }
 impl<'a> From<Account<'a>> for String {
     fn from(domain: Account<'a>) -> Self {
-        domain.0.into_owned()
+        domain.0.into_string()
     }
 }
 #[cfg(test)]
@@ -52,6 +141,35 @@ mod tests {
         assert_eq!(account.as_str(), "test123");
     }
 
+    #[test]
+    fn test_org_and_tenant() {
+        assert_eq!(Account::new("acme.eu").org_tenant(), Some(("acme", "eu")));
+        for unstructured in ["acme", ".eu", "acme.", "acme.eu.west", "."] {
+            assert_eq!(
+                Account::new(unstructured).org_tenant(),
+                None,
+                "{unstructured}"
+            );
+        }
+        assert!(Account::structured("acme", "eu.west").is_err());
+        assert!(Account::structured("", "eu").is_err());
+        assert!(matches!(
+            Account::structured("ac:me", "eu"),
+            Err(ArnError::ParseFailure("Account", _))
+        ));
+        assert!(Account::structured("acme", "e\u{7}u").is_err());
+        // The grammar reserves `?` only from the root onwards, so an account may hold it.
+        let queried = Account::structured("acme?", "eu").unwrap();
+        assert_eq!(queried.org_tenant(), Some(("acme?", "eu")));
+        assert_eq!(
+            StructuredAccounts.validate(ComponentKind::Part, "acme"),
+            Ok(())
+        );
+        assert!(StructuredAccounts
+            .validate(ComponentKind::Account, "acme")
+            .is_err());
+    }
+
     #[test]
     fn test_account_default() {
         let account = Account::default();
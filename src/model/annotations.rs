@@ -0,0 +1,161 @@
+use std::collections::BTreeMap;
+
+/// Non-identifying key/value metadata attached to an [`Arn`](crate::Arn).
+///
+/// Annotations carry routing hints, display names and similar data alongside an identifier.
+/// They never take part in equality, hashing or the canonical `Display` form.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct Annotations(BTreeMap<String, String>);
+
+impl Annotations {
+    /// Creates an empty set of annotations.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the value stored under `key`.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(String::as_str)
+    }
+
+    /// Stores `value` under `key`, returning the previous value.
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<String>) -> Option<String> {
+        self.0.insert(key.into(), value.into())
+    }
+
+    /// Removes the value stored under `key`, returning it.
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        self.0.remove(key)
+    }
+
+    /// Returns `true` if a value is stored under `key`.
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.0.contains_key(key)
+    }
+
+    /// Iterates over the annotations in key order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Returns the number of annotations.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if there are no annotations.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for Annotations {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        Annotations(
+            iter.into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        )
+    }
+}
+
+/// Opt-in serde format that keeps an Arn's annotations.
+///
+/// The default `Serialize` implementation of [`Arn`](crate::Arn) writes only the canonical
+/// string. Use this module with `#[serde(with = "akton_arn::annotated")]` to write
+/// `{"arn": "...", "annotations": {...}}` instead.
+#[cfg(feature = "serde")]
+pub mod annotated {
+    use super::Annotations;
+    use crate::Arn;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize)]
+    struct AnnotatedRef<'r, 'a> {
+        arn: &'r Arn<'a>,
+        annotations: &'r Annotations,
+    }
+
+    #[derive(Deserialize)]
+    struct AnnotatedOwned<'a> {
+        arn: Arn<'a>,
+        #[serde(default)]
+        annotations: Annotations,
+    }
+
+    /// Serializes an Arn together with its annotations.
+    pub fn serialize<S: Serializer>(arn: &Arn<'_>, serializer: S) -> Result<S::Ok, S::Error> {
+        AnnotatedRef {
+            arn,
+            annotations: arn.annotations(),
+        }
+        .serialize(serializer)
+    }
+
+    /// Deserializes an Arn together with its annotations.
+    pub fn deserialize<'de, 'a, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Arn<'a>, D::Error> {
+        let AnnotatedOwned {
+            mut arn,
+            annotations,
+        } = AnnotatedOwned::deserialize(deserializer)?;
+        *arn.annotations_mut() = annotations;
+        Ok(arn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_annotations_map() {
+        let mut annotations: Annotations = [("b", "2"), ("a", "1")].into_iter().collect();
+        assert_eq!(annotations.insert("a", "one"), Some("1".to_string()));
+        assert_eq!(annotations.get("a"), Some("one"));
+        assert!(annotations.contains_key("b"));
+        assert_eq!(
+            annotations.iter().collect::<Vec<_>>(),
+            vec![("a", "one"), ("b", "2")]
+        );
+        assert_eq!(annotations.remove("b"), Some("2".to_string()));
+        assert_eq!(annotations.len(), 1);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_annotated_serde_format() -> anyhow::Result<()> {
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct Envelope {
+            #[serde(with = "crate::annotated")]
+            target: crate::Arn<'static>,
+        }
+
+        let target = crate::ArnParser::new("arn:akton:hr:acme:root/team")
+            .parse()?
+            .with_annotation("display_name", "Team");
+        let json = serde_json::to_string(&Envelope { target })?;
+        assert_eq!(
+            json,
+            r#"{"target":{"arn":"arn:akton:hr:acme:root/team","annotations":{"display_name":"Team"}}}"#
+        );
+        let restored: Envelope = serde_json::from_str(&json)?;
+        assert_eq!(
+            restored.target.annotations().get("display_name"),
+            Some("Team")
+        );
+
+        // The default format drops annotations.
+        assert_eq!(
+            serde_json::to_string(&restored.target)?,
+            r#""arn:akton:hr:acme:root/team""#
+        );
+        Ok(())
+    }
+}
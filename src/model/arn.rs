@@ -1,33 +1,112 @@
 use crate::errors::ArnError;
-use crate::{Account, ArnComponent, Category, Domain, Part, Parts, Root};
+use crate::format::{ArnFormat, CANONICAL_SCHEME};
+use crate::parser::ComponentRules;
+use crate::time_source::{SystemClock, TimeSource};
+use crate::{Account, Annotations, Category, ComponentKind, Domain, Extensions, Part, Parts, Root};
 use std::borrow::Cow;
+use std::cmp::Ordering;
 use std::fmt;
 use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::iter::Peekable;
+use std::str::Chars;
+use std::time::{Duration, UNIX_EPOCH};
 
 /// Represents an Akton Resource Name (Arn), which uniquely identifies resources within the Akton framework.
-#[derive(Debug, PartialEq, Clone, Eq)]
+///
+/// Equality, hashing and ordering consider the identifying components, the version and
+/// [`Extensions`]; [`Annotations`] and the [scheme](Arn::scheme) are ignored. Two Arns minted
+/// from the same name differ in their generated root ids, so compare them with
+/// [`Arn::same_resource`] instead; [`Arn::identical`] also compares the scheme.
+///
+/// `Ord` is the total canonical order: components are compared in turn (domain, category,
+/// account, root, then parts element by element, then the version, then extensions), so an
+/// unversioned Arn sorts before its versions, which sort numerically, and an ancestor always
+/// sorts before its descendants, which follow it contiguously; [`Arn::prefix_range`] selects
+/// a whole subtree of a `BTreeMap`. It says nothing about containment between unrelated Arns;
+/// use [`Arn::hierarchy_cmp`] or [`ByHierarchy`](crate::ByHierarchy) for that.
+///
+/// The components are read through [`Arn::domain`], [`Arn::parts`] and the like, and changed
+/// only through validating methods such as [`Arn::set_domain`] and [`Arn::push_part`]. Code
+/// written against the former public fields can move to [`RawArn`](crate::raw::RawArn).
+#[derive(Debug, Clone)]
 pub struct Arn<'a> {
-    pub domain: Domain<'a>,
-    pub category: Category<'a>,
-    pub account: Account<'a>,
-    pub root: Root<'a>,
-    pub parts: Parts<'a>,
+    pub(crate) domain: Domain<'a>,
+    pub(crate) category: Category<'a>,
+    pub(crate) account: Account<'a>,
+    pub(crate) root: Root<'a>,
+    pub(crate) parts: Parts<'a>,
+    pub(crate) annotations: Annotations,
+    pub(crate) version: Option<u64>,
+    pub(crate) extensions: Extensions,
+    /// The scheme the Arn was written with, kept for [`Arn::scheme`].
+    pub(crate) scheme: Cow<'static, str>,
+}
+
+/// An Arn that owns all of its components, as returned by parsing owned strings or
+/// [`Arn::into_owned`]. Use it in struct fields and signatures instead of spelling out the
+/// lifetime.
+///
+/// ```
+/// use akton_arn::prelude::*;
+///
+/// struct Handle {
+///     arn: OwnedArn,
+/// }
+///
+/// let handle = Handle { arn: OwnedArnParser::new("arn:akton:hr:acme:root".to_string()).parse().unwrap() };
+/// assert_eq!(handle.arn.account().as_str(), "acme");
+/// ```
+pub type OwnedArn = Arn<'static>;
+
+impl PartialEq for Arn<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.domain == other.domain
+            && self.category == other.category
+            && self.account == other.account
+            && self.root == other.root
+            && self.parts == other.parts
+            && self.version == other.version
+            && self.extensions == other.extensions
+    }
+}
+
+impl Eq for Arn<'_> {}
+
+impl Hash for Arn<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.domain.hash(state);
+        self.category.hash(state);
+        self.account.hash(state);
+        self.root.hash(state);
+        self.parts.hash(state);
+        self.version.hash(state);
+        self.extensions.hash(state);
+    }
+}
+
+impl PartialOrd for Arn<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Arn<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.domain
+            .cmp(&other.domain)
+            .then_with(|| self.category.cmp(&other.category))
+            .then_with(|| self.account.cmp(&other.account))
+            .then_with(|| self.root.cmp(&other.root))
+            .then_with(|| self.parts.cmp(&other.parts))
+            .then_with(|| self.version.cmp(&other.version))
+            .then_with(|| self.extensions.cmp(&other.extensions))
+    }
 }
 
 impl Display for Arn<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        let mut display = format!(
-            "{}{}:{}:{}:{}",
-            Domain::prefix(),
-            self.domain,
-            self.category,
-            self.account,
-            self.root
-        );
-        if !self.parts.0.is_empty() {
-            display = format!("{}/{}", display, self.parts);
-        }
-        write!(f, "{}", display)
+        ArnFormat::DEFAULT.write(self, f)
     }
 }
 use std::ops::Add;
@@ -44,6 +123,10 @@ impl<'a> Add for Arn<'a> {
             account: self.account,
             root: self.root,
             parts: Parts(new_parts),
+            annotations: Annotations::default(),
+            version: None,
+            extensions: Extensions::default(),
+            scheme: Cow::Borrowed(CANONICAL_SCHEME),
         }
     }
 }
@@ -62,9 +145,213 @@ impl<'a> Arn<'a> {
             account,
             root,
             parts,
+            annotations: Annotations::default(),
+            version: None,
+            extensions: Extensions::default(),
+            scheme: Cow::Borrowed(CANONICAL_SCHEME),
+        }
+    }
+
+    /// Assembles an Arn from component strings validated upstream, without checking them again.
+    ///
+    /// Meant for hot paths such as rehydrating Arns from storage written by this crate. Each
+    /// component goes through its `new_unchecked` constructor, so invalid input panics in
+    /// debug builds and yields an Arn that may not round-trip in release builds.
+    ///
+    /// ```
+    /// use akton_arn::{Arn, ArnParser};
+    ///
+    /// let arn = Arn::from_components_unchecked("akton", "hr", "acme", "root", ["teams", "a"]);
+    /// assert_eq!(arn, ArnParser::new("arn:akton:hr:acme:root/teams/a").parse().unwrap());
+    /// ```
+    pub fn from_components_unchecked<P: Into<Cow<'a, str>>>(
+        domain: impl Into<Cow<'a, str>>,
+        category: impl Into<Cow<'a, str>>,
+        account: impl Into<Cow<'a, str>>,
+        root: impl Into<Cow<'a, str>>,
+        parts: impl IntoIterator<Item = P>,
+    ) -> Self {
+        Arn::new(
+            Domain::new_unchecked(domain),
+            Category::new(category),
+            Account::new(account),
+            Root::new_unchecked(root),
+            Parts::new(parts.into_iter().map(Part::new_unchecked).collect()),
+        )
+    }
+
+    /// Converts the Arn into an owned version with 'static lifetime
+    pub fn into_owned(self) -> Arn<'static> {
+        Arn {
+            domain: self.domain.into_owned(),
+            category: self.category.into_owned(),
+            account: self.account.into_owned(),
+            root: self.root.into_owned(),
+            parts: self.parts.into_owned(),
+            annotations: self.annotations,
+            version: self.version,
+            extensions: self.extensions,
+            scheme: self.scheme,
         }
     }
 
+    /// Returns the domain, such as `akton`.
+    pub fn domain(&self) -> &Domain<'a> {
+        &self.domain
+    }
+
+    /// Returns the category, such as `hr`.
+    pub fn category(&self) -> &Category<'a> {
+        &self.category
+    }
+
+    /// Returns the account, such as `acme`.
+    pub fn account(&self) -> &Account<'a> {
+        &self.account
+    }
+
+    /// Returns the root, including any generated id suffix.
+    pub fn root(&self) -> &Root<'a> {
+        &self.root
+    }
+
+    /// Returns the parts below the root.
+    pub fn parts(&self) -> &Parts<'a> {
+        &self.parts
+    }
+
+    /// Returns the scheme this Arn was parsed from, such as `ern` for `ern:akton:hr:acme:root`.
+    ///
+    /// The parser accepts every scheme in [`KNOWN_SCHEMES`](crate::KNOWN_SCHEMES) and records the one it saw, but
+    /// the rest of the Arn is the same model whichever was used: the scheme is not part of its
+    /// identity, and `Display` always writes `arn:`. Arns built rather than parsed report `arn`.
+    ///
+    /// ```
+    /// use akton_arn::ArnParser;
+    ///
+    /// let legacy = ArnParser::new("eid:akton:hr:acme:root").parse().unwrap();
+    /// let current = ArnParser::new("arn:akton:hr:acme:root").parse().unwrap();
+    /// assert_eq!(legacy.scheme(), "eid");
+    /// assert_eq!(legacy, current);
+    /// assert_eq!(legacy.to_string(), "arn:akton:hr:acme:root");
+    /// ```
+    pub fn scheme(&self) -> &str {
+        &self.scheme
+    }
+
+    /// Returns the annotations attached to this Arn.
+    pub fn annotations(&self) -> &Annotations {
+        &self.annotations
+    }
+
+    /// Returns the annotations attached to this Arn for modification.
+    pub fn annotations_mut(&mut self) -> &mut Annotations {
+        &mut self.annotations
+    }
+
+    /// Attaches an annotation, replacing any previous value for `key`.
+    ///
+    /// Annotations travel with this Arn and its clones but are not inherited by Arns derived
+    /// from it, such as its parent or children.
+    pub fn with_annotation(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.annotations.insert(key, value);
+        self
+    }
+
+    /// Returns the version in this Arn's `@v<n>` suffix, or `None` for an unversioned Arn.
+    ///
+    /// The version follows the last part and precedes any extensions:
+    /// `arn:akton:ledger:acme:account/orders@v3?region=eu`.
+    pub fn version(&self) -> Option<u64> {
+        self.version
+    }
+
+    /// Addresses a specific version of this resource, replacing any previous version.
+    ///
+    /// ```
+    /// use akton_arn::ArnParser;
+    ///
+    /// let arn = ArnParser::new("arn:akton:ledger:acme:account/orders").parse().unwrap();
+    /// let v3 = arn.clone().with_version(3);
+    /// assert_eq!(v3.to_string(), "arn:akton:ledger:acme:account/orders@v3");
+    /// assert_ne!(v3, arn);
+    /// assert!(v3.same_resource_ignoring_version(&arn));
+    /// ```
+    pub fn with_version(mut self, version: u64) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Removes the version, addressing the resource itself rather than one of its versions.
+    pub fn without_version(mut self) -> Self {
+        self.version = None;
+        self
+    }
+
+    /// Returns `true` if both Arns address the same resource, whatever their versions.
+    pub fn same_resource_ignoring_version(&self, other: &Arn) -> bool {
+        self.domain == other.domain
+            && self.category == other.category
+            && self.account == other.account
+            && self.root == other.root
+            && self.parts == other.parts
+            && self.extensions == other.extensions
+    }
+
+    /// Returns `true` if both Arns name the same resource, ignoring any id generated for
+    /// their roots.
+    ///
+    /// Roots are compared by [`Root::name`], so `order_01h455vb4pex5vsknk084sn02q` matches
+    /// any other root minted as `order` as well as a plain `order`. Everything else that
+    /// `==` compares must be equal.
+    ///
+    /// ```
+    /// use akton_arn::Arn;
+    ///
+    /// let mint = || Arn::with_root("order").unwrap();
+    /// let (first, second) = (mint(), mint());
+    /// assert_ne!(first, second);
+    /// assert!(first.same_resource(&second));
+    /// ```
+    pub fn same_resource(&self, other: &Arn) -> bool {
+        self.domain == other.domain
+            && self.category == other.category
+            && self.account == other.account
+            && self.root.name() == other.root.name()
+            && self.parts == other.parts
+            && self.version == other.version
+            && self.extensions == other.extensions
+    }
+
+    /// Returns `true` if both Arns are equal and were written with the same scheme, so they
+    /// are the same text byte for byte.
+    pub fn identical(&self, other: &Arn) -> bool {
+        self == other && self.scheme == other.scheme
+    }
+
+    /// Returns the extensions in this Arn's `?key=value` suffix.
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
+
+    /// Returns the extensions in this Arn's `?key=value` suffix for modification.
+    pub fn extensions_mut(&mut self) -> &mut Extensions {
+        &mut self.extensions
+    }
+
+    /// Sets an extension, replacing any previous value for `key` in place.
+    ///
+    /// Extensions qualify this resource only, so like annotations and the version they are not
+    /// inherited by Arns derived from it, such as its parent or children.
+    pub fn with_extension(
+        mut self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Result<Self, ArnError> {
+        self.extensions.insert(key, value)?;
+        Ok(self)
+    }
+
     /// Creates a new Arn with the given root and default values for other fields
     pub fn with_root(root: impl Into<Cow<'a, str>>) -> Result<Self, ArnError> {
         let root = Root::new(root)?;
@@ -83,6 +370,10 @@ impl<'a> Arn<'a> {
             account: self.account.clone(),
             root: new_root,
             parts: self.parts.clone(),
+            annotations: Annotations::default(),
+            version: None,
+            extensions: Extensions::default(),
+            scheme: Cow::Borrowed(CANONICAL_SCHEME),
         })
     }
 
@@ -94,6 +385,10 @@ impl<'a> Arn<'a> {
             account: Account::default(),
             root: Root::default(),
             parts: Parts::default(),
+            annotations: Annotations::default(),
+            version: None,
+            extensions: Extensions::default(),
+            scheme: Cow::Borrowed(CANONICAL_SCHEME),
         })
     }
 
@@ -105,6 +400,10 @@ impl<'a> Arn<'a> {
             account: Account::default(),
             root: Root::default(),
             parts: Parts::default(),
+            annotations: Annotations::default(),
+            version: None,
+            extensions: Extensions::default(),
+            scheme: Cow::Borrowed(CANONICAL_SCHEME),
         })
     }
 
@@ -116,6 +415,10 @@ impl<'a> Arn<'a> {
             account,
             root: Root::default(),
             parts: Parts::default(),
+            annotations: Annotations::default(),
+            version: None,
+            extensions: Extensions::default(),
+            scheme: Cow::Borrowed(CANONICAL_SCHEME),
         })
     }
 
@@ -128,6 +431,10 @@ impl<'a> Arn<'a> {
             account: self.account.clone(),
             root: self.root.clone(),
             parts: new_parts,
+            annotations: Annotations::default(),
+            version: None,
+            extensions: Extensions::default(),
+            scheme: Cow::Borrowed(CANONICAL_SCHEME),
         })
     }
 
@@ -135,16 +442,101 @@ impl<'a> Arn<'a> {
         &self,
         parts: impl IntoIterator<Item = impl Into<Cow<'a, str>>>,
     ) -> Result<Self, ArnError> {
-        let new_parts: Result<Vec<Part<'a>>, _> = parts.into_iter().map(Part::new).collect();
+        let new_parts: Result<Parts<'a>, _> = parts.into_iter().map(Part::new).collect();
+        Ok(Arn {
+            domain: self.domain.clone(),
+            category: self.category.clone(),
+            account: self.account.clone(),
+            root: self.root.clone(),
+            parts: new_parts?,
+            annotations: Annotations::default(),
+            version: None,
+            extensions: Extensions::default(),
+            scheme: Cow::Borrowed(CANONICAL_SCHEME),
+        })
+    }
+
+    /// Returns an Arn with the attribute part `key=value`, replacing the value of the first
+    /// existing part with that key or appending a new part otherwise.
+    ///
+    /// ```
+    /// use akton_arn::Arn;
+    ///
+    /// let arn = Arn::with_root("root").unwrap().with_attr("region", "us-east-1").unwrap();
+    /// assert_eq!(arn.parts().attr("region"), Some("us-east-1"));
+    /// let moved = arn.with_attr("region", "eu-west-1").unwrap();
+    /// assert_eq!(moved.parts().to_string(), "region=eu-west-1");
+    /// ```
+    pub fn with_attr(&self, key: &str, value: &str) -> Result<Self, ArnError> {
+        if key.is_empty() || key.contains('=') {
+            return Err(ArnError::ValidationFailure(
+                ComponentKind::Part,
+                format!("attribute key {key:?} must be non-empty and must not contain '='"),
+            ));
+        }
+        let attr = Part::new(format!("{key}={value}"))?;
+        let mut new_parts = self.parts.clone();
+        match new_parts
+            .0
+            .iter_mut()
+            .find(|part| part.as_attr().is_some_and(|(k, _)| k == key))
+        {
+            Some(existing) => *existing = attr,
+            None => new_parts.0.push(attr),
+        }
         Ok(Arn {
             domain: self.domain.clone(),
             category: self.category.clone(),
             account: self.account.clone(),
             root: self.root.clone(),
-            parts: Parts(new_parts?),
+            parts: new_parts,
+            annotations: Annotations::default(),
+            version: None,
+            extensions: Extensions::default(),
+            scheme: Cow::Borrowed(CANONICAL_SCHEME),
         })
     }
 
+    /// Appends a part in place, the mutating counterpart of [`Arn::add_part`].
+    ///
+    /// Unlike the builder-style methods, the in-place mutators reuse this Arn's storage and
+    /// keep its annotations. On error the Arn is left unchanged.
+    pub fn push_part(&mut self, part: impl Into<Cow<'a, str>>) -> Result<(), ArnError> {
+        self.parts.0.push(Part::new(part)?);
+        Ok(())
+    }
+
+    /// Removes and returns the last part, turning this Arn into its parent in place.
+    pub fn pop_part(&mut self) -> Option<Part<'a>> {
+        self.parts.0.pop()
+    }
+
+    /// Replaces the domain in place, checking it as [`ArnParser`](crate::ArnParser) would. On
+    /// error the Arn is left unchanged.
+    pub fn set_domain(&mut self, domain: impl Into<Cow<'a, str>>) -> Result<(), ArnError> {
+        let rules = ComponentRules::default();
+        self.domain =
+            Domain::new_unchecked(rules.fit_component(ComponentKind::Domain, domain.into())?);
+        Ok(())
+    }
+
+    /// Replaces the category in place, checking it as [`ArnParser`](crate::ArnParser) would.
+    /// On error the Arn is left unchanged.
+    pub fn set_category(&mut self, category: impl Into<Cow<'a, str>>) -> Result<(), ArnError> {
+        let rules = ComponentRules::default();
+        self.category =
+            Category::new(rules.fit_component(ComponentKind::Category, category.into())?);
+        Ok(())
+    }
+
+    /// Replaces the account in place, checking it as [`ArnParser`](crate::ArnParser) would.
+    /// On error the Arn is left unchanged.
+    pub fn set_account(&mut self, account: impl Into<Cow<'a, str>>) -> Result<(), ArnError> {
+        let rules = ComponentRules::default();
+        self.account = Account::new(rules.fit_component(ComponentKind::Account, account.into())?);
+        Ok(())
+    }
+
     pub fn is_child_of(&self, other: &Arn) -> bool {
         self.domain == other.domain
             && self.category == other.category
@@ -154,19 +546,207 @@ impl<'a> Arn<'a> {
             && self.parts.0.starts_with(&other.parts.0)
     }
 
+    /// Returns the Arn one level up the hierarchy, or `None` if the Arn has no parts.
+    ///
+    /// Runs in O(depth) time.
     pub fn parent(&self) -> Option<Self> {
-        if self.parts.0.is_empty() {
-            None
-        } else {
-            Some(Arn {
-                domain: self.domain.clone(),
-                category: self.category.clone(),
-                account: self.account.clone(),
-                root: self.root.clone(),
-                parts: Parts(self.parts.0[..self.parts.0.len() - 1].to_vec()),
-            })
+        self.parts
+            .0
+            .len()
+            .checked_sub(1)
+            .map(|depth| self.truncated(depth))
+    }
+
+    /// Returns the number of parts below the root.
+    pub fn depth(&self) -> usize {
+        self.parts.0.len()
+    }
+
+    /// Returns how long ago the root was minted, or `None` if it carries no timestamp.
+    ///
+    /// See [`Root::created_at`]. A root minted in the future, by a clock running ahead, is
+    /// zero seconds old.
+    pub fn age(&self) -> Option<Duration> {
+        self.age_with_time_source(&SystemClock)
+    }
+
+    /// Like [`Arn::age`], but measures the age at the current time of `time_source`.
+    pub fn age_with_time_source(&self, time_source: &dyn TimeSource) -> Option<Duration> {
+        let created = self.root.created_at()?.duration_since(UNIX_EPOCH).ok()?;
+        Some(time_source.now().saturating_sub(created))
+    }
+
+    /// Iterates over the ancestors of this Arn, nearest first, ending with the Arn that has no parts.
+    ///
+    /// Ancestors are produced lazily at O(depth) each, and skipping ahead with `nth` does not
+    /// build the ancestors it passes over.
+    pub fn ancestors(&self) -> Ancestors<'_, 'a> {
+        Ancestors {
+            arn: self,
+            remaining: self.parts.0.len(),
         }
     }
+
+    /// Returns the deepest Arn that is an ancestor of, or equal to, both Arns.
+    ///
+    /// Returns `None` if the Arns differ in any component other than their parts. Runs in
+    /// O(depth) time without recursion.
+    pub fn common_ancestor(&self, other: &Arn) -> Option<Arn<'a>> {
+        if self.domain != other.domain
+            || self.category != other.category
+            || self.account != other.account
+            || self.root != other.root
+        {
+            return None;
+        }
+        let shared = self
+            .parts
+            .0
+            .iter()
+            .zip(&other.parts.0)
+            .take_while(|(a, b)| a == b)
+            .count();
+        Some(self.truncated(shared))
+    }
+
+    /// Returns a copy of this Arn keeping only the first `depth` parts.
+    pub(crate) fn truncated(&self, depth: usize) -> Arn<'a> {
+        Arn {
+            domain: self.domain.clone(),
+            category: self.category.clone(),
+            account: self.account.clone(),
+            root: self.root.clone(),
+            parts: self.parts.0[..depth].iter().cloned().collect(),
+            annotations: Annotations::default(),
+            version: None,
+            extensions: Extensions::default(),
+            scheme: Cow::Borrowed(CANONICAL_SCHEME),
+        }
+    }
+
+    /// Renders the Arn for display in a terminal, neutralizing characters smuggled in through components.
+    ///
+    /// ANSI escape sequences are stripped entirely, from the ESC to their final character or
+    /// string terminator, while other control characters, a lone ESC and bidirectional
+    /// overrides are replaced by their `\u{..}` escape so they remain visible.
+    pub fn sanitize_for_terminal(&self) -> String {
+        let rendered = self.to_string();
+        let mut sanitized = String::with_capacity(rendered.len());
+        let mut chars = rendered.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\u{1b}' if skip_escape_sequence(&mut chars) => {}
+                c if c.is_control() || is_bidi_control(c) => {
+                    sanitized.extend(c.escape_unicode());
+                }
+                c => sanitized.push(c),
+            }
+        }
+        sanitized
+    }
+}
+
+/// Iterator over the ancestors of an Arn, returned by [`Arn::ancestors`].
+#[derive(Debug, Clone)]
+pub struct Ancestors<'r, 'a> {
+    arn: &'r Arn<'a>,
+    remaining: usize,
+}
+
+impl<'a> Iterator for Ancestors<'_, 'a> {
+    type Item = Arn<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.nth(0)
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        match n.checked_add(1).and_then(|k| self.remaining.checked_sub(k)) {
+            Some(remaining) => {
+                self.remaining = remaining;
+                Some(self.arn.truncated(remaining))
+            }
+            None => {
+                self.remaining = 0;
+                None
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for Ancestors<'_, '_> {}
+
+/// Consumes the rest of the escape sequence whose ESC was just read, returning `false` if no
+/// sequence follows it.
+///
+/// Components never hold a separator, so a sequence ends at the next one rather than hiding it.
+fn skip_escape_sequence(chars: &mut Peekable<Chars<'_>>) -> bool {
+    let separators = [
+        ArnFormat::DEFAULT.component_separator(),
+        ArnFormat::DEFAULT.part_separator(),
+    ];
+    let next = |chars: &mut Peekable<Chars<'_>>| chars.next_if(|c| !separators.contains(c));
+    match chars.peek() {
+        Some(c) if (' '..='~').contains(c) && !separators.contains(c) => {}
+        _ => return false,
+    }
+    match next(chars) {
+        // CSI: parameters and intermediates up to a final byte in '@'..='~'
+        Some('[') => {
+            while let Some(c) = next(chars) {
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+        }
+        // OSC, DCS, SOS, PM and APC: a string terminated by BEL or ST (ESC \)
+        Some(']' | 'P' | 'X' | '^' | '_') => {
+            while let Some(c) = next(chars) {
+                if c == '\u{7}' || (c == '\u{1b}' && chars.next_if_eq(&'\\').is_some()) {
+                    break;
+                }
+            }
+        }
+        // Any other escape: intermediates in ' '..='/', then one final byte
+        Some(' '..='/') => {
+            while let Some(c) = next(chars) {
+                if !(' '..='/').contains(&c) {
+                    break;
+                }
+            }
+        }
+        // A two-byte escape
+        _ => {}
+    }
+    true
+}
+
+/// Returns `true` for Unicode bidirectional formatting characters that can reorder displayed text.
+fn is_bidi_control(c: char) -> bool {
+    matches!(c, '\u{200e}' | '\u{200f}' | '\u{202a}'..='\u{202e}' | '\u{2066}'..='\u{2069}')
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Arn<'_> {
+    /// Serializes the Arn as its canonical string form.
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, 'a> serde::Deserialize<'de> for Arn<'a> {
+    /// Deserializes an Arn from its canonical string form, validating it with [`ArnParser`](crate::ArnParser).
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        crate::ArnParser::new(value)
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
 }
 
 impl<'a> Default for Arn<'a> {
@@ -178,6 +758,10 @@ impl<'a> Default for Arn<'a> {
             account: Account::default(),
             root: Root::default(),
             parts: Parts::new(Vec::default()),
+            annotations: Annotations::default(),
+            version: None,
+            extensions: Extensions::default(),
+            scheme: Cow::Borrowed(CANONICAL_SCHEME),
         }
     }
 }
@@ -217,7 +801,7 @@ mod tests {
             Category::from_str("hr").unwrap(),
             Account::from_str("company123").unwrap(),
             parent_root.clone(),
-            Parts(vec![
+            Parts::new(vec![
                 Part::from_str("department_a").unwrap(),
                 Part::from_str("team1").unwrap(),
             ]),
@@ -228,7 +812,7 @@ mod tests {
             Category::from_str("hr").unwrap(),
             Account::from_str("company123").unwrap(),
             Root::from_str("root_b").unwrap(),
-            Parts(vec![Part::from_str("role_x").unwrap()]),
+            Parts::new(vec![Part::from_str("role_x").unwrap()]),
         );
 
         let combined = parent + child;
@@ -239,7 +823,7 @@ mod tests {
         assert_eq!(combined.root, parent_root);
         assert_eq!(
             combined.parts,
-            Parts(vec![
+            Parts::new(vec![
                 Part::from_str("department_a").unwrap(),
                 Part::from_str("team1").unwrap(),
                 Part::from_str("role_x").unwrap(),
@@ -255,7 +839,7 @@ mod tests {
             Category::from_str("hr").unwrap(),
             Account::from_str("company123").unwrap(),
             Root::from_str("rootp").unwrap(),
-            Parts(vec![Part::from_str("department_a").unwrap()]),
+            Parts::new(vec![Part::from_str("department_a").unwrap()]),
         );
 
         let child = Arn::new(
@@ -263,14 +847,14 @@ mod tests {
             Category::from_str("hr").unwrap(),
             Account::from_str("company123").unwrap(),
             Root::from_str("rootc").unwrap(),
-            Parts(vec![]),
+            Parts::new(vec![]),
         );
 
         let combined = parent + child;
 
         assert_eq!(
             combined.parts,
-            Parts(vec![Part::from_str("department_a").unwrap()])
+            Parts::new(vec![Part::from_str("department_a").unwrap()])
         );
     }
 
@@ -281,19 +865,19 @@ mod tests {
             Category::from_str("hr").unwrap(),
             Account::from_str("company123").unwrap(),
             Root::from_str("rootp").unwrap(),
-            Parts(vec![]),
+            Parts::new(vec![]),
         );
         let child = Arn::new(
             Domain::from_str("akton-internal").unwrap(),
             Category::from_str("hr").unwrap(),
             Account::from_str("company123").unwrap(),
             Root::from_str("rootc").unwrap(),
-            Parts(vec![Part::from_str("role_x").unwrap()]),
+            Parts::new(vec![Part::from_str("role_x").unwrap()]),
         );
         let combined = parent + child;
         assert_eq!(
             combined.parts,
-            Parts(vec![Part::from_str("role_x").unwrap()])
+            Parts::new(vec![Part::from_str("role_x").unwrap()])
         );
     }
 
@@ -304,7 +888,7 @@ mod tests {
             Category::from_str("hr").unwrap(),
             Account::from_str("company123").unwrap(),
             Root::from_str("rootp").unwrap(),
-            Parts(vec![Part::from_str("department_a").unwrap()]),
+            Parts::new(vec![Part::from_str("department_a").unwrap()]),
         );
 
         let child = Arn::new(
@@ -312,7 +896,7 @@ mod tests {
             Category::from_str("hr").unwrap(),
             Account::from_str("company123").unwrap(),
             Root::from_str("rootc").unwrap(),
-            Parts(vec![Part::from_str("team1").unwrap()]),
+            Parts::new(vec![Part::from_str("team1").unwrap()]),
         );
 
         let combined = parent + child;
@@ -343,4 +927,286 @@ mod tests {
         assert!(invalid_part.is_err());
         Ok(())
     }
+
+    #[test]
+    fn test_annotations_do_not_affect_identity() -> anyhow::Result<()> {
+        let plain = Arn::with_root("root")?;
+        let annotated = plain
+            .clone()
+            .with_annotation("display_name", "Payroll")
+            .with_annotation("region", "us-east-1");
+        assert_eq!(annotated, plain);
+        assert_eq!(annotated.to_string(), plain.to_string());
+        assert_eq!(annotated.annotations().get("region"), Some("us-east-1"));
+        assert_eq!(annotated.annotations().len(), 2);
+        assert!(annotated.add_part("child")?.annotations().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_extensions_are_part_of_identity() -> anyhow::Result<()> {
+        let plain = Arn::with_root("root")?.with_parts(["orders"])?;
+        let v3 = plain.clone().with_extension("version", "3")?;
+        assert_ne!(v3, plain);
+        assert!(plain < v3);
+        assert_eq!(v3.to_string(), format!("{plain}?version=3"));
+        let parsed = crate::ArnParser::new(v3.to_string()).parse()?;
+        assert_eq!(parsed, v3);
+        assert_eq!(parsed.extensions().get("version"), Some("3"));
+        assert!(v3.add_part("items")?.extensions().is_empty());
+        assert!(plain.clone().with_extension("a&b", "1").is_err());
+
+        // Only the resource carries a query; `?` earlier in the Arn is component text.
+        let text = "arn:akton:h?r:ac?me:root/orders?version=3";
+        let parsed = crate::ArnParser::new(text).parse()?;
+        assert_eq!(parsed.category().as_str(), "h?r");
+        assert_eq!(parsed.account().as_str(), "ac?me");
+        assert_eq!(parsed.extensions().get("version"), Some("3"));
+        assert_eq!(parsed.to_string(), text);
+        Ok(())
+    }
+
+    #[test]
+    fn test_versions() -> anyhow::Result<()> {
+        let arn = Arn::with_root("root")?.with_parts(["orders"])?;
+        let v2 = arn.clone().with_version(2);
+        let v10 = arn
+            .clone()
+            .with_version(10)
+            .with_extension("region", "eu")?;
+        assert_eq!(v10.to_string(), format!("{arn}@v10?region=eu"));
+        assert_eq!(crate::ArnParser::new(v10.to_string()).parse()?, v10);
+        assert_eq!(v2.version(), Some(2));
+        assert!(arn < v2 && v2 < v10.clone().without_version().with_version(3));
+        assert_ne!(v2, arn);
+        assert!(v2.same_resource_ignoring_version(&arn.clone().with_version(7)));
+        assert!(!v2.same_resource_ignoring_version(&v10));
+        assert_eq!(v2.clone().without_version(), arn);
+        assert_eq!(v2.add_part("items")?.version(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_same_resource_and_identical() -> anyhow::Result<()> {
+        let first = Arn::with_root("order")?.with_parts(["items"])?;
+        let second = Arn::with_root("order")?.with_parts(["items"])?;
+        assert_ne!(first, second);
+        assert!(first.same_resource(&second));
+        assert!(!first.same_resource(&Arn::with_root("invoice")?.with_parts(["items"])?));
+        assert!(!first.same_resource(&second.clone().with_version(2)));
+
+        let mut named = first.clone();
+        named.root = Root::named("order")?;
+        assert!(named.same_resource(&first));
+        assert!(!named.identical(&first));
+
+        let named = crate::ArnParser::new("arn:akton:hr:acme:order/items").parse()?;
+        let legacy = crate::ArnParser::new("ern:akton:hr:acme:order/items").parse()?;
+        assert_eq!(named, legacy);
+        assert!(named.identical(&named.clone()));
+        assert!(!named.identical(&legacy));
+        Ok(())
+    }
+
+    #[test]
+    fn test_age() -> anyhow::Result<()> {
+        use crate::SimulatedClock;
+
+        let minted = Duration::from_secs(1_700_000_000);
+        let clock = SimulatedClock::new(minted);
+        let root = Root::new_with_time_source("x", &clock)?;
+        let arn = Arn::new(
+            Domain::new("akton")?,
+            Category::new("hr"),
+            Account::new("acme"),
+            root,
+            Parts::new(Vec::new()),
+        );
+        clock.advance(Duration::from_secs(90));
+        assert_eq!(
+            arn.age_with_time_source(&clock),
+            Some(Duration::from_secs(90))
+        );
+        clock.set(minted - Duration::from_secs(5));
+        assert_eq!(arn.age_with_time_source(&clock), Some(Duration::ZERO));
+        assert!(crate::ArnParser::new("arn:akton:hr:acme:root")
+            .parse()?
+            .age()
+            .is_none());
+        assert!(Arn::default().age().is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_with_attr() -> anyhow::Result<()> {
+        let arn = Arn::with_root("root")?
+            .with_parts(["orders", "region=us"])?
+            .with_annotation("owner", "ops");
+        let updated = arn.with_attr("region", "eu")?.with_attr("tier", "gold")?;
+        assert_eq!(updated.parts.to_string(), "orders/region=eu/tier=gold");
+        assert!(updated.annotations().is_empty());
+        assert!(arn.with_attr("", "x").is_err());
+        assert!(arn.with_attr("a=b", "x").is_err());
+        assert_eq!(
+            arn.with_attr("region", "a/b"),
+            Err(ArnError::InvalidPartFormat)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_in_place_mutators_match_builder_methods() -> anyhow::Result<()> {
+        let base = Arn::with_root("root")?.with_parts(["a"])?;
+        let mut arn = base.clone().with_annotation("owner", "ops");
+        arn.push_part("b")?;
+        assert_eq!(arn, base.add_part("b")?);
+        assert_eq!(arn.annotations().get("owner"), Some("ops"));
+        assert_eq!(arn.push_part("bad/part"), Err(ArnError::InvalidPartFormat));
+        assert_eq!(arn.pop_part().map(|p| p.to_string()), Some("b".to_string()));
+        assert_eq!(arn, base);
+
+        arn.set_domain("billing")?;
+        arn.set_category("invoices")?;
+        arn.set_account("acme")?;
+        assert!(arn.set_domain("").is_err());
+        assert!(matches!(
+            arn.set_category("invoices:paid"),
+            Err(ArnError::ParseFailure("Category", _))
+        ));
+        assert!(matches!(
+            arn.set_account("acme:root"),
+            Err(ArnError::ParseFailure("Account", _))
+        ));
+        assert!(matches!(
+            arn.set_account("ac\u{1b}me"),
+            Err(ArnError::ValidationFailure(ComponentKind::Account, _))
+        ));
+        assert!(matches!(
+            arn.set_category("c".repeat(crate::MAX_COMPONENT_LEN + 1)),
+            Err(ArnError::ValidationFailure(ComponentKind::Category, _))
+        ));
+        // `?` and `@` belong to the resource, so the parser accepts them here.
+        arn.set_account("acme?@")?;
+        assert_eq!(arn.to_string().parse::<Arn>()?, arn);
+        arn.set_account("acme")?;
+        assert_eq!(arn.domain.as_str(), "billing");
+        assert_eq!(arn.category.as_str(), "invoices");
+        assert_eq!(arn.account.as_str(), "acme");
+
+        assert!(arn.pop_part().is_some());
+        assert_eq!(arn.pop_part(), None);
+        Ok(())
+    }
+
+    fn deep_arn(depth: usize) -> Arn<'static> {
+        let path: Vec<String> = (0..depth).map(|i| format!("p{i}")).collect();
+        crate::ArnParser::new(format!("arn:akton:hr:acme:root/{}", path.join("/")))
+            .with_limits(crate::ArnLimits::unlimited())
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_ancestors_and_common_ancestor() -> anyhow::Result<()> {
+        let arn = Arn::with_root("root")?.with_parts(["a", "b", "c"])?;
+        let ancestors: Vec<String> = arn.ancestors().map(|a| a.parts.to_string()).collect();
+        assert_eq!(ancestors, vec!["a/b", "a", ""]);
+
+        let sibling = arn.with_parts(["a", "x"])?;
+        assert_eq!(
+            arn.common_ancestor(&sibling).map(|a| a.parts.to_string()),
+            Some("a".to_string())
+        );
+        assert_eq!(arn.common_ancestor(&arn), Some(arn.clone()));
+        assert_eq!(arn.common_ancestor(&Arn::with_root("other")?), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_deep_hierarchy_operations() {
+        const DEPTH: usize = 20_000;
+        let arn = deep_arn(DEPTH);
+        assert_eq!(arn.depth(), DEPTH);
+        assert_eq!(arn.parent().map(|p| p.depth()), Some(DEPTH - 1));
+        assert_eq!(
+            arn.ancestors().nth(9_999).map(|a| a.depth()),
+            Some(DEPTH - 10_000)
+        );
+        assert!(arn.is_child_of(&deep_arn(DEPTH / 2)));
+
+        let mut diverged = arn.clone();
+        diverged.parts.0[DEPTH - 1] = Part::new("other").unwrap();
+        assert_eq!(
+            arn.common_ancestor(&diverged).map(|a| a.depth()),
+            Some(DEPTH - 1)
+        );
+
+        let rendered = arn.to_string();
+        let parsed = crate::ArnParser::new(rendered)
+            .with_limits(crate::ArnLimits::unlimited())
+            .parse();
+        assert_eq!(parsed.unwrap(), arn);
+        let bytes = arn.to_bytes();
+        assert_eq!(
+            Arn::from_bytes_with_limits(&bytes, crate::ArnLimits::unlimited()).unwrap(),
+            arn
+        );
+        assert!(matches!(
+            Arn::from_bytes(&bytes),
+            Err(ArnError::TooLong(_, crate::MAX_ARN_LEN))
+        ));
+    }
+
+    #[test]
+    fn test_sanitize_for_terminal() -> anyhow::Result<()> {
+        let arn = Arn::new(
+            Domain::new("akton")?,
+            Category::new("hr"),
+            Account::new("evil\u{1b}[31mred\u{1b}[0m"),
+            Root::new("root")?,
+            Parts::new(vec![
+                Part::new("title\u{1b}]0;pwned\u{7}")?,
+                Part::new("bell\u{7}\u{202e}txt")?,
+            ]),
+        );
+        let sanitized = arn.sanitize_for_terminal();
+        assert!(sanitized.starts_with("arn:akton:hr:evilred:root"));
+        assert!(sanitized.ends_with("/title/bell\\u{7}\\u{202e}txt"));
+        assert!(!sanitized.contains('\u{1b}'));
+        Ok(())
+    }
+
+    #[test]
+    fn test_sanitize_for_terminal_strips_whole_sequences() -> anyhow::Result<()> {
+        for (smuggled, shown) in [
+            ("a\u{1b}(0b", "ab"),
+            ("a\u{1b}7b\u{1b}8", "ab"),
+            ("a\u{1b}Pq#0;2;0;0;0\u{1b}\\b", "ab"),
+            ("a\u{1b}_payload\u{7}b", "ab"),
+            ("a\u{1b}]8;;x\u{1b}\\b", "ab"),
+            ("a\u{1b}", "a\\u{1b}"),
+            ("a\u{1b}[31", "a"),
+            ("a\u{1b}\u{1b}[2Jb", "a\\u{1b}b"),
+        ] {
+            let arn = Arn::new(
+                Domain::new("akton")?,
+                Category::new("hr"),
+                Account::new(smuggled),
+                Root::new("root")?,
+                Parts::default(),
+            );
+            let sanitized = arn.sanitize_for_terminal();
+            assert!(
+                sanitized.starts_with(&format!("arn:akton:hr:{shown}:root")),
+                "{smuggled:?} became {sanitized:?}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_sanitize_for_terminal_leaves_clean_arn_untouched() {
+        let arn = Arn::default();
+        assert_eq!(arn.sanitize_for_terminal(), arn.to_string());
+    }
 }
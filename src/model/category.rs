@@ -1,26 +1,98 @@
-use derive_more::{AsRef, From, Into};
+use crate::model::SharedStr;
+use crate::traits::ArnValidator;
+use crate::ComponentKind;
 use std::borrow::Cow;
 use std::fmt;
+
+/// Separates the levels of a hierarchical category such as `hr.payroll`.
+pub const CATEGORY_SEPARATOR: char = '.';
+
 /// Represents a category in the Arn system, typically indicating the service.
+///
+/// A category may be hierarchical, with levels separated by [`CATEGORY_SEPARATOR`]:
+/// `hr.payroll` sits within `hr`. Patterns match a category and everything within it with
+/// `hr.**` (see [`ArnPattern`](crate::ArnPattern)).
+///
+/// ```
+/// use akton_arn::Category;
+///
+/// let payroll = Category::new("hr.payroll.uk");
+/// assert_eq!(payroll.parent().unwrap().as_str(), "hr.payroll");
+/// assert_eq!(payroll.segments().collect::<Vec<_>>(), ["hr", "payroll", "uk"]);
+/// assert!(payroll.is_within(&Category::new("hr")));
+/// assert!(!Category::new("hrx").is_within(&Category::new("hr")));
+/// ```
+#[derive(Eq, Debug, PartialEq, PartialOrd, Ord, Clone, Hash)]
+pub struct Category<'a>(pub(crate) SharedStr<'a>);
 
-#[derive(AsRef, From, Into, Eq, Debug, PartialEq, Clone, Hash)]
-pub struct Category<'a>(pub(crate) Cow<'a, str>);
+impl_shared_str_conversions!(Category);
 
 impl<'a> Category<'a> {
     pub fn as_str(&self) -> &str {
         &self.0
     }
     pub fn new(value: impl Into<Cow<'a, str>>) -> Self {
-        Category(value.into())
+        Category(value.into().into())
     }
     pub fn into_owned(self) -> Category<'static> {
-        Category(Cow::Owned(self.0.into_owned()))
+        Category(self.0.into_owned())
+    }
+
+    /// Returns the category one level up, or `None` for a top-level category.
+    pub fn parent(&self) -> Option<Category<'_>> {
+        self.as_str()
+            .rsplit_once(CATEGORY_SEPARATOR)
+            .map(|(parent, _)| Category(SharedStr::borrowed(parent)))
+    }
+
+    /// Returns the levels of the category, from the top.
+    pub fn segments(&self) -> impl Iterator<Item = &str> {
+        self.as_str().split(CATEGORY_SEPARATOR)
+    }
+
+    /// Returns `true` if the category is `other` or lies below it.
+    pub fn is_within(&self, other: &Category) -> bool {
+        category_lineage(self.as_str()).any(|category| category == other.as_str())
+    }
+}
+
+/// Returns `category` and every category above it, from the most specific.
+pub(crate) fn category_lineage(category: &str) -> impl Iterator<Item = &str> {
+    std::iter::successors(Some(category), |category| {
+        category
+            .rsplit_once(CATEGORY_SEPARATOR)
+            .map(|(parent, _)| parent)
+    })
+}
+
+/// A validator requiring every level of a hierarchical category to be non-empty, so
+/// `hr..payroll` and `hr.` are rejected.
+///
+/// ```
+/// use akton_arn::{ArnParser, HierarchicalCategories};
+///
+/// let parse = |input| ArnParser::new(input).with_validator(HierarchicalCategories).parse();
+/// assert!(parse("arn:akton:hr.payroll:acme:root").is_ok());
+/// assert!(parse("arn:akton:hr..payroll:acme:root").is_err());
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HierarchicalCategories;
+
+impl ArnValidator for HierarchicalCategories {
+    fn validate(&self, kind: ComponentKind, value: &str) -> Result<(), String> {
+        if kind != ComponentKind::Category || value.split(CATEGORY_SEPARATOR).all(|s| !s.is_empty())
+        {
+            return Ok(());
+        }
+        Err(format!(
+            "{value:?} has an empty level between {CATEGORY_SEPARATOR:?} separators"
+        ))
     }
 }
 
 impl<'a> Default for Category<'a> {
     fn default() -> Self {
-        Category(Cow::Borrowed("system"))
+        Category(SharedStr::borrowed("system"))
     }
 }
 
@@ -34,12 +106,12 @@ impl<'a> std::str::FromStr for Category<'a> {
     type Err = std::convert::Infallible;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Category(Cow::Owned(s.to_owned())))
+        Ok(Category(s.to_owned().into()))
     }
 }
 impl<'a> From<Category<'a>> for String {
     fn from(category: Category<'a>) -> Self {
-        category.0.into_owned()
+        category.0.into_string()
     }
 }
 #[cfg(test)]
@@ -52,6 +124,26 @@ mod tests {
         assert_eq!(category.as_str(), "test");
     }
 
+    #[test]
+    fn test_category_hierarchy() {
+        let category = Category::new("hr.payroll");
+        assert_eq!(category.parent(), Some(Category::new("hr")));
+        assert_eq!(Category::new("hr").parent(), None);
+        assert!(category.is_within(&category));
+        assert!(!Category::new("hr").is_within(&category));
+        assert_eq!(
+            category_lineage("a.b.c").collect::<Vec<_>>(),
+            ["a.b.c", "a.b", "a"]
+        );
+        assert!(HierarchicalCategories
+            .validate(ComponentKind::Category, ".hr")
+            .is_err());
+        assert_eq!(
+            HierarchicalCategories.validate(ComponentKind::Part, "a..b"),
+            Ok(())
+        );
+    }
+
     #[test]
     fn test_category_default() {
         let category = Category::default();
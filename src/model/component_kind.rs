@@ -0,0 +1,30 @@
+use std::fmt;
+
+/// Identifies which component of an Arn a value belongs to.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum ComponentKind {
+    Domain,
+    Category,
+    Account,
+    Root,
+    Part,
+}
+
+impl ComponentKind {
+    /// Returns the human-readable name of the component.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ComponentKind::Domain => "Domain",
+            ComponentKind::Category => "Category",
+            ComponentKind::Account => "Account",
+            ComponentKind::Root => "Root",
+            ComponentKind::Part => "Part",
+        }
+    }
+}
+
+impl fmt::Display for ComponentKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
@@ -1,10 +1,12 @@
 use crate::errors::ArnError;
-use derive_more::{AsRef, From, Into};
+use crate::model::SharedStr;
 use std::borrow::Cow;
 use std::fmt;
 
-#[derive(AsRef, From, Into, Eq, Debug, PartialEq, Clone)]
-pub struct Domain<'a>(pub(crate) Cow<'a, str>);
+#[derive(Eq, Debug, PartialEq, PartialOrd, Ord, Clone, Hash)]
+pub struct Domain<'a>(pub(crate) SharedStr<'a>);
+
+impl_shared_str_conversions!(Domain);
 
 impl<'a> Domain<'a> {
     pub fn as_str(&self) -> &str {
@@ -12,21 +14,34 @@ impl<'a> Domain<'a> {
     }
 
     pub fn into_owned(self) -> Domain<'static> {
-        Domain(Cow::Owned(self.0.into_owned()))
+        Domain(self.0.into_owned())
     }
     pub fn new(value: impl Into<Cow<'a, str>>) -> Result<Self, ArnError> {
         let val = value.into();
         if val.is_empty() {
-            Err(ArnError::ParseFailure("Domain", "cannot be empty".to_string()))
+            Err(ArnError::ParseFailure(
+                "Domain",
+                "cannot be empty".to_string(),
+            ))
         } else {
-            Ok(Domain(val))
+            Ok(Domain(val.into()))
         }
     }
+
+    /// Wraps a domain already validated upstream, skipping the check [`Domain::new`] makes.
+    ///
+    /// An invalid value panics in debug builds and yields an Arn that may not round-trip in
+    /// release builds.
+    pub fn new_unchecked(value: impl Into<Cow<'a, str>>) -> Self {
+        let value = value.into();
+        debug_assert!(!value.is_empty(), "domain cannot be empty");
+        Domain(value.into())
+    }
 }
 
 impl<'a> Default for Domain<'a> {
     fn default() -> Self {
-        Domain(Cow::Borrowed("akton"))
+        Domain(SharedStr::borrowed("akton"))
     }
 }
 
@@ -45,7 +60,7 @@ impl<'a> std::str::FromStr for Domain<'a> {
 }
 impl<'a> From<Domain<'a>> for String {
     fn from(domain: Domain<'a>) -> Self {
-        domain.0.into_owned()
+        domain.0.into_string()
     }
 }
 #[cfg(test)]
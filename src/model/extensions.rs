@@ -0,0 +1,160 @@
+use crate::errors::ArnError;
+use std::fmt;
+use std::str::FromStr;
+
+/// Non-hierarchical qualifiers carried in an Arn's trailing `?key=value&key2=value2` suffix.
+///
+/// Extensions hold qualifiers such as a version or region that identify a variant of a
+/// resource without adding a level to its hierarchy. Unlike [`Annotations`](crate::Annotations)
+/// they are part of the canonical form: they keep their insertion order, are rendered by
+/// `Display`, survive parsing and take part in equality, hashing and ordering.
+///
+/// Keys must be non-empty and may not contain `=` or `&`; values may not contain `&`.
+///
+/// ```
+/// use akton_arn::ArnParser;
+///
+/// let arn = ArnParser::new("arn:akton:hr:acme:root/orders?version=3&region=eu").parse().unwrap();
+/// assert_eq!(arn.extensions().get("region"), Some("eu"));
+/// assert_eq!(arn.to_string(), "arn:akton:hr:acme:root/orders?version=3&region=eu");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Extensions(pub(crate) Vec<(String, String)>);
+
+impl Extensions {
+    /// Creates an empty set of extensions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the value stored under `key`.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Stores `value` under `key`, keeping the key's position if it is already present.
+    ///
+    /// Returns the previous value, or an error if the key or value is not allowed.
+    pub fn insert(
+        &mut self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Result<Option<String>, ArnError> {
+        let (key, value) = (key.into(), value.into());
+        validate(&key, &value)?;
+        match self.0.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, existing)) => Ok(Some(std::mem::replace(existing, value))),
+            None => {
+                self.0.push((key, value));
+                Ok(None)
+            }
+        }
+    }
+
+    /// Removes the value stored under `key`, returning it.
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        let index = self.0.iter().position(|(k, _)| k == key)?;
+        Some(self.0.remove(index).1)
+    }
+
+    /// Returns `true` if a value is stored under `key`.
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Iterates over the extensions in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Returns the number of extensions.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if there are no extensions.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+fn validate(key: &str, value: &str) -> Result<(), ArnError> {
+    if key.is_empty() || key.contains(['=', '&']) {
+        return Err(ArnError::ParseFailure(
+            "Extensions",
+            format!("key {key:?} must be non-empty and must not contain '=' or '&'"),
+        ));
+    }
+    if value.contains('&') {
+        return Err(ArnError::ParseFailure(
+            "Extensions",
+            format!("value {value:?} of {key} must not contain '&'"),
+        ));
+    }
+    Ok(())
+}
+
+impl fmt::Display for Extensions {
+    /// Formats the extensions as `key=value` pairs joined with `&`, without the leading `?`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (key, value)) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str("&")?;
+            }
+            write!(f, "{key}={value}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Extensions {
+    type Err = ArnError;
+
+    /// Parses `key=value` pairs joined with `&`, rejecting empty input and duplicate keys.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut extensions = Extensions::new();
+        for pair in s.split('&') {
+            let (key, value) = pair.split_once('=').ok_or_else(|| {
+                ArnError::ParseFailure("Extensions", format!("{pair:?} is not key=value"))
+            })?;
+            if extensions.insert(key, value)?.is_some() {
+                return Err(ArnError::ParseFailure(
+                    "Extensions",
+                    format!("duplicate key {key:?}"),
+                ));
+            }
+        }
+        Ok(extensions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extensions_keep_insertion_order() {
+        let mut extensions: Extensions = "version=3&region=eu".parse().unwrap();
+        assert_eq!(extensions.insert("version", "4"), Ok(Some("3".to_string())));
+        extensions.insert("tier", "gold").unwrap();
+        assert_eq!(extensions.to_string(), "version=4&region=eu&tier=gold");
+        assert_eq!(extensions.remove("region"), Some("eu".to_string()));
+        assert_eq!(
+            extensions.iter().collect::<Vec<_>>(),
+            [("version", "4"), ("tier", "gold")]
+        );
+    }
+
+    #[test]
+    fn test_invalid_extensions() {
+        for input in ["", "a", "=1", "a=1&a=2", "a=1&", "a=1&&b=2"] {
+            assert!(input.parse::<Extensions>().is_err(), "{input:?}");
+        }
+        assert_eq!("a==b".parse::<Extensions>().unwrap().get("a"), Some("=b"));
+        assert!(Extensions::new().insert("a", "x&y").is_err());
+        assert!(Extensions::new().insert("a=b", "x").is_err());
+    }
+}
@@ -0,0 +1,253 @@
+use crate::model::{Arn, Part, Root};
+use std::cmp::Ordering;
+use std::ops::Range;
+
+impl<'a> Arn<'a> {
+    /// Compares two Arns by the hierarchy partial order.
+    ///
+    /// Returns `Less` if `self` is an ancestor of `other`, `Greater` if it is a descendant,
+    /// `Equal` if they are the same Arn, and `None` if neither contains the other. Unlike
+    /// `Ord`, which is lexical, this answers containment questions.
+    pub fn hierarchy_cmp(&self, other: &Arn) -> Option<Ordering> {
+        if self == other {
+            Some(Ordering::Equal)
+        } else if other.is_child_of(self) {
+            Some(Ordering::Less)
+        } else if self.is_child_of(other) {
+            Some(Ordering::Greater)
+        } else {
+            None
+        }
+    }
+
+    /// Wraps the Arn so that comparison operators follow the hierarchy partial order.
+    pub fn by_hierarchy(&self) -> ByHierarchy<'_, '_> {
+        ByHierarchy(self)
+    }
+
+    /// Returns the smallest Arn that sorts after this resource and all of its descendants.
+    ///
+    /// The last part (or the root, for an Arn without parts) is followed by a NUL character,
+    /// so the result is only meant as an exclusive bound for range queries, not as a resource.
+    pub fn successor(&self) -> Arn<'a> {
+        let mut next = self.truncated(self.depth());
+        match next.parts.0.pop() {
+            Some(last) => next
+                .parts
+                .0
+                .push(Part::from_shared(format!("{last}\0").into())),
+            None => next.root = Root(format!("{}\0", next.root).into()),
+        }
+        next
+    }
+
+    /// Returns the range of the canonical order holding this resource and all of its descendants.
+    ///
+    /// Because `Ord` compares parts before the version and extensions, the range also holds
+    /// every version and qualified variant of the resource and of its descendants.
+    ///
+    /// ```
+    /// use akton_arn::{Arn, ArnParser};
+    /// use std::collections::BTreeSet;
+    ///
+    /// let arns: BTreeSet<Arn> = [
+    ///     "arn:akton:hr:acme:root/team",
+    ///     "arn:akton:hr:acme:root/team/alice",
+    ///     "arn:akton:hr:acme:root/team@v2",
+    ///     "arn:akton:hr:acme:root/team-b",
+    ///     "arn:akton:hr:acme:root/ops",
+    /// ]
+    /// .into_iter()
+    /// .map(|s| ArnParser::new(s).parse().unwrap())
+    /// .collect();
+    ///
+    /// let team = ArnParser::new("arn:akton:hr:acme:root/team").parse().unwrap();
+    /// let subtree: Vec<String> = arns.range(team.prefix_range()).map(ToString::to_string).collect();
+    /// assert_eq!(
+    ///     subtree,
+    ///     [
+    ///         "arn:akton:hr:acme:root/team",
+    ///         "arn:akton:hr:acme:root/team@v2",
+    ///         "arn:akton:hr:acme:root/team/alice",
+    ///     ]
+    /// );
+    /// ```
+    pub fn prefix_range(&self) -> Range<Arn<'a>> {
+        self.truncated(self.depth())..self.successor()
+    }
+
+    /// Returns `(start, end)` keys bounding every descendant in a byte-ordered key-value store.
+    ///
+    /// Keys are the canonical string form, as stored by a
+    /// [`RegistryBackend`](crate::RegistryBackend). Every descendant's key starts with this
+    /// resource's key followed by `/`, so scanning `start..end` (end exclusive) in RocksDB,
+    /// FoundationDB or sled visits exactly the descendants without a secondary index. The
+    /// resource itself and its versions are not included.
+    ///
+    /// ```
+    /// use akton_arn::ArnParser;
+    ///
+    /// let team = ArnParser::new("arn:akton:hr:acme:root/team@v2").parse().unwrap();
+    /// let (start, end) = team.descendant_range_bounds();
+    /// assert_eq!(start, b"arn:akton:hr:acme:root/team/");
+    /// assert_eq!(end, b"arn:akton:hr:acme:root/team0");
+    /// ```
+    pub fn descendant_range_bounds(&self) -> (Vec<u8>, Vec<u8>) {
+        let key = self.truncated(self.depth()).to_string();
+        // '0' is the byte after '/', so `end` is the first key past the `start` prefix.
+        (
+            format!("{key}/").into_bytes(),
+            format!("{key}0").into_bytes(),
+        )
+    }
+}
+
+/// An Arn compared by the hierarchy partial order instead of the canonical total order.
+///
+/// Ancestors are less than their descendants and unrelated Arns are incomparable, so
+/// `a.by_hierarchy() < b.by_hierarchy()` reads as "`a` contains `b`".
+///
+/// ```
+/// use akton_arn::ArnParser;
+///
+/// let team = ArnParser::new("arn:akton:hr:acme:root/team").parse().unwrap();
+/// let member = ArnParser::new("arn:akton:hr:acme:root/team/alice").parse().unwrap();
+/// let other = ArnParser::new("arn:akton:hr:acme:root/ops").parse().unwrap();
+///
+/// assert!(team.by_hierarchy() < member.by_hierarchy());
+/// assert!(other < team, "lexically ordered");
+/// assert_eq!(other.by_hierarchy().partial_cmp(&team.by_hierarchy()), None, "but not contained");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ByHierarchy<'r, 'a>(pub &'r Arn<'a>);
+
+impl PartialEq for ByHierarchy<'_, '_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl PartialOrd for ByHierarchy<'_, '_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.0.hierarchy_cmp(other.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArnParser;
+
+    fn arn(s: &str) -> Arn<'static> {
+        ArnParser::new(s.to_string()).parse().unwrap()
+    }
+
+    #[test]
+    fn test_hierarchy_partial_order() {
+        let root = arn("arn:akton:hr:acme:root");
+        let team = arn("arn:akton:hr:acme:root/team");
+        let member = arn("arn:akton:hr:acme:root/team/alice");
+        let other = arn("arn:akton:hr:acme:root/ops");
+
+        assert_eq!(root.hierarchy_cmp(&member), Some(Ordering::Less));
+        assert_eq!(member.hierarchy_cmp(&team), Some(Ordering::Greater));
+        assert_eq!(team.hierarchy_cmp(&team.clone()), Some(Ordering::Equal));
+        assert_eq!(team.hierarchy_cmp(&other), None);
+        assert_eq!(
+            team.hierarchy_cmp(&arn("arn:akton:it:acme:root/team/alice")),
+            None
+        );
+
+        assert!(team.by_hierarchy() <= member.by_hierarchy());
+        assert_eq!(other.by_hierarchy().partial_cmp(&team.by_hierarchy()), None);
+    }
+
+    #[test]
+    fn test_prefix_range_selects_exactly_the_subtree() {
+        use std::collections::BTreeMap;
+
+        let inputs = [
+            "arn:akton:hr:acme:root",
+            "arn:akton:hr:acme:root/a",
+            "arn:akton:hr:acme:root/a/b",
+            "arn:akton:hr:acme:root/a/b/c@v1",
+            "arn:akton:hr:acme:root/a?k=v",
+            "arn:akton:hr:acme:root/a-",
+            "arn:akton:hr:acme:root/ab",
+            "arn:akton:hr:acme:root/b",
+            "arn:akton:hr:acme:root0",
+            "arn:akton:hr:acme:rooz/a",
+        ];
+        let map: BTreeMap<Arn, &str> = inputs.iter().map(|s| (arn(s), *s)).collect();
+        for input in inputs {
+            let prefix = arn(input);
+            let actual: Vec<&str> = map.range(prefix.prefix_range()).map(|(_, s)| *s).collect();
+            // Everything whose first `depth` parts match, whatever its version or extensions.
+            let depth = prefix.depth();
+            let mut expected: Vec<&str> = inputs
+                .iter()
+                .copied()
+                .filter(|s| {
+                    let other = arn(s);
+                    other.depth() >= depth && other.truncated(depth) == prefix.truncated(depth)
+                })
+                .collect();
+            expected.sort_by_key(|s| arn(s));
+            assert_eq!(actual, expected, "{input}");
+        }
+        assert!(arn("arn:akton:hr:acme:root/a").successor() > arn("arn:akton:hr:acme:root/a/zzz"));
+    }
+
+    #[test]
+    fn test_descendant_range_bounds_scan_byte_ordered_keys() {
+        use std::collections::BTreeSet;
+
+        let keys: BTreeSet<Vec<u8>> = [
+            "arn:akton:hr:acme:root/a",
+            "arn:akton:hr:acme:root/a@v1",
+            "arn:akton:hr:acme:root/a/b",
+            "arn:akton:hr:acme:root/a/b@v3?k=v",
+            "arn:akton:hr:acme:root/a/b/c",
+            "arn:akton:hr:acme:root/a-",
+            "arn:akton:hr:acme:root/a0",
+            "arn:akton:hr:acme:root/ab",
+        ]
+        .into_iter()
+        .map(|s| s.as_bytes().to_vec())
+        .collect();
+        let (start, end) = arn("arn:akton:hr:acme:root/a").descendant_range_bounds();
+        let scanned: Vec<&str> = keys
+            .range(start..end)
+            .map(|key| std::str::from_utf8(key).unwrap())
+            .collect();
+        assert_eq!(
+            scanned,
+            [
+                "arn:akton:hr:acme:root/a/b",
+                "arn:akton:hr:acme:root/a/b/c",
+                "arn:akton:hr:acme:root/a/b@v3?k=v",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_canonical_order_is_total_and_ancestors_first() {
+        let mut arns = [
+            arn("arn:akton:hr:acme:root/team/alice"),
+            arn("arn:akton:hr:acme:root/ops"),
+            arn("arn:akton:hr:acme:root"),
+            arn("arn:akton:hr:acme:root/team"),
+        ];
+        arns.sort();
+        let sorted: Vec<String> = arns.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            sorted,
+            [
+                "arn:akton:hr:acme:root",
+                "arn:akton:hr:acme:root/ops",
+                "arn:akton:hr:acme:root/team",
+                "arn:akton:hr:acme:root/team/alice",
+            ]
+        );
+    }
+}
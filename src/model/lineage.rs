@@ -0,0 +1,229 @@
+use crate::errors::ArnError;
+use crate::Arn;
+use std::time::SystemTime;
+
+/// A single rename or move of a resource from one Arn to another.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LineageRecord {
+    /// The Arn the resource was known by before the rename.
+    pub from: Arn<'static>,
+    /// The Arn the resource is known by after the rename.
+    pub to: Arn<'static>,
+    /// When the rename took effect.
+    pub at: SystemTime,
+    /// Why the resource was renamed or moved.
+    pub reason: String,
+}
+
+/// The chain of renames and moves a resource has gone through, oldest first.
+///
+/// Audit systems use a lineage to resolve references to a resource by any name it has held.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ArnLineage {
+    origin: Arn<'static>,
+    records: Vec<LineageRecord>,
+}
+
+impl ArnLineage {
+    /// Starts a lineage for a resource first known by `origin`.
+    pub fn new(origin: Arn<'_>) -> Self {
+        ArnLineage {
+            origin: origin.into_owned(),
+            records: Vec::new(),
+        }
+    }
+
+    /// Returns the first Arn the resource was known by.
+    pub fn origin(&self) -> &Arn<'static> {
+        &self.origin
+    }
+
+    /// Returns the recorded renames, oldest first.
+    pub fn records(&self) -> &[LineageRecord] {
+        &self.records
+    }
+
+    /// Returns the Arn the resource is currently known by.
+    pub fn current(&self) -> &Arn<'static> {
+        self.records
+            .last()
+            .map_or(&self.origin, |record| &record.to)
+    }
+
+    /// Records a rename of the current Arn to `to`, effective now.
+    pub fn rename(&mut self, to: Arn<'_>, reason: impl Into<String>) -> Result<(), ArnError> {
+        self.rename_at(to, SystemTime::now(), reason)
+    }
+
+    /// Records a rename of the current Arn to `to`, effective at `at`.
+    ///
+    /// Fails if `to` is the current Arn or if `at` precedes the most recent rename.
+    pub fn rename_at(
+        &mut self,
+        to: Arn<'_>,
+        at: SystemTime,
+        reason: impl Into<String>,
+    ) -> Result<(), ArnError> {
+        if &to == self.current() {
+            return Err(ArnError::BrokenLineage(format!(
+                "{} is already the current name",
+                to
+            )));
+        }
+        if self.records.last().is_some_and(|last| at < last.at) {
+            return Err(ArnError::BrokenLineage(
+                "rename predates the most recent rename".to_string(),
+            ));
+        }
+        self.records.push(LineageRecord {
+            from: self.current().clone(),
+            to: to.into_owned(),
+            at,
+            reason: reason.into(),
+        });
+        Ok(())
+    }
+
+    /// Returns `true` if the resource has ever been known by `arn`, including its current name.
+    pub fn was_known_as(&self, arn: &Arn) -> bool {
+        &self.origin == arn || self.records.iter().any(|record| &record.to == arn)
+    }
+
+    /// Returns the Arn the resource was known by at the given point in time.
+    pub fn name_at(&self, at: SystemTime) -> &Arn<'static> {
+        self.records
+            .iter()
+            .take_while(|record| record.at <= at)
+            .last()
+            .map_or(&self.origin, |record| &record.to)
+    }
+
+    /// Merges the records of another lineage of the same resource into this one.
+    ///
+    /// The lineages may overlap or one may continue the other; records are deduplicated and
+    /// ordered by time. Fails, leaving `self` untouched, if the combined records do not form a
+    /// single unbroken chain that includes both origins.
+    pub fn merge(&mut self, other: &ArnLineage) -> Result<(), ArnError> {
+        let mut records = self.records.clone();
+        for record in &other.records {
+            if !records.contains(record) {
+                records.push(record.clone());
+            }
+        }
+        records.sort_by_key(|record| record.at);
+
+        let origin = records.first().map_or(&self.origin, |record| &record.from);
+        let mut current = origin;
+        for record in &records {
+            if &record.from != current {
+                return Err(ArnError::BrokenLineage(format!(
+                    "expected a rename from {} but found one from {}",
+                    current, record.from
+                )));
+            }
+            current = &record.to;
+        }
+
+        let merged = ArnLineage {
+            origin: origin.clone(),
+            records,
+        };
+        for lineage_origin in [&self.origin, &other.origin] {
+            if !merged.was_known_as(lineage_origin) {
+                return Err(ArnError::BrokenLineage(format!(
+                    "{} is not part of the merged lineage",
+                    lineage_origin
+                )));
+            }
+        }
+        *self = merged;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArnParser;
+    use std::time::Duration;
+
+    fn arn(s: &str) -> Arn<'static> {
+        ArnParser::new(s.to_string()).parse().unwrap()
+    }
+
+    fn at(secs: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn test_lineage_tracks_current_and_history() -> anyhow::Result<()> {
+        let mut lineage = ArnLineage::new(arn("arn:akton:hr:acme:root/team_a"));
+        lineage.rename_at(arn("arn:akton:hr:acme:root/team_b"), at(10), "reorg")?;
+        lineage.rename_at(arn("arn:akton:ops:acme:root/team_b"), at(20), "moved")?;
+
+        assert_eq!(lineage.current(), &arn("arn:akton:ops:acme:root/team_b"));
+        assert!(lineage.was_known_as(&arn("arn:akton:hr:acme:root/team_a")));
+        assert!(lineage.was_known_as(&arn("arn:akton:hr:acme:root/team_b")));
+        assert!(!lineage.was_known_as(&arn("arn:akton:hr:acme:root/team_c")));
+        assert_eq!(
+            lineage.name_at(at(15)),
+            &arn("arn:akton:hr:acme:root/team_b")
+        );
+        assert_eq!(lineage.records()[1].reason, "moved");
+        Ok(())
+    }
+
+    #[test]
+    fn test_lineage_rejects_out_of_order_renames() -> anyhow::Result<()> {
+        let mut lineage = ArnLineage::new(arn("arn:akton:hr:acme:root/a"));
+        lineage.rename_at(arn("arn:akton:hr:acme:root/b"), at(10), "first")?;
+        assert!(lineage
+            .rename_at(arn("arn:akton:hr:acme:root/c"), at(5), "late")
+            .is_err());
+        assert!(lineage
+            .rename_at(arn("arn:akton:hr:acme:root/b"), at(20), "noop")
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_lineage_merge_continuation() -> anyhow::Result<()> {
+        let mut first = ArnLineage::new(arn("arn:akton:hr:acme:root/a"));
+        first.rename_at(arn("arn:akton:hr:acme:root/b"), at(10), "first")?;
+        let mut second = ArnLineage::new(arn("arn:akton:hr:acme:root/b"));
+        second.rename_at(arn("arn:akton:hr:acme:root/c"), at(20), "second")?;
+
+        first.merge(&second)?;
+        assert_eq!(first.origin(), &arn("arn:akton:hr:acme:root/a"));
+        assert_eq!(first.current(), &arn("arn:akton:hr:acme:root/c"));
+        assert_eq!(first.records().len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_lineage_merge_rejects_unrelated() -> anyhow::Result<()> {
+        let mut first = ArnLineage::new(arn("arn:akton:hr:acme:root/a"));
+        first.rename_at(arn("arn:akton:hr:acme:root/b"), at(10), "first")?;
+        let mut unrelated = ArnLineage::new(arn("arn:akton:hr:acme:root/x"));
+        unrelated.rename_at(arn("arn:akton:hr:acme:root/y"), at(20), "other")?;
+
+        let before = first.clone();
+        assert!(first.merge(&unrelated).is_err());
+        assert_eq!(first, before);
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_lineage_serde_round_trip() -> anyhow::Result<()> {
+        let mut lineage = ArnLineage::new(arn("arn:akton:hr:acme:root/a"));
+        lineage.rename_at(arn("arn:akton:hr:acme:root/b"), at(10), "reorg")?;
+        let json = serde_json::to_string(&lineage)?;
+        assert!(json.contains("\"arn:akton:hr:acme:root/b\""));
+        let restored: ArnLineage = serde_json::from_str(&json)?;
+        assert_eq!(restored, lineage);
+        Ok(())
+    }
+}
@@ -1,32 +1,194 @@
 use crate::errors::ArnError;
-use derive_more::{AsRef, From, Into};
+use crate::model::SharedStr;
 use std::borrow::Cow;
+use std::cmp::Ordering;
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+use uuid::Uuid;
 
-#[derive(AsRef, From, Into, Eq, Debug, PartialEq, Clone)]
-pub struct Part<'a>(pub(crate) Cow<'a, str>);
+/// One `/`-separated segment below an Arn's root.
+///
+/// A part whose text is a number or a UUID carries the parsed value, returned by
+/// [`Part::as_u64`] and [`Part::as_uuid`] without parsing again. Equality, ordering and
+/// hashing see only the text.
+#[derive(Clone)]
+pub struct Part<'a>(pub(crate) SharedStr<'a>, Typed);
+
+/// What a part's text parses as. A text is never both a `u64` and a UUID.
+///
+/// Worked out when the part is created rather than on first use, so that `Part` has no
+/// interior mutability and Arns stay usable as map keys; texts that are neither fail on
+/// their first character or on their length. Both values are `Copy` and held inline, so
+/// parsing a part never allocates.
+#[derive(Debug, Clone, Copy)]
+enum Typed {
+    Text,
+    U64(u64),
+    Uuid(Uuid),
+}
+
+impl Typed {
+    fn of(text: &str) -> Typed {
+        // Only the digits `u64` displays as, so `as_u64` never reports `+7` or `007` as 7.
+        let canonical =
+            text.bytes().all(|b| b.is_ascii_digit()) && (text == "0" || !text.starts_with('0'));
+        if let Some(value) = text.parse().ok().filter(|_| canonical) {
+            Typed::U64(value)
+        } else if let Ok(value) = Uuid::parse_str(text) {
+            Typed::Uuid(value)
+        } else {
+            Typed::Text
+        }
+    }
+}
+
+impl_shared_str_conversions!(Part, Part::from_shared);
 impl<'a> Part<'a> {
+    /// Wraps text the caller has already checked.
+    pub(crate) fn from_shared(value: SharedStr<'a>) -> Self {
+        let typed = Typed::of(&value);
+        Part(value, typed)
+    }
+
     pub fn as_str(&self) -> &str {
         &self.0
     }
 
+    /// Returns a part with the same text stored in `text`, keeping the parsed value.
+    #[cfg(feature = "arena")]
+    pub(crate) fn rehomed<'b>(&self, text: SharedStr<'b>) -> Part<'b> {
+        debug_assert_eq!(*text, *self.0);
+        Part(text, self.1)
+    }
+
     pub fn into_owned(self) -> Part<'static> {
-        Part(Cow::Owned(self.0.into_owned()))
+        Part(self.0.into_owned(), self.1)
     }
 
     pub fn new(value: impl Into<Cow<'a, str>>) -> Result<Part<'a>, ArnError> {
         let value = value.into();
-        if value.contains(':') || value.contains('/') {
-            return Err(ArnError::InvalidPartFormat);
+        check_part(&value)?;
+        Ok(Part::from_shared(value.into()))
+    }
+
+    /// Wraps a part already validated upstream, skipping the checks [`Part::new`] makes.
+    ///
+    /// An invalid value panics in debug builds and yields an Arn that may not round-trip in
+    /// release builds.
+    pub fn new_unchecked(value: impl Into<Cow<'a, str>>) -> Self {
+        let value = value.into();
+        debug_assert!(
+            !value.is_empty() && !value.contains([':', '/', '?', '@']),
+            "invalid part {value:?}"
+        );
+        Part::from_shared(value.into())
+    }
+
+    /// Splits a `key=value` attribute part at its first `=`, or returns `None` for plain parts.
+    ///
+    /// ```
+    /// use akton_arn::Part;
+    ///
+    /// assert_eq!(Part::new("region=us-east-1").unwrap().as_attr(), Some(("region", "us-east-1")));
+    /// assert_eq!(Part::new("orders").unwrap().as_attr(), None);
+    /// ```
+    pub fn as_attr(&self) -> Option<(&str, &str)> {
+        split_attr(&self.0)
+    }
+
+    /// Returns the part as a decimal number, such as an order id.
+    ///
+    /// Only canonical digits count: `0`, or digits without a leading zero or a sign, so the
+    /// number displays exactly as the part does.
+    ///
+    /// ```
+    /// use akton_arn::Part;
+    ///
+    /// assert_eq!(Part::new("1042").unwrap().as_u64(), Some(1042));
+    /// assert_eq!(Part::new("orders").unwrap().as_u64(), None);
+    /// assert_eq!(Part::new("007").unwrap().as_u64(), None);
+    /// ```
+    pub fn as_u64(&self) -> Option<u64> {
+        match self.1 {
+            Typed::U64(value) => Some(value),
+            _ => None,
         }
-        if value.is_empty() {
-            return Err(ArnError::ParseFailure(
-                "Part",
-                "cannot be empty".to_string(),
-            ));
+    }
+
+    /// Returns the part as a UUID in any form [`Uuid::parse_str`] accepts.
+    ///
+    /// ```
+    /// use akton_arn::Part;
+    ///
+    /// let part = Part::new("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+    /// assert_eq!(part.as_uuid().unwrap().to_string(), part.as_str());
+    /// assert_eq!(Part::new("1042").unwrap().as_uuid(), None);
+    /// ```
+    pub fn as_uuid(&self) -> Option<Uuid> {
+        match self.1 {
+            Typed::Uuid(value) => Some(value),
+            _ => None,
         }
-        Ok(Part(value))
     }
+
+    /// Parses the part as any [`FromStr`] type.
+    ///
+    /// Unlike [`Part::as_u64`] and [`Part::as_uuid`], this parses the text on every call.
+    pub fn parse<T: FromStr>(&self) -> Result<T, T::Err> {
+        self.0.parse()
+    }
+}
+
+impl fmt::Debug for Part<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Part").field(&self.0).finish()
+    }
+}
+
+impl PartialEq for Part<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Eq for Part<'_> {}
+
+impl PartialOrd for Part<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Part<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl Hash for Part<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+/// Checks `value` as [`Part::new`] does.
+pub(crate) fn check_part(value: &str) -> Result<(), ArnError> {
+    if value.contains([':', '/', '?', '@']) {
+        return Err(ArnError::InvalidPartFormat);
+    }
+    if value.is_empty() {
+        return Err(ArnError::ParseFailure(
+            "Part",
+            "cannot be empty".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Splits a part's text as [`Part::as_attr`] does.
+pub(crate) fn split_attr(part: &str) -> Option<(&str, &str)> {
+    part.split_once('=').filter(|(key, _)| !key.is_empty())
 }
 
 impl<'a> fmt::Display for Part<'a> {
@@ -42,7 +204,7 @@ impl<'a> std::str::FromStr for Part<'a> {
 }
 impl<'a> From<Part<'a>> for String {
     fn from(part: Part<'a>) -> Self {
-        part.0.into_owned()
+        part.0.into_string()
     }
 }
 
@@ -50,6 +212,73 @@ impl<'a> From<Part<'a>> for String {
 mod tests {
     use super::*;
 
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "invalid part")]
+    fn test_new_unchecked_asserts_in_debug() {
+        Part::new_unchecked("a/b");
+    }
+
+    #[test]
+    fn test_typed_payloads() -> anyhow::Result<()> {
+        let id = Part::new("18446744073709551615")?;
+        assert_eq!(id.as_u64(), Some(u64::MAX));
+        assert_eq!(id.as_uuid(), None);
+        assert_eq!(id.clone().into_owned().as_u64(), Some(u64::MAX));
+        assert_eq!(Part::new("18446744073709551616")?.as_u64(), None);
+        assert_eq!(Part::new("-1")?.as_u64(), None);
+        for noncanonical in ["+7", "007", "00"] {
+            assert_eq!(Part::new(noncanonical)?.as_u64(), None, "{noncanonical}");
+        }
+        assert_eq!(Part::new("0")?.as_u64(), Some(0));
+        assert_eq!(Part::new("10")?.as_u64(), Some(10));
+
+        let uuid = Uuid::new_v4();
+        let part = Part::new(uuid.simple().to_string())?;
+        assert_eq!(part.as_uuid(), Some(uuid));
+        assert_eq!(part.as_u64(), None);
+        assert_eq!(part.parse::<Uuid>()?, uuid);
+        assert_eq!(Part::new("2.5")?.parse::<f64>()?, 2.5);
+
+        let parsed = Part::new("7")?;
+        assert_eq!(parsed.as_u64(), Some(7));
+        assert_eq!(parsed, Part::new("7")?);
+        assert_eq!(format!("{parsed:?}"), r#"Part("7")"#);
+        Ok(())
+    }
+
+    #[test]
+    fn test_typed_payloads_agree_with_text() -> anyhow::Result<()> {
+        let uuid = Uuid::new_v4();
+        for text in [
+            "0".to_string(),
+            "42".to_string(),
+            "042".to_string(),
+            u64::MAX.to_string(),
+            "orders".to_string(),
+            uuid.to_string(),
+            uuid.simple().to_string(),
+            uuid.braced().to_string(),
+        ] {
+            let part = Part::new(text.as_str())?;
+            assert_eq!(
+                part.as_u64().map(|n| n.to_string()),
+                part.parse::<u64>()
+                    .ok()
+                    .map(|n| n.to_string())
+                    .filter(|n| *n == text),
+                "{text}"
+            );
+            assert_eq!(part.as_uuid(), Uuid::parse_str(&text).ok(), "{text}");
+            assert_eq!(
+                part.clone().into_owned().as_uuid(),
+                part.as_uuid(),
+                "{text}"
+            );
+        }
+        Ok(())
+    }
+
     #[test]
     fn test_part_creation() -> anyhow::Result<()> {
         let part = Part::new("segment")?;
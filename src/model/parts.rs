@@ -1,9 +1,246 @@
 use crate::Part;
+use arrayvec::ArrayVec;
+use std::cmp::Ordering;
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::{Bound, Deref, DerefMut, RangeBounds};
+
+/// Number of parts stored inline before [`Parts`] spills to the heap.
+pub(crate) const INLINE_PARTS: usize = 4;
 
 /// Represents a collection of parts in the Arn, handling multiple segments.
-#[derive(Debug, PartialEq, Clone, Eq, Default)]
-pub struct Parts<'a>(pub(crate) Vec<Part<'a>>);
+///
+/// Most Arns have only a few parts, so up to four are stored inside the Arn itself and only
+/// deeper paths allocate.
+#[derive(Debug, PartialEq, PartialOrd, Ord, Clone, Eq, Default, Hash)]
+pub struct Parts<'a>(pub(crate) PartsVec<'a>);
+
+/// Part storage that stays inline up to [`INLINE_PARTS`] parts and moves to a `Vec` beyond.
+///
+/// Unlike `smallvec`, this keeps `Parts` covariant in its lifetime, so an `Arn<'static>` can
+/// still be used where a shorter-lived `Arn` is expected. Comparisons and hashing see only
+/// the parts, never where they are stored.
+#[derive(Clone)]
+pub(crate) enum PartsVec<'a> {
+    Inline(ArrayVec<Part<'a>, INLINE_PARTS>),
+    Heap(Vec<Part<'a>>),
+}
+
+impl<'a> PartsVec<'a> {
+    pub(crate) fn push(&mut self, part: Part<'a>) {
+        match self {
+            PartsVec::Inline(inline) => {
+                if let Err(full) = inline.try_push(part) {
+                    let mut heap = Vec::with_capacity(INLINE_PARTS * 2);
+                    heap.extend(inline.drain(..));
+                    heap.push(full.element());
+                    *self = PartsVec::Heap(heap);
+                }
+            }
+            PartsVec::Heap(heap) => heap.push(part),
+        }
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<Part<'a>> {
+        match self {
+            PartsVec::Inline(inline) => inline.pop(),
+            PartsVec::Heap(heap) => heap.pop(),
+        }
+    }
+
+    /// Returns `true` if the parts have moved to the heap.
+    #[cfg(test)]
+    pub(crate) fn spilled(&self) -> bool {
+        matches!(self, PartsVec::Heap(_))
+    }
+}
+
+impl Default for PartsVec<'_> {
+    fn default() -> Self {
+        PartsVec::Inline(ArrayVec::new())
+    }
+}
+
+impl<'a> Deref for PartsVec<'a> {
+    type Target = [Part<'a>];
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            PartsVec::Inline(inline) => inline,
+            PartsVec::Heap(heap) => heap,
+        }
+    }
+}
+
+impl DerefMut for PartsVec<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self {
+            PartsVec::Inline(inline) => inline,
+            PartsVec::Heap(heap) => heap,
+        }
+    }
+}
+
+impl<'a> From<Vec<Part<'a>>> for PartsVec<'a> {
+    fn from(parts: Vec<Part<'a>>) -> Self {
+        if parts.len() <= INLINE_PARTS {
+            PartsVec::Inline(parts.into_iter().collect())
+        } else {
+            PartsVec::Heap(parts)
+        }
+    }
+}
+
+impl<'a> Extend<Part<'a>> for PartsVec<'a> {
+    fn extend<T: IntoIterator<Item = Part<'a>>>(&mut self, iter: T) {
+        for part in iter {
+            self.push(part);
+        }
+    }
+}
+
+impl<'a> FromIterator<Part<'a>> for PartsVec<'a> {
+    fn from_iter<T: IntoIterator<Item = Part<'a>>>(iter: T) -> Self {
+        let mut parts = PartsVec::default();
+        parts.extend(iter);
+        parts
+    }
+}
+
+impl<'a> IntoIterator for PartsVec<'a> {
+    type Item = Part<'a>;
+    type IntoIter = PartsVecIntoIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        match self {
+            PartsVec::Inline(inline) => PartsVecIntoIter::Inline(inline.into_iter()),
+            PartsVec::Heap(heap) => PartsVecIntoIter::Heap(heap.into_iter()),
+        }
+    }
+}
+
+impl<'p, 'a> IntoIterator for &'p PartsVec<'a> {
+    type Item = &'p Part<'a>;
+    type IntoIter = std::slice::Iter<'p, Part<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// The owning iterator of [`PartsVec`].
+pub(crate) enum PartsVecIntoIter<'a> {
+    Inline(arrayvec::IntoIter<Part<'a>, INLINE_PARTS>),
+    Heap(std::vec::IntoIter<Part<'a>>),
+}
+
+impl<'a> Iterator for PartsVecIntoIter<'a> {
+    type Item = Part<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            PartsVecIntoIter::Inline(inline) => inline.next(),
+            PartsVecIntoIter::Heap(heap) => heap.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            PartsVecIntoIter::Inline(inline) => inline.size_hint(),
+            PartsVecIntoIter::Heap(heap) => heap.size_hint(),
+        }
+    }
+}
+
+/// What [`Parts::get`] accepts: a `usize` index, or a deprecated `&str` attribute key.
+///
+/// The trait is sealed and cannot be implemented outside this crate.
+pub trait PartsIndex<'p, 'a>: sealed::Sealed {
+    /// What the index selects.
+    type Output;
+
+    #[doc(hidden)]
+    fn get(self, parts: &'p Parts<'a>) -> Option<Self::Output>;
+}
+
+mod sealed {
+    pub trait Sealed {}
+    impl Sealed for usize {}
+    impl Sealed for &str {}
+}
+
+impl<'p, 'a: 'p> PartsIndex<'p, 'a> for usize {
+    type Output = &'p Part<'a>;
+
+    fn get(self, parts: &'p Parts<'a>) -> Option<Self::Output> {
+        parts.0.get(self)
+    }
+}
+
+impl<'p, 'a> PartsIndex<'p, 'a> for &str {
+    type Output = &'p str;
+
+    fn get(self, parts: &'p Parts<'a>) -> Option<Self::Output> {
+        parts.attr(self)
+    }
+}
+
+/// The owning iterator of [`Parts`], yielding each part from the one nearest the root.
+pub struct PartsIntoIter<'a>(PartsVecIntoIter<'a>);
+
+impl<'a> Iterator for PartsIntoIter<'a> {
+    type Item = Part<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl ExactSizeIterator for PartsIntoIter<'_> {}
+
+impl fmt::Debug for PartsIntoIter<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PartsIntoIter")
+            .field("remaining", &self.len())
+            .finish()
+    }
+}
+
+impl fmt::Debug for PartsVec<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl PartialEq for PartsVec<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for PartsVec<'_> {}
+
+impl PartialOrd for PartsVec<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PartsVec<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (**self).cmp(&**other)
+    }
+}
+
+impl Hash for PartsVec<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
 
 impl<'a> Parts<'a> {
     /// Constructs a new collection of `Parts`.
@@ -12,7 +249,7 @@ impl<'a> Parts<'a> {
     ///
     /// * `parts` - A vector of `Part` representing the parts of the Arn.
     pub fn new(parts: Vec<Part<'a>>) -> Self {
-        Parts(parts)
+        Parts(parts.into())
     }
 
     /// Adds a part to the collection.
@@ -28,29 +265,125 @@ impl<'a> Parts<'a> {
         self
     }
 
+    /// Returns the value of the first `key=value` attribute part with the given key.
+    pub fn attr(&self, key: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find_map(|part| part.as_attr().filter(|(k, _)| *k == key).map(|(_, v)| v))
+    }
+
+    /// Returns the part at `index`, counting from zero at the one nearest the root.
+    ///
+    /// Passing a key instead of an index returns the value of that attribute part, as
+    /// [`Parts::attr`] does. That form is deprecated; it is kept from before `get` took
+    /// indexes.
+    pub fn get<'p, I: PartsIndex<'p, 'a>>(&'p self, index: I) -> Option<I::Output> {
+        index.get(self)
+    }
+
+    /// Returns the part nearest the root.
+    pub fn first(&self) -> Option<&Part<'a>> {
+        self.0.first()
+    }
+
+    /// Returns the deepest part.
+    pub fn last(&self) -> Option<&Part<'a>> {
+        self.0.last()
+    }
+
+    /// Returns the parts in order, from the one nearest the root.
+    pub fn iter(&self) -> std::slice::Iter<'_, Part<'a>> {
+        self.0.iter()
+    }
+
+    /// Returns a copy of the parts in `range`, as for slicing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds or decreasing.
+    ///
+    /// ```
+    /// use akton_arn::ArnParser;
+    ///
+    /// let arn = ArnParser::new("arn:akton:hr:acme:root/teams/eng/leads").parse().unwrap();
+    /// assert_eq!(arn.parts().slice(1..).to_string(), "eng/leads");
+    /// assert_eq!(arn.parts().slice(..1).to_string(), "teams");
+    /// ```
+    pub fn slice(&self, range: impl RangeBounds<usize>) -> Parts<'a> {
+        let bounds: (Bound<usize>, Bound<usize>) =
+            (range.start_bound().cloned(), range.end_bound().cloned());
+        Parts(self.0[bounds].iter().cloned().collect())
+    }
+
+    /// Returns `true` if `prefix` is a leading run of these parts, whole part by whole part.
+    pub fn starts_with(&self, prefix: &Parts) -> bool {
+        self.0.starts_with(&prefix.0)
+    }
+
+    /// Returns the parts after `prefix`, or `None` if they do not start with it.
+    ///
+    /// ```
+    /// use akton_arn::ArnParser;
+    ///
+    /// let teams = ArnParser::new("arn:akton:hr:acme:root/teams").parse().unwrap();
+    /// let lead = ArnParser::new("arn:akton:hr:acme:root/teams/eng/lead").parse().unwrap();
+    /// assert_eq!(lead.parts().strip_prefix(teams.parts()).unwrap().to_string(), "eng/lead");
+    /// assert!(teams.parts().strip_prefix(lead.parts()).is_none());
+    /// ```
+    pub fn strip_prefix(&self, prefix: &Parts) -> Option<Parts<'a>> {
+        self.starts_with(prefix).then(|| self.slice(prefix.len()..))
+    }
+
+    /// Returns the number of parts.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the Arn ends at its root.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
     /// Converts the Parts into an owned version with 'static lifetime
     pub fn into_owned(self) -> Parts<'static> {
         Parts(self.0.into_iter().map(|part| part.into_owned()).collect())
     }
 }
 
-
 impl<'a> FromIterator<Part<'a>> for Parts<'a> {
     fn from_iter<T: IntoIterator<Item = Part<'a>>>(iter: T) -> Self {
         Parts(iter.into_iter().collect())
     }
 }
 
+impl<'a> IntoIterator for Parts<'a> {
+    type Item = Part<'a>;
+    type IntoIter = PartsIntoIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        PartsIntoIter(self.0.into_iter())
+    }
+}
+
+impl<'p, 'a> IntoIterator for &'p Parts<'a> {
+    type Item = &'p Part<'a>;
+    type IntoIter = std::slice::Iter<'p, Part<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
 impl<'a> fmt::Display for Parts<'a> {
     /// Formats the collection of parts as a string, joining them with '/'.
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let parts_str = self
-            .0
-            .iter()
-            .map(|p| p.as_str())
-            .collect::<Vec<_>>()
-            .join("/");
-        write!(f, "{}", parts_str)
+        for (i, part) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str("/")?;
+            }
+            f.write_str(part.as_str())?;
+        }
+        Ok(())
     }
 }
 
@@ -89,6 +422,74 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_shallow_parts_stay_inline() -> anyhow::Result<()> {
+        let mut parts = Parts::new(vec![Part::new("a")?, Part::new("b")?]);
+        assert!(!parts.0.spilled());
+        for name in ["c", "d", "e"] {
+            parts = parts.add_part(Part::new(name)?);
+        }
+        assert!(parts.0.spilled());
+        assert_eq!(parts.to_string(), "a/b/c/d/e");
+        let collected: Parts = parts.0.iter().cloned().collect();
+        assert_eq!(collected, parts);
+        let from_vec = Parts::new(parts.0.iter().cloned().collect());
+        assert!(from_vec.0.spilled());
+        assert_eq!(from_vec, parts);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parts_get_attribute() -> anyhow::Result<()> {
+        let parts: Parts = ["orders", "region=us-east-1", "=x", "tier=a=b", "region=eu"]
+            .into_iter()
+            .map(Part::new)
+            .collect::<Result<_, _>>()?;
+        assert_eq!(parts.attr("region"), Some("us-east-1"));
+        assert_eq!(parts.attr("tier"), Some("a=b"));
+        assert_eq!(parts.attr("orders"), None);
+        assert_eq!(parts.attr(""), None);
+        assert_eq!(parts.get("region"), Some("us-east-1"));
+        assert_eq!(parts.get("orders"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parts_indexing_and_slicing() -> anyhow::Result<()> {
+        let parts: Parts = ["a", "b", "c", "d", "e"]
+            .into_iter()
+            .map(Part::new)
+            .collect::<Result<_, _>>()?;
+        assert_eq!(parts.len(), 5);
+        assert_eq!(parts.get(1).map(Part::as_str), Some("b"));
+        assert_eq!(parts.get(5), None);
+        assert_eq!(parts.first().map(Part::as_str), Some("a"));
+        assert_eq!(parts.last().map(Part::as_str), Some("e"));
+        assert_eq!(Parts::default().first(), None);
+
+        let middle = parts.slice(1..=3);
+        assert_eq!(middle.to_string(), "b/c/d");
+        assert!(!middle.0.spilled());
+        assert_eq!(parts.slice(..).to_string(), parts.to_string());
+        assert!(parts.slice(5..).is_empty());
+
+        let head = parts.slice(..2);
+        assert!(parts.starts_with(&head) && parts.starts_with(&Parts::default()));
+        assert!(!parts.starts_with(&middle) && !head.starts_with(&parts));
+        assert_eq!(
+            parts.strip_prefix(&head).map(|p| p.to_string()).as_deref(),
+            Some("c/d/e")
+        );
+        assert_eq!(parts.strip_prefix(&middle), None);
+
+        let borrowed: Vec<String> = (&parts).into_iter().map(|p| p.to_string()).collect();
+        let owned = parts.into_iter();
+        assert_eq!(owned.len(), 5);
+        let owned: Vec<String> = owned.map(String::from).collect();
+        assert_eq!(borrowed, owned);
+        Ok(())
+    }
+
     #[test]
     fn test_parts_into_owned() -> anyhow::Result<()> {
         let parts = Parts::new(vec![Part::new("segment1")?, Part::new("segment2")?]);
@@ -1,11 +1,33 @@
 use crate::errors::ArnError;
-use derive_more::{AsRef, From, Into};
+use crate::model::SharedStr;
+use crate::snowflake;
+use crate::time_source::TimeSource;
 use std::borrow::Cow;
 use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use type_safe_id::{DynamicType, TypeSafeId};
+use uuid::{Builder, Uuid, Version};
 
-#[derive(AsRef, From, Into, Eq, Debug, PartialEq, Clone)]
-pub struct Root<'a>(pub(crate) Cow<'a, str>);
+/// The top of an Arn's resource path: either a plain name or a name with a generated id.
+///
+/// [`Root::new`] and the other minting constructors always generate a fresh TypeID such as
+/// `order_01h455vb4pex5vsknk084sn02q`, so two calls never produce equal roots.
+/// [`Root::named`] and parsing (`"order".parse()`, or an Arn through
+/// [`ArnParser`](crate::ArnParser)) keep the text as it is, so displaying and parsing a root
+/// gives back an equal root of the same [`RootKind`].
+#[derive(Eq, Debug, PartialEq, PartialOrd, Ord, Clone, Hash)]
+pub struct Root<'a>(pub(crate) SharedStr<'a>);
+
+/// Whether a [`Root`] is a plain name or carries a generated id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RootKind {
+    /// A literal name, as created by [`Root::named`].
+    Named,
+    /// A TypeID: a name followed by `_` and a generated id.
+    Generated,
+}
+
+impl_shared_str_conversions!(Root);
 
 impl<'a> Root<'a> {
     pub fn as_str(&self) -> &str {
@@ -13,20 +35,133 @@ impl<'a> Root<'a> {
     }
 
     pub fn into_owned(self) -> Root<'static> {
-        Root(Cow::Owned(self.0.into_owned()))
+        Root(self.0.into_owned())
+    }
+
+    /// Creates a root that is exactly `name`, without a generated id.
+    ///
+    /// Fails if `name` is empty or contains a character that delimits another part of an Arn
+    /// (`:`, `/`, `?` or `@`).
+    pub fn named(name: impl Into<Cow<'a, str>>) -> Result<Self, ArnError> {
+        let name = name.into();
+        if name.is_empty() || name.contains([':', '/', '?', '@']) {
+            return Err(ArnError::ParseFailure(
+                "Root",
+                format!("{name:?} must be non-empty and must not contain ':', '/', '?' or '@'"),
+            ));
+        }
+        Ok(Root(name.into()))
+    }
+
+    /// Wraps a root already validated upstream, such as one read back from storage, skipping
+    /// the checks [`Root::named`] makes.
+    ///
+    /// An invalid value panics in debug builds and yields an Arn that may not round-trip in
+    /// release builds.
+    pub fn new_unchecked(value: impl Into<Cow<'a, str>>) -> Self {
+        let value = value.into();
+        debug_assert!(
+            !value.is_empty() && !value.contains([':', '/', '?', '@']),
+            "invalid root {value:?}"
+        );
+        Root(value.into())
+    }
+
+    /// Wraps a root read back from an encoding other than the string form.
+    ///
+    /// Accepts any root the parser can produce, and rejects text it would have split off as
+    /// a part, a version or a query, which would not survive being displayed and parsed.
+    pub(crate) fn decoded(value: impl Into<Cow<'a, str>>) -> Result<Self, ArnError> {
+        let value = value.into();
+        if value.contains(['/', '?', '@']) {
+            return Err(ArnError::ParseFailure(
+                "Root",
+                format!("{value:?} must not contain '/', '?' or '@'"),
+            ));
+        }
+        Ok(Root(value.into()))
+    }
+
+    /// Tells a plain name from a generated TypeID.
+    ///
+    /// A named root spelled exactly like a TypeID is indistinguishable from a generated one
+    /// and is reported as generated.
+    pub fn kind(&self) -> RootKind {
+        if self.uuid().is_some() {
+            RootKind::Generated
+        } else {
+            RootKind::Named
+        }
+    }
+
+    /// Returns `true` if the root carries a generated id.
+    pub fn is_generated(&self) -> bool {
+        self.kind() == RootKind::Generated
+    }
+
+    /// Returns the root's name: the whole root if named, or the TypeID prefix if generated.
+    pub fn name(&self) -> &str {
+        match self.kind() {
+            RootKind::Named => self.as_str(),
+            RootKind::Generated => self.as_str().rsplit_once('_').map_or("", |(name, _)| name),
+        }
+    }
+
+    /// Mints a root named `value` (or `akton` when empty) with an id taken from `time_source`.
+    ///
+    /// Unlike [`Root::new`], which always uses the wall clock, this lets simulations mint
+    /// reproducible roots; see [`TimeSource`].
+    pub fn new_with_time_source(
+        value: impl Into<Cow<'a, str>>,
+        time_source: &dyn TimeSource,
+    ) -> Result<Self, ArnError> {
+        let value = value.into();
+        let name = if value.is_empty() { AKTON } else { &value };
+        let millis = time_source.now().as_millis() as u64;
+        let uuid =
+            Builder::from_unix_timestamp_millis(millis, &time_source.random_bytes()).into_uuid();
+        Ok(Root(
+            TypeSafeId::from_type_and_uuid(DynamicType::new(name)?, uuid)
+                .to_string()
+                .into(),
+        ))
     }
 
     pub fn new(value: impl Into<Cow<'a, str>>) -> Result<Self, ArnError> {
         let value = value.into();
         let value = if value.is_empty() {
             let val = AKTON;
-            TypeSafeId::from_type_and_uuid(DynamicType::new(val)?, uuid::Uuid::now_v7())
-                .to_string()
+            TypeSafeId::from_type_and_uuid(DynamicType::new(val)?, uuid::Uuid::now_v7()).to_string()
         } else {
             TypeSafeId::from_type_and_uuid(DynamicType::new(&value)?, uuid::Uuid::now_v7())
                 .to_string()
         };
-        Ok(Root(Cow::from(value)))
+        Ok(Root(value.into()))
+    }
+
+    /// Returns the UUID behind a TypeID root, or `None` for a root that is not a TypeID.
+    pub(crate) fn uuid(&self) -> Option<Uuid> {
+        let id: TypeSafeId<DynamicType> = self.as_str().parse().ok()?;
+        Some(id.uuid())
+    }
+
+    /// Returns when a time-based root was minted, decoded from its id.
+    ///
+    /// Works for UUIDv7 roots, as minted by [`Root::new`], a [`TimeSource`] or a
+    /// [`MonotonicGenerator`](crate::MonotonicGenerator), to the millisecond, and for roots
+    /// minted by [`Root::new_with_snowflake`]. Returns `None` for roots without a timestamp,
+    /// such as those of a [`NamespaceGenerator`](crate::NamespaceGenerator) or roots parsed
+    /// from plain names.
+    pub fn created_at(&self) -> Option<SystemTime> {
+        let uuid = self.uuid()?;
+        match uuid.get_version()? {
+            Version::SortRand => {
+                let (secs, nanos) = uuid.get_timestamp()?.to_unix();
+                Some(UNIX_EPOCH + Duration::new(secs, nanos))
+            }
+            Version::Custom => Some(UNIX_EPOCH + snowflake::timestamp(self.snowflake_id()?)),
+            _ => None,
+        }
     }
 }
 
@@ -43,18 +178,20 @@ impl<'a> fmt::Display for Root<'a> {
         write!(f, "{id}")
     }
 }
-const AKTON: &str = "akton";
+pub(crate) const AKTON: &str = "akton";
 
 impl<'a> std::str::FromStr for Root<'a> {
     type Err = ArnError;
+
+    /// Parses a root verbatim, as [`Root::named`] does; a TypeID stays [`RootKind::Generated`].
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Root::new(s.to_string())
+        Root::named(s.to_string())
     }
 }
 
 impl<'a> From<Root<'a>> for String {
     fn from(root: Root<'a>) -> Self {
-        root.0.into_owned()
+        root.0.into_string()
     }
 }
 #[cfg(test)]
@@ -85,6 +222,25 @@ mod tests {
         assert!(root.as_str().starts_with("test"));
     }
 
+    #[test]
+    fn test_named_and_generated_roots_round_trip() {
+        let named = Root::named("orders").unwrap();
+        assert_eq!(named.kind(), RootKind::Named);
+        assert_eq!(named.name(), "orders");
+        assert_eq!(named, "orders".parse::<Root>().unwrap());
+
+        let generated = Root::new("order_item").unwrap();
+        assert!(generated.is_generated());
+        assert_eq!(generated.name(), "order_item");
+        let parsed: Root = generated.to_string().parse().unwrap();
+        assert_eq!(parsed, generated);
+        assert_eq!(parsed.kind(), RootKind::Generated);
+
+        for invalid in ["", "a:b", "a/b", "a?b", "a@v1"] {
+            assert!(Root::named(invalid).is_err(), "{invalid:?}");
+        }
+    }
+
     #[test]
     fn test_root_equality() {
         let root1 = Root::new("test");
@@ -94,6 +250,24 @@ mod tests {
         assert_ne!(root1, root3);
     }
 
+    #[test]
+    fn test_created_at() {
+        use crate::{IdScope, NamespaceGenerator, SteppedClock};
+
+        let minted = Duration::from_millis(1_700_000_000_123);
+        let clock = SteppedClock::new(minted, Duration::ZERO);
+        let root = Root::new_with_time_source("x", &clock).unwrap();
+        assert_eq!(root.created_at(), Some(UNIX_EPOCH + minted));
+
+        let before = SystemTime::now() - Duration::from_millis(1);
+        let created = Root::new("x").unwrap().created_at().unwrap();
+        assert!(created >= before && created <= SystemTime::now());
+
+        let namespaced = Root::generate(&IdScope::named("x"), &NamespaceGenerator::default());
+        assert_eq!(namespaced.unwrap().created_at(), None);
+        assert_eq!(Root("plain".into()).created_at(), None);
+    }
+
     #[test]
     fn test_root_into_string() {
         let root = Root::new("test").unwrap();
@@ -0,0 +1,211 @@
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// The string behind every Arn component: borrowed from the input, or shared behind an `Arc`.
+///
+/// Cloning never copies the text, so Arns derived from one another (`add_part`, `parent`,
+/// `ancestors`) share their domain, category, account, root and common parts instead of
+/// duplicating them. Comparisons and hashing see only the text, never how it is stored.
+///
+/// Both variants hold a `Cow` (always `Cow::Borrowed` and `Cow::Owned` respectively) only so the
+/// deprecated `AsRef<Cow<str>>` impls on the components have one to lend.
+#[derive(Clone)]
+pub(crate) enum SharedStr<'a> {
+    Borrowed(Cow<'a, str>),
+    Shared(Arc<Cow<'static, str>>),
+}
+
+impl<'a> SharedStr<'a> {
+    pub(crate) const fn borrowed(value: &'a str) -> Self {
+        SharedStr::Borrowed(Cow::Borrowed(value))
+    }
+
+    pub(crate) fn as_cow(&self) -> &Cow<'a, str> {
+        match self {
+            SharedStr::Borrowed(value) => value,
+            SharedStr::Shared(value) => value,
+        }
+    }
+}
+
+impl SharedStr<'_> {
+    /// Returns a version that owns (or shares ownership of) its text.
+    ///
+    /// Borrowed text is copied once; shared text is not copied at all.
+    pub(crate) fn into_owned(self) -> SharedStr<'static> {
+        match self {
+            SharedStr::Borrowed(value) => value.into_owned().into(),
+            SharedStr::Shared(value) => SharedStr::Shared(value),
+        }
+    }
+
+    pub(crate) fn into_string(self) -> String {
+        self.to_string()
+    }
+}
+
+impl Deref for SharedStr<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_cow()
+    }
+}
+
+impl<'a> From<&'a str> for SharedStr<'a> {
+    fn from(value: &'a str) -> Self {
+        SharedStr::borrowed(value)
+    }
+}
+
+impl From<String> for SharedStr<'_> {
+    fn from(value: String) -> Self {
+        SharedStr::Shared(Arc::new(Cow::Owned(value)))
+    }
+}
+
+impl<'a> From<Cow<'a, str>> for SharedStr<'a> {
+    fn from(value: Cow<'a, str>) -> Self {
+        match value {
+            Cow::Borrowed(value) => SharedStr::borrowed(value),
+            Cow::Owned(value) => value.into(),
+        }
+    }
+}
+
+impl<'a> From<SharedStr<'a>> for Cow<'a, str> {
+    fn from(value: SharedStr<'a>) -> Self {
+        match value {
+            SharedStr::Borrowed(value) => value,
+            SharedStr::Shared(value) => Cow::Owned(value.to_string()),
+        }
+    }
+}
+
+impl fmt::Debug for SharedStr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl fmt::Display for SharedStr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self)
+    }
+}
+
+impl PartialEq for SharedStr<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for SharedStr<'_> {}
+
+impl PartialOrd for SharedStr<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SharedStr<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (**self).cmp(&**other)
+    }
+}
+
+impl Hash for SharedStr<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+/// Implements the conversions between a component newtype and `Cow<str>` that its public API offers.
+///
+/// Newtypes holding more than the string name the function building them from a `SharedStr`.
+macro_rules! impl_shared_str_conversions {
+    ($type:ident) => {
+        impl_shared_str_conversions!($type, $type);
+    };
+    ($type:ident, $new:path) => {
+        impl<'a> From<std::borrow::Cow<'a, str>> for $type<'a> {
+            fn from(value: std::borrow::Cow<'a, str>) -> Self {
+                $new(value.into())
+            }
+        }
+
+        impl<'a> From<$type<'a>> for std::borrow::Cow<'a, str> {
+            fn from(value: $type<'a>) -> Self {
+                value.0.into()
+            }
+        }
+
+        impl AsRef<str> for $type<'_> {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        /// Deprecated: use `AsRef<str>` or `as_str` instead. Kept for compatibility; Rust cannot
+        /// attach `#[deprecated]` to a trait impl.
+        impl<'a> AsRef<std::borrow::Cow<'a, str>> for $type<'a> {
+            fn as_ref(&self) -> &std::borrow::Cow<'a, str> {
+                self.0.as_cow()
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArnParser;
+
+    #[test]
+    fn test_derived_arns_share_components() {
+        let parent = ArnParser::new("arn:akton:hr:acme:root/a").parse().unwrap();
+        let child = parent.add_part("b").unwrap();
+        let grandchild = child.add_part("c").unwrap();
+        for arn in [&child, &grandchild] {
+            assert!(std::ptr::eq(arn.domain.as_str(), parent.domain.as_str()));
+            assert!(std::ptr::eq(arn.root.as_str(), parent.root.as_str()));
+            assert!(std::ptr::eq(
+                arn.parts.0[0].as_str(),
+                parent.parts.0[0].as_str()
+            ));
+        }
+        let ancestor = grandchild.ancestors().last().unwrap();
+        assert!(std::ptr::eq(
+            ancestor.account.as_str(),
+            parent.account.as_str()
+        ));
+    }
+
+    #[test]
+    fn test_storage_is_invisible_to_comparisons() {
+        let borrowed = SharedStr::from("x");
+        let shared = SharedStr::from("x".to_string());
+        assert_eq!(borrowed, shared);
+        assert_eq!(format!("{borrowed:?}"), format!("{shared:?}"));
+        assert!(matches!(
+            borrowed.clone().into_owned(),
+            SharedStr::Shared(_)
+        ));
+        assert_eq!(Cow::from(shared), Cow::<str>::Owned("x".to_string()));
+    }
+
+    #[test]
+    fn test_components_still_lend_a_cow() {
+        let arn = ArnParser::new("arn:akton:hr:acme:root/a").parse().unwrap();
+        let owned = arn.clone().into_owned();
+        let borrowed: &Cow<str> = arn.domain.as_ref();
+        let shared: &Cow<str> = owned.account.as_ref();
+        assert!(matches!(borrowed, Cow::Borrowed("akton")));
+        assert_eq!(shared, "acme");
+        assert_eq!(AsRef::<Cow<str>>::as_ref(&arn.parts.0[0]), "a");
+    }
+}
@@ -0,0 +1,134 @@
+use crate::errors::ArnError;
+use crate::model::{Arn, Part};
+use std::borrow::Cow;
+use std::fmt;
+use std::fmt::Write;
+
+/// Default quota, in bytes after encoding, for segments appended with [`Arn::push_user_segment`].
+pub const MAX_USER_SEGMENT_LEN: usize = 128;
+
+/// Why [`Arn::push_user_segment`] rejected an untrusted segment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SegmentRejection {
+    /// The raw input was empty.
+    Empty,
+    /// The input was `.` or `..`, which resolve to other resources once mapped to paths or URLs.
+    DotSegment,
+    /// The encoded segment exceeds the quota.
+    TooLong {
+        /// Length of the encoded segment in bytes.
+        len: usize,
+        /// The quota it was checked against.
+        max: usize,
+    },
+}
+
+impl fmt::Display for SegmentRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SegmentRejection::Empty => write!(f, "segment is empty"),
+            SegmentRejection::DotSegment => write!(f, "segment is a relative path component"),
+            SegmentRejection::TooLong { len, max } => {
+                write!(
+                    f,
+                    "encoded segment is {len} bytes, exceeding the limit of {max}"
+                )
+            }
+        }
+    }
+}
+
+impl Arn<'_> {
+    /// Appends untrusted input, such as a user-chosen name, as a new last part.
+    ///
+    /// Every byte outside the URL-unreserved set `[A-Za-z0-9-._~]` is percent-encoded, so the
+    /// input can never introduce separators, control characters or look-alike Unicode. The
+    /// encoded segment must fit within [`MAX_USER_SEGMENT_LEN`]; use
+    /// [`Arn::push_user_segment_with_limit`] for a different quota. On rejection the Arn is
+    /// left unchanged.
+    pub fn push_user_segment(&mut self, raw: &str) -> Result<(), ArnError> {
+        self.push_user_segment_with_limit(raw, MAX_USER_SEGMENT_LEN)
+    }
+
+    /// Like [`Arn::push_user_segment`], with an explicit quota on the encoded length.
+    pub fn push_user_segment_with_limit(&mut self, raw: &str, max: usize) -> Result<(), ArnError> {
+        let rejection = match raw {
+            "" => Some(SegmentRejection::Empty),
+            "." | ".." => Some(SegmentRejection::DotSegment),
+            _ => None,
+        };
+        if let Some(rejection) = rejection {
+            return Err(ArnError::RejectedUserSegment(rejection));
+        }
+        let encoded = percent_encode(raw);
+        if encoded.len() > max {
+            return Err(ArnError::RejectedUserSegment(SegmentRejection::TooLong {
+                len: encoded.len(),
+                max,
+            }));
+        }
+        self.parts.0.push(Part::new(Cow::Owned(encoded))?);
+        Ok(())
+    }
+}
+
+fn percent_encode(raw: &str) -> String {
+    let mut encoded = String::with_capacity(raw.len());
+    for byte in raw.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            write!(encoded, "%{byte:02X}").expect("formatting into a String cannot fail");
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArnParser;
+
+    fn base() -> Arn<'static> {
+        ArnParser::new("arn:akton:hr:acme:root/uploads")
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_user_segment_is_encoded() {
+        let mut arn = base();
+        arn.push_user_segment("../etc/passwd").unwrap();
+        arn.push_user_segment("a:b c\u{202e}").unwrap();
+        assert_eq!(
+            arn.to_string(),
+            "arn:akton:hr:acme:root/uploads/..%2Fetc%2Fpasswd/a%3Ab%20c%E2%80%AE"
+        );
+        assert_eq!(arn.depth(), 3);
+        assert_eq!(ArnParser::new(arn.to_string()).parse().unwrap(), arn);
+    }
+
+    #[test]
+    fn test_user_segment_rejections() {
+        let mut arn = base();
+        assert_eq!(
+            arn.push_user_segment(""),
+            Err(ArnError::RejectedUserSegment(SegmentRejection::Empty))
+        );
+        assert_eq!(
+            arn.push_user_segment(".."),
+            Err(ArnError::RejectedUserSegment(SegmentRejection::DotSegment))
+        );
+        assert_eq!(
+            arn.push_user_segment_with_limit("ü", 5),
+            Err(ArnError::RejectedUserSegment(SegmentRejection::TooLong {
+                len: 6,
+                max: 5
+            }))
+        );
+        assert!(arn
+            .push_user_segment(&"x".repeat(MAX_USER_SEGMENT_LEN + 1))
+            .is_err());
+        assert_eq!(arn, base(), "rejected input leaves the Arn unchanged");
+    }
+}
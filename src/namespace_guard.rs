@@ -0,0 +1,196 @@
+use crate::backend::{MemoryBackend, RegistryBackend};
+use crate::errors::ArnError;
+use crate::model::Arn;
+use std::fmt;
+use std::sync::Mutex;
+
+/// Records the Arns issued so far and rejects a second Arn with the same name.
+///
+/// A name is an Arn without its version and extensions, so `.../orders@v2` claims the same
+/// name as `.../orders`. Names always include the account, so uniqueness is per account.
+/// Claims live in a [`RegistryBackend`], in memory by default; give the guard a persistent
+/// backend to keep them across restarts or share them between services. Attach the guard
+/// to an [`ArnBuilder`](crate::ArnBuilder) with
+/// [`with_namespace_guard`](crate::ArnBuilder::with_namespace_guard) to claim each name as
+/// the Arn is built.
+///
+/// Claims through one guard are serialized. Checking and recording a name are two backend
+/// calls, though, so guards in different processes sharing a backend can both claim a name
+/// that neither has seen yet.
+///
+/// ```
+/// use akton_arn::{ArnError, ArnParser, NamespaceGuard};
+///
+/// let guard = NamespaceGuard::new();
+/// let orders = ArnParser::new("arn:akton:hr:acme:root/orders").parse().unwrap();
+/// guard.claim(&orders).unwrap();
+/// assert!(matches!(guard.claim(&orders.clone().with_version(2)), Err(ArnError::AlreadyRegistered(_))));
+/// assert!(guard.release(&orders).unwrap());
+/// guard.claim(&orders).unwrap();
+/// ```
+pub struct NamespaceGuard {
+    backend: Box<dyn RegistryBackend>,
+    /// Held while a name is checked and recorded.
+    claiming: Mutex<()>,
+}
+
+impl NamespaceGuard {
+    /// Creates a guard keeping its claims in memory.
+    pub fn new() -> Self {
+        Self::with_backend(MemoryBackend::new())
+    }
+
+    /// Creates a guard keeping its claims in `backend`, honoring the claims already there.
+    ///
+    /// Claims are stored under the claimed Arn itself, with an empty value, so the backend
+    /// must not also hold [`ArnRegistry`](crate::ArnRegistry) entries: a registry entry would
+    /// read as a claim, and a claim as an entry with empty metadata. Use a separate file, sled
+    /// tree, SQLite table or Redis namespace.
+    pub fn with_backend(backend: impl RegistryBackend + 'static) -> Self {
+        NamespaceGuard {
+            backend: Box::new(backend),
+            claiming: Mutex::new(()),
+        }
+    }
+
+    /// Claims the name of `arn`, failing with [`ArnError::AlreadyRegistered`] if it is taken.
+    pub fn claim(&self, arn: &Arn) -> Result<(), ArnError> {
+        let name = name_of(arn);
+        let _claiming = self.claiming.lock().unwrap();
+        if self.backend.get(&name)?.is_some() {
+            return Err(ArnError::AlreadyRegistered(name.to_string()));
+        }
+        self.backend.put(&name, &[])
+    }
+
+    /// Claims the name of `arn` and hands it back, for claiming in a chain of calls.
+    pub fn issue<'a>(&self, arn: Arn<'a>) -> Result<Arn<'a>, ArnError> {
+        self.claim(&arn)?;
+        Ok(arn)
+    }
+
+    /// Returns `true` if the name of `arn` is claimed.
+    pub fn is_claimed(&self, arn: &Arn) -> Result<bool, ArnError> {
+        Ok(self.backend.get(&name_of(arn))?.is_some())
+    }
+
+    /// Frees the name of `arn` to be claimed again, returning `true` if it was claimed.
+    pub fn release(&self, arn: &Arn) -> Result<bool, ArnError> {
+        let _claiming = self.claiming.lock().unwrap();
+        self.backend.remove(&name_of(arn))
+    }
+
+    /// Returns every claimed name whose canonical form starts with `prefix`, in lexical
+    /// order, such as `"arn:akton:hr:acme:"` for one account.
+    pub fn claimed(&self, prefix: &str) -> Result<Vec<Arn<'static>>, ArnError> {
+        Ok(self
+            .backend
+            .scan_prefix(prefix)?
+            .into_iter()
+            .map(|(arn, _)| arn)
+            .collect())
+    }
+}
+
+impl Default for NamespaceGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for NamespaceGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NamespaceGuard").finish_non_exhaustive()
+    }
+}
+
+/// Returns the name `arn` claims: the Arn without its version, extensions or annotations.
+fn name_of<'a>(arn: &Arn<'a>) -> Arn<'a> {
+    Arn::new(
+        arn.domain.clone(),
+        arn.category.clone(),
+        arn.account.clone(),
+        arn.root.clone(),
+        arn.parts.clone(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Account, ArnBuilder, ArnParser, Category, Domain, Root};
+    use std::sync::Arc;
+
+    fn arn(s: &str) -> Arn<'_> {
+        ArnParser::new(s).parse().unwrap()
+    }
+
+    #[test]
+    fn test_claims_are_per_name() {
+        let guard = NamespaceGuard::new();
+        guard
+            .claim(&arn("arn:akton:hr:acme:root/a?region=eu"))
+            .unwrap();
+        assert!(guard.is_claimed(&arn("arn:akton:hr:acme:root/a")).unwrap());
+        assert!(guard.claim(&arn("arn:akton:hr:acme:root/a@v3")).is_err());
+        guard.claim(&arn("arn:akton:hr:globex:root/a")).unwrap();
+        guard.claim(&arn("arn:akton:hr:acme:root/a/b")).unwrap();
+        let claimed: Vec<String> = guard
+            .claimed("arn:akton:hr:acme:")
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            claimed,
+            ["arn:akton:hr:acme:root/a", "arn:akton:hr:acme:root/a/b"]
+        );
+        assert!(!guard.release(&arn("arn:akton:hr:acme:root/z")).unwrap());
+    }
+
+    #[test]
+    fn test_claims_persist_in_the_backend() {
+        let path =
+            std::env::temp_dir().join(format!("akton-arn-namespace-guard-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let first = NamespaceGuard::with_backend(crate::FileBackend::open(&path).unwrap());
+        first.claim(&arn("arn:akton:hr:acme:root/a")).unwrap();
+        drop(first);
+        let restarted = NamespaceGuard::with_backend(crate::FileBackend::open(&path).unwrap());
+        assert!(restarted.claim(&arn("arn:akton:hr:acme:root/a")).is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_builder_claims_on_build() {
+        let guard = Arc::new(NamespaceGuard::new());
+        let build = |root: &'static str| {
+            ArnBuilder::new()
+                .with_namespace_guard(guard.clone())
+                .with::<Domain>("akton")
+                .and_then(|b| b.with::<Category>("hr"))
+                .and_then(|b| b.with::<Account>("acme"))
+                .and_then(|b| b.with::<Root>(root))
+                .and_then(|b| b.build())
+        };
+        let built = build("payroll").unwrap();
+        assert!(guard.is_claimed(&built).unwrap());
+        assert!(build("payroll").is_ok(), "each build mints a new root id");
+        let named = || {
+            ArnBuilder::new()
+                .with_namespace_guard(guard.clone())
+                .with_root_config(
+                    crate::RootConfig::new()
+                        .with_suffix(crate::RootSuffix::Omitted)
+                        .unwrap(),
+                )
+                .with::<Domain>("akton")
+                .and_then(|b| b.with::<Category>("hr"))
+                .and_then(|b| b.with::<Account>("acme"))
+                .and_then(|b| b.with::<Root>("payroll"))
+                .and_then(|b| b.build())
+        };
+        assert!(named().is_ok());
+        assert!(matches!(named(), Err(ArnError::AlreadyRegistered(_))));
+    }
+}
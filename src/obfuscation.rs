@@ -0,0 +1,170 @@
+use crate::errors::ArnError;
+use crate::model::{Account, Arn};
+use crate::parser::ArnParser;
+use crate::short_id::{decode, encode};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::fmt;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Length of the synthetic IV prefixed to every obfuscated account.
+const TAG_LEN: usize = 16;
+
+/// Deterministically encrypts the [`Account`] component of Arns shown to external consumers.
+///
+/// The same account always obfuscates to the same token under the same key, so partners can
+/// still join on it, while the internal tenant id is only recoverable with the key. Tokens are
+/// built SIV-style: an HMAC-SHA256 tag over the account doubles as the IV for an HMAC-derived
+/// keystream, and `tag || ciphertext` is rendered in Crockford base32. Tampered tokens and
+/// tokens minted under another key are rejected with [`ArnError::InvalidSignature`].
+#[derive(Clone)]
+pub struct Obfuscator {
+    key: Vec<u8>,
+}
+
+impl Obfuscator {
+    /// Creates an obfuscator from a secret key.
+    pub fn new(key: impl Into<Vec<u8>>) -> Self {
+        Obfuscator { key: key.into() }
+    }
+
+    /// Returns the opaque token for an account.
+    pub fn obfuscate_account(&self, account: &Account) -> Account<'static> {
+        let plaintext = account.as_str().as_bytes();
+        let tag = self.tag(plaintext);
+        let mut token = Vec::with_capacity(TAG_LEN + plaintext.len());
+        token.extend_from_slice(&tag);
+        token.extend(
+            plaintext
+                .iter()
+                .zip(self.keystream(&tag))
+                .map(|(b, k)| b ^ k),
+        );
+        Account::new(encode(&token))
+    }
+
+    /// Recovers the account from a token produced by [`Obfuscator::obfuscate_account`].
+    pub fn reveal_account(&self, account: &Account) -> Result<Account<'static>, ArnError> {
+        let token = decode(account.as_str())?;
+        if token.len() < TAG_LEN {
+            return Err(ArnError::DecodeFailure(
+                "account token is too short".to_string(),
+            ));
+        }
+        let (tag, ciphertext) = token.split_at(TAG_LEN);
+        let plaintext: Vec<u8> = ciphertext
+            .iter()
+            .zip(self.keystream(tag))
+            .map(|(b, k)| b ^ k)
+            .collect();
+        let mut mac = self.mac(b"tag");
+        mac.update(&plaintext);
+        mac.verify_truncated_left(tag)
+            .map_err(|_| ArnError::InvalidSignature)?;
+        let account =
+            String::from_utf8(plaintext).map_err(|e| ArnError::DecodeFailure(e.to_string()))?;
+        Ok(Account::new(account))
+    }
+
+    /// Returns a copy of `arn` with its account obfuscated, ready to render for external consumers.
+    pub fn obfuscate(&self, arn: &Arn) -> Arn<'static> {
+        let mut external = arn.clone().into_owned();
+        external.account = self.obfuscate_account(&arn.account);
+        external
+    }
+
+    /// Reverses [`Obfuscator::obfuscate`].
+    pub fn reveal(&self, arn: &Arn) -> Result<Arn<'static>, ArnError> {
+        let mut internal = arn.clone().into_owned();
+        internal.account = self.reveal_account(&arn.account)?;
+        Ok(internal)
+    }
+
+    /// Parses an externally rendered Arn and reveals its account.
+    pub fn parse(&self, arn: &str) -> Result<Arn<'static>, ArnError> {
+        self.reveal(&ArnParser::new(arn).parse()?)
+    }
+
+    fn mac(&self, label: &[u8]) -> HmacSha256 {
+        let mut mac =
+            HmacSha256::new_from_slice(&self.key).expect("HMAC accepts keys of any length");
+        mac.update(label);
+        mac
+    }
+
+    fn tag(&self, plaintext: &[u8]) -> [u8; TAG_LEN] {
+        let mut mac = self.mac(b"tag");
+        mac.update(plaintext);
+        let digest = mac.finalize().into_bytes();
+        let mut tag = [0; TAG_LEN];
+        tag.copy_from_slice(&digest[..TAG_LEN]);
+        tag
+    }
+
+    /// HMAC in counter mode, keyed by the tag.
+    fn keystream<'s>(&'s self, tag: &'s [u8]) -> impl Iterator<Item = u8> + 's {
+        (0u32..).flat_map(move |block| {
+            let mut mac = self.mac(b"stream");
+            mac.update(tag);
+            mac.update(&block.to_be_bytes());
+            mac.finalize().into_bytes()
+        })
+    }
+}
+
+impl fmt::Debug for Obfuscator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Obfuscator").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Arn<'static> {
+        ArnParser::new("arn:akton-internal:hr:tenant-42:root/departmentA/team1")
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_obfuscation_is_deterministic_and_reversible() {
+        let obfuscator = Obfuscator::new("secret");
+        let external = obfuscator.obfuscate(&sample());
+        assert_eq!(external, obfuscator.obfuscate(&sample()));
+        let rendered = external.to_string();
+        assert!(!rendered.contains("tenant-42"));
+        assert!(rendered.ends_with(":root/departmentA/team1"));
+        assert_eq!(obfuscator.parse(&rendered).unwrap(), sample());
+    }
+
+    #[test]
+    fn test_tokens_differ_per_key_and_account() {
+        let a = Obfuscator::new("key-a");
+        let b = Obfuscator::new("key-b");
+        let account = Account::new("tenant-42");
+        assert_ne!(a.obfuscate_account(&account), b.obfuscate_account(&account));
+        assert_ne!(
+            a.obfuscate_account(&account),
+            a.obfuscate_account(&Account::new("tenant-43"))
+        );
+        assert_eq!(
+            b.reveal_account(&a.obfuscate_account(&account)),
+            Err(ArnError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn test_tampered_and_malformed_tokens_are_rejected() {
+        let obfuscator = Obfuscator::new("secret");
+        let token = obfuscator.obfuscate_account(&Account::new("tenant-42"));
+        let mut tampered = token.as_str().to_string();
+        let last = if tampered.ends_with('0') { "1" } else { "0" };
+        tampered.replace_range(tampered.len() - 1.., last);
+        assert!(obfuscator.reveal_account(&Account::new(tampered)).is_err());
+        assert!(obfuscator.reveal_account(&Account::new("ABC")).is_err());
+        assert!(obfuscator.parse("arn:a:b:tenant-42:root").is_err());
+    }
+}
@@ -1,13 +1,93 @@
+use crate::case_fold::CaseFold;
 use crate::errors::ArnError;
-use crate::model::{Account, Arn, Category, Domain, Part, Parts};
-use crate::Root;
+use crate::format::ArnFormat;
+use crate::limits::ArnLimits;
+use crate::model::{check_part, Account, Arn, Category, Domain, Extensions, Part, Parts};
+use crate::traits::{run_validators, ArnValidator, ArnVisitor};
+use crate::validation_mode::ValidationMode;
+use crate::{ComponentKind, Root};
 use std::borrow::Cow;
-use std::str::FromStr;
+use std::ops::ControlFlow;
+use std::sync::Arc;
+
+/// A parser over an owned string, producing an [`OwnedArn`](crate::OwnedArn).
+pub type OwnedArnParser = ArnParser<'static>;
 
 /// A parser for decoding Arn strings into their constituent components.
 pub struct ArnParser<'a> {
     /// The Arn string to be parsed.
     arn: Cow<'a, str>,
+    /// Custom rules run against each component as it is parsed.
+    validators: Vec<Arc<dyn ArnValidator>>,
+    /// The grammar the string is written in.
+    format: ArnFormat,
+    /// How components are normalized and checked.
+    rules: ComponentRules,
+}
+
+/// How each component is normalized and checked before it is stored, shared with the builder.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct ComponentRules {
+    /// Size and character limits for the components and the whole Arn.
+    pub(crate) limits: ArnLimits,
+    /// Which characters components may contain.
+    pub(crate) mode: ValidationMode,
+    /// Which components are lowercased.
+    pub(crate) case_fold: CaseFold,
+}
+
+impl ComponentRules {
+    /// Normalizes `value` as the validation mode says, folds its case and fits it to the limits.
+    pub(crate) fn fit<'v>(
+        &self,
+        kind: ComponentKind,
+        value: Cow<'v, str>,
+    ) -> Result<Cow<'v, str>, ArnError> {
+        let value = self.mode.apply(kind, value)?;
+        self.limits.fit(kind, self.case_fold.apply(kind, value))
+    }
+
+    /// Fits a component that was not split out of an Arn string, such as one decoded from
+    /// bytes or set on an Arn, and checks it as [`walk`] checks a parsed one.
+    ///
+    /// Splitting a string never yields a component holding the separators around it, so those
+    /// are rejected too: the Arn would otherwise display as a string that parses differently.
+    pub(crate) fn fit_component<'v>(
+        &self,
+        kind: ComponentKind,
+        value: Cow<'v, str>,
+    ) -> Result<Cow<'v, str>, ArnError> {
+        let (reserved, listed): (&[char], _) = match kind {
+            ComponentKind::Domain | ComponentKind::Category | ComponentKind::Account => {
+                (&[':'], "':'")
+            }
+            ComponentKind::Root => (&['/', '?', '@'], "'/', '?' or '@'"),
+            // `check_part` rejects the separators a part may not hold.
+            ComponentKind::Part => (&[], ""),
+        };
+        if value.contains(reserved) {
+            return Err(ArnError::ParseFailure(
+                kind.as_str(),
+                format!("{value:?} must not contain {listed}"),
+            ));
+        }
+        let value = self.fit(kind, value)?;
+        check_component(kind, &value)?;
+        Ok(value)
+    }
+}
+
+/// Checks what the grammar requires of a fitted component: a domain or part is never empty,
+/// and a part holds no separator.
+fn check_component(kind: ComponentKind, value: &str) -> Result<(), ArnError> {
+    match kind {
+        ComponentKind::Domain if value.is_empty() => Err(ArnError::ParseFailure(
+            "Domain",
+            "cannot be empty".to_string(),
+        )),
+        ComponentKind::Part => check_part(value),
+        _ => Ok(()),
+    }
 }
 
 impl<'a> ArnParser<'a> {
@@ -21,7 +101,43 @@ impl<'a> ArnParser<'a> {
     ///
     /// Returns an `ArnParser` instance initialized with the given Arn string.
     pub fn new(arn: impl Into<Cow<'a, str>>) -> Self {
-        Self { arn: arn.into() }
+        Self {
+            arn: arn.into(),
+            validators: Vec::new(),
+            format: ArnFormat::DEFAULT,
+            rules: ComponentRules::default(),
+        }
+    }
+
+    /// Registers a validator that is run against each component as it is parsed.
+    pub fn with_validator(mut self, validator: impl ArnValidator + 'static) -> Self {
+        self.validators.push(Arc::new(validator));
+        self
+    }
+
+    /// Applies `limits` instead of [`ArnLimits::default`].
+    pub fn with_limits(mut self, limits: ArnLimits) -> Self {
+        self.rules.limits = limits;
+        self
+    }
+
+    /// Checks and normalizes component characters as `mode` says instead of
+    /// [`ValidationMode::Permissive`].
+    pub fn with_validation_mode(mut self, mode: ValidationMode) -> Self {
+        self.rules.mode = mode;
+        self
+    }
+
+    /// Lowercases the components `fold` selects while parsing.
+    pub fn with_case_fold(mut self, fold: CaseFold) -> Self {
+        self.rules.case_fold = fold;
+        self
+    }
+
+    /// Reads the string in `format` instead of the canonical `arn:` grammar.
+    pub fn with_format(mut self, format: ArnFormat) -> Self {
+        self.format = format;
+        self
     }
 
     /// Parses the Arn into its component parts and returns them as a structured result.
@@ -31,46 +147,276 @@ impl<'a> ArnParser<'a> {
     ///
     /// Returns an `Arn` instance containing the parsed components.
     /// If parsing fails, returns an error message as a `String`.
+    ///
+    /// A parser over borrowed input, such as a slice of a network buffer, returns an Arn whose
+    /// components borrow from that input instead of copying it; call [`Arn::into_owned`] to
+    /// keep it past the buffer. Components that validation rewrites, by normalizing or folding
+    /// them, are the only ones copied. A parser over an owned string copies every component.
+    ///
+    /// ```
+    /// use akton_arn::ArnParser;
+    ///
+    /// let buffer = b"arn:akton:hr:acme:root/orders".to_vec();
+    /// let input = std::str::from_utf8(&buffer).unwrap();
+    /// let arn = ArnParser::new(input).parse().unwrap();
+    /// assert_eq!(arn.account().as_str().as_ptr(), input[13..].as_ptr());
+    /// ```
     pub fn parse(&self) -> Result<Arn<'a>, ArnError> {
-        let parts: Vec<&str> = self.arn.splitn(5, ':').collect();
-
-        if parts.len() != 5 || parts[0] != "arn" {
-            return Err(ArnError::InvalidFormat);
+        match &self.arn {
+            Cow::Borrowed(input) => parse_str(input, &self.validators, &self.format, &self.rules),
+            Cow::Owned(input) => {
+                parse_str(input, &self.validators, &self.format, &self.rules).map(Arn::into_owned)
+            }
         }
+    }
+
+    /// Reads the Arn component by component into `visitor` instead of building an [`Arn`].
+    ///
+    /// Components are checked and validated as [`ArnParser::parse`] checks them, so an input
+    /// `parse` rejects fails here too, up to the point the visitor stops reading; what lies
+    /// beyond is never examined. Components borrow from the input unless normalizing or
+    /// folding rewrote them, and nothing else is allocated but the extensions. The overall
+    /// length limit applies to the input as written.
+    ///
+    /// See [`ArnVisitor`] for an example.
+    pub fn parse_with<'p>(&'p self, visitor: &mut impl ArnVisitor<'p>) -> Result<(), ArnError> {
+        self.rules.limits.check_total_len(self.arn.len())?;
+        walk(
+            &self.arn,
+            &self.validators,
+            &self.format,
+            &self.rules,
+            visitor,
+        )
+    }
+
+    /// Parses every input in order, returning one result per input.
+    ///
+    /// The Arns borrow their components from the inputs rather than copying them, so an Arn
+    /// with up to four parts needs no allocation of its own. Keep the input buffer alive while the
+    /// results are in use, or call [`Arn::into_owned`] on those you keep.
+    ///
+    /// ```
+    /// use akton_arn::ArnParser;
+    ///
+    /// let csv = "arn:akton:hr:acme:root/a\nnot-an-arn\narn:akton:hr:acme:root/b";
+    /// let results = ArnParser::parse_many(csv.lines());
+    /// assert_eq!(results.len(), 3);
+    /// assert!(results[1].is_err());
+    /// assert_eq!(results[2].as_ref().unwrap().parts().to_string(), "b");
+    /// ```
+    pub fn parse_many<'i>(
+        inputs: impl IntoIterator<Item = &'i str>,
+    ) -> Vec<Result<Arn<'i>, ArnError>> {
+        inputs
+            .into_iter()
+            .map(|input| parse_str(input, &[], &ArnFormat::DEFAULT, &ComponentRules::default()))
+            .collect()
+    }
+
+    /// Parses the inputs in parallel on the rayon thread pool, returning results in input order.
+    ///
+    /// Like [`ArnParser::parse_many`], the Arns borrow from the inputs.
+    #[cfg(feature = "rayon")]
+    pub fn par_parse_many<'i>(inputs: &[&'i str]) -> Vec<Result<Arn<'i>, ArnError>> {
+        use rayon::prelude::*;
+        inputs
+            .par_iter()
+            .map(|input| parse_str(input, &[], &ArnFormat::DEFAULT, &ComponentRules::default()))
+            .collect()
+    }
+}
+
+/// Parses `input` in `format` into an Arn borrowing from it, fitting each component to
+/// `rules` and running `validators` on it.
+pub(crate) fn parse_str<'s>(
+    input: &'s str,
+    validators: &[Arc<dyn ArnValidator>],
+    format: &ArnFormat,
+    rules: &ComponentRules,
+) -> Result<Arn<'s>, ArnError> {
+    let mut assembler = Assembler::default();
+    walk(input, validators, format, rules, &mut assembler)?;
+    let Assembler {
+        scheme,
+        domain,
+        category,
+        account,
+        root,
+        parts,
+        version,
+        extensions,
+    } = assembler;
+    let mut arn = Arn::new(
+        Domain::new_unchecked(domain),
+        Category::new(category),
+        Account::new(account),
+        Root(root.into()),
+        parts,
+    );
+    arn.version = version;
+    arn.extensions = extensions;
+    arn.scheme = scheme;
+    rules.limits.check_len(&arn)?;
+    Ok(arn)
+}
 
-        let domain = Domain::from_str(parts[1])?;
-        let category = Category::from_str(parts[2])?;
-        let account = Account::from_str(parts[3])?;
-
-        // Split the root and the path part
-        let root_path: Vec<&str> = parts[4].splitn(2, '/').collect();
-        let root_str = root_path[0];
-        let root = Root{ 0: root_str.to_string().into() };
-
-        // Continue with the path parts
-        let mut arn_parts = Vec::new();
-        if root_path.len() > 1 {
-            let path_parts: Vec<&str> = root_path[1].split('/').collect();
-            for part in path_parts.iter() {
-                arn_parts.push(Part::from_str(part)?);
+/// Reads `input` in `format`, handing each component to `visitor` once it is fitted to
+/// `rules` and has passed `validators`, until the visitor breaks.
+pub(crate) fn walk<'s>(
+    input: &'s str,
+    validators: &[Arc<dyn ArnValidator>],
+    format: &ArnFormat,
+    rules: &ComponentRules,
+    visitor: &mut impl ArnVisitor<'s>,
+) -> Result<(), ArnError> {
+    macro_rules! visit {
+        ($call:expr) => {
+            if $call.is_break() {
+                return Ok(());
             }
+        };
+    }
+    let fit = |kind, value: &'s str| -> Result<Cow<'s, str>, ArnError> {
+        let value = rules.fit(kind, value.into())?;
+        check_component(kind, &value)?;
+        run_validators(validators, kind, &value)?;
+        Ok(value)
+    };
+    let separator = format.component_separator();
+    let mut parts = [""; 5];
+    let mut found = 0;
+    for (slot, field) in parts.iter_mut().zip(input.splitn(5, separator)) {
+        *slot = field;
+        found += 1;
+    }
+    if found != 5 {
+        return Err(ArnError::InvalidFormat);
+    }
+    let scheme = format
+        .detect_scheme(parts[0])
+        .ok_or(ArnError::InvalidFormat)?;
+    visit!(visitor.visit_scheme(scheme));
+    visit!(visitor.visit_domain(fit(ComponentKind::Domain, parts[1])?));
+    visit!(visitor.visit_category(fit(ComponentKind::Category, parts[2])?));
+    visit!(visitor.visit_account(fit(ComponentKind::Account, parts[3])?));
+
+    // The query and version suffixes belong to the resource; `?` and `@` are only reserved
+    // from the root onwards.
+    let (resource, query) = match parts[4].split_once('?') {
+        Some((resource, query)) => (resource, Some(query)),
+        None => (parts[4], None),
+    };
+    let (resource, version) = match resource.split_once('@') {
+        Some((resource, suffix)) => (resource, Some(suffix)),
+        None => (resource, None),
+    };
+
+    // Split the root and the path part
+    let (root, path) = match resource.split_once(format.part_separator()) {
+        Some((root, path)) => (root, Some(path)),
+        None => (resource, None),
+    };
+    visit!(visitor.visit_root(fit(ComponentKind::Root, root)?));
+
+    // Continue with the path parts
+    if let Some(path) = path {
+        for part in path.split(format.part_separator()) {
+            visit!(visitor.visit_part(fit(ComponentKind::Part, part)?));
         }
+    }
+    if let Some(suffix) = version {
+        visit!(visitor.visit_version(parse_version(suffix)?));
+    }
+    if let Some(query) = query {
+        visit!(visitor.visit_extensions(query.parse()?));
+    }
+    Ok(())
+}
 
-        // // Process the remaining parts after root
-        // let remaining_parts: Vec<&str> = parts[4].split("/").collect();
-        // for part in remaining_parts.iter() {
-        //     arn_parts.push(Part::from_str(part).map_err(|_| ParseError::InvalidPartFormat)?);
-        // }
+/// Collects the components [`walk`] visits into the pieces of an Arn.
+struct Assembler<'s> {
+    scheme: Cow<'static, str>,
+    domain: Cow<'s, str>,
+    category: Cow<'s, str>,
+    account: Cow<'s, str>,
+    root: Cow<'s, str>,
+    parts: Parts<'s>,
+    version: Option<u64>,
+    extensions: Extensions,
+}
+
+impl Default for Assembler<'_> {
+    fn default() -> Self {
+        Assembler {
+            scheme: Cow::Borrowed(""),
+            domain: Cow::Borrowed(""),
+            category: Cow::Borrowed(""),
+            account: Cow::Borrowed(""),
+            root: Cow::Borrowed(""),
+            parts: Parts::default(),
+            version: None,
+            extensions: Extensions::default(),
+        }
+    }
+}
+
+impl<'s> ArnVisitor<'s> for Assembler<'s> {
+    fn visit_scheme(&mut self, scheme: Cow<'static, str>) -> ControlFlow<()> {
+        self.scheme = scheme;
+        ControlFlow::Continue(())
+    }
+
+    fn visit_domain(&mut self, domain: Cow<'s, str>) -> ControlFlow<()> {
+        self.domain = domain;
+        ControlFlow::Continue(())
+    }
+
+    fn visit_category(&mut self, category: Cow<'s, str>) -> ControlFlow<()> {
+        self.category = category;
+        ControlFlow::Continue(())
+    }
+
+    fn visit_account(&mut self, account: Cow<'s, str>) -> ControlFlow<()> {
+        self.account = account;
+        ControlFlow::Continue(())
+    }
+
+    fn visit_root(&mut self, root: Cow<'s, str>) -> ControlFlow<()> {
+        self.root = root;
+        ControlFlow::Continue(())
+    }
+
+    fn visit_part(&mut self, part: Cow<'s, str>) -> ControlFlow<()> {
+        self.parts.0.push(Part::from_shared(part.into()));
+        ControlFlow::Continue(())
+    }
 
-        let parts = Parts::new(arn_parts);
-        Ok(Arn::new(domain, category, account, root, parts))
+    fn visit_version(&mut self, version: u64) -> ControlFlow<()> {
+        self.version = Some(version);
+        ControlFlow::Continue(())
     }
+
+    fn visit_extensions(&mut self, extensions: Extensions) -> ControlFlow<()> {
+        self.extensions = extensions;
+        ControlFlow::Continue(())
+    }
+}
+
+/// Parses the `v<n>` after the `@` that ends the resource path.
+fn parse_version(suffix: &str) -> Result<u64, ArnError> {
+    suffix
+        .strip_prefix('v')
+        .filter(|digits| digits.bytes().all(|b| b.is_ascii_digit()))
+        .and_then(|digits| digits.parse().ok())
+        .ok_or_else(|| ArnError::ParseFailure("Version", format!("{suffix:?} is not v<number>")))
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::model::SharedStr;
 
     #[test]
     fn test_valid_arn_parsing() {
@@ -83,6 +429,31 @@ mod tests {
         assert_eq!(arn.domain.as_str(), "custom");
     }
 
+    #[test]
+    fn test_version_suffix() {
+        let arn = ArnParser::new("arn:akton:ledger:acme:account/orders@v3?region=eu")
+            .parse()
+            .unwrap();
+        assert_eq!(arn.version(), Some(3));
+        assert_eq!(arn.parts.0.len(), 1);
+        assert_eq!(
+            ArnParser::new("arn:akton:ledger:acme:account@v0")
+                .parse()
+                .unwrap()
+                .version(),
+            Some(0)
+        );
+        for input in [
+            "arn:akton:ledger:acme:account/orders@3",
+            "arn:akton:ledger:acme:account/orders@v",
+            "arn:akton:ledger:acme:account/orders@v+3",
+            "arn:akton:ledger:acme:account/a@v1/b",
+            "arn:akton:ledger:acme:account/a@v1@v2",
+        ] {
+            assert!(ArnParser::new(input).parse().is_err(), "{input}");
+        }
+    }
+
     #[test]
     fn test_invalid_arn_format() {
         let arn_str = "invalid:arn:format";
@@ -110,4 +481,157 @@ mod tests {
         let result = parser.parse();
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_parse_borrows_from_borrowed_input() {
+        let buffer = String::from("arn:akton:hr:acme:root/a/B");
+        let arn = ArnParser::new(buffer.as_str()).parse().unwrap();
+        assert!(matches!(
+            arn.domain.0,
+            SharedStr::Borrowed(Cow::Borrowed("akton"))
+        ));
+        assert!(matches!(
+            arn.root.0,
+            SharedStr::Borrowed(Cow::Borrowed("root"))
+        ));
+        assert!(matches!(
+            arn.parts.0[1].0,
+            SharedStr::Borrowed(Cow::Borrowed("B"))
+        ));
+
+        let folded = ArnParser::new(buffer.as_str())
+            .with_case_fold(CaseFold::all())
+            .parse()
+            .unwrap();
+        assert!(matches!(
+            folded.parts.0[0].0,
+            SharedStr::Borrowed(Cow::Borrowed("a"))
+        ));
+        assert!(matches!(folded.parts.0[1].0, SharedStr::Shared(_)));
+
+        let owned = ArnParser::new(buffer.clone()).parse().unwrap();
+        assert!(matches!(owned.domain.0, SharedStr::Shared(_)));
+        assert_eq!(owned, arn);
+    }
+
+    #[test]
+    fn test_parse_many_borrows_from_inputs() {
+        let inputs = [
+            "arn:akton:hr:acme:root/a/b",
+            "arn:akton:hr",
+            "arn:akton:hr:acme:root/a:b",
+            "arn:akton:hr:acme:root",
+        ]
+        .map(String::from);
+        let results = ArnParser::parse_many(inputs.iter().map(String::as_str));
+        assert_eq!(results.len(), 4);
+        let first = results[0].as_ref().unwrap();
+        assert!(matches!(
+            first.domain.0,
+            SharedStr::Borrowed(Cow::Borrowed(_))
+        ));
+        assert!(matches!(
+            first.parts.0[1].0,
+            SharedStr::Borrowed(Cow::Borrowed("b"))
+        ));
+        assert_eq!(results[1], Err(ArnError::InvalidFormat));
+        assert_eq!(results[2], Err(ArnError::InvalidPartFormat));
+        for (input, result) in inputs.iter().zip(&results) {
+            assert_eq!(
+                result.as_ref().ok(),
+                ArnParser::new(input.as_str()).parse().ok().as_ref()
+            );
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par_parse_many_preserves_order() {
+        let inputs: Vec<String> = (0..1000)
+            .map(|i| format!("arn:akton:hr:acme:root/{i}"))
+            .collect();
+        let inputs: Vec<&str> = inputs.iter().map(String::as_str).collect();
+        let results = ArnParser::par_parse_many(&inputs);
+        assert_eq!(results, ArnParser::parse_many(inputs.iter().copied()));
+    }
+
+    #[test]
+    fn test_arn_parsing_with_validator() {
+        let no_admin = |kind: ComponentKind, value: &str| {
+            if kind == ComponentKind::Part && value == "admin" {
+                Err("admin parts are reserved".to_string())
+            } else {
+                Ok(())
+            }
+        };
+
+        let parser =
+            ArnParser::new("arn:custom:service:account123:root/resource").with_validator(no_admin);
+        assert!(parser.parse().is_ok());
+
+        let parser =
+            ArnParser::new("arn:custom:service:account123:root/admin").with_validator(no_admin);
+        assert_eq!(
+            parser.parse().err(),
+            Some(ArnError::ValidationFailure(
+                ComponentKind::Part,
+                "admin parts are reserved".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_with_visits_in_order_and_stops_early() {
+        #[derive(Default)]
+        struct Record<'i> {
+            seen: Vec<Cow<'i, str>>,
+            stop_after: usize,
+        }
+        impl<'i> ArnVisitor<'i> for Record<'i> {
+            fn visit_domain(&mut self, domain: Cow<'i, str>) -> ControlFlow<()> {
+                self.visit_part(domain)
+            }
+            fn visit_account(&mut self, account: Cow<'i, str>) -> ControlFlow<()> {
+                self.visit_part(account)
+            }
+            fn visit_part(&mut self, part: Cow<'i, str>) -> ControlFlow<()> {
+                self.seen.push(part);
+                if self.seen.len() == self.stop_after {
+                    ControlFlow::Break(())
+                } else {
+                    ControlFlow::Continue(())
+                }
+            }
+            fn visit_version(&mut self, version: u64) -> ControlFlow<()> {
+                self.visit_part(format!("v{version}").into())
+            }
+        }
+
+        let parser = ArnParser::new("arn:akton:hr:acme:root/a/b@v2");
+        let mut all = Record::default();
+        parser.parse_with(&mut all).unwrap();
+        assert_eq!(all.seen, ["akton", "acme", "a", "b", "v2"]);
+        assert!(matches!(all.seen[1], Cow::Borrowed(_)));
+
+        let broken = ArnParser::new("arn:akton:hr:acme:root/a/b:c?=");
+        assert!(broken.parse().is_err());
+        let mut two = Record {
+            stop_after: 2,
+            ..Record::default()
+        };
+        broken.parse_with(&mut two).unwrap();
+        assert_eq!(two.seen, ["akton", "acme"]);
+        assert_eq!(
+            broken.parse_with(&mut Record::default()),
+            Err(ArnError::InvalidPartFormat)
+        );
+
+        let folded = ArnParser::new("arn:AKTON:hr:acme:root").with_case_fold(CaseFold::all());
+        let mut one = Record {
+            stop_after: 1,
+            ..Record::default()
+        };
+        folded.parse_with(&mut one).unwrap();
+        assert_eq!(one.seen, ["akton"]);
+    }
 }
@@ -0,0 +1,341 @@
+use crate::errors::ArnError;
+use crate::pattern::{escape_class_char, escape_regex};
+use std::fmt;
+
+/// Characters a part can never contain, so a glob naming them could never match.
+const SEPARATORS: [char; 4] = [':', '/', '?', '@'];
+
+/// Most words [`PartGlob::words`] lists before giving up.
+const MAX_WORDS: usize = 256;
+
+/// A part pattern using negation, character classes or alternation, such as `!{tmp,cache}`
+/// or `v[0-9]`.
+///
+/// See [`ArnPattern`](crate::ArnPattern) for the grammar.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct PartGlob {
+    negated: bool,
+    atoms: Vec<Atom>,
+    /// The text the glob was parsed from, written back by `Display`.
+    source: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Atom {
+    Literal(String),
+    /// `[a-c]`: one character in any of the ranges, or in none of them if negated.
+    Class {
+        negated: bool,
+        ranges: Vec<(char, char)>,
+    },
+    /// `{a,b}`: any one of the alternatives.
+    Alt(Vec<String>),
+}
+
+impl PartGlob {
+    /// Returns `true` if `segment` needs parsing as a glob rather than as a literal part.
+    pub(crate) fn is_glob(segment: &str) -> bool {
+        segment.starts_with('!') || segment.contains(['[', '{', '\\'])
+    }
+
+    pub(crate) fn parse(source: &str) -> Result<PartGlob, ArnError> {
+        let fail = |reason: &str| ArnError::ParseFailure("Part", format!("{source:?} {reason}"));
+        let (negated, body) = match source.strip_prefix('!') {
+            Some(body) => (true, body),
+            None => (false, source),
+        };
+        let mut chars = body.chars();
+        let mut atoms = Vec::new();
+        let mut literal = String::new();
+        while let Some(c) = chars.next() {
+            let atom = match c {
+                '[' => {
+                    let mut negated = false;
+                    let mut ranges = Vec::new();
+                    let mut pending: Option<char> = None;
+                    let mut range_start: Option<char> = None;
+                    loop {
+                        let c = chars.next().ok_or_else(|| fail("has an unclosed '['"))?;
+                        let c = match c {
+                            ']' => break,
+                            '!' | '^' if ranges.is_empty() && pending.is_none() && !negated => {
+                                negated = true;
+                                continue;
+                            }
+                            '-' if pending.is_some() && range_start.is_none() => {
+                                range_start = pending.take();
+                                continue;
+                            }
+                            '\\' => chars.next().ok_or_else(|| fail("ends with '\\'"))?,
+                            c => c,
+                        };
+                        if let Some(start) = range_start.take() {
+                            if start > c {
+                                return Err(fail("has a range running backwards"));
+                            }
+                            ranges.push((start, c));
+                        } else if let Some(single) = pending.replace(c) {
+                            ranges.push((single, single));
+                        }
+                    }
+                    if let Some(start) = range_start {
+                        // A `-` before the `]` is literal, as in `[a-]`.
+                        ranges.extend([(start, start), ('-', '-')]);
+                    }
+                    if let Some(single) = pending {
+                        ranges.push((single, single));
+                    }
+                    if ranges.is_empty() {
+                        return Err(fail("has an empty '[]'"));
+                    }
+                    Atom::Class { negated, ranges }
+                }
+                '{' => {
+                    let mut alternatives = vec![String::new()];
+                    loop {
+                        match chars.next().ok_or_else(|| fail("has an unclosed '{'"))? {
+                            '}' => break,
+                            ',' => alternatives.push(String::new()),
+                            '\\' => {
+                                let c = chars.next().ok_or_else(|| fail("ends with '\\'"))?;
+                                alternatives.last_mut().expect("never empty").push(c);
+                            }
+                            '[' | '{' => return Err(fail("nests brackets inside '{}'")),
+                            c => alternatives.last_mut().expect("never empty").push(c),
+                        }
+                    }
+                    Atom::Alt(alternatives)
+                }
+                ']' | '}' | ',' => return Err(fail(&format!("has an unmatched {c:?}"))),
+                '\\' => {
+                    literal.push(chars.next().ok_or_else(|| fail("ends with '\\'"))?);
+                    continue;
+                }
+                c => {
+                    literal.push(c);
+                    continue;
+                }
+            };
+            if !literal.is_empty() {
+                atoms.push(Atom::Literal(std::mem::take(&mut literal)));
+            }
+            atoms.push(atom);
+        }
+        if !literal.is_empty() {
+            atoms.push(Atom::Literal(literal));
+        }
+        if atoms.is_empty() {
+            return Err(fail("matches nothing"));
+        }
+        let texts = atoms.iter().flat_map(|atom| match atom {
+            Atom::Literal(text) => std::slice::from_ref(text),
+            Atom::Alt(alternatives) => alternatives.as_slice(),
+            Atom::Class { .. } => &[],
+        });
+        if texts.clone().any(|text| text.contains(SEPARATORS)) {
+            return Err(fail("contains ':', '/', '?' or '@'"));
+        }
+        Ok(PartGlob {
+            negated,
+            atoms,
+            source: source.to_string(),
+        })
+    }
+
+    /// Returns `true` if the part `value` matches, comparing letters without regard to case
+    /// if `fold` is set.
+    pub(crate) fn matches(&self, value: &str, fold: bool) -> bool {
+        match_atoms(&self.atoms, value, fold) != self.negated
+    }
+
+    /// Lists every part the glob matches, or `None` if it is negated or matches more than a
+    /// few hundred.
+    pub(crate) fn words(&self) -> Option<Vec<String>> {
+        if self.negated {
+            return None;
+        }
+        let mut words = vec![String::new()];
+        for atom in &self.atoms {
+            let choices: Vec<String> = match atom {
+                Atom::Literal(text) => vec![text.clone()],
+                Atom::Alt(alternatives) => alternatives.clone(),
+                Atom::Class { negated: true, .. } => return None,
+                Atom::Class { ranges, .. } => {
+                    let count: u64 = ranges
+                        .iter()
+                        .map(|(start, end)| u64::from(*end as u32 - *start as u32) + 1)
+                        .sum();
+                    if count > MAX_WORDS as u64 {
+                        return None;
+                    }
+                    ranges
+                        .iter()
+                        .flat_map(|(start, end)| *start..=*end)
+                        .map(String::from)
+                        .collect()
+                }
+            };
+            if words.len() * choices.len() > MAX_WORDS {
+                return None;
+            }
+            words = words
+                .iter()
+                .flat_map(|word| choices.iter().map(move |choice| format!("{word}{choice}")))
+                .collect();
+        }
+        words.retain(|word| !word.is_empty());
+        Some(words)
+    }
+
+    /// Returns a regex matching one part as the glob does.
+    ///
+    /// A negated glob needs a negative lookahead, which not every engine supports.
+    pub(crate) fn to_regex(&self, fold: bool, part_separator: char) -> String {
+        let mut regex = String::new();
+        for atom in &self.atoms {
+            match atom {
+                Atom::Literal(text) => regex.push_str(&escape_regex(text)),
+                Atom::Alt(alternatives) => {
+                    let alternatives: Vec<String> =
+                        alternatives.iter().map(|text| escape_regex(text)).collect();
+                    regex.push_str(&format!("(?:{})", alternatives.join("|")));
+                }
+                Atom::Class { negated, ranges } => {
+                    regex.push('[');
+                    if *negated {
+                        regex.push('^');
+                    }
+                    for (start, end) in ranges {
+                        regex.push_str(&escape_class_char(*start));
+                        if start != end {
+                            regex.push('-');
+                            regex.push_str(&escape_class_char(*end));
+                        }
+                    }
+                    if *negated {
+                        for c in [part_separator, '?', '@'] {
+                            regex.push_str(&escape_class_char(c));
+                        }
+                    }
+                    regex.push(']');
+                }
+            }
+        }
+        if self.negated {
+            let ends: String = [part_separator, '?', '@']
+                .into_iter()
+                .map(escape_class_char)
+                .collect();
+            regex = format!("(?!(?:{regex})(?:[{ends}]|$))[^{ends}]+");
+        }
+        if fold {
+            format!("(?i:{regex})")
+        } else {
+            regex
+        }
+    }
+}
+
+/// Matches `value` against `atoms` in full, backtracking over alternatives.
+fn match_atoms(atoms: &[Atom], value: &str, fold: bool) -> bool {
+    let Some((atom, rest)) = atoms.split_first() else {
+        return value.is_empty();
+    };
+    match atom {
+        Atom::Literal(text) => {
+            strip_prefix(value, text, fold).is_some_and(|tail| match_atoms(rest, tail, fold))
+        }
+        Atom::Alt(alternatives) => alternatives.iter().any(|text| {
+            strip_prefix(value, text, fold).is_some_and(|tail| match_atoms(rest, tail, fold))
+        }),
+        Atom::Class { negated, ranges } => {
+            let mut chars = value.chars();
+            let Some(c) = chars.next() else {
+                return false;
+            };
+            let in_class = |c: char| {
+                ranges
+                    .iter()
+                    .any(|(start, end)| (*start..=*end).contains(&c))
+            };
+            let found =
+                in_class(c) || (fold && c.to_lowercase().chain(c.to_uppercase()).any(in_class));
+            found != *negated && match_atoms(rest, chars.as_str(), fold)
+        }
+    }
+}
+
+/// Strips `prefix` from `value`, comparing each character without regard to case if `fold`
+/// is set.
+fn strip_prefix<'v>(value: &'v str, prefix: &str, fold: bool) -> Option<&'v str> {
+    if !fold {
+        return value.strip_prefix(prefix);
+    }
+    let mut chars = value.chars();
+    for expected in prefix.chars() {
+        let c = chars.next()?;
+        if !c.to_lowercase().eq(expected.to_lowercase()) {
+            return None;
+        }
+    }
+    Some(chars.as_str())
+}
+
+impl fmt::Display for PartGlob {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glob(source: &str) -> PartGlob {
+        PartGlob::parse(source).unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_match() {
+        let version = glob("v[0-9]");
+        assert!(version.matches("v7", false));
+        assert!(!version.matches("v10", false) && !version.matches("V7", false));
+        assert!(version.matches("V7", true));
+
+        let alt = glob("{tmp,cache}-[!a-c]");
+        assert!(alt.matches("tmp-x", false) && alt.matches("cache-d", false));
+        assert!(!alt.matches("tmp-b", false) && !alt.matches("logs-x", false));
+
+        let negated = glob("!{tmp,cache}");
+        assert!(negated.matches("logs", false));
+        assert!(!negated.matches("tmp", false));
+        assert!(negated.matches("TMP", false) && !negated.matches("TMP", true));
+
+        let escaped = glob(r"\!a\[b\]");
+        assert!(escaped.matches("!a[b]", false));
+        assert!(glob("[-a]").matches("-", false) && glob("[a-]").matches("-", false));
+        assert!(glob("{a,}b").matches("b", false));
+        assert_eq!(glob("!{a,b}").to_string(), "!{a,b}");
+    }
+
+    #[test]
+    fn test_invalid_globs() {
+        for source in [
+            "!", "[a", "[]", "[!]", "[c-a]", "{a", "a]", "b}", "a,b", "{a,[b]}", "x\\", "{a?,b}",
+        ] {
+            assert!(PartGlob::parse(source).is_err(), "{source:?}");
+        }
+    }
+
+    #[test]
+    fn test_words() {
+        assert_eq!(
+            glob("{a,b}[0-1]").words().unwrap(),
+            ["a0", "a1", "b0", "b1"]
+        );
+        assert_eq!(glob("{a,}").words().unwrap(), ["a"]);
+        assert!(glob("!a").words().is_none());
+        assert!(glob("[!a]").words().is_none());
+        assert!(glob("[a-z][a-z]").words().is_none());
+    }
+}
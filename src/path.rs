@@ -0,0 +1,251 @@
+use crate::errors::ArnError;
+use crate::model::Arn;
+use crate::parser::ArnParser;
+use std::fmt;
+use std::ops::Deref;
+use std::str::FromStr;
+
+/// An Arn carried in a single URL path segment, such as `/resources/{arn}`.
+///
+/// Parts are separated by `/`, which collides with routing, so in a path segment the Arn is
+/// percent-encoded: [`ArnPath::encode`] escapes `/`, `%` and every byte that is not allowed
+/// verbatim in a segment, and [`ArnPath::decode`] reverses it. `Display` and `FromStr` use the
+/// same codec, so `format!("/resources/{}", ArnPath::from(arn))` builds a routable URL.
+///
+/// With the `axum` or `actix-web` feature, `ArnPath` is also an extractor that decodes the
+/// route's single path parameter. Routes that capture the Arn unencoded with a wildcard
+/// (`/resources/*arn`) can instead extract `Path<Arn>` directly with the `serde` feature.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ArnPath(pub Arn<'static>);
+
+impl ArnPath {
+    /// Percent-encodes an Arn for use as a single path segment.
+    pub fn encode(arn: &Arn) -> String {
+        let mut encoded = String::new();
+        for byte in arn.to_string().bytes() {
+            if byte.is_ascii_alphanumeric()
+                || matches!(
+                    byte,
+                    b'-' | b'.' | b'_' | b'~' | b':' | b'@' | b'!' | b'$' | b'&'
+                )
+                || matches!(byte, b'\'' | b'(' | b')' | b'*' | b'+' | b',' | b';' | b'=')
+            {
+                encoded.push(byte as char);
+            } else {
+                encoded.push_str(&format!("%{byte:02X}"));
+            }
+        }
+        encoded
+    }
+
+    /// Decodes a path segment produced by [`ArnPath::encode`] and parses the Arn.
+    ///
+    /// Segments that were already partially decoded by a router are accepted as long as `%`
+    /// itself was left encoded.
+    pub fn decode(segment: &str) -> Result<Arn<'static>, ArnError> {
+        let invalid =
+            || ArnError::DecodeFailure(format!("invalid percent-encoding in {segment:?}"));
+        let mut bytes = Vec::with_capacity(segment.len());
+        let mut input = segment.bytes();
+        while let Some(byte) = input.next() {
+            if byte == b'%' {
+                let hex = [
+                    input.next().ok_or_else(invalid)?,
+                    input.next().ok_or_else(invalid)?,
+                ];
+                let hex = std::str::from_utf8(&hex).map_err(|_| invalid())?;
+                bytes.push(u8::from_str_radix(hex, 16).map_err(|_| invalid())?);
+            } else {
+                bytes.push(byte);
+            }
+        }
+        let decoded =
+            String::from_utf8(bytes).map_err(|e| ArnError::DecodeFailure(e.to_string()))?;
+        ArnParser::new(decoded).parse()
+    }
+
+    /// Returns the wrapped Arn.
+    pub fn into_inner(self) -> Arn<'static> {
+        self.0
+    }
+}
+
+impl From<Arn<'_>> for ArnPath {
+    fn from(arn: Arn<'_>) -> Self {
+        ArnPath(arn.into_owned())
+    }
+}
+
+impl Deref for ArnPath {
+    type Target = Arn<'static>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl fmt::Display for ArnPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&ArnPath::encode(&self.0))
+    }
+}
+
+impl FromStr for ArnPath {
+    type Err = ArnError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ArnPath::decode(s).map(ArnPath)
+    }
+}
+
+impl FromStr for Arn<'static> {
+    type Err = ArnError;
+
+    /// Parses an Arn with the default [`ArnParser`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ArnParser::new(s.to_string()).parse()
+    }
+}
+
+#[cfg(feature = "axum")]
+mod axum_extractor {
+    use super::ArnPath;
+    use axum::extract::{FromRequestParts, RawPathParams};
+    use axum::http::request::Parts;
+    use axum::http::StatusCode;
+    use axum::response::{IntoResponse, Response};
+
+    #[axum::async_trait]
+    impl<S: Send + Sync> FromRequestParts<S> for ArnPath {
+        type Rejection = Response;
+
+        /// Decodes the route's only path parameter, answering `400` if it is not a valid Arn.
+        async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+            let params = RawPathParams::from_request_parts(parts, state)
+                .await
+                .map_err(IntoResponse::into_response)?;
+            let mut params = params.iter();
+            let (Some((_, segment)), None) = (params.next(), params.next()) else {
+                return Err((
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "ArnPath requires a route with exactly one path parameter",
+                )
+                    .into_response());
+            };
+            // axum has already percent-decoded the parameter.
+            crate::ArnParser::new(segment.to_string())
+                .parse()
+                .map(ArnPath)
+                .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()).into_response())
+        }
+    }
+}
+
+#[cfg(feature = "actix-web")]
+mod actix_extractor {
+    use super::ArnPath;
+    use actix_web::dev::Payload;
+    use actix_web::error::{ErrorBadRequest, ErrorInternalServerError};
+    use actix_web::{Error, FromRequest, HttpRequest};
+    use std::future::{ready, Ready};
+
+    impl FromRequest for ArnPath {
+        type Error = Error;
+        type Future = Ready<Result<Self, Error>>;
+
+        /// Decodes the route's only path parameter, answering `400` if it is not a valid Arn.
+        fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+            let mut params = req.match_info().iter();
+            let result = match (params.next(), params.next()) {
+                (Some((_, segment)), None) => segment.parse().map_err(ErrorBadRequest),
+                _ => Err(ErrorInternalServerError(
+                    "ArnPath requires a route with exactly one path parameter",
+                )),
+            };
+            ready(result)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arn(s: &str) -> Arn<'static> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_path_codec_round_trip() {
+        let arn = arn("arn:akton:hr:acme?:root/team a/100%25/ü");
+        let encoded = ArnPath::encode(&arn);
+        assert_eq!(
+            encoded,
+            "arn:akton:hr:acme%3F:root%2Fteam%20a%2F100%2525%2F%C3%BC"
+        );
+        assert!(!encoded.contains('/'));
+        assert_eq!(ArnPath::decode(&encoded).unwrap(), arn);
+        let path: ArnPath = encoded.parse().unwrap();
+        assert_eq!(path.to_string(), encoded);
+        assert_eq!(path.depth(), 3);
+    }
+
+    #[test]
+    fn test_path_decode_errors() {
+        assert!(ArnPath::decode("arn:a:b:c:r%2").is_err());
+        assert!(ArnPath::decode("arn:a:b:c:r%ZZ").is_err());
+        assert!(ArnPath::decode("not-an-arn").is_err());
+        assert!("arn:a:b".parse::<Arn>().is_err());
+    }
+
+    #[cfg(feature = "axum")]
+    #[tokio::test]
+    async fn test_axum_extractor() {
+        use axum::body::Body;
+        use axum::http::Request;
+        use axum::routing::get;
+        use axum::Router;
+        use tower::ServiceExt;
+
+        let app = Router::new().route(
+            "/resources/:arn",
+            get(|ArnPath(arn): ArnPath| async move { arn.parts.to_string() }),
+        );
+        let uri = format!("/resources/{}", ArnPath::from(arn("arn:a:b:c:r/x%2Fy/z")));
+        let response = app
+            .clone()
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        let body = axum::body::to_bytes(response.into_body(), 1024)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"x%2Fy/z");
+
+        let response = app
+            .oneshot(Request::get("/resources/nope").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 400);
+    }
+
+    #[cfg(feature = "actix-web")]
+    #[tokio::test]
+    async fn test_actix_extractor() {
+        use actix_web::test::TestRequest;
+        use actix_web::FromRequest;
+
+        let segment = ArnPath::encode(&arn("arn:a:b:c:r/x%2Fy/z"));
+        let req = TestRequest::default()
+            .param("arn", segment)
+            .to_http_request();
+        let path = ArnPath::extract(&req).await.unwrap();
+        assert_eq!(path.parts.to_string(), "x%2Fy/z");
+
+        let req = TestRequest::default()
+            .param("arn", "nope")
+            .to_http_request();
+        assert!(ArnPath::extract(&req).await.is_err());
+    }
+}
@@ -0,0 +1,1068 @@
+use crate::case_fold::{eq_ignore_case, CaseFold};
+use crate::errors::ArnError;
+use crate::format::{ArnFormat, CANONICAL_SCHEME, KNOWN_SCHEMES};
+use crate::model::{
+    category_lineage, split_attr, split_org_tenant, Account, Arn, Category, Domain, Part, Parts,
+    Root, CATEGORY_SEPARATOR, ORG_TENANT_SEPARATOR,
+};
+use crate::part_glob::PartGlob;
+use crate::pattern_matcher::{ArnMatcher, MatchOptions};
+use crate::ComponentKind;
+use std::fmt;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+/// A pattern matching a set of Arns.
+///
+/// The string form mirrors an Arn: `arn:<domain>:<category>:<account>:<root>[/<parts>]`. Any of
+/// the four leading components may be `*` to match every value, and the account may be
+/// `<org>.*` or `*.<tenant>` to match structured accounts by one half (see [`Account::org`]),
+/// and the category may be `<category>.**` to match it and every category within it (see
+/// [`Category::is_within`]). Within the parts, `*` matches
+/// exactly one part, `**` matches any number of parts, including none, and `key=*` matches
+/// one `key=value` attribute part with any value (see [`Part::as_attr`]).
+///
+/// A part may also be a glob matching one part: `[a-c]` matches one character in a class,
+/// `[!a-c]` or `[^a-c]` one character outside it, and `{tmp,cache}` any one of the
+/// alternatives. A leading `!` negates the whole part, so `!{tmp,cache}` matches any single
+/// part except those two. A `\` makes the next character literal, such as `\!` for a part
+/// starting with `!`. The full grammar of a part:
+///
+/// ```text
+/// part      = "*" | "**" | key "=*" | ["!"] glob
+/// glob      = atom+
+/// atom      = literal | class | alt
+/// class     = "[" ["!" | "^"] (char | char "-" char)+ "]"
+/// alt       = "{" text ("," text)* "}"
+/// literal   = char+
+/// char      = escaped | any character but "[", "]", "{", "}", ",", "\", ":", "/", "?", "@"
+/// text      = char*
+/// escaped   = "\" any character but ":", "/", "?", "@"
+/// ```
+///
+/// ```
+/// use akton_arn::{ArnParser, ArnPattern};
+///
+/// let pattern: ArnPattern = "arn:*:hr:acme:root/teams/**".parse().unwrap();
+/// let arn = ArnParser::new("arn:akton:hr:acme:root/teams/a/b").parse().unwrap();
+/// assert!(pattern.matches(&arn));
+///
+/// let routed: ArnPattern = "arn:akton:hr:acme:root/!{tmp,cache}/v[0-9]".parse().unwrap();
+/// let arn = ArnParser::new("arn:akton:hr:acme:root/logs/v2").parse().unwrap();
+/// assert!(routed.matches(&arn));
+/// let arn = ArnParser::new("arn:akton:hr:acme:root/tmp/v2").parse().unwrap();
+/// assert!(!routed.matches(&arn));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ArnPattern {
+    domain: Segment,
+    category: Segment,
+    account: Segment,
+    root: Segment,
+    parts: Vec<PartPattern>,
+    /// Components compared without regard to case.
+    case_fold: CaseFold,
+}
+
+/// One segment of an [`ArnPattern`], as listed by [`ArnPattern::tokens`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PatternToken<'p> {
+    /// A literal, and whether it is compared without regard to case.
+    Exact(&'p str, bool),
+    /// Any single segment.
+    One,
+    /// Any number of parts, including none.
+    Many,
+    /// An attribute part with the given key, and whether the key ignores case.
+    Attr(&'p str, bool),
+    /// A part matching the glob, and whether it ignores case.
+    Glob(&'p PartGlob, bool),
+    /// A structured account matching the org and tenant, and whether they ignore case.
+    OrgTenant(&'p OrgTenant, bool),
+    /// A category and every category within it, and whether it ignores case.
+    Subtree(&'p str, bool),
+}
+
+/// Matches one of the leading, single-valued components.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Segment {
+    Any,
+    Exact(String),
+    /// An account pattern such as `acme.*`.
+    OrgTenant(OrgTenant),
+    /// A category pattern such as `hr.**`, holding `hr`.
+    Subtree(String),
+}
+
+/// Matches a structured account by its org and tenant, where `None` matches any value.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct OrgTenant {
+    pub(crate) org: Option<String>,
+    pub(crate) tenant: Option<String>,
+}
+
+impl OrgTenant {
+    /// Returns `true` if `account` is structured and both halves match, ignoring case if
+    /// `fold` is set.
+    pub(crate) fn matches(&self, account: &str, fold: bool) -> bool {
+        let side = |expected: &Option<String>, value: &str| {
+            expected.as_deref().is_none_or(|expected| {
+                if fold {
+                    eq_ignore_case(expected, value)
+                } else {
+                    expected == value
+                }
+            })
+        };
+        split_org_tenant(account)
+            .is_some_and(|(org, tenant)| side(&self.org, org) && side(&self.tenant, tenant))
+    }
+
+    fn to_regex(&self, fold: &CaseFold, syntax: &RegexSyntax) -> String {
+        let side = |value: &Option<String>| match value {
+            Some(value) => folded_literal(ComponentKind::Account, fold, value),
+            None => format!("{}+", syntax.not_org_tenant),
+        };
+        format!(r"{}\.{}", side(&self.org), side(&self.tenant))
+    }
+}
+
+impl fmt::Display for OrgTenant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let side = |value: &Option<String>| value.clone().unwrap_or_else(|| "*".to_string());
+        write!(
+            f,
+            "{}{ORG_TENANT_SEPARATOR}{}",
+            side(&self.org),
+            side(&self.tenant)
+        )
+    }
+}
+
+/// Matches parts below the root.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum PartPattern {
+    Exact(String),
+    /// `*`: exactly one part.
+    One,
+    /// `**`: zero or more parts.
+    Many,
+    /// `key=*`: one attribute part with the given key.
+    Attr(String),
+    /// One part matching a negation, class or alternation.
+    Glob(PartGlob),
+}
+
+impl ArnPattern {
+    /// Returns `true` if `arn` falls within the pattern.
+    ///
+    /// Part globs are matched iteratively, so very deep Arns cannot exhaust the stack.
+    pub fn matches(&self, arn: &Arn) -> bool {
+        self.matches_components(
+            [
+                arn.domain.as_str(),
+                arn.category.as_str(),
+                arn.account.as_str(),
+                arn.root.as_str(),
+            ],
+            &arn.parts.0,
+        )
+    }
+
+    /// Matches the domain, category, account, root and parts of an Arn held in another form,
+    /// such as an archived one.
+    pub(crate) fn matches_components(
+        &self,
+        [domain, category, account, root]: [&str; 4],
+        parts: &[impl AsRef<str>],
+    ) -> bool {
+        let fold = &self.case_fold;
+        self.domain.matches(ComponentKind::Domain, domain, fold)
+            && self
+                .category
+                .matches(ComponentKind::Category, category, fold)
+            && self.account.matches(ComponentKind::Account, account, fold)
+            && self.root.matches(ComponentKind::Root, root, fold)
+            && glob_match(&self.parts, parts, fold)
+    }
+
+    /// Returns an anchored regular expression matching the canonical string of every Arn the
+    /// pattern matches, for filtering in systems that only understand regexes.
+    ///
+    /// The expression accepts any of the [`KNOWN_SCHEMES`](crate::KNOWN_SCHEMES) and any
+    /// version and extensions after the parts, as [`ArnPattern::matches`] does, and uses only
+    /// syntax common to most engines; folded components are wrapped in `(?i:...)`. Negated
+    /// parts such as `!tmp` are the exception: they need a negative lookahead, which engines
+    /// like RE2 and the `regex` crate lack.
+    ///
+    /// ```
+    /// use akton_arn::ArnPattern;
+    ///
+    /// let pattern: ArnPattern = "arn:*:hr:acme:root/teams/**".parse().unwrap();
+    /// assert_eq!(
+    ///     pattern.to_regex(),
+    ///     r"^(?:arn|ern|eid):[^:]+:hr:acme:root/teams(?:/[^/?@]+)*(?:@v[0-9]+)?(?:\?.*)?$"
+    /// );
+    /// ```
+    pub fn to_regex(&self) -> String {
+        self.to_regex_with(&ArnFormat::DEFAULT)
+    }
+
+    /// Returns [`ArnPattern::to_regex`] for Arns written in `format`, with its scheme and
+    /// separators.
+    ///
+    /// ```
+    /// use akton_arn::{ArnFormat, ArnPattern};
+    ///
+    /// let legacy = ArnFormat::new().with_scheme("res").unwrap().with_part_separator('.').unwrap();
+    /// let pattern: ArnPattern = "arn:akton:hr:*:root/*".parse().unwrap();
+    /// assert_eq!(
+    ///     pattern.to_regex_with(&legacy),
+    ///     r"^res:akton:hr:[^:]*:root\.[^.?@]+(?:@v[0-9]+)?(?:\?.*)?$"
+    /// );
+    /// ```
+    pub fn to_regex_with(&self, format: &ArnFormat) -> String {
+        let fold = &self.case_fold;
+        let syntax = RegexSyntax::new(format);
+        let mut regex = String::from("^");
+        if format.scheme() == CANONICAL_SCHEME {
+            regex.push_str(&format!("(?:{})", KNOWN_SCHEMES.join("|")));
+        } else {
+            regex.push_str(&escape_regex(format.scheme()));
+        }
+        for (kind, segment, any) in [
+            (
+                ComponentKind::Domain,
+                &self.domain,
+                format!("{}+", syntax.not_component),
+            ),
+            (
+                ComponentKind::Category,
+                &self.category,
+                format!("{}*", syntax.not_component),
+            ),
+            (
+                ComponentKind::Account,
+                &self.account,
+                format!("{}*", syntax.not_component),
+            ),
+            (
+                ComponentKind::Root,
+                &self.root,
+                format!("{}*", syntax.not_part),
+            ),
+        ] {
+            regex.push_str(&syntax.component);
+            regex.push_str(&segment.to_regex(kind, fold, &any, &syntax));
+        }
+        let (sep, not_part) = (&syntax.part, &syntax.not_part);
+        for part in &self.parts {
+            let part = match part {
+                PartPattern::Exact(value) => folded_literal(ComponentKind::Part, fold, value),
+                PartPattern::One => format!("{not_part}+"),
+                PartPattern::Many => {
+                    regex.push_str(&format!("(?:{sep}{not_part}+)*"));
+                    continue;
+                }
+                PartPattern::Attr(key) => format!(
+                    "{}={not_part}*",
+                    folded_literal(ComponentKind::Part, fold, key)
+                ),
+                PartPattern::Glob(glob) => {
+                    glob.to_regex(fold.folds(ComponentKind::Part), format.part_separator())
+                }
+            };
+            regex.push_str(sep);
+            regex.push_str(&part);
+        }
+        regex.push_str(r"(?:@v[0-9]+)?(?:\?.*)?$");
+        regex
+    }
+
+    /// Compiles [`ArnPattern::to_regex`].
+    ///
+    /// # Panics
+    ///
+    /// Panics for patterns with negated parts, whose lookahead the `regex` crate does not
+    /// support; use [`ArnPattern::try_to_compiled_regex`] for patterns that may have them.
+    #[cfg(feature = "regex")]
+    pub fn to_compiled_regex(&self) -> regex::Regex {
+        self.try_to_compiled_regex()
+            .expect("patterns without negated parts compile")
+    }
+
+    /// Compiles [`ArnPattern::to_regex`], failing for patterns with negated parts.
+    #[cfg(feature = "regex")]
+    pub fn try_to_compiled_regex(&self) -> Result<regex::Regex, regex::Error> {
+        regex::Regex::new(&self.to_regex())
+    }
+
+    /// Lists what the pattern matches at each segment: the four leading components, then
+    /// the parts.
+    pub(crate) fn tokens(&self) -> Vec<PatternToken<'_>> {
+        let fold = &self.case_fold;
+        let leading = [
+            (ComponentKind::Domain, &self.domain),
+            (ComponentKind::Category, &self.category),
+            (ComponentKind::Account, &self.account),
+            (ComponentKind::Root, &self.root),
+        ]
+        .map(|(kind, segment)| match segment {
+            Segment::Any => PatternToken::One,
+            Segment::Exact(value) => PatternToken::Exact(value, fold.folds(kind)),
+            Segment::OrgTenant(org_tenant) => PatternToken::OrgTenant(org_tenant, fold.folds(kind)),
+            Segment::Subtree(category) => PatternToken::Subtree(category, fold.folds(kind)),
+        });
+        let folded = fold.folds(ComponentKind::Part);
+        let parts = self.parts.iter().map(|part| match part {
+            PartPattern::Exact(value) => PatternToken::Exact(value, folded),
+            PartPattern::One => PatternToken::One,
+            PartPattern::Many => PatternToken::Many,
+            PartPattern::Attr(key) => PatternToken::Attr(key, folded),
+            PartPattern::Glob(glob) => PatternToken::Glob(glob, folded),
+        });
+        leading.into_iter().chain(parts).collect()
+    }
+
+    /// Returns `true` if the pattern names a root rather than matching every one.
+    pub(crate) fn has_literal_root(&self) -> bool {
+        matches!(self.root, Segment::Exact(_))
+    }
+
+    /// Compiles the pattern into an [`ArnMatcher`] with default options.
+    pub fn compile_matcher(&self) -> ArnMatcher {
+        ArnMatcher::new(self.clone(), MatchOptions::default())
+            .expect("default options accept every pattern")
+    }
+
+    /// Compares the components `fold` selects without regard to case.
+    ///
+    /// Part folding applies to exact parts, part globs and the keys of `key=*` globs. The
+    /// option is not part of the string form.
+    pub fn with_case_fold(mut self, fold: CaseFold) -> Self {
+        self.case_fold = fold;
+        self
+    }
+}
+
+impl Segment {
+    fn matches(&self, kind: ComponentKind, value: &str, fold: &CaseFold) -> bool {
+        match self {
+            Segment::Any => true,
+            Segment::Exact(expected) => fold.same(kind, expected, value),
+            Segment::OrgTenant(org_tenant) => org_tenant.matches(value, fold.folds(kind)),
+            Segment::Subtree(category) => {
+                category_lineage(value).any(|value| fold.same(kind, category, value))
+            }
+        }
+    }
+
+    fn parse(value: &str) -> Segment {
+        match value {
+            "*" => Segment::Any,
+            exact => Segment::Exact(exact.to_string()),
+        }
+    }
+
+    /// Parses an account, reading `<org>.*`, `*.<tenant>` and `*.*` as structured.
+    fn parse_account(value: &str) -> Segment {
+        match split_org_tenant(value) {
+            Some((org, tenant)) if org == "*" || tenant == "*" => {
+                let side = |side: &str| (side != "*").then(|| side.to_string());
+                Segment::OrgTenant(OrgTenant {
+                    org: side(org),
+                    tenant: side(tenant),
+                })
+            }
+            _ => Segment::parse(value),
+        }
+    }
+
+    /// Parses a category, reading `<category>.**` as that category and everything within it.
+    fn parse_category(value: &str) -> Segment {
+        match value.strip_suffix(".**") {
+            Some(category) if !category.is_empty() => Segment::Subtree(category.to_string()),
+            _ => Segment::parse(value),
+        }
+    }
+}
+
+impl Segment {
+    fn to_regex(
+        &self,
+        kind: ComponentKind,
+        fold: &CaseFold,
+        any: &str,
+        syntax: &RegexSyntax,
+    ) -> String {
+        match self {
+            Segment::Any => any.to_string(),
+            Segment::Exact(value) => folded_literal(kind, fold, value),
+            Segment::OrgTenant(org_tenant) => org_tenant.to_regex(fold, syntax),
+            Segment::Subtree(category) => format!(
+                r"{}(?:\.{}*)?",
+                folded_literal(kind, fold, category),
+                syntax.not_component
+            ),
+        }
+    }
+}
+
+/// The separators of an [`ArnFormat`] and the character classes excluding them.
+struct RegexSyntax {
+    component: String,
+    part: String,
+    /// Any character within a component.
+    not_component: String,
+    /// Any character within the root or a part.
+    not_part: String,
+    /// Any character within one half of a structured account.
+    not_org_tenant: String,
+}
+
+impl RegexSyntax {
+    fn new(format: &ArnFormat) -> Self {
+        let (component, part) = (format.component_separator(), format.part_separator());
+        RegexSyntax {
+            component: escape_regex(&component.to_string()),
+            part: escape_regex(&part.to_string()),
+            not_component: excluding(&[component]),
+            not_part: excluding(&[part, '?', '@']),
+            not_org_tenant: excluding(&[component, ORG_TENANT_SEPARATOR]),
+        }
+    }
+}
+
+/// Escapes `value` for a regex, matching it without regard to case if `fold` folds `kind`.
+fn folded_literal(kind: ComponentKind, fold: &CaseFold, value: &str) -> String {
+    let escaped = escape_regex(value);
+    if fold.folds(kind) {
+        format!("(?i:{escaped})")
+    } else {
+        escaped
+    }
+}
+
+/// Escapes the characters of `value` that are special in a regex.
+///
+/// Only the metacharacters every engine knows are escaped: JavaScript in `u` mode rejects
+/// escapes of other punctuation.
+pub(crate) fn escape_regex(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if r"\.+*?()|[]{}^$".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Escapes `c` for use inside a character class.
+pub(crate) fn escape_class_char(c: char) -> String {
+    if r"\[]^-".contains(c) {
+        format!("\\{c}")
+    } else {
+        c.to_string()
+    }
+}
+
+/// Returns a character class matching any character but `chars`.
+fn excluding(chars: &[char]) -> String {
+    let chars: String = chars.iter().map(|&c| escape_class_char(c)).collect();
+    format!("[^{chars}]")
+}
+
+/// Wildcard matching over parts, backtracking to the most recent `**` on a mismatch.
+fn glob_match(pattern: &[PartPattern], parts: &[impl AsRef<str>], fold: &CaseFold) -> bool {
+    let (mut p, mut a) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while a < parts.len() {
+        match pattern.get(p) {
+            Some(PartPattern::Many) => {
+                backtrack = Some((p, a));
+                p += 1;
+            }
+            Some(PartPattern::One) => {
+                p += 1;
+                a += 1;
+            }
+            Some(PartPattern::Exact(expected))
+                if fold.same(ComponentKind::Part, expected, parts[a].as_ref()) =>
+            {
+                p += 1;
+                a += 1;
+            }
+            Some(PartPattern::Attr(key))
+                if split_attr(parts[a].as_ref())
+                    .is_some_and(|(k, _)| fold.same(ComponentKind::Part, key, k)) =>
+            {
+                p += 1;
+                a += 1;
+            }
+            Some(PartPattern::Glob(glob))
+                if glob.matches(parts[a].as_ref(), fold.folds(ComponentKind::Part)) =>
+            {
+                p += 1;
+                a += 1;
+            }
+            _ => match backtrack {
+                Some((star, consumed)) => {
+                    backtrack = Some((star, consumed + 1));
+                    p = star + 1;
+                    a = consumed + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|part| *part == PartPattern::Many)
+}
+
+/// Parses a `/`-separated parts glob.
+fn parse_parts_glob(glob: &str) -> Result<Vec<PartPattern>, ArnError> {
+    glob.split('/')
+        .map(|part| match part {
+            "*" => Ok(PartPattern::One),
+            "**" => Ok(PartPattern::Many),
+            attr if attr.len() > 2
+                && attr.ends_with("=*")
+                && !attr[..attr.len() - 2].contains('=') =>
+            {
+                Ok(PartPattern::Attr(attr[..attr.len() - 2].to_string()))
+            }
+            glob if PartGlob::is_glob(glob) => PartGlob::parse(glob).map(PartPattern::Glob),
+            exact => Part::new(exact).map(|part| PartPattern::Exact(part.as_str().to_string())),
+        })
+        .collect()
+}
+
+impl FromStr for ArnPattern {
+    type Err = ArnError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let components: Vec<&str> = s.splitn(5, ':').collect();
+        if components.len() != 5 || components[0] != "arn" {
+            return Err(ArnError::InvalidFormat);
+        }
+        let (root, parts) = match components[4].split_once('/') {
+            Some((root, glob)) => (root, parse_parts_glob(glob)?),
+            None => (components[4], Vec::new()),
+        };
+        if components[1] != "*" {
+            Domain::new(components[1])?;
+        }
+        Ok(ArnPattern {
+            domain: Segment::parse(components[1]),
+            category: Segment::parse_category(components[2]),
+            account: Segment::parse_account(components[3]),
+            root: Segment::parse(root),
+            parts,
+            case_fold: CaseFold::none(),
+        })
+    }
+}
+
+impl fmt::Display for Segment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Segment::Any => write!(f, "*"),
+            Segment::Exact(value) => write!(f, "{value}"),
+            Segment::OrgTenant(org_tenant) => write!(f, "{org_tenant}"),
+            Segment::Subtree(category) => write!(f, "{category}{CATEGORY_SEPARATOR}**"),
+        }
+    }
+}
+
+impl fmt::Display for ArnPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "arn:{}:{}:{}:{}",
+            self.domain, self.category, self.account, self.root
+        )?;
+        for part in &self.parts {
+            match part {
+                PartPattern::Exact(value) => write!(f, "/{value}")?,
+                PartPattern::One => write!(f, "/*")?,
+                PartPattern::Many => write!(f, "/**")?,
+                PartPattern::Attr(key) => write!(f, "/{key}=*")?,
+                PartPattern::Glob(glob) => write!(f, "/{glob}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A builder for constructing [`ArnPattern`]s with the same component ordering as [`ArnBuilder`](crate::ArnBuilder).
+///
+/// Each state only offers the methods for the next component, so a pattern cannot be built
+/// with components out of order or missing.
+///
+/// ```
+/// use akton_arn::ArnPatternBuilder;
+///
+/// let pattern = ArnPatternBuilder::new()
+///     .domain_any()
+///     .category("hr")
+///     .account("acme")
+///     .root_any()
+///     .parts_glob("teams/**")
+///     .unwrap()
+///     .build();
+/// assert_eq!(pattern.to_string(), "arn:*:hr:acme:*/teams/**");
+/// ```
+///
+/// Like `globset`'s `GlobBuilder`, the builder also takes matching options, at any step, and
+/// can [`compile`](ArnPatternBuilder::compile) the pattern into an [`ArnMatcher`] applying
+/// them, so callers needing different strictness share one matcher:
+///
+/// ```
+/// use akton_arn::{ArnParser, ArnPatternBuilder};
+///
+/// let matcher = ArnPatternBuilder::parse("arn:akton:hr:*:root/teams/**")
+///     .unwrap()
+///     .case_insensitive(true)
+///     .require_literal_root(true)
+///     .max_depth(2)
+///     .compile()
+///     .unwrap();
+/// let arn = ArnParser::new("arn:akton:HR:acme:root/Teams/eng").parse().unwrap();
+/// assert!(matcher.is_match(&arn));
+/// let arn = ArnParser::new("arn:akton:hr:acme:root/teams/eng/leads").parse().unwrap();
+/// assert!(!matcher.is_match(&arn));
+///
+/// let loose = ArnPatternBuilder::parse("arn:akton:hr:*:*/**").unwrap();
+/// assert!(loose.require_literal_root(true).compile().is_err());
+/// ```
+pub struct ArnPatternBuilder<State> {
+    pattern: ArnPattern,
+    options: MatchOptions,
+    _marker: PhantomData<State>,
+}
+
+impl ArnPatternBuilder<()> {
+    /// Creates a new pattern builder, starting with the `Domain` component.
+    pub fn new() -> ArnPatternBuilder<Domain<'static>> {
+        ArnPatternBuilder {
+            pattern: ArnPattern {
+                domain: Segment::Any,
+                category: Segment::Any,
+                account: Segment::Any,
+                root: Segment::Any,
+                parts: Vec::new(),
+                case_fold: CaseFold::none(),
+            },
+            options: MatchOptions::default(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Starts from a pattern in its string form, leaving only the options to set.
+    pub fn parse(pattern: &str) -> Result<ArnPatternBuilder<Parts<'static>>, ArnError> {
+        Ok(ArnPatternBuilder {
+            pattern: pattern.parse()?,
+            options: MatchOptions::default(),
+            _marker: PhantomData,
+        })
+    }
+}
+
+impl<State> ArnPatternBuilder<State> {
+    fn transition<Next>(self, update: impl FnOnce(&mut ArnPattern)) -> ArnPatternBuilder<Next> {
+        let mut pattern = self.pattern;
+        update(&mut pattern);
+        ArnPatternBuilder {
+            pattern,
+            options: self.options,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Compares every component without regard to case when `yes` is set, as
+    /// [`ArnPattern::with_case_fold`] does with [`CaseFold::all`].
+    ///
+    /// Passing `false` leaves the fold as it is, so it never undoes a finer fold set with
+    /// [`with_case_fold`](ArnPatternBuilder::with_case_fold); the builder starts with
+    /// [`CaseFold::none`].
+    pub fn case_insensitive(mut self, yes: bool) -> Self {
+        if yes {
+            self.pattern.case_fold = CaseFold::all();
+        }
+        self
+    }
+
+    /// Compares the components `fold` selects without regard to case, replacing any fold set
+    /// before, as [`ArnPattern::with_case_fold`] does.
+    pub fn with_case_fold(mut self, fold: CaseFold) -> Self {
+        self.pattern.case_fold = fold;
+        self
+    }
+
+    /// Makes [`compile`](ArnPatternBuilder::compile) reject a pattern whose root is `*`, so
+    /// a rule cannot reach across every tree of an account by accident.
+    pub fn require_literal_root(mut self, yes: bool) -> Self {
+        self.options.require_literal_root = yes;
+        self
+    }
+
+    /// Makes the compiled matcher reject Arns with more than `depth` parts, however many a
+    /// `**` would allow.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.options.max_depth = Some(depth);
+        self
+    }
+
+    /// Compiles the pattern with the options set, failing with
+    /// [`ArnError::ValidationFailure`] if it breaks one of them.
+    fn compile_finished(self) -> Result<ArnMatcher, ArnError> {
+        ArnMatcher::new(self.pattern, self.options)
+    }
+}
+
+impl ArnPatternBuilder<Domain<'static>> {
+    /// Matches only the given domain.
+    pub fn domain(self, domain: &str) -> Result<ArnPatternBuilder<Category<'static>>, ArnError> {
+        let domain = Domain::new(domain)?;
+        Ok(self.transition(|p| p.domain = Segment::Exact(domain.as_str().to_string())))
+    }
+
+    /// Matches every domain.
+    pub fn domain_any(self) -> ArnPatternBuilder<Category<'static>> {
+        self.transition(|p| p.domain = Segment::Any)
+    }
+}
+
+impl ArnPatternBuilder<Category<'static>> {
+    /// Matches only the given category.
+    pub fn category(self, category: &str) -> ArnPatternBuilder<Account<'static>> {
+        self.transition(|p| p.category = Segment::Exact(category.to_string()))
+    }
+
+    /// Matches every category.
+    pub fn category_any(self) -> ArnPatternBuilder<Account<'static>> {
+        self.transition(|p| p.category = Segment::Any)
+    }
+
+    /// Matches the given category and every category within it, as `<category>.**` does.
+    pub fn category_within(self, category: &str) -> ArnPatternBuilder<Account<'static>> {
+        self.transition(|p| p.category = Segment::Subtree(category.to_string()))
+    }
+}
+
+impl ArnPatternBuilder<Account<'static>> {
+    /// Matches only the given account.
+    pub fn account(self, account: &str) -> ArnPatternBuilder<Root<'static>> {
+        self.transition(|p| p.account = Segment::Exact(account.to_string()))
+    }
+
+    /// Matches every account.
+    pub fn account_any(self) -> ArnPatternBuilder<Root<'static>> {
+        self.transition(|p| p.account = Segment::Any)
+    }
+
+    /// Matches every structured account of the given org, as `<org>.*` does.
+    pub fn account_org(self, org: &str) -> ArnPatternBuilder<Root<'static>> {
+        self.transition(|p| {
+            p.account = Segment::OrgTenant(OrgTenant {
+                org: Some(org.to_string()),
+                tenant: None,
+            })
+        })
+    }
+
+    /// Matches every structured account of the given tenant, as `*.<tenant>` does.
+    pub fn account_tenant(self, tenant: &str) -> ArnPatternBuilder<Root<'static>> {
+        self.transition(|p| {
+            p.account = Segment::OrgTenant(OrgTenant {
+                org: None,
+                tenant: Some(tenant.to_string()),
+            })
+        })
+    }
+}
+
+impl ArnPatternBuilder<Root<'static>> {
+    /// Matches only the given root, compared against the full root string including any id suffix.
+    pub fn root(self, root: &str) -> ArnPatternBuilder<Part<'static>> {
+        self.transition(|p| p.root = Segment::Exact(root.to_string()))
+    }
+
+    /// Matches every root.
+    pub fn root_any(self) -> ArnPatternBuilder<Part<'static>> {
+        self.transition(|p| p.root = Segment::Any)
+    }
+}
+
+impl ArnPatternBuilder<Part<'static>> {
+    /// Matches parts against a `/`-separated glob where `*` is one part, `**` any number of parts
+    /// and `key=*` one attribute part with that key.
+    pub fn parts_glob(self, glob: &str) -> Result<ArnPatternBuilder<Parts<'static>>, ArnError> {
+        let parts = parse_parts_glob(glob)?;
+        Ok(self.transition(|p| p.parts = parts))
+    }
+
+    /// Finalizes a pattern matching only Arns without parts.
+    pub fn build(self) -> ArnPattern {
+        self.pattern
+    }
+
+    /// Compiles a matcher for Arns without parts, applying the options set.
+    pub fn compile(self) -> Result<ArnMatcher, ArnError> {
+        self.compile_finished()
+    }
+}
+
+impl ArnPatternBuilder<Parts<'static>> {
+    /// Finalizes the pattern.
+    pub fn build(self) -> ArnPattern {
+        self.pattern
+    }
+
+    /// Compiles a matcher applying the options set.
+    pub fn compile(self) -> Result<ArnMatcher, ArnError> {
+        self.compile_finished()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArnParser;
+
+    fn arn(s: &str) -> Arn<'static> {
+        ArnParser::new(s.to_string()).parse().unwrap()
+    }
+
+    fn pattern(s: &str) -> ArnPattern {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_pattern_wildcards() {
+        let p = pattern("arn:*:hr:acme:root/teams/*/members/**");
+        assert!(p.matches(&arn("arn:akton:hr:acme:root/teams/a/members")));
+        assert!(p.matches(&arn("arn:other:hr:acme:root/teams/b/members/x/y")));
+        assert!(!p.matches(&arn("arn:akton:hr:acme:root/teams/members")));
+        assert!(!p.matches(&arn("arn:akton:it:acme:root/teams/a/members")));
+        assert!(pattern("arn:*:*:*:*/**").matches(&arn("arn:a:b:c:d")));
+        assert!(!pattern("arn:a:b:c:d").matches(&arn("arn:a:b:c:d/e")));
+    }
+
+    #[test]
+    fn test_double_star_backtracks() {
+        let p = pattern("arn:a:b:c:d/**/x/**/y");
+        assert!(p.matches(&arn("arn:a:b:c:d/x/y")));
+        assert!(p.matches(&arn("arn:a:b:c:d/1/x/2/x/3/y")));
+        assert!(!p.matches(&arn("arn:a:b:c:d/1/y/x")));
+    }
+
+    #[test]
+    fn test_attribute_presence() {
+        let p = pattern("arn:a:b:c:d/**/region=*/**");
+        assert!(p.matches(&arn("arn:a:b:c:d/region=us-east-1")));
+        assert!(p.matches(&arn("arn:a:b:c:d/x/region=eu/y")));
+        assert!(!p.matches(&arn("arn:a:b:c:d/x/zone=eu/y")));
+        assert!(!p.matches(&arn("arn:a:b:c:d/region")));
+        assert!(pattern("arn:a:b:c:d/region=eu").matches(&arn("arn:a:b:c:d/region=eu")));
+        assert!(!pattern("arn:a:b:c:d/region=eu").matches(&arn("arn:a:b:c:d/region=us")));
+    }
+
+    #[test]
+    fn test_pattern_round_trip_and_errors() {
+        for s in [
+            "arn:a:b:c:d/**/region=*",
+            "arn:*:hr:acme:root/**",
+            "arn:akton:*:*:*",
+            "arn:a:b:c:d/*/e",
+            r"arn:a:b:c:d/!{tmp,cache}/v[0-9]/\!x",
+            "arn:a:b:acme.*:d",
+            "arn:a:b:*.*:d",
+            "arn:a:hr.payroll.**:c:d",
+        ] {
+            assert_eq!(pattern(s).to_string(), s);
+        }
+        assert!("arn:a:b:c".parse::<ArnPattern>().is_err());
+        assert!("arn:a:b:c:d/x//y".parse::<ArnPattern>().is_err());
+        assert!("arn:a:b:c:d/[a-".parse::<ArnPattern>().is_err());
+        assert!("arn:a:b:c:d/!".parse::<ArnPattern>().is_err());
+    }
+
+    #[test]
+    fn test_part_globs() {
+        let p = pattern("arn:a:b:c:d/**/!{tmp,cache}/v[0-9]");
+        assert!(p.matches(&arn("arn:a:b:c:d/logs/v1")));
+        assert!(p.matches(&arn("arn:a:b:c:d/tmp/logs/v1")));
+        assert!(!p.matches(&arn("arn:a:b:c:d/tmp/v1")));
+        assert!(!p.matches(&arn("arn:a:b:c:d/logs/v10")));
+        assert!(!p.matches(&arn("arn:a:b:c:d/logs/V1")));
+        assert!(p
+            .with_case_fold(CaseFold::none().fold(ComponentKind::Part))
+            .matches(&arn("arn:a:b:c:d/logs/V1")));
+        assert!(pattern(r"arn:a:b:c:d/\!x").matches(&arn("arn:a:b:c:d/!x")));
+    }
+
+    #[test]
+    fn test_org_tenant_accounts() {
+        let org = pattern("arn:a:b:acme.*:*");
+        assert!(org.matches(&arn("arn:a:b:acme.eu:d")));
+        assert!(!org.matches(&arn("arn:a:b:acme:d")));
+        assert!(!org.matches(&arn("arn:a:b:acme.eu.west:d")));
+        assert!(!org.matches(&arn("arn:a:b:beta.eu:d")));
+        assert!(!org.matches(&arn("arn:a:b:ACME.eu:d")));
+        assert!(org
+            .with_case_fold(CaseFold::none().fold(ComponentKind::Account))
+            .matches(&arn("arn:a:b:ACME.eu:d")));
+        let tenant = pattern("arn:a:b:*.eu:*");
+        assert!(tenant.matches(&arn("arn:a:b:beta.eu:d")));
+        assert!(!tenant.matches(&arn("arn:a:b:beta.us:d")));
+        assert!(pattern("arn:a:b:*.*:*").matches(&arn("arn:a:b:beta.us:d")));
+        assert!(!pattern("arn:a:b:*.*:*").matches(&arn("arn:a:b:beta:d")));
+        assert_eq!(
+            ArnPatternBuilder::new()
+                .domain_any()
+                .category_any()
+                .account_tenant("eu")
+                .root_any()
+                .build(),
+            pattern("arn:*:*:*.eu:*")
+        );
+        assert_eq!(
+            pattern("arn:a:b:acme.*:d").to_regex(),
+            r"^(?:arn|ern|eid):a:b:acme\.[^:.]+:d(?:@v[0-9]+)?(?:\?.*)?$"
+        );
+    }
+
+    #[test]
+    fn test_category_subtrees() {
+        let hr = pattern("arn:a:hr.**:c:d");
+        assert!(hr.matches(&arn("arn:a:hr:c:d")));
+        assert!(hr.matches(&arn("arn:a:hr.payroll.uk:c:d")));
+        assert!(!hr.matches(&arn("arn:a:hrx:c:d")));
+        assert!(!hr.matches(&arn("arn:a:it.hr:c:d")));
+        assert!(!hr.matches(&arn("arn:a:HR.payroll:c:d")));
+        assert!(hr
+            .with_case_fold(CaseFold::none().fold(ComponentKind::Category))
+            .matches(&arn("arn:a:HR.payroll:c:d")));
+        assert_eq!(
+            ArnPatternBuilder::new()
+                .domain("a")
+                .unwrap()
+                .category_within("hr")
+                .account("c")
+                .root("d")
+                .build(),
+            pattern("arn:a:hr.**:c:d")
+        );
+        assert_eq!(pattern("arn:a:.**:c:d").to_string(), "arn:a:.**:c:d");
+        assert!(!pattern("arn:a:.**:c:d").matches(&arn("arn:a:hr:c:d")));
+    }
+
+    #[test]
+    fn test_builder_matches_string_form() {
+        let built = ArnPatternBuilder::new()
+            .domain("akton")
+            .unwrap()
+            .category_any()
+            .account("acme")
+            .root("root")
+            .parts_glob("a/**")
+            .unwrap()
+            .build();
+        assert_eq!(built, pattern("arn:akton:*:acme:root/a/**"));
+
+        let exact = ArnPatternBuilder::new()
+            .domain_any()
+            .category("hr")
+            .account_any()
+            .root_any()
+            .build();
+        assert!(exact.matches(&arn("arn:x:hr:y:z")));
+        assert!(!exact.matches(&arn("arn:x:hr:y:z/p")));
+        assert!(ArnPatternBuilder::new().domain("").is_err());
+    }
+
+    #[test]
+    fn test_to_regex() {
+        assert_eq!(
+            pattern("arn:akton:h.r:*:*/env=*/*").to_regex(),
+            r"^(?:arn|ern|eid):akton:h\.r:[^:]*:[^/?@]*/env=[^/?@]*/[^/?@]+(?:@v[0-9]+)?(?:\?.*)?$"
+        );
+        assert_eq!(
+            pattern("arn:akton:hr:a&b:root/x-y~z#1/[!-]").to_regex(),
+            r"^(?:arn|ern|eid):akton:hr:a&b:root/x-y~z#1/[^\-/?@](?:@v[0-9]+)?(?:\?.*)?$"
+        );
+        let legacy = ArnFormat::new()
+            .with_scheme("r.s")
+            .unwrap()
+            .with_component_separator('|')
+            .unwrap()
+            .with_part_separator('-')
+            .unwrap();
+        assert_eq!(
+            pattern("arn:akton:*:acme.*:*/*/!tmp").to_regex_with(&legacy),
+            r"^r\.s\|akton\|[^|]*\|acme\.[^|.]+\|[^\-?@]*-[^\-?@]+-(?!(?:tmp)(?:[\-?@]|$))[^\-?@]+(?:@v[0-9]+)?(?:\?.*)?$"
+        );
+        let folded = pattern("arn:akton:hr:acme:root/Teams")
+            .with_case_fold(CaseFold::none().fold(ComponentKind::Part));
+        assert!(folded.to_regex().contains("/(?i:Teams)(?:@v"));
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_compiled_regex_agrees_with_matches() {
+        let arns = [
+            "arn:akton:hr:acme:root",
+            "arn:akton:hr:acme:root/teams",
+            "arn:akton:hr:acme:root/teams/a/b@v3",
+            "arn:akton:hr:acme:root/env=prod/x?region=eu",
+            "arn:akton:hr:acme:root/env/x",
+            "arn:akton:ops:acme:root/teams",
+            "arn:akton:hr:acme:rootx/teams",
+            "ern:akton:hr:acme:root/teams",
+            "arn:akton::acme:root/teams",
+            "arn:akton:hr::root",
+            "arn:akton:hr:acme:",
+            "arn:akton:hr:acme:root/a&b-c~#",
+            "arn:akton:hr:ac.me:root/TEAMS/a",
+            "arn:akton:hr:acme.eu:root",
+            "arn:akton:hr:Acme.EU:root",
+            "arn:akton:hr:acme.eu.west:root",
+            "arn:akton:hr.payroll:acme:root",
+            "arn:akton:HR.payroll.uk:acme:root",
+            "arn:akton:hrx:acme:root",
+        ];
+        let patterns = [
+            "arn:*:*:*:*/**",
+            "arn:akton:hr:acme:root",
+            "arn:akton:hr:*:root/teams/**",
+            "arn:akton:hr:acme:root/*/*",
+            "arn:akton:hr:acme:root/**/b",
+            "arn:akton:hr:acme:root/env=*/*",
+            "arn:akton:hr:ac.me:root/teams/*",
+            "arn:akton:hr:acme:root/{teams,env=prod}/[a-c]",
+            "arn:akton:hr:acme:root/[^a-s]*/**",
+            "arn:akton:hr:acme.*:root",
+            "arn:akton:hr:*.eu:*",
+            "arn:akton:hr.**:*:root",
+            "arn:akton:*:*:*",
+            "arn:akton:hr:acme:root/a&b-c~#",
+            "arn:akton:hr:acme:root/[!-]/**",
+        ];
+        for p in patterns {
+            for fold in [CaseFold::none(), CaseFold::all()] {
+                let pattern = pattern(p).with_case_fold(fold);
+                let regex = pattern.to_compiled_regex();
+                for a in arns {
+                    assert_eq!(
+                        regex.is_match(a),
+                        pattern.matches(&arn(a)),
+                        "{p} against {a} with {fold:?}"
+                    );
+                }
+            }
+        }
+        assert!(pattern("arn:a:b:c:d/!tmp").try_to_compiled_regex().is_err());
+        assert!(pattern("arn:a:b:c:d/tmp").try_to_compiled_regex().is_ok());
+    }
+}
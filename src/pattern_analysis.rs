@@ -0,0 +1,436 @@
+use crate::case_fold::lowercase;
+use crate::model::{category_lineage, split_attr, split_org_tenant};
+use crate::pattern::{ArnPattern, PatternToken};
+
+/// A rule made redundant by another, as reported by [`ArnPattern::find_shadowed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Shadowed {
+    /// The index of the redundant rule.
+    pub rule: usize,
+    /// The index of a rule matching everything it does.
+    pub by: usize,
+}
+
+impl ArnPattern {
+    /// Returns `true` if every Arn `other` matches is also matched by this pattern.
+    ///
+    /// The check works on the patterns themselves, handling `**` and `key=*`, and takes
+    /// [`CaseFold`](crate::CaseFold) into account: a folded literal covers any casing of
+    /// itself, while an unfolded one never covers a folded one.
+    ///
+    /// ```
+    /// use akton_arn::ArnPattern;
+    ///
+    /// let broad: ArnPattern = "arn:akton:hr:*:root/**".parse().unwrap();
+    /// let narrow: ArnPattern = "arn:akton:hr:acme:root/teams/*".parse().unwrap();
+    /// assert!(broad.covers(&narrow));
+    /// assert!(!narrow.covers(&broad));
+    /// ```
+    pub fn covers(&self, other: &ArnPattern) -> bool {
+        let (a, b) = (self.tokens(), other.tokens());
+        let mut memo = vec![None; (a.len() + 1) * (b.len() + 1)];
+        covers_from(&a, &b, 0, 0, &mut memo)
+    }
+
+    /// Returns `true` if some Arn is matched by both patterns.
+    ///
+    /// ```
+    /// use akton_arn::ArnPattern;
+    ///
+    /// let teams: ArnPattern = "arn:akton:hr:acme:root/teams/**".parse().unwrap();
+    /// let leaves: ArnPattern = "arn:akton:hr:*:root/**/lead".parse().unwrap();
+    /// let users: ArnPattern = "arn:akton:hr:acme:root/users/*".parse().unwrap();
+    /// assert!(teams.overlaps(&leaves));
+    /// assert!(!teams.overlaps(&users));
+    /// ```
+    pub fn overlaps(&self, other: &ArnPattern) -> bool {
+        let (a, b) = (self.tokens(), other.tokens());
+        let mut memo = vec![None; (a.len() + 1) * (b.len() + 1)];
+        overlaps_from(&a, &b, 0, 0, &mut memo)
+    }
+
+    /// Finds the rules in `patterns` that another rule already covers.
+    ///
+    /// Of two rules covering each other, the later one is reported. A rule covered only by
+    /// several others together is not reported.
+    pub fn find_shadowed(patterns: &[ArnPattern]) -> Vec<Shadowed> {
+        (0..patterns.len())
+            .filter_map(|rule| {
+                let by = (0..patterns.len()).find(|&by| {
+                    by != rule
+                        && patterns[by].covers(&patterns[rule])
+                        && (by < rule || !patterns[rule].covers(&patterns[by]))
+                })?;
+                Some(Shadowed { rule, by })
+            })
+            .collect()
+    }
+
+    /// Returns the index pairs `(i, j)`, with `i < j`, of rules in `patterns` that match at
+    /// least one Arn in common.
+    pub fn find_overlapping(patterns: &[ArnPattern]) -> Vec<(usize, usize)> {
+        (0..patterns.len())
+            .flat_map(|i| (i + 1..patterns.len()).map(move |j| (i, j)))
+            .filter(|&(i, j)| patterns[i].overlaps(&patterns[j]))
+            .collect()
+    }
+
+    /// Returns `patterns` without the rules [`ArnPattern::find_shadowed`] reports, in their
+    /// original order. The result matches exactly the Arns the full set does.
+    pub fn minimize(patterns: &[ArnPattern]) -> Vec<ArnPattern> {
+        let shadowed: Vec<usize> = Self::find_shadowed(patterns)
+            .into_iter()
+            .map(|shadowed| shadowed.rule)
+            .collect();
+        patterns
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !shadowed.contains(index))
+            .map(|(_, pattern)| pattern.clone())
+            .collect()
+    }
+}
+
+/// Returns `true` if `a[i..]` matches every segment sequence `b[j..]` does.
+fn covers_from(
+    a: &[PatternToken],
+    b: &[PatternToken],
+    i: usize,
+    j: usize,
+    memo: &mut [Option<bool>],
+) -> bool {
+    let key = i * (b.len() + 1) + j;
+    if let Some(known) = memo[key] {
+        return known;
+    }
+    let result = match (a.get(i), b.get(j)) {
+        (Some(PatternToken::Many), Some(_)) => {
+            covers_from(a, b, i + 1, j, memo) || covers_from(a, b, i, j + 1, memo)
+        }
+        (Some(PatternToken::Many), None) => covers_from(a, b, i + 1, j, memo),
+        (_, Some(PatternToken::Many)) | (None, Some(_)) => false,
+        (Some(_), None) => false,
+        (None, None) => true,
+        (Some(a_token), Some(b_token)) => {
+            token_covers(a_token, b_token) && covers_from(a, b, i + 1, j + 1, memo)
+        }
+    };
+    memo[key] = Some(result);
+    result
+}
+
+/// Returns `true` if some segment sequence matches both `a[i..]` and `b[j..]`.
+fn overlaps_from(
+    a: &[PatternToken],
+    b: &[PatternToken],
+    i: usize,
+    j: usize,
+    memo: &mut [Option<bool>],
+) -> bool {
+    let key = i * (b.len() + 1) + j;
+    if let Some(known) = memo[key] {
+        return known;
+    }
+    let result = match (a.get(i), b.get(j)) {
+        (None, None) => true,
+        (Some(PatternToken::Many), _) | (_, Some(PatternToken::Many)) => {
+            let a_many = a.get(i) == Some(&PatternToken::Many);
+            let b_many = b.get(j) == Some(&PatternToken::Many);
+            (a_many && overlaps_from(a, b, i + 1, j, memo))
+                || (b_many && overlaps_from(a, b, i, j + 1, memo))
+                || (a_many && j < b.len() && !b_many && overlaps_from(a, b, i, j + 1, memo))
+                || (b_many && i < a.len() && !a_many && overlaps_from(a, b, i + 1, j, memo))
+        }
+        (Some(a_token), Some(b_token)) => {
+            tokens_meet(a_token, b_token) && overlaps_from(a, b, i + 1, j + 1, memo)
+        }
+        _ => false,
+    };
+    memo[key] = Some(result);
+    result
+}
+
+/// Returns `true` if every segment `b` matches is matched by `a`; neither is `**`.
+///
+/// A glob matching a short list of parts is treated as that list of literals. Otherwise
+/// only an identical glob or `*` covers it.
+fn token_covers(a: &PatternToken, b: &PatternToken) -> bool {
+    match (a, b) {
+        (PatternToken::One, _) => true,
+        (_, PatternToken::Glob(glob, fold)) => match glob.words() {
+            Some(words) => words
+                .iter()
+                .all(|word| token_covers(a, &PatternToken::Exact(word, *fold))),
+            None => a == b,
+        },
+        (PatternToken::Glob(glob, fold), PatternToken::Exact(value, value_fold)) => {
+            (*fold || !*value_fold) && glob.matches(value, *fold)
+        }
+        (PatternToken::Exact(a, a_fold), PatternToken::Exact(b, b_fold)) => {
+            literal_covers(a, *a_fold, b, *b_fold)
+        }
+        (PatternToken::Attr(key, fold), PatternToken::Exact(b, b_fold)) => {
+            split_attr(b).is_some_and(|(b_key, _)| literal_covers(key, *fold, b_key, *b_fold))
+        }
+        (PatternToken::Attr(a, a_fold), PatternToken::Attr(b, b_fold)) => {
+            literal_covers(a, *a_fold, b, *b_fold)
+        }
+        (PatternToken::Subtree(a, a_fold), PatternToken::Subtree(b, b_fold))
+        | (PatternToken::Subtree(a, a_fold), PatternToken::Exact(b, b_fold)) => {
+            category_lineage(b).any(|b| literal_covers(a, *a_fold, b, *b_fold))
+        }
+        (PatternToken::OrgTenant(a, a_fold), PatternToken::OrgTenant(b, b_fold)) => {
+            let side = |a: &Option<String>, b: &Option<String>| match (a, b) {
+                (None, _) => true,
+                (Some(_), None) => false,
+                (Some(a), Some(b)) => literal_covers(a, *a_fold, b, *b_fold),
+            };
+            side(&a.org, &b.org) && side(&a.tenant, &b.tenant)
+        }
+        (PatternToken::OrgTenant(a, fold), PatternToken::Exact(b, b_fold)) => split_org_tenant(b)
+            .is_some_and(|(org, tenant)| {
+                let side = |a: &Option<String>, b: &str| {
+                    a.as_deref()
+                        .is_none_or(|a| literal_covers(a, *fold, b, *b_fold))
+                };
+                side(&a.org, org) && side(&a.tenant, tenant)
+            }),
+        _ => false,
+    }
+}
+
+/// Returns `true` if some segment is matched by both `a` and `b`; neither is `**`.
+///
+/// A glob that cannot be listed as literals is assumed to meet anything but a literal it
+/// rejects, so overlaps are never missed.
+fn tokens_meet(a: &PatternToken, b: &PatternToken) -> bool {
+    match (a, b) {
+        (PatternToken::One, _) | (_, PatternToken::One) => true,
+        (PatternToken::Glob(glob, fold), other) | (other, PatternToken::Glob(glob, fold)) => {
+            match (glob.words(), other) {
+                (Some(words), _) => words
+                    .iter()
+                    .any(|word| tokens_meet(&PatternToken::Exact(word, *fold), other)),
+                (None, PatternToken::Exact(value, value_fold)) => {
+                    glob.matches(value, *fold || *value_fold)
+                }
+                (None, _) => true,
+            }
+        }
+        (PatternToken::Exact(a, a_fold), PatternToken::Exact(b, b_fold))
+        | (PatternToken::Attr(a, a_fold), PatternToken::Attr(b, b_fold)) => {
+            literals_meet(a, *a_fold, b, *b_fold)
+        }
+        (PatternToken::Attr(key, fold), PatternToken::Exact(value, value_fold))
+        | (PatternToken::Exact(value, value_fold), PatternToken::Attr(key, fold)) => {
+            split_attr(value)
+                .is_some_and(|(value_key, _)| literals_meet(key, *fold, value_key, *value_fold))
+        }
+        (PatternToken::Subtree(a, a_fold), PatternToken::Subtree(b, b_fold)) => {
+            category_lineage(a).any(|a| literals_meet(a, *a_fold, b, *b_fold))
+                || category_lineage(b).any(|b| literals_meet(a, *a_fold, b, *b_fold))
+        }
+        (PatternToken::Subtree(category, fold), PatternToken::Exact(value, value_fold))
+        | (PatternToken::Exact(value, value_fold), PatternToken::Subtree(category, fold)) => {
+            category_lineage(value).any(|value| literals_meet(category, *fold, value, *value_fold))
+        }
+        (PatternToken::OrgTenant(a, a_fold), PatternToken::OrgTenant(b, b_fold)) => {
+            let side = |a: &Option<String>, b: &Option<String>| match (a, b) {
+                (Some(a), Some(b)) => literals_meet(a, *a_fold, b, *b_fold),
+                _ => true,
+            };
+            side(&a.org, &b.org) && side(&a.tenant, &b.tenant)
+        }
+        (PatternToken::OrgTenant(org_tenant, fold), PatternToken::Exact(value, value_fold))
+        | (PatternToken::Exact(value, value_fold), PatternToken::OrgTenant(org_tenant, fold)) => {
+            org_tenant.matches(value, *fold || *value_fold)
+        }
+        _ => false,
+    }
+}
+
+fn literal_covers(a: &str, a_fold: bool, b: &str, b_fold: bool) -> bool {
+    if a_fold {
+        lowercase(a) == lowercase(b)
+    } else {
+        !b_fold && a == b
+    }
+}
+
+fn literals_meet(a: &str, a_fold: bool, b: &str, b_fold: bool) -> bool {
+    if a_fold || b_fold {
+        lowercase(a) == lowercase(b)
+    } else {
+        a == b
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ArnParser, CaseFold, ComponentKind};
+
+    fn patterns(list: &[&str]) -> Vec<ArnPattern> {
+        list.iter().map(|p| p.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_covers_agrees_with_sample_arns() {
+        let rules = patterns(&[
+            "arn:*:*:*:*/**",
+            "arn:akton:hr:*:root/**",
+            "arn:akton:hr:acme:root/**/b",
+            "arn:akton:hr:acme:root/*/b",
+            "arn:akton:hr:acme:root/a/b",
+            "arn:akton:hr:acme:root/env=*/b",
+            "arn:akton:hr:acme:root/env=prod/b",
+            "arn:akton:hr:acme:root/*/*",
+            "arn:akton:hr:acme:root",
+        ]);
+        let arns: Vec<_> = [
+            "arn:akton:hr:acme:root",
+            "arn:akton:hr:acme:root/a/b",
+            "arn:akton:hr:acme:root/env=prod/b",
+            "arn:akton:hr:acme:root/env=dev/b",
+            "arn:akton:hr:acme:root/x/y/b",
+            "arn:akton:hr:acme:root/a/c",
+            "arn:akton:ops:acme:root/a/b",
+        ]
+        .iter()
+        .map(|a| ArnParser::new(a.to_string()).parse().unwrap())
+        .collect();
+        for a in &rules {
+            for b in &rules {
+                if a.covers(b) {
+                    for arn in arns.iter().filter(|arn| b.matches(arn)) {
+                        assert!(a.matches(arn), "{a} covers {b} but misses {arn}");
+                    }
+                }
+                let shared = arns.iter().any(|arn| a.matches(arn) && b.matches(arn));
+                if shared {
+                    assert!(a.overlaps(b), "{a} and {b} share an Arn");
+                }
+            }
+        }
+        let [any, hr, deep_b, one_b, exact, attr, attr_exact, two, root] = &rules[..] else {
+            unreachable!()
+        };
+        assert!(any.covers(hr) && hr.covers(deep_b) && deep_b.covers(one_b));
+        assert!(one_b.covers(exact) && attr.covers(attr_exact) && two.covers(attr));
+        assert!(!one_b.covers(deep_b) && !attr.covers(exact) && !two.covers(root));
+        assert!(!exact.overlaps(attr) && !root.overlaps(two) && deep_b.overlaps(two));
+    }
+
+    #[test]
+    fn test_case_folding() {
+        let fold = CaseFold::none().fold(ComponentKind::Part);
+        let folded: ArnPattern = "arn:akton:hr:acme:root/Teams".parse().unwrap();
+        let folded = folded.with_case_fold(fold);
+        let exact: ArnPattern = "arn:akton:hr:acme:root/teams".parse().unwrap();
+        assert!(folded.covers(&exact));
+        assert!(!exact.covers(&folded));
+        assert!(exact.overlaps(&folded));
+    }
+
+    #[test]
+    fn test_part_globs() {
+        let rules = patterns(&[
+            "arn:akton:hr:acme:root/{tmp,cache}",
+            "arn:akton:hr:acme:root/[a-c]x",
+            "arn:akton:hr:acme:root/!{tmp,cache}",
+            "arn:akton:hr:acme:root/tmp",
+            "arn:akton:hr:acme:root/bx",
+            "arn:akton:hr:acme:root/logs",
+            "arn:akton:hr:acme:root/{tmp,logs}",
+        ]);
+        let [alt, class, negated, tmp, bx, logs, tmp_logs] = &rules[..] else {
+            unreachable!()
+        };
+        assert!(alt.covers(tmp) && class.covers(bx) && negated.covers(logs));
+        assert!(negated.covers(bx) && negated.covers(negated) && !negated.covers(tmp));
+        assert!(!alt.covers(tmp_logs) && !tmp.covers(alt));
+        assert!(alt.overlaps(tmp_logs) && !alt.overlaps(class) && !alt.overlaps(negated));
+        assert!(negated.overlaps(tmp_logs) && negated.overlaps(class));
+        assert_eq!(
+            ArnPattern::find_shadowed(&rules),
+            [
+                Shadowed { rule: 1, by: 2 },
+                Shadowed { rule: 3, by: 0 },
+                Shadowed { rule: 4, by: 1 },
+                Shadowed { rule: 5, by: 2 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_org_tenant_accounts() {
+        let rules = patterns(&[
+            "arn:akton:hr:*.*:root",
+            "arn:akton:hr:acme.*:root",
+            "arn:akton:hr:*.eu:root",
+            "arn:akton:hr:acme.eu:root",
+            "arn:akton:hr:acme:root",
+        ]);
+        let [any, org, tenant, exact, flat] = &rules[..] else {
+            unreachable!()
+        };
+        assert!(any.covers(org) && any.covers(tenant) && org.covers(exact));
+        assert!(tenant.covers(exact) && !org.covers(tenant) && !exact.covers(org));
+        assert!(!any.covers(flat) && !any.overlaps(flat) && org.overlaps(tenant));
+        assert!(!org.overlaps(&patterns(&["arn:akton:hr:beta.*:root"])[0]));
+        let folded = tenant
+            .clone()
+            .with_case_fold(CaseFold::none().fold(ComponentKind::Account));
+        assert!(folded.covers(&patterns(&["arn:akton:hr:acme.EU:root"])[0]));
+        assert!(!tenant.covers(&folded));
+    }
+
+    #[test]
+    fn test_category_subtrees() {
+        let rules = patterns(&[
+            "arn:akton:hr.**:acme:root",
+            "arn:akton:hr.payroll.**:acme:root",
+            "arn:akton:hr.payroll.uk:acme:root",
+            "arn:akton:hrx:acme:root",
+            "arn:akton:hr:acme:root",
+        ]);
+        let [hr, payroll, uk, hrx, flat] = &rules[..] else {
+            unreachable!()
+        };
+        assert!(hr.covers(payroll) && payroll.covers(uk) && hr.covers(flat));
+        assert!(!payroll.covers(hr) && !hr.covers(hrx) && !uk.covers(payroll));
+        assert!(hr.overlaps(payroll) && payroll.overlaps(hr) && payroll.overlaps(uk));
+        assert!(!hr.overlaps(hrx) && !payroll.overlaps(flat));
+    }
+
+    #[test]
+    fn test_shadowed_overlapping_and_minimize() {
+        let rules = patterns(&[
+            "arn:akton:hr:acme:root/teams/*",
+            "arn:akton:hr:*:root/teams/**",
+            "arn:akton:hr:acme:root/users",
+            "arn:akton:hr:*:root/teams/**",
+            "arn:akton:ops:*:*",
+        ]);
+        assert_eq!(
+            ArnPattern::find_shadowed(&rules),
+            [Shadowed { rule: 0, by: 1 }, Shadowed { rule: 3, by: 1 }]
+        );
+        assert_eq!(
+            ArnPattern::find_overlapping(&rules),
+            [(0, 1), (0, 3), (1, 3)]
+        );
+        let minimized: Vec<String> = ArnPattern::minimize(&rules)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            minimized,
+            [
+                "arn:akton:hr:*:root/teams/**",
+                "arn:akton:hr:acme:root/users",
+                "arn:akton:ops:*:*",
+            ]
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use crate::errors::ArnError;
+use crate::model::Arn;
+use crate::pattern::ArnPattern;
+use crate::ComponentKind;
+use std::fmt;
+
+/// The options an [`ArnPatternBuilder`](crate::ArnPatternBuilder) applies when compiling.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub(crate) struct MatchOptions {
+    pub(crate) require_literal_root: bool,
+    pub(crate) max_depth: Option<usize>,
+}
+
+/// An [`ArnPattern`] compiled with matching options, ready to test any number of Arns.
+///
+/// Built by [`ArnPatternBuilder::compile`](crate::ArnPatternBuilder::compile), or by
+/// [`ArnPattern::compile_matcher`] for the default options.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ArnMatcher {
+    pattern: ArnPattern,
+    max_depth: Option<usize>,
+}
+
+impl ArnMatcher {
+    pub(crate) fn new(pattern: ArnPattern, options: MatchOptions) -> Result<Self, ArnError> {
+        if options.require_literal_root && !pattern.has_literal_root() {
+            return Err(ArnError::ValidationFailure(
+                ComponentKind::Root,
+                format!("{pattern} must name its root"),
+            ));
+        }
+        Ok(ArnMatcher {
+            pattern,
+            max_depth: options.max_depth,
+        })
+    }
+
+    /// Returns `true` if `arn` matches the pattern within the options.
+    pub fn is_match(&self, arn: &Arn) -> bool {
+        self.max_depth.is_none_or(|max| arn.parts.0.len() <= max) && self.pattern.matches(arn)
+    }
+
+    /// Returns the pattern being matched.
+    pub fn pattern(&self) -> &ArnPattern {
+        &self.pattern
+    }
+
+    /// Returns the most parts a matching Arn may have, if limited.
+    pub fn max_depth(&self) -> Option<usize> {
+        self.max_depth
+    }
+}
+
+impl fmt::Display for ArnMatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.pattern.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ArnError, ArnParser, ArnPatternBuilder, CaseFold, ComponentKind};
+
+    #[test]
+    fn test_options() {
+        let arn = |s: &str| ArnParser::new(s.to_string()).parse().unwrap();
+        let strict = ArnPatternBuilder::new()
+            .domain("akton")
+            .unwrap()
+            .category("hr")
+            .account_any()
+            .root("root")
+            .parts_glob("**")
+            .unwrap()
+            .max_depth(1)
+            .require_literal_root(true)
+            .compile()
+            .unwrap();
+        assert!(strict.is_match(&arn("arn:akton:hr:acme:root/a")));
+        assert!(!strict.is_match(&arn("arn:akton:hr:acme:root/a/b")));
+        assert!(!strict.is_match(&arn("arn:akton:HR:acme:root")));
+        assert_eq!(strict.max_depth(), Some(1));
+
+        let loose = ArnPatternBuilder::parse("arn:akton:hr:*:root/**")
+            .unwrap()
+            .case_insensitive(true)
+            .compile()
+            .unwrap();
+        assert!(loose.is_match(&arn("arn:akton:HR:acme:root/a/b")));
+        assert_eq!(loose.max_depth(), None);
+        assert_eq!(loose.to_string(), "arn:akton:hr:*:root/**");
+
+        let domain_only = CaseFold::none().fold(ComponentKind::Domain);
+        let folded = ArnPatternBuilder::parse("arn:akton:hr:*:root")
+            .unwrap()
+            .with_case_fold(domain_only)
+            .case_insensitive(false)
+            .compile()
+            .unwrap();
+        assert!(folded.is_match(&arn("arn:AKTON:hr:acme:root")));
+        assert!(!folded.is_match(&arn("arn:akton:HR:acme:root")));
+        let narrowed = ArnPatternBuilder::parse("arn:akton:hr:*:root")
+            .unwrap()
+            .case_insensitive(true)
+            .with_case_fold(domain_only)
+            .compile()
+            .unwrap();
+        assert!(!narrowed.is_match(&arn("arn:akton:HR:acme:root")));
+
+        let any_root = ArnPatternBuilder::new()
+            .domain_any()
+            .category_any()
+            .account_any()
+            .root_any()
+            .require_literal_root(true)
+            .compile();
+        assert!(matches!(
+            any_root,
+            Err(ArnError::ValidationFailure(ComponentKind::Root, _))
+        ));
+    }
+}
@@ -0,0 +1,156 @@
+//! Counters for holding hot paths to their performance targets.
+//!
+//! Timing varies between machines, but the number of heap allocations an operation makes does
+//! not, and it dominates the cost of parsing and building short strings. Install a
+//! [`CountingAllocator`] as the global allocator of a test or benchmark binary and wrap the
+//! operation in [`CountingAllocator::measure`] to check it against the targets below. The
+//! crate's `tests/perf.rs` enforces them, and `benches/arn.rs` times the same operations.
+//!
+//! | Operation                                                   | Allocations |
+//! |-------------------------------------------------------------|-------------|
+//! | `ArnParser::parse` of a borrowed Arn with up to four parts  | 0           |
+//! | `extract_account` and the other extractors                  | 0           |
+//! | `Arn::to_string`                                            | 1           |
+//! | `ArnPattern::matches`                                       | 0           |
+//! | `Arn::parent`, `Arn::is_child_of`, `Arn::ancestors`         | 0           |
+//! | `ArnArena::parse` into a warm arena                         | 0           |
+//!
+//! Reallocations are counted separately and not targeted: `to_string` grows its buffer as it
+//! writes.
+//!
+//! ```
+//! use akton_arn::perf::CountingAllocator;
+//! use akton_arn::ArnParser;
+//! use std::alloc::System;
+//!
+//! #[global_allocator]
+//! static ALLOCATOR: CountingAllocator = CountingAllocator::new(System);
+//!
+//! fn main() {
+//!     let (arn, stats) = ALLOCATOR.measure(|| ArnParser::new("arn:akton:hr:acme:root/a").parse());
+//!     assert!(arn.is_ok());
+//!     assert_eq!(stats.allocations, 0);
+//! }
+//! ```
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::ops::Sub;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Allocation counts read from a [`CountingAllocator`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    /// Calls to `alloc` and `alloc_zeroed`.
+    pub allocations: u64,
+    /// Calls to `realloc`.
+    pub reallocations: u64,
+    /// Bytes requested by allocations, plus the growth requested by reallocations.
+    pub bytes: u64,
+}
+
+impl Sub for AllocStats {
+    type Output = AllocStats;
+
+    fn sub(self, earlier: AllocStats) -> AllocStats {
+        AllocStats {
+            allocations: self.allocations - earlier.allocations,
+            reallocations: self.reallocations - earlier.reallocations,
+            bytes: self.bytes - earlier.bytes,
+        }
+    }
+}
+
+/// A global allocator that counts the allocations it passes on to another.
+///
+/// Counts cover every thread, so measure on one thread while the others are idle.
+#[derive(Debug, Default)]
+pub struct CountingAllocator<A = System> {
+    inner: A,
+    allocations: AtomicU64,
+    reallocations: AtomicU64,
+    bytes: AtomicU64,
+}
+
+impl<A> CountingAllocator<A> {
+    /// Wraps `inner`, usually [`System`], with every count at zero.
+    pub const fn new(inner: A) -> Self {
+        CountingAllocator {
+            inner,
+            allocations: AtomicU64::new(0),
+            reallocations: AtomicU64::new(0),
+            bytes: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the counts since the allocator was installed.
+    pub fn stats(&self) -> AllocStats {
+        AllocStats {
+            allocations: self.allocations.load(Ordering::Relaxed),
+            reallocations: self.reallocations.load(Ordering::Relaxed),
+            bytes: self.bytes.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Runs `f`, returning its result and the allocations made while it ran.
+    pub fn measure<R>(&self, f: impl FnOnce() -> R) -> (R, AllocStats) {
+        let before = self.stats();
+        let result = f();
+        (result, self.stats() - before)
+    }
+
+    fn count(&self, counter: &AtomicU64, bytes: usize) {
+        counter.fetch_add(1, Ordering::Relaxed);
+        self.bytes.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+}
+
+// SAFETY: every call is forwarded unchanged to `inner`, which upholds the `GlobalAlloc`
+// contract; the counters are atomics and never allocate.
+unsafe impl<A: GlobalAlloc> GlobalAlloc for CountingAllocator<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        self.count(&self.allocations, layout.size());
+        // SAFETY: the caller upholds `alloc`'s contract.
+        unsafe { self.inner.alloc(layout) }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        self.count(&self.allocations, layout.size());
+        // SAFETY: the caller upholds `alloc_zeroed`'s contract.
+        unsafe { self.inner.alloc_zeroed(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // SAFETY: the caller upholds `dealloc`'s contract.
+        unsafe { self.inner.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        self.count(&self.reallocations, new_size.saturating_sub(layout.size()));
+        // SAFETY: the caller upholds `realloc`'s contract.
+        unsafe { self.inner.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_forwarded_calls() {
+        let allocator = CountingAllocator::new(System);
+        let layout = Layout::from_size_align(16, 8).unwrap();
+        let ((), stats) = allocator.measure(|| unsafe {
+            let ptr = allocator.alloc(layout);
+            let ptr = allocator.realloc(ptr, layout, 48);
+            allocator.dealloc(ptr, Layout::from_size_align(48, 8).unwrap());
+        });
+        assert_eq!(
+            stats,
+            AllocStats {
+                allocations: 1,
+                reallocations: 1,
+                bytes: 48,
+            }
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use crate::errors::ArnError;
+use crate::model::{Arn, Part, Parts, Root};
+use std::borrow::Cow;
+
+impl Arn<'_> {
+    /// Returns the root and parts as an RFC 6901 JSON Pointer, such as `/root/departmentA/team1`.
+    ///
+    /// Each component becomes one reference token, with `~` escaped as `~0` and `/` as `~1`,
+    /// so configuration stores that mirror resource trees as nested JSON objects can address
+    /// an Arn's node directly.
+    pub fn part_pointer(&self) -> String {
+        let mut pointer = String::new();
+        for token in
+            std::iter::once(self.root.as_str()).chain(self.parts.0.iter().map(Part::as_str))
+        {
+            pointer.push('/');
+            for c in token.chars() {
+                match c {
+                    '~' => pointer.push_str("~0"),
+                    '/' => pointer.push_str("~1"),
+                    c => pointer.push(c),
+                }
+            }
+        }
+        pointer
+    }
+
+    /// Returns a copy of this Arn with the root and parts replaced by those in `pointer`.
+    ///
+    /// Reverses [`Arn::part_pointer`]: the first token is the root, the rest are parts. Fails
+    /// if the pointer is empty, does not start with `/`, contains an invalid escape, or yields
+    /// an invalid part.
+    pub fn with_part_pointer(&self, pointer: &str) -> Result<Arn<'static>, ArnError> {
+        let invalid = |reason: &str| {
+            ArnError::ParseFailure("JSON Pointer", format!("{reason} in {pointer:?}"))
+        };
+        let rest = pointer
+            .strip_prefix('/')
+            .ok_or_else(|| invalid("missing leading '/'"))?;
+        let mut tokens = rest.split('/').map(|token| {
+            let mut unescaped = String::with_capacity(token.len());
+            let mut chars = token.chars();
+            while let Some(c) = chars.next() {
+                if c != '~' {
+                    unescaped.push(c);
+                    continue;
+                }
+                match chars.next() {
+                    Some('0') => unescaped.push('~'),
+                    Some('1') => unescaped.push('/'),
+                    _ => return Err(invalid("invalid '~' escape")),
+                }
+            }
+            Ok(unescaped)
+        });
+        let root = tokens.next().ok_or_else(|| invalid("missing root"))??;
+        let parts = tokens
+            .map(|token| Part::new(Cow::Owned(token?)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Arn::new(
+            self.domain.clone().into_owned(),
+            self.category.clone().into_owned(),
+            self.account.clone().into_owned(),
+            Root::decoded(root)?,
+            Parts::new(parts),
+        ))
+    }
+
+    /// Looks up this Arn's node in a JSON document keyed by hierarchy, using [`Arn::part_pointer`].
+    #[cfg(feature = "json")]
+    pub fn resolve_in<'v>(&self, document: &'v serde_json::Value) -> Option<&'v serde_json::Value> {
+        document.pointer(&self.part_pointer())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArnParser;
+
+    fn arn(s: &str) -> Arn<'static> {
+        ArnParser::new(s.to_string()).parse().unwrap()
+    }
+
+    #[test]
+    fn test_part_pointer_round_trip() {
+        let arn = arn("arn:akton:hr:acme:root/department_a/team~1");
+        let pointer = arn.part_pointer();
+        assert_eq!(pointer, "/root/department_a/team~01");
+        assert_eq!(arn.with_part_pointer(&pointer).unwrap(), arn);
+
+        let base = self::arn("arn:akton:hr:acme:other");
+        assert_eq!(
+            base.with_part_pointer("/root/department_a")
+                .unwrap()
+                .to_string(),
+            "arn:akton:hr:acme:root/department_a"
+        );
+    }
+
+    #[test]
+    fn test_invalid_pointers() {
+        let base = arn("arn:akton:hr:acme:root");
+        assert!(base.with_part_pointer("root").is_err());
+        assert!(base.with_part_pointer("/root/a~2").is_err());
+        assert!(base.with_part_pointer("/root//b").is_err());
+        // A part may not contain '/', even escaped.
+        assert!(base.with_part_pointer("/root/a~1b").is_err());
+        assert!(base.with_part_pointer("/ro~1ot/a").is_err());
+        assert!(base.with_part_pointer("/root@v2/a").is_err());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_resolve_in_document() {
+        let document = serde_json::json!({
+            "root": { "department_a": { "team1": { "replicas": 3 } } }
+        });
+        let team = arn("arn:akton:hr:acme:root/department_a/team1");
+        assert_eq!(team.resolve_in(&document).unwrap()["replicas"], 3);
+        assert!(arn("arn:akton:hr:acme:root/missing")
+            .resolve_in(&document)
+            .is_none());
+    }
+}
@@ -0,0 +1,96 @@
+use crate::errors::ArnError;
+use crate::model::{Account, Category, Domain};
+use std::borrow::Cow;
+
+/// The domain, category and account a service puts in every Arn it builds.
+///
+/// A service usually builds all its Arns under one account, so it keeps a profile and starts
+/// each Arn with [`ArnBuilder::with_defaults`](crate::ArnBuilder::with_defaults), leaving
+/// call sites to add only the root and parts.
+///
+/// ```
+/// use akton_arn::{ArnBuilder, ArnProfile, Part, Root};
+///
+/// let payroll = ArnProfile::new("akton", "hr", "acme").unwrap();
+/// let arn = ArnBuilder::new()
+///     .with_defaults(&payroll).unwrap()
+///     .with::<Root>("payslips").unwrap()
+///     .with::<Part>("2024").unwrap()
+///     .build()
+///     .unwrap();
+/// assert!(arn.to_string().starts_with("arn:akton:hr:acme:payslips"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ArnProfile {
+    domain: Domain<'static>,
+    category: Category<'static>,
+    account: Account<'static>,
+}
+
+impl ArnProfile {
+    /// Creates a profile for Arns under `domain`, `category` and `account`.
+    ///
+    /// Fails if `domain` is empty.
+    pub fn new(
+        domain: impl Into<Cow<'static, str>>,
+        category: impl Into<Cow<'static, str>>,
+        account: impl Into<Cow<'static, str>>,
+    ) -> Result<Self, ArnError> {
+        Ok(ArnProfile {
+            domain: Domain::new(domain)?,
+            category: Category::new(category),
+            account: Account::new(account),
+        })
+    }
+
+    /// Returns the domain every Arn starts with.
+    pub fn domain(&self) -> &Domain<'static> {
+        &self.domain
+    }
+
+    /// Returns the category every Arn is in.
+    pub fn category(&self) -> &Category<'static> {
+        &self.category
+    }
+
+    /// Returns the account every Arn belongs to.
+    pub fn account(&self) -> &Account<'static> {
+        &self.account
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ArnBuilder, ComponentKind, Root};
+
+    #[test]
+    fn test_profile_supplies_leading_components() -> anyhow::Result<()> {
+        let profile = ArnProfile::new("akton", "hr", String::from("acme"))?;
+        let arn = ArnBuilder::new()
+            .with_defaults(&profile)?
+            .with::<Root>("payroll")?
+            .build()?;
+        assert_eq!(
+            (&arn.domain, &arn.category, &arn.account),
+            (profile.domain(), profile.category(), profile.account())
+        );
+        assert!(ArnProfile::new("", "hr", "acme").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_profile_goes_through_builder_checks() {
+        let profile = ArnProfile::new("akton", "hr", "acme").unwrap();
+        let no_acme = |kind: ComponentKind, value: &str| match (kind, value) {
+            (ComponentKind::Account, "acme") => Err("acme is suspended".to_string()),
+            _ => Ok(()),
+        };
+        assert!(matches!(
+            ArnBuilder::new()
+                .with_validator(no_acme)
+                .with_defaults(&profile),
+            Err(ArnError::ValidationFailure(ComponentKind::Account, _))
+        ));
+    }
+}
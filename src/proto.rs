@@ -0,0 +1,177 @@
+//! Protobuf support, enabled with the `proto` feature.
+//!
+//! [`ArnProto`] is the `akton.arn.v1.Arn` message of `proto/akton_arn.proto`, carrying each
+//! component in its own field so gRPC services can exchange Arns without re-parsing strings at
+//! every hop. Annotations and the scheme are not carried, as they are not part of an Arn's
+//! identity.
+
+use crate::builder::{ArnBuilder, ArnBuilderParts};
+use crate::errors::ArnError;
+use crate::model::Arn;
+
+/// The `akton.arn.v1.Arn` message.
+#[derive(Clone, PartialEq, Eq, prost::Message)]
+pub struct ArnProto {
+    #[prost(string, tag = "1")]
+    pub domain: String,
+    #[prost(string, tag = "2")]
+    pub category: String,
+    #[prost(string, tag = "3")]
+    pub account: String,
+    #[prost(string, tag = "4")]
+    pub root: String,
+    /// The parts after the root, nearest the root first.
+    #[prost(string, repeated, tag = "5")]
+    pub parts: Vec<String>,
+    #[prost(uint64, optional, tag = "6")]
+    pub version: Option<u64>,
+    /// The extensions, in order.
+    #[prost(message, repeated, tag = "7")]
+    pub extensions: Vec<ExtensionProto>,
+}
+
+/// The `akton.arn.v1.Extension` message.
+#[derive(Clone, PartialEq, Eq, prost::Message)]
+pub struct ExtensionProto {
+    #[prost(string, tag = "1")]
+    pub key: String,
+    #[prost(string, tag = "2")]
+    pub value: String,
+}
+
+impl Arn<'_> {
+    /// Converts the Arn into its protobuf message.
+    ///
+    /// ```
+    /// use akton_arn::{Arn, ArnParser};
+    /// use prost::Message;
+    ///
+    /// let arn = ArnParser::new("arn:akton:hr:acme:root/teams/a@v2").parse().unwrap();
+    /// let bytes = arn.to_proto().encode_to_vec();
+    /// let decoded = akton_arn::ArnProto::decode(bytes.as_slice()).unwrap();
+    /// assert_eq!(Arn::from_proto(decoded).unwrap(), arn);
+    /// ```
+    pub fn to_proto(&self) -> ArnProto {
+        ArnProto {
+            domain: self.domain.to_string(),
+            category: self.category.to_string(),
+            account: self.account.to_string(),
+            root: self.root.to_string(),
+            parts: self.parts.iter().map(|part| part.to_string()).collect(),
+            version: self.version,
+            extensions: self
+                .extensions
+                .iter()
+                .map(|(key, value)| ExtensionProto {
+                    key: key.to_string(),
+                    value: value.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Converts a protobuf message back into an Arn, checking its components as
+    /// [`ArnParser`](crate::ArnParser) would.
+    ///
+    /// A message from another service is untrusted input, so an empty domain or root, or a
+    /// component the grammar does not allow, is an error. The category and account may be
+    /// empty, as in the string form.
+    pub fn from_proto(proto: ArnProto) -> Result<Arn<'static>, ArnError> {
+        let required = |name: &str, value: String| {
+            if value.is_empty() {
+                Err(ArnError::MissingPart(name.to_string()))
+            } else {
+                Ok(Some(value))
+            }
+        };
+        let saved = ArnBuilderParts {
+            domain: required("domain", proto.domain)?,
+            category: Some(proto.category),
+            account: Some(proto.account),
+            root: required("root", proto.root)?,
+            parts: proto.parts,
+        };
+        let mut arn = ArnBuilder::new().resume(saved)?.build()?;
+        arn.version = proto.version;
+        for extension in proto.extensions {
+            arn.extensions.insert(extension.key, extension.value)?;
+        }
+        Ok(arn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArnParser;
+    use prost::Message;
+
+    #[test]
+    fn test_proto_round_trip() {
+        let arn = ArnParser::new("arn:akton:hr:acme:root/teams/a@v3?region=eu&tier=gold")
+            .parse()
+            .unwrap();
+        let proto = arn.to_proto();
+        assert_eq!(proto.parts, ["teams", "a"]);
+        assert_eq!(proto.version, Some(3));
+        let decoded = ArnProto::decode(proto.encode_to_vec().as_slice()).unwrap();
+        assert_eq!(decoded, proto);
+        assert_eq!(Arn::from_proto(decoded).unwrap(), arn);
+
+        let unversioned = ArnParser::new("arn:akton:hr:acme:root").parse().unwrap();
+        assert_eq!(
+            Arn::from_proto(unversioned.to_proto()).unwrap().version(),
+            None
+        );
+
+        let unscoped = ArnParser::new("arn:akton::acme:root").parse().unwrap();
+        assert_eq!(Arn::from_proto(unscoped.to_proto()).unwrap(), unscoped);
+        let anonymous = ArnParser::new("arn:akton:hr::root/a").parse().unwrap();
+        assert_eq!(Arn::from_proto(anonymous.to_proto()).unwrap(), anonymous);
+    }
+
+    #[test]
+    fn test_invalid_messages_are_rejected() {
+        let valid = ArnParser::new("arn:akton:hr:acme:root")
+            .parse()
+            .unwrap()
+            .to_proto();
+        assert_eq!(
+            Arn::from_proto(ArnProto {
+                root: String::new(),
+                ..valid.clone()
+            }),
+            Err(ArnError::MissingPart("root".to_string()))
+        );
+        assert_eq!(
+            Arn::from_proto(ArnProto {
+                domain: String::new(),
+                ..valid.clone()
+            }),
+            Err(ArnError::MissingPart("domain".to_string()))
+        );
+        assert!(Arn::from_proto(ArnProto {
+            root: "root/teams".to_string(),
+            ..valid.clone()
+        })
+        .is_err());
+        for (category, account) in [("a:b", "acme"), ("hr", "acme:root")] {
+            assert!(matches!(
+                Arn::from_proto(ArnProto {
+                    category: category.to_string(),
+                    account: account.to_string(),
+                    ..valid.clone()
+                }),
+                Err(ArnError::ParseFailure("Category" | "Account", _))
+            ));
+        }
+        assert!(Arn::from_proto(ArnProto {
+            extensions: vec![ExtensionProto {
+                key: "a=b".to_string(),
+                value: "c".to_string(),
+            }],
+            ..valid
+        })
+        .is_err());
+    }
+}
@@ -0,0 +1,217 @@
+use crate::errors::ArnError;
+use crate::model::Arn;
+use crate::scratch::with_canonical;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Usage limits for tenants, accounts or subtrees, keyed by prefixes of the canonical Arn.
+///
+/// Each limit is set on a prefix such as `arn:akton:hr:acme` (an account) or
+/// `arn:akton:hr:acme:root/teams` (a subtree). A prefix covers an Arn only if it ends at a
+/// component or part boundary of the Arn's canonical form, so `arn:akton:hr:acme` covers the
+/// `acme` account but not `acme2`. An Arn is charged to the longest prefix covering it, its
+/// nearest configured ancestor; Arns no prefix covers are unlimited.
+///
+/// Consuming only needs a shared reference and is atomic, so one ledger can be shared
+/// between threads.
+///
+/// ```
+/// use akton_arn::{ArnError, ArnParser, QuotaLedger};
+///
+/// let mut quotas = QuotaLedger::new();
+/// quotas.set_limit("arn:akton:hr:acme", 10);
+/// quotas.set_limit("arn:akton:hr:acme:root/uploads", 2);
+///
+/// let report = ArnParser::new("arn:akton:hr:acme:root/reports/q1").parse().unwrap();
+/// let upload = ArnParser::new("arn:akton:hr:acme:root/uploads/a.png").parse().unwrap();
+/// assert_eq!(quotas.try_consume(&report, 4), Ok(()));
+/// assert_eq!(quotas.try_consume(&upload, 2), Ok(()));
+/// assert!(matches!(quotas.try_consume(&upload, 1), Err(ArnError::QuotaExceeded(..))));
+/// assert_eq!(quotas.remaining(&report), Some(6));
+/// ```
+#[derive(Debug, Default)]
+pub struct QuotaLedger {
+    quotas: BTreeMap<String, Quota>,
+}
+
+#[derive(Debug)]
+struct Quota {
+    limit: u64,
+    used: AtomicU64,
+}
+
+impl QuotaLedger {
+    /// Creates a ledger without limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits the Arns charged to `prefix` to `limit` units, keeping what they already used.
+    pub fn set_limit(&mut self, prefix: impl Into<String>, limit: u64) {
+        self.quotas
+            .entry(prefix.into())
+            .and_modify(|quota| quota.limit = limit)
+            .or_insert_with(|| Quota {
+                limit,
+                used: AtomicU64::new(0),
+            });
+    }
+
+    /// Removes the limit on `prefix`, returning `true` if there was one.
+    ///
+    /// Arns charged to it are charged to the next shorter prefix from then on.
+    pub fn remove_limit(&mut self, prefix: &str) -> bool {
+        self.quotas.remove(prefix).is_some()
+    }
+
+    /// Charges `n` units to the prefix nearest `arn`, if that leaves it within its limit.
+    ///
+    /// Fails with [`ArnError::QuotaExceeded`], charging nothing, if it would not. Arns no
+    /// prefix covers are always allowed.
+    pub fn try_consume(&self, arn: &Arn, n: u64) -> Result<(), ArnError> {
+        self.nearest(arn, |prefix, quota| {
+            quota
+                .used
+                .fetch_update(Ordering::AcqRel, Ordering::Acquire, |used| {
+                    used.checked_add(n).filter(|total| *total <= quota.limit)
+                })
+                .map(|_| ())
+                .map_err(|used| {
+                    let left = quota.limit.saturating_sub(used);
+                    ArnError::QuotaExceeded(prefix.to_string(), left, n)
+                })
+        })
+        .unwrap_or(Ok(()))
+    }
+
+    /// Gives back up to `n` units charged for `arn`, such as when a resource is deleted.
+    pub fn release(&self, arn: &Arn, n: u64) {
+        self.nearest(arn, |_, quota| {
+            let _ = quota
+                .used
+                .fetch_update(Ordering::AcqRel, Ordering::Acquire, |used| {
+                    Some(used.saturating_sub(n))
+                });
+        });
+    }
+
+    /// Returns the units left at the prefix nearest `arn`, or `None` if `arn` is unlimited.
+    pub fn remaining(&self, arn: &Arn) -> Option<u64> {
+        self.nearest(arn, |_, quota| {
+            quota
+                .limit
+                .saturating_sub(quota.used.load(Ordering::Acquire))
+        })
+    }
+
+    /// Returns the units used against `prefix`, or `None` if it has no limit.
+    pub fn usage(&self, prefix: &str) -> Option<u64> {
+        self.quotas
+            .get(prefix)
+            .map(|quota| quota.used.load(Ordering::Acquire))
+    }
+
+    /// Sets the usage of every prefix back to zero, as at the start of a billing period.
+    pub fn reset(&self) {
+        for quota in self.quotas.values() {
+            quota.used.store(0, Ordering::Release);
+        }
+    }
+
+    /// Calls `f` with the longest configured prefix covering `arn`.
+    fn nearest<R>(&self, arn: &Arn, f: impl FnOnce(&str, &Quota) -> R) -> Option<R> {
+        with_canonical(arn, |canonical| {
+            let (prefix, quota) = canonical
+                .char_indices()
+                .filter(|(_, c)| matches!(c, ':' | '/' | '@' | '?'))
+                .map(|(index, _)| index)
+                .chain([canonical.len()])
+                .rev()
+                .find_map(|end| self.quotas.get_key_value(&canonical[..end]))?;
+            Some(f(prefix, quota))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArnParser;
+    use std::sync::Arc;
+
+    fn arn(s: &str) -> Arn<'static> {
+        ArnParser::new(s).parse().unwrap().into_owned()
+    }
+
+    #[test]
+    fn test_nearest_prefix_is_charged() {
+        let mut quotas = QuotaLedger::new();
+        quotas.set_limit("arn:akton:hr", 100);
+        quotas.set_limit("arn:akton:hr:acme", 5);
+        quotas.set_limit("arn:akton:hr:acme:root/teams", 3);
+
+        quotas
+            .try_consume(&arn("arn:akton:hr:acme:root/teams/eng"), 3)
+            .unwrap();
+        quotas
+            .try_consume(&arn("arn:akton:hr:acme:root/teamsx"), 1)
+            .unwrap();
+        quotas
+            .try_consume(&arn("arn:akton:hr:acme2:root"), 7)
+            .unwrap();
+        assert_eq!(quotas.usage("arn:akton:hr:acme:root/teams"), Some(3));
+        assert_eq!(quotas.usage("arn:akton:hr:acme"), Some(1));
+        assert_eq!(quotas.usage("arn:akton:hr"), Some(7));
+        assert_eq!(quotas.usage("arn:akton"), None);
+
+        assert_eq!(
+            quotas.try_consume(&arn("arn:akton:hr:acme:root/teams"), 1),
+            Err(ArnError::QuotaExceeded(
+                "arn:akton:hr:acme:root/teams".to_string(),
+                0,
+                1
+            ))
+        );
+        assert_eq!(
+            quotas.try_consume(&arn("arn:akton:ops:acme:root"), u64::MAX),
+            Ok(())
+        );
+        assert_eq!(quotas.remaining(&arn("arn:akton:ops:acme:root")), None);
+
+        quotas.release(&arn("arn:akton:hr:acme:root/teams/eng"), 10);
+        assert_eq!(
+            quotas.remaining(&arn("arn:akton:hr:acme:root/teams")),
+            Some(3)
+        );
+        assert!(quotas.remove_limit("arn:akton:hr:acme:root/teams"));
+        assert_eq!(
+            quotas.remaining(&arn("arn:akton:hr:acme:root/teams")),
+            Some(4)
+        );
+        quotas.reset();
+        assert_eq!(quotas.usage("arn:akton:hr"), Some(0));
+    }
+
+    #[test]
+    fn test_concurrent_consumers_never_exceed_the_limit() {
+        let mut quotas = QuotaLedger::new();
+        quotas.set_limit("arn:akton:hr:acme", 1_000);
+        let quotas = Arc::new(quotas);
+        let granted: u64 = (0..8)
+            .map(|_| {
+                let quotas = Arc::clone(&quotas);
+                std::thread::spawn(move || {
+                    let arn = arn("arn:akton:hr:acme:root/jobs");
+                    (0..500)
+                        .filter(|_| quotas.try_consume(&arn, 1).is_ok())
+                        .count() as u64
+                })
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .sum();
+        assert_eq!(granted, 1_000);
+        assert_eq!(quotas.usage("arn:akton:hr:acme"), Some(1_000));
+    }
+}
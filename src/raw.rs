@@ -0,0 +1,67 @@
+//! Public-field access to the components of an [`Arn`], for code written when they were
+//! public fields of `Arn` itself.
+//!
+//! New code should read components through accessors such as [`Arn::domain`] and change them
+//! through the validating setters. A [`RawArn`] is converted back without checks beyond those
+//! each component made when it was created.
+//!
+//! ```
+//! use akton_arn::raw::RawArn;
+//! use akton_arn::{Arn, ArnParser, Part, Parts};
+//!
+//! let arn = ArnParser::new("arn:akton:hr:acme:root/teams").parse().unwrap();
+//! let mut raw = RawArn::from(arn);
+//! raw.parts = Parts::new(vec![Part::new("users").unwrap()]);
+//! let arn = Arn::from(raw);
+//! assert_eq!(arn.to_string(), "arn:akton:hr:acme:root/users");
+//! ```
+
+use crate::model::{Account, Annotations, Arn, Category, Domain, Extensions, Parts, Root};
+use std::borrow::Cow;
+
+/// Every field of an [`Arn`], public.
+#[derive(Debug, Clone)]
+pub struct RawArn<'a> {
+    pub domain: Domain<'a>,
+    pub category: Category<'a>,
+    pub account: Account<'a>,
+    pub root: Root<'a>,
+    pub parts: Parts<'a>,
+    pub version: Option<u64>,
+    pub extensions: Extensions,
+    pub annotations: Annotations,
+    /// The scheme reported by [`Arn::scheme`].
+    pub scheme: Cow<'static, str>,
+}
+
+impl<'a> From<Arn<'a>> for RawArn<'a> {
+    fn from(arn: Arn<'a>) -> Self {
+        RawArn {
+            domain: arn.domain,
+            category: arn.category,
+            account: arn.account,
+            root: arn.root,
+            parts: arn.parts,
+            version: arn.version,
+            extensions: arn.extensions,
+            annotations: arn.annotations,
+            scheme: arn.scheme,
+        }
+    }
+}
+
+impl<'a> From<RawArn<'a>> for Arn<'a> {
+    fn from(raw: RawArn<'a>) -> Self {
+        Arn {
+            domain: raw.domain,
+            category: raw.category,
+            account: raw.account,
+            root: raw.root,
+            parts: raw.parts,
+            version: raw.version,
+            extensions: raw.extensions,
+            annotations: raw.annotations,
+            scheme: raw.scheme,
+        }
+    }
+}
@@ -0,0 +1,705 @@
+use self::change_log::ChangeLog;
+use crate::backend::Watchers;
+use crate::errors::ArnError;
+use crate::model::Arn;
+use crate::pattern::ArnPattern;
+use crate::scratch::with_canonical;
+use crate::time_source::{SystemClock, TimeSource};
+use crate::traits::{run_validators, ArnValidator};
+use crate::ComponentKind;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::ops::Bound;
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+use std::time::Duration;
+
+mod change_log;
+#[cfg(feature = "json")]
+mod json;
+
+pub use self::change_log::LoggedChange;
+
+#[cfg(feature = "json")]
+pub use self::json::REGISTRY_JSON_VERSION;
+
+/// An in-memory catalogue of Arns, each carrying metadata of type `M`.
+///
+/// Entries are keyed by their canonical string form, so iteration follows lexical order.
+/// Besides exact lookups, entries can be resolved by prefix or [`ArnPattern`], observed through
+/// [`subscribe`](ArnRegistry::subscribe), and captured with
+/// [`snapshot`](ArnRegistry::snapshot) to be [restored](ArnRegistry::restore) later. Each
+/// entry records when it was registered, read from the wall clock unless
+/// [`with_time_source`](ArnRegistry::with_time_source) says otherwise.
+///
+/// Entries live only in memory. Persisting them is manual: copy them to a
+/// [`RegistryBackend`](crate::RegistryBackend) with
+/// [`save_snapshot`](ArnRegistry::save_snapshot) and back with
+/// [`load_snapshot`](ArnRegistry::load_snapshot).
+///
+/// ```
+/// use akton_arn::{ArnParser, ArnPattern, ArnRegistry, RegistryEvent};
+///
+/// let mut registry = ArnRegistry::new();
+/// let events = registry.subscribe("arn:akton:hr:acme:");
+/// let team = ArnParser::new("arn:akton:hr:acme:root/team").parse().unwrap();
+/// registry.register(team.clone(), "team").unwrap();
+/// assert_eq!(events.try_recv(), Ok(RegistryEvent::Registered(team.into_owned())));
+///
+/// let pattern: ArnPattern = "arn:akton:hr:*:root/**".parse().unwrap();
+/// assert_eq!(registry.matching(&pattern).count(), 1);
+/// assert_eq!(registry.scan_prefix("arn:akton:hr:").count(), 1);
+/// ```
+pub struct ArnRegistry<M = ()> {
+    /// Each Arn with its metadata and the time it was registered since the Unix epoch.
+    entries: BTreeMap<String, (Arn<'static>, M, Duration)>,
+    watchers: Watchers<RegistryEvent>,
+    clock: Arc<dyn TimeSource>,
+    /// The sequence number of the latest change.
+    sequence: u64,
+    log: Option<ChangeLog<M>>,
+}
+
+impl<M> Default for ArnRegistry<M> {
+    fn default() -> Self {
+        ArnRegistry {
+            entries: BTreeMap::new(),
+            watchers: Watchers::default(),
+            clock: Arc::new(SystemClock),
+            sequence: 0,
+            log: None,
+        }
+    }
+}
+
+impl<M: Clone> Clone for ArnRegistry<M> {
+    /// Copies the entries, time source and change log; the clone starts without subscribers.
+    fn clone(&self) -> Self {
+        ArnRegistry {
+            entries: self.entries.clone(),
+            watchers: Watchers::default(),
+            clock: self.clock.clone(),
+            sequence: self.sequence,
+            log: self.log.clone(),
+        }
+    }
+}
+
+impl<M: fmt::Debug> fmt::Debug for ArnRegistry<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArnRegistry")
+            .field("entries", &self.entries)
+            .field("watchers", &self.watchers)
+            .field("sequence", &self.sequence)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A change observed through [`ArnRegistry::subscribe`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistryEvent {
+    /// The Arn was registered.
+    Registered(Arn<'static>),
+    /// The Arn's metadata was replaced.
+    Updated(Arn<'static>),
+    /// The Arn was unregistered.
+    Unregistered(Arn<'static>),
+}
+
+impl RegistryEvent {
+    /// Returns the Arn that changed.
+    pub fn arn(&self) -> &Arn<'static> {
+        match self {
+            RegistryEvent::Registered(arn)
+            | RegistryEvent::Updated(arn)
+            | RegistryEvent::Unregistered(arn) => arn,
+        }
+    }
+}
+
+/// The entries of an [`ArnRegistry`] at one point in time, in lexical order.
+///
+/// With the `serde` feature, snapshots serialize as a list of `[arn, metadata]` pairs.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct RegistrySnapshot<M> {
+    pub entries: Vec<(Arn<'static>, M)>,
+}
+
+impl<M> ArnRegistry<M> {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads registration times from `time_source` instead of the wall clock.
+    pub fn with_time_source(mut self, time_source: impl TimeSource + 'static) -> Self {
+        self.clock = Arc::new(time_source);
+        self
+    }
+
+    /// Registers an Arn with its metadata, failing if the Arn is already registered.
+    pub fn register(&mut self, arn: Arn<'_>, metadata: M) -> Result<(), ArnError> {
+        let key = arn.to_string();
+        if self.entries.contains_key(&key) {
+            return Err(ArnError::AlreadyRegistered(key));
+        }
+        let registered_at = self.clock.now();
+        self.insert(key, arn.into_owned(), metadata, registered_at);
+        Ok(())
+    }
+
+    /// Removes an Arn from the registry, returning its metadata if it was registered.
+    pub fn unregister(&mut self, arn: &Arn) -> Option<M> {
+        let (key, (arn, metadata, _)) = with_canonical(arn, |key| self.entries.remove_entry(key))?;
+        self.record(&key, RegistryEvent::Unregistered(arn), None);
+        Some(metadata)
+    }
+
+    /// Replaces the metadata of a registered Arn, returning the previous metadata.
+    ///
+    /// The Arn keeps its registration time. Fails with [`ArnError::NotRegistered`] if the Arn
+    /// is not registered.
+    pub fn update(&mut self, arn: &Arn, metadata: M) -> Result<M, ArnError> {
+        let key = arn.to_string();
+        let Some((arn, _, _)) = self.entries.get(&key) else {
+            return Err(ArnError::NotRegistered(key));
+        };
+        self.record(&key, RegistryEvent::Updated(arn.clone()), Some(&metadata));
+        let (_, previous, _) = self.entries.get_mut(&key).expect("checked above");
+        Ok(std::mem::replace(previous, metadata))
+    }
+
+    /// Inserts an entry and notifies subscribers.
+    fn insert(&mut self, key: String, arn: Arn<'static>, metadata: M, registered_at: Duration) {
+        self.record(
+            &key,
+            RegistryEvent::Registered(arn.clone()),
+            Some(&metadata),
+        );
+        self.entries.insert(key, (arn, metadata, registered_at));
+    }
+
+    /// Numbers a change, notifies subscribers and appends it to the change log, if enabled.
+    fn record(&mut self, key: &str, event: RegistryEvent, metadata: Option<&M>) {
+        self.sequence += 1;
+        self.watchers.notify(key, || event.clone());
+        if let Some(log) = &mut self.log {
+            log.append(self.sequence, event, metadata);
+        }
+    }
+
+    /// Iterates over the entries whose canonical form starts with `prefix`, in lexical order.
+    ///
+    /// Prefixes are plain strings, so `"arn:akton:hr:acme:"` selects every Arn of one
+    /// account, as it does for [`RegistryBackend::scan_prefix`](crate::RegistryBackend::scan_prefix).
+    pub fn scan_prefix<'r>(
+        &'r self,
+        prefix: &'r str,
+    ) -> impl Iterator<Item = (&'r Arn<'static>, &'r M)> + 'r {
+        self.entries
+            .range::<str, _>((Bound::Included(prefix), Bound::Unbounded))
+            .take_while(move |(key, _)| key.starts_with(prefix))
+            .map(|(_, (arn, metadata, _))| (arn, metadata))
+    }
+
+    /// Iterates over the entries matching `pattern`, in lexical order.
+    pub fn matching<'r>(
+        &'r self,
+        pattern: &'r ArnPattern,
+    ) -> impl Iterator<Item = (&'r Arn<'static>, &'r M)> + 'r {
+        self.iter().filter(|(arn, _)| pattern.matches(arn))
+    }
+
+    /// Subscribes to registrations, updates and removals of Arns whose canonical form starts
+    /// with `prefix`.
+    ///
+    /// Events are delivered until the receiver is dropped, including those caused by
+    /// [`import`](ArnRegistry::import) and [`restore`](ArnRegistry::restore).
+    pub fn subscribe(&self, prefix: &str) -> Receiver<RegistryEvent> {
+        self.watchers.subscribe(prefix)
+    }
+
+    /// Captures the current entries.
+    pub fn snapshot(&self) -> RegistrySnapshot<M>
+    where
+        M: Clone,
+    {
+        RegistrySnapshot {
+            entries: self
+                .entries
+                .values()
+                .map(|(arn, metadata, _)| (arn.clone(), metadata.clone()))
+                .collect(),
+        }
+    }
+
+    /// Replaces every entry with those of `snapshot`, keeping subscribers.
+    ///
+    /// Subscribers see every current entry unregistered, then every snapshot entry registered.
+    /// The restored entries are registered now. Fails with [`ArnError::AlreadyRegistered`],
+    /// leaving the registry unchanged, if the snapshot holds an Arn twice.
+    pub fn restore(&mut self, snapshot: RegistrySnapshot<M>) -> Result<(), ArnError> {
+        let registered_at = self.clock.now();
+        self.replace(
+            snapshot
+                .entries
+                .into_iter()
+                .map(|(arn, metadata)| (arn, metadata, registered_at)),
+        )
+    }
+
+    /// Replaces every entry, as [`restore`](ArnRegistry::restore) describes.
+    fn replace(
+        &mut self,
+        entries: impl IntoIterator<Item = (Arn<'static>, M, Duration)>,
+    ) -> Result<(), ArnError> {
+        let mut restored = BTreeMap::new();
+        for (arn, metadata, registered_at) in entries {
+            let key = arn.to_string();
+            if restored.contains_key(&key) {
+                return Err(ArnError::AlreadyRegistered(key));
+            }
+            restored.insert(key, (arn, metadata, registered_at));
+        }
+        for (key, (arn, _, _)) in std::mem::take(&mut self.entries) {
+            self.record(&key, RegistryEvent::Unregistered(arn), None);
+        }
+        for (key, (arn, metadata, registered_at)) in restored {
+            self.insert(key, arn, metadata, registered_at);
+        }
+        Ok(())
+    }
+
+    /// Returns the metadata registered for an exact Arn.
+    pub fn get(&self, arn: &Arn) -> Option<&M> {
+        with_canonical(arn, |key| self.entries.get(key)).map(|(_, metadata, _)| metadata)
+    }
+
+    /// Returns when an exact Arn was registered, as a duration since the Unix epoch.
+    pub fn registered_at(&self, arn: &Arn) -> Option<Duration> {
+        with_canonical(arn, |key| self.entries.get(key)).map(|(_, _, registered_at)| *registered_at)
+    }
+
+    /// Returns `true` if the exact Arn is registered.
+    pub fn contains(&self, arn: &Arn) -> bool {
+        with_canonical(arn, |key| self.entries.contains_key(key))
+    }
+
+    /// Returns the number of registered Arns.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if nothing is registered.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterates over all registered Arns and their metadata in lexical order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Arn<'static>, &M)> {
+        self.entries
+            .values()
+            .map(|(arn, metadata, _)| (arn, metadata))
+    }
+
+    /// Validates a whole batch of entries before applying any of them.
+    ///
+    /// Every entry is checked for duplicates within the batch and against the registry, for
+    /// conformance with the validators in `options`, and, when required, for the existence of
+    /// its parent either in the registry or among the accepted entries of the batch. Entries
+    /// whose parent is rejected are rejected in turn.
+    ///
+    /// With [`ImportOptions::atomic`] (the default) nothing is applied unless every entry is
+    /// accepted; otherwise the accepted entries are applied and the rest reported.
+    pub fn import<'a>(
+        &mut self,
+        entries: impl IntoIterator<Item = (Arn<'a>, M)>,
+        options: &ImportOptions,
+    ) -> ImportReport {
+        let mut seen = HashSet::new();
+        let mut candidates = Vec::new();
+        let mut rejected = Vec::new();
+
+        for (index, (arn, metadata)) in entries.into_iter().enumerate() {
+            let arn = arn.into_owned();
+            let reason = if !seen.insert(arn.clone()) {
+                Some(RejectionReason::DuplicateInBatch)
+            } else if options.reject_existing && self.contains(&arn) {
+                Some(RejectionReason::AlreadyRegistered)
+            } else {
+                conformance(&arn, &options.validators)
+                    .err()
+                    .map(RejectionReason::Invalid)
+            };
+            match reason {
+                Some(reason) => rejected.push(ImportRejection { index, arn, reason }),
+                None => candidates.push((index, arn, metadata)),
+            }
+        }
+
+        if options.require_parents {
+            // Visiting shallower entries first means a parent's fate is always known before its
+            // children are checked, so rejections cascade in a single pass.
+            candidates.sort_by_key(|(_, arn, _)| arn.parts.0.len());
+            let mut accepted: HashSet<Arn<'static>> = HashSet::new();
+            let mut kept = Vec::with_capacity(candidates.len());
+            for (index, arn, metadata) in candidates {
+                match arn.parent() {
+                    Some(parent) if !accepted.contains(&parent) && !self.contains(&parent) => {
+                        rejected.push(ImportRejection {
+                            index,
+                            arn,
+                            reason: RejectionReason::MissingParent(Box::new(parent)),
+                        });
+                    }
+                    _ => {
+                        accepted.insert(arn.clone());
+                        kept.push((index, arn, metadata));
+                    }
+                }
+            }
+            kept.sort_by_key(|(index, _, _)| *index);
+            candidates = kept;
+        }
+
+        rejected.sort_by_key(|rejection| rejection.index);
+        let accepted: Vec<Arn<'static>> =
+            candidates.iter().map(|(_, arn, _)| arn.clone()).collect();
+        let applied = rejected.is_empty() || !options.atomic;
+        if applied {
+            let registered_at = self.clock.now();
+            for (_, arn, metadata) in candidates {
+                let key = arn.to_string();
+                if self.entries.contains_key(&key) {
+                    self.record(&key, RegistryEvent::Updated(arn), Some(&metadata));
+                    self.entries.get_mut(&key).expect("checked above").1 = metadata;
+                } else {
+                    self.insert(key, arn, metadata, registered_at);
+                }
+            }
+        }
+        ImportReport {
+            accepted,
+            rejected,
+            applied,
+        }
+    }
+}
+
+/// Runs the import validators against every component of an Arn.
+fn conformance(arn: &Arn, validators: &[Arc<dyn ArnValidator>]) -> Result<(), ArnError> {
+    run_validators(validators, ComponentKind::Domain, arn.domain.as_str())?;
+    run_validators(validators, ComponentKind::Category, arn.category.as_str())?;
+    run_validators(validators, ComponentKind::Account, arn.account.as_str())?;
+    run_validators(validators, ComponentKind::Root, arn.root.as_str())?;
+    for part in &arn.parts.0 {
+        run_validators(validators, ComponentKind::Part, part.as_str())?;
+    }
+    Ok(())
+}
+
+/// Controls how [`ArnRegistry::import`] validates and applies a batch.
+#[derive(Clone)]
+pub struct ImportOptions {
+    atomic: bool,
+    require_parents: bool,
+    reject_existing: bool,
+    validators: Vec<Arc<dyn ArnValidator>>,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        ImportOptions {
+            atomic: true,
+            require_parents: false,
+            reject_existing: true,
+            validators: Vec::new(),
+        }
+    }
+}
+
+impl ImportOptions {
+    /// Creates options for an atomic import that rejects already registered Arns.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether any rejection rolls back the whole batch (default `true`).
+    pub fn atomic(mut self, atomic: bool) -> Self {
+        self.atomic = atomic;
+        self
+    }
+
+    /// Sets whether every entry with parts must have its parent registered or in the batch (default `false`).
+    pub fn require_parents(mut self, require_parents: bool) -> Self {
+        self.require_parents = require_parents;
+        self
+    }
+
+    /// Sets whether entries already in the registry are rejected rather than overwritten (default `true`).
+    ///
+    /// An overwritten entry keeps its registration time, as with [`ArnRegistry::update`], and
+    /// is reported to subscribers and the change log as [`RegistryEvent::Updated`].
+    pub fn reject_existing(mut self, reject_existing: bool) -> Self {
+        self.reject_existing = reject_existing;
+        self
+    }
+
+    /// Adds a validator every component of every entry must satisfy.
+    pub fn with_validator(mut self, validator: impl ArnValidator + 'static) -> Self {
+        self.validators.push(Arc::new(validator));
+        self
+    }
+}
+
+/// Why an entry was rejected by [`ArnRegistry::import`].
+#[derive(Debug, PartialEq)]
+pub enum RejectionReason {
+    /// The same Arn appeared earlier in the batch.
+    DuplicateInBatch,
+    /// The Arn is already registered.
+    AlreadyRegistered,
+    /// A component failed validation.
+    Invalid(ArnError),
+    /// The parent Arn is neither registered nor accepted in the batch.
+    MissingParent(Box<Arn<'static>>),
+}
+
+/// A rejected entry, identified by its position in the imported batch.
+#[derive(Debug, PartialEq)]
+pub struct ImportRejection {
+    pub index: usize,
+    pub arn: Arn<'static>,
+    pub reason: RejectionReason,
+}
+
+/// The outcome of [`ArnRegistry::import`].
+#[derive(Debug, PartialEq)]
+pub struct ImportReport {
+    /// Entries that passed validation, in batch order.
+    pub accepted: Vec<Arn<'static>>,
+    /// Entries that failed validation, in batch order.
+    pub rejected: Vec<ImportRejection>,
+    /// Whether the accepted entries were written to the registry.
+    pub applied: bool,
+}
+
+impl ImportReport {
+    /// Returns `true` if every entry was accepted and applied.
+    pub fn is_clean(&self) -> bool {
+        self.rejected.is_empty() && self.applied
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArnParser;
+
+    fn arn(s: &str) -> Arn<'static> {
+        ArnParser::new(s.to_string()).parse().unwrap()
+    }
+
+    #[test]
+    fn test_register_and_get() -> anyhow::Result<()> {
+        let mut registry = ArnRegistry::new();
+        registry.register(arn("arn:akton:hr:acme:root/team"), "team")?;
+        assert_eq!(
+            registry.get(&arn("arn:akton:hr:acme:root/team")),
+            Some(&"team")
+        );
+        assert!(registry
+            .register(arn("arn:akton:hr:acme:root/team"), "again")
+            .is_err());
+        assert_eq!(
+            registry.unregister(&arn("arn:akton:hr:acme:root/team")),
+            Some("team")
+        );
+        assert!(registry.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_by_prefix_and_pattern() {
+        let mut registry = ArnRegistry::new();
+        for (input, metadata) in [
+            ("arn:akton:hr:acme:root/team", 1),
+            ("arn:akton:hr:acme:root/team/a", 2),
+            ("arn:akton:hr:acme2:root/team", 3),
+            ("arn:akton:it:acme:root/ops", 4),
+        ] {
+            registry.register(arn(input), metadata).unwrap();
+        }
+        let scanned: Vec<i32> = registry
+            .scan_prefix("arn:akton:hr:acme:")
+            .map(|(_, m)| *m)
+            .collect();
+        assert_eq!(scanned, [1, 2]);
+        assert_eq!(registry.scan_prefix("arn:akton:hr:acme").count(), 3);
+        assert_eq!(registry.scan_prefix("arn:nothing").count(), 0);
+
+        let pattern: ArnPattern = "arn:akton:*:*:root/*".parse().unwrap();
+        let matched: Vec<i32> = registry.matching(&pattern).map(|(_, m)| *m).collect();
+        // Lexical order: "acme2:" sorts before "acme:".
+        assert_eq!(matched, [3, 1, 4]);
+    }
+
+    #[test]
+    fn test_subscribers_see_every_change() {
+        let mut registry = ArnRegistry::new();
+        let acme = registry.subscribe("arn:akton:hr:acme:");
+        let all = registry.subscribe("");
+        let team = arn("arn:akton:hr:acme:root/team");
+        let other = arn("arn:akton:hr:other:root/team");
+        registry.register(team.clone(), ()).unwrap();
+        registry.register(other.clone(), ()).unwrap();
+        registry.unregister(&team);
+        registry.import([(team.clone(), ())], &ImportOptions::new());
+        let overwrite = ImportOptions::new().reject_existing(false);
+        assert!(registry.import([(team.clone(), ())], &overwrite).applied);
+        assert_eq!(
+            acme.try_iter().collect::<Vec<_>>(),
+            [
+                RegistryEvent::Registered(team.clone()),
+                RegistryEvent::Unregistered(team.clone()),
+                RegistryEvent::Registered(team.clone()),
+                RegistryEvent::Updated(team),
+            ]
+        );
+        assert_eq!(all.try_iter().count(), 5);
+
+        drop(acme);
+        registry.unregister(&other);
+        assert_eq!(all.try_recv(), Ok(RegistryEvent::Unregistered(other)));
+    }
+
+    #[test]
+    fn test_snapshot_and_restore() {
+        let mut registry = ArnRegistry::new();
+        let a = arn("arn:akton:hr:acme:root/a");
+        let b = arn("arn:akton:hr:acme:root/b");
+        registry.register(a.clone(), "a").unwrap();
+        let snapshot = registry.snapshot();
+        registry.register(b.clone(), "b").unwrap();
+        let events = registry.subscribe("");
+
+        registry.restore(snapshot.clone()).unwrap();
+        assert_eq!(registry.snapshot(), snapshot);
+        assert!(!registry.contains(&b));
+        assert_eq!(
+            events.try_iter().collect::<Vec<_>>(),
+            [
+                RegistryEvent::Unregistered(a.clone()),
+                RegistryEvent::Unregistered(b),
+                RegistryEvent::Registered(a.clone()),
+            ]
+        );
+
+        let duplicate = RegistrySnapshot {
+            entries: vec![(a.clone(), "x"), (a.clone(), "y")],
+        };
+        assert!(registry.restore(duplicate).is_err());
+        assert_eq!(registry.get(&a), Some(&"a"));
+    }
+
+    #[test]
+    fn test_atomic_import_rolls_back_on_rejection() -> anyhow::Result<()> {
+        let mut registry = ArnRegistry::new();
+        registry.register(arn("arn:akton:hr:acme:root"), ())?;
+        let batch = vec![
+            (arn("arn:akton:hr:acme:root/a"), ()),
+            (arn("arn:akton:hr:acme:root/a"), ()),
+            (arn("arn:akton:hr:acme:root"), ()),
+        ];
+        let report = registry.import(batch, &ImportOptions::new());
+        assert!(!report.applied);
+        assert_eq!(report.accepted, vec![arn("arn:akton:hr:acme:root/a")]);
+        assert_eq!(report.rejected.len(), 2);
+        assert_eq!(report.rejected[0].index, 1);
+        assert_eq!(report.rejected[0].reason, RejectionReason::DuplicateInBatch);
+        assert_eq!(
+            report.rejected[1].reason,
+            RejectionReason::AlreadyRegistered
+        );
+        assert_eq!(registry.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_checks_parents_and_cascades() {
+        let mut registry = ArnRegistry::new();
+        let batch = vec![
+            (arn("arn:akton:hr:acme:root/a/b/c"), ()),
+            (arn("arn:akton:hr:acme:root/a/b"), ()),
+            (arn("arn:akton:hr:acme:root/x/y"), ()),
+            (arn("arn:akton:hr:acme:root/a"), ()),
+            (arn("arn:akton:hr:acme:root"), ()),
+        ];
+        let options = ImportOptions::new().require_parents(true).atomic(false);
+        let report = registry.import(batch, &options);
+        assert!(report.applied);
+        assert_eq!(report.rejected.len(), 1);
+        assert_eq!(
+            report.rejected[0].reason,
+            RejectionReason::MissingParent(Box::new(arn("arn:akton:hr:acme:root/x")))
+        );
+        assert_eq!(registry.len(), 4);
+        assert!(registry.contains(&arn("arn:akton:hr:acme:root/a/b/c")));
+    }
+
+    #[test]
+    fn test_import_applies_validators() {
+        let mut registry = ArnRegistry::new();
+        let numeric_account = |kind: ComponentKind, value: &str| {
+            if kind == ComponentKind::Account && !value.chars().all(|c| c.is_ascii_digit()) {
+                Err("account must be numeric".to_string())
+            } else {
+                Ok(())
+            }
+        };
+        let batch = vec![
+            (arn("arn:akton:hr:123:root"), ()),
+            (arn("arn:akton:hr:acme:root"), ()),
+        ];
+        let options = ImportOptions::new()
+            .with_validator(numeric_account)
+            .atomic(false);
+        let report = registry.import(batch, &options);
+        assert_eq!(report.accepted, vec![arn("arn:akton:hr:123:root")]);
+        assert!(matches!(
+            report.rejected[0].reason,
+            RejectionReason::Invalid(ArnError::ValidationFailure(ComponentKind::Account, _))
+        ));
+        assert!(!report.is_clean());
+        assert_eq!(registry.len(), 1);
+    }
+
+    #[test]
+    fn test_import_deep_chain_cascades_in_one_pass() {
+        const DEPTH: usize = 2_000;
+        let mut registry = ArnRegistry::new();
+        let deepest = {
+            let path: Vec<String> = (0..DEPTH).map(|i| format!("p{i}")).collect();
+            ArnParser::new(format!("arn:akton:hr:acme:root/{}", path.join("/")))
+                .with_limits(crate::ArnLimits::unlimited())
+                .parse()
+                .unwrap()
+        };
+        // The chain is missing its shallowest link, so every entry is orphaned.
+        let batch: Vec<_> = deepest
+            .ancestors()
+            .take(DEPTH - 1)
+            .map(|a| (a, ()))
+            .collect();
+        let options = ImportOptions::new().require_parents(true).atomic(false);
+        let report = registry.import(batch, &options);
+        assert!(report.accepted.is_empty());
+        assert_eq!(report.rejected.len(), DEPTH - 1);
+        assert!(registry.is_empty());
+    }
+}
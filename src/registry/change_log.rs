@@ -0,0 +1,239 @@
+use super::{ArnRegistry, RegistryEvent};
+use crate::errors::ArnError;
+
+/// A change recorded in the change log of an [`ArnRegistry`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoggedChange<M> {
+    sequence: u64,
+    event: RegistryEvent,
+    metadata: Option<M>,
+}
+
+impl<M> LoggedChange<M> {
+    /// Returns the position of the change. Every change to the registry takes the next number,
+    /// starting from 1, whether or not it was logged.
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// Returns what changed.
+    pub fn event(&self) -> &RegistryEvent {
+        &self.event
+    }
+
+    /// Returns the new metadata of a registered or updated Arn, or `None` for an unregistered
+    /// one.
+    pub fn metadata(&self) -> Option<&M> {
+        self.metadata.as_ref()
+    }
+}
+
+/// The changes a registry has recorded since its log was enabled or last compacted.
+#[derive(Clone)]
+pub(super) struct ChangeLog<M> {
+    /// The sequence number after which every change is logged.
+    start: u64,
+    changes: Vec<LoggedChange<M>>,
+    /// Copies metadata into the log, captured where `M: Clone` is known.
+    clone: fn(&M) -> M,
+}
+
+impl<M> ChangeLog<M> {
+    pub(super) fn append(&mut self, sequence: u64, event: RegistryEvent, metadata: Option<&M>) {
+        self.changes.push(LoggedChange {
+            sequence,
+            event,
+            metadata: metadata.map(self.clone),
+        });
+    }
+}
+
+impl<M> ArnRegistry<M> {
+    /// Records every later change in an append-only log, for consumers that keep a copy of the
+    /// registry up to date with [`changes_since`](ArnRegistry::changes_since) and
+    /// [`replay`](ArnRegistry::replay) instead of polling it.
+    ///
+    /// A consumer starts from a [`snapshot`](ArnRegistry::snapshot) and the
+    /// [`sequence`](ArnRegistry::sequence) it was taken at, then replays the changes after it:
+    ///
+    /// ```
+    /// use akton_arn::{ArnParser, ArnRegistry};
+    ///
+    /// let team = ArnParser::new("arn:akton:hr:acme:root/team").parse().unwrap();
+    /// let mut source = ArnRegistry::new().with_change_log();
+    /// source.register(team.clone(), 1).unwrap();
+    ///
+    /// let mut cache = ArnRegistry::new();
+    /// cache.restore(source.snapshot()).unwrap();
+    /// let seen = source.sequence();
+    ///
+    /// source.update(&team, 2).unwrap();
+    /// cache.replay(source.changes_since(seen).unwrap()).unwrap();
+    /// assert_eq!(cache.get(&team), Some(&2));
+    /// ```
+    pub fn with_change_log(mut self) -> Self
+    where
+        M: Clone,
+    {
+        self.log = Some(ChangeLog {
+            start: self.sequence,
+            changes: Vec::new(),
+            clone: M::clone,
+        });
+        self
+    }
+
+    /// Returns the sequence number of the latest change, or 0 if nothing has changed.
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// Returns the logged changes after `sequence`, oldest first.
+    ///
+    /// Returns `None` if the log does not reach back that far, because it was enabled or
+    /// [compacted](ArnRegistry::compact_change_log) later or is not enabled at all; the
+    /// consumer has missed changes and must start again from a snapshot.
+    pub fn changes_since(&self, sequence: u64) -> Option<&[LoggedChange<M>]> {
+        let log = self.log.as_ref().filter(|log| sequence >= log.start)?;
+        let first = log
+            .changes
+            .partition_point(|change| change.sequence <= sequence);
+        Some(&log.changes[first..])
+    }
+
+    /// Drops the logged changes up to and including `sequence`, once every consumer has seen
+    /// them.
+    pub fn compact_change_log(&mut self, sequence: u64) {
+        let current = self.sequence;
+        if let Some(log) = &mut self.log {
+            let first = log
+                .changes
+                .partition_point(|change| change.sequence <= sequence);
+            log.changes.drain(..first);
+            log.start = log.start.max(sequence.min(current));
+        }
+    }
+
+    /// Applies changes logged by another registry, in order, notifying this registry's
+    /// subscribers and logging them in its own sequence.
+    ///
+    /// Replayed registrations take this registry's current time. Replaying a registration of
+    /// an Arn already present fails with [`ArnError::AlreadyRegistered`], and an update or
+    /// removal of an Arn not present with [`ArnError::NotRegistered`]; either means the two
+    /// registries have diverged. The changes before the failing one stay applied.
+    pub fn replay<'c>(
+        &mut self,
+        changes: impl IntoIterator<Item = &'c LoggedChange<M>>,
+    ) -> Result<(), ArnError>
+    where
+        M: Clone + 'c,
+    {
+        for change in changes {
+            match (&change.event, &change.metadata) {
+                (RegistryEvent::Registered(arn), Some(metadata)) => {
+                    self.register(arn.clone(), metadata.clone())?;
+                }
+                (RegistryEvent::Updated(arn), Some(metadata)) => {
+                    self.update(arn, metadata.clone())?;
+                }
+                (RegistryEvent::Unregistered(arn), None) => {
+                    self.unregister(arn)
+                        .ok_or_else(|| ArnError::NotRegistered(arn.to_string()))?;
+                }
+                _ => unreachable!("the log pairs metadata with every registration and update"),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Arn;
+    use crate::registry::ImportOptions;
+    use crate::ArnParser;
+
+    fn arn(s: &str) -> Arn<'static> {
+        ArnParser::new(s.to_string()).parse().unwrap()
+    }
+
+    #[test]
+    fn test_log_numbers_every_change() {
+        let a = arn("arn:akton:hr:acme:root/a");
+        let b = arn("arn:akton:hr:acme:root/b");
+        let mut registry = ArnRegistry::new();
+        registry.register(a.clone(), "a").unwrap();
+        assert_eq!(registry.changes_since(0), None);
+
+        let mut registry = registry.with_change_log();
+        registry.register(b.clone(), "b").unwrap();
+        assert_eq!(registry.update(&b, "b2"), Ok("b"));
+        assert_eq!(
+            registry.update(&arn("arn:akton:hr:acme:root/c"), "c"),
+            Err(ArnError::NotRegistered(
+                "arn:akton:hr:acme:root/c".to_string()
+            ))
+        );
+        registry.unregister(&a);
+
+        let changes = registry.changes_since(1).unwrap();
+        let summary: Vec<_> = changes
+            .iter()
+            .map(|change| {
+                (
+                    change.sequence(),
+                    change.event().clone(),
+                    change.metadata().copied(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (2, RegistryEvent::Registered(b.clone()), Some("b")),
+                (3, RegistryEvent::Updated(b), Some("b2")),
+                (4, RegistryEvent::Unregistered(a), None),
+            ]
+        );
+        assert_eq!(registry.changes_since(0), None);
+        assert_eq!(registry.changes_since(3).unwrap().len(), 1);
+        assert!(registry.changes_since(9).unwrap().is_empty());
+
+        registry.compact_change_log(3);
+        assert_eq!(registry.changes_since(2), None);
+        assert_eq!(registry.changes_since(3).unwrap()[0].sequence(), 4);
+    }
+
+    #[test]
+    fn test_replay_keeps_a_replica_consistent() {
+        let mut source = ArnRegistry::new().with_change_log();
+        let mut replica = ArnRegistry::new();
+        let mut seen = source.sequence();
+        for round in 0..3 {
+            let entry = arn(&format!("arn:akton:hr:acme:root/{round}"));
+            source.register(entry.clone(), round).unwrap();
+            source.update(&entry, round * 10).unwrap();
+            if round == 1 {
+                source.unregister(&arn("arn:akton:hr:acme:root/0"));
+            }
+            replica.replay(source.changes_since(seen).unwrap()).unwrap();
+            seen = source.sequence();
+            assert_eq!(replica.snapshot(), source.snapshot());
+        }
+
+        // Overwriting an entry through an import is logged as an update, which replays.
+        let entry = arn("arn:akton:hr:acme:root/1");
+        let overwrite = ImportOptions::new().reject_existing(false);
+        assert!(source.import([(entry.clone(), 99)], &overwrite).applied);
+        replica.replay(source.changes_since(seen).unwrap()).unwrap();
+        assert_eq!(replica.get(&entry), Some(&99));
+        assert_eq!(replica.snapshot(), source.snapshot());
+
+        let stale = source.changes_since(0).unwrap().to_vec();
+        assert!(matches!(
+            replica.replay(&stale),
+            Err(ArnError::AlreadyRegistered(_))
+        ));
+    }
+}
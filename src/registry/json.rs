@@ -0,0 +1,260 @@
+use super::ArnRegistry;
+use crate::errors::ArnError;
+use crate::model::Arn;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Identifies a registry document, so other JSON handed to
+/// [`ArnRegistry::import_json`] is rejected.
+const FORMAT: &str = "akton-arn-registry";
+
+/// The version of the document layout [`ArnRegistry::export_json`] writes.
+///
+/// Bump it whenever a field changes meaning or a required field is added, and keep importing
+/// every earlier version.
+pub const REGISTRY_JSON_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct Document<E> {
+    format: String,
+    version: u32,
+    /// Milliseconds since the Unix epoch.
+    exported_at: u64,
+    entries: Vec<E>,
+}
+
+#[derive(Serialize)]
+struct ExportedEntry<'r, M> {
+    arn: &'r Arn<'static>,
+    /// Milliseconds since the Unix epoch.
+    registered_at: u64,
+    metadata: &'r M,
+}
+
+#[derive(Deserialize)]
+struct ImportedEntry<M> {
+    arn: Arn<'static>,
+    registered_at: u64,
+    metadata: M,
+}
+
+#[derive(Deserialize)]
+struct Header {
+    format: String,
+    version: u32,
+}
+
+fn millis(time: Duration) -> u64 {
+    u64::try_from(time.as_millis()).unwrap_or(u64::MAX)
+}
+
+fn decode_error(e: serde_json::Error) -> ArnError {
+    ArnError::DecodeFailure(e.to_string())
+}
+
+fn encode_error(e: serde_json::Error) -> ArnError {
+    ArnError::EncodeFailure(e.to_string())
+}
+
+impl<M> ArnRegistry<M> {
+    /// Writes every entry to a versioned JSON document, for backups, bootstrapping replicas
+    /// and inspecting a registry by eye.
+    ///
+    /// The document names its format and [version](REGISTRY_JSON_VERSION), records when it
+    /// was exported, and lists the entries in lexical order, each with its metadata and the
+    /// time it was registered. Times are milliseconds since the Unix epoch, so exporting the
+    /// same registry at the same time always yields the same text:
+    ///
+    /// ```json
+    /// {
+    ///   "format": "akton-arn-registry",
+    ///   "version": 1,
+    ///   "exported_at": 1700000000000,
+    ///   "entries": [
+    ///     { "arn": "arn:akton:hr:acme:root/team", "registered_at": 1690000000000, "metadata": "team" }
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// Times are truncated to whole milliseconds, so an entry imported from the document
+    /// reports a [registration time](ArnRegistry::registered_at) up to a millisecond earlier
+    /// than the original when its clock is finer than that.
+    ///
+    /// Fails with [`ArnError::EncodeFailure`] if the metadata cannot be written as JSON.
+    pub fn export_json(&self) -> Result<String, ArnError>
+    where
+        M: Serialize,
+    {
+        let document = Document {
+            format: FORMAT.to_string(),
+            version: REGISTRY_JSON_VERSION,
+            exported_at: millis(self.clock.now()),
+            entries: self
+                .entries
+                .values()
+                .map(|(arn, metadata, registered_at)| ExportedEntry {
+                    arn,
+                    registered_at: millis(*registered_at),
+                    metadata,
+                })
+                .collect(),
+        };
+        serde_json::to_string_pretty(&document).map_err(encode_error)
+    }
+
+    /// Replaces every entry with those of a document written by
+    /// [`export_json`](ArnRegistry::export_json), keeping their registration times.
+    ///
+    /// Subscribers are notified as [`restore`](ArnRegistry::restore) describes. Fails with
+    /// [`ArnError::DecodeFailure`] if the text is not a registry document, comes from a newer
+    /// version of the crate, or holds an invalid Arn or metadata, and with
+    /// [`ArnError::AlreadyRegistered`] if it holds an Arn twice. The registry is unchanged
+    /// when it fails.
+    ///
+    /// ```
+    /// use akton_arn::{ArnParser, ArnRegistry};
+    ///
+    /// let mut registry = ArnRegistry::new();
+    /// let team = ArnParser::new("arn:akton:hr:acme:root/team").parse().unwrap();
+    /// registry.register(team.clone(), "team".to_string()).unwrap();
+    ///
+    /// let mut replica = ArnRegistry::<String>::new();
+    /// replica.import_json(&registry.export_json().unwrap()).unwrap();
+    /// assert_eq!(replica.get(&team), Some(&"team".to_string()));
+    /// ```
+    pub fn import_json(&mut self, json: &str) -> Result<(), ArnError>
+    where
+        M: DeserializeOwned,
+    {
+        let header: Header = serde_json::from_str(json).map_err(decode_error)?;
+        if header.format != FORMAT {
+            return Err(ArnError::DecodeFailure(format!(
+                "expected a {FORMAT:?} document, found {:?}",
+                header.format
+            )));
+        }
+        if header.version > REGISTRY_JSON_VERSION {
+            return Err(ArnError::DecodeFailure(format!(
+                "registry document version {} is newer than the supported version {REGISTRY_JSON_VERSION}",
+                header.version
+            )));
+        }
+        let document: Document<ImportedEntry<M>> =
+            serde_json::from_str(json).map_err(decode_error)?;
+        self.replace(document.entries.into_iter().map(|entry| {
+            (
+                entry.arn,
+                entry.metadata,
+                Duration::from_millis(entry.registered_at),
+            )
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ArnParser, SteppedClock};
+
+    fn arn(s: &str) -> Arn<'static> {
+        ArnParser::new(s.to_string()).parse().unwrap()
+    }
+
+    #[test]
+    fn test_export_is_stable_and_round_trips() {
+        let clock = SteppedClock::new(Duration::from_secs(1_700_000_000), Duration::from_secs(1));
+        let mut registry = ArnRegistry::new().with_time_source(clock);
+        registry
+            .register(
+                arn("arn:akton:hr:acme:root/b"),
+                serde_json::json!({ "n": 2 }),
+            )
+            .unwrap();
+        registry
+            .register(arn("arn:akton:hr:acme:root/a"), serde_json::json!(null))
+            .unwrap();
+        let exported = registry.export_json().unwrap();
+        let document: serde_json::Value = serde_json::from_str(&exported).unwrap();
+        assert_eq!(
+            document,
+            serde_json::json!({
+                "format": "akton-arn-registry",
+                "version": 1,
+                "exported_at": 1_700_000_002_000u64,
+                "entries": [
+                    { "arn": "arn:akton:hr:acme:root/a", "registered_at": 1_700_000_001_000u64, "metadata": null },
+                    { "arn": "arn:akton:hr:acme:root/b", "registered_at": 1_700_000_000_000u64, "metadata": { "n": 2 } },
+                ]
+            })
+        );
+
+        let mut replica = ArnRegistry::new();
+        let events = replica.subscribe("");
+        replica.import_json(&exported).unwrap();
+        assert_eq!(replica.snapshot(), registry.snapshot());
+        assert_eq!(
+            replica.registered_at(&arn("arn:akton:hr:acme:root/b")),
+            Some(Duration::from_secs(1_700_000_000))
+        );
+        assert_eq!(events.try_iter().count(), 2);
+    }
+
+    #[test]
+    fn test_export_truncates_times_to_milliseconds() {
+        let clock = SteppedClock::new(Duration::new(1_700_000_000, 1_999_999), Duration::ZERO);
+        let mut registry = ArnRegistry::new().with_time_source(clock);
+        let team = arn("arn:akton:hr:acme:root/team");
+        registry.register(team.clone(), ()).unwrap();
+        let mut replica = ArnRegistry::<()>::new();
+        replica
+            .import_json(&registry.export_json().unwrap())
+            .unwrap();
+        assert_eq!(
+            replica.registered_at(&team),
+            Some(Duration::new(1_700_000_000, 1_000_000))
+        );
+    }
+
+    #[test]
+    fn test_export_reports_unwritable_metadata_as_encode_failure() {
+        let mut registry = ArnRegistry::new();
+        let metadata = std::collections::HashMap::from([((1, 2), ())]);
+        registry
+            .register(arn("arn:akton:hr:acme:root"), metadata)
+            .unwrap();
+        assert!(matches!(
+            registry.export_json(),
+            Err(ArnError::EncodeFailure(_))
+        ));
+    }
+
+    #[test]
+    fn test_import_rejects_foreign_and_newer_documents() {
+        let mut registry: ArnRegistry<()> = ArnRegistry::new();
+        registry
+            .register(arn("arn:akton:hr:acme:root"), ())
+            .unwrap();
+        for json in [
+            "[]",
+            r#"{ "format": "other", "version": 1, "exported_at": 0, "entries": [] }"#,
+            r#"{ "format": "akton-arn-registry", "version": 2, "exported_at": 0, "entries": [] }"#,
+            r#"{ "format": "akton-arn-registry", "version": 1, "exported_at": 0,
+                 "entries": [{ "arn": "not-an-arn", "registered_at": 0, "metadata": null }] }"#,
+        ] {
+            assert!(matches!(
+                registry.import_json(json),
+                Err(ArnError::DecodeFailure(_))
+            ));
+        }
+        let duplicate = r#"{ "format": "akton-arn-registry", "version": 1, "exported_at": 0, "entries": [
+            { "arn": "arn:akton:hr:acme:root/a", "registered_at": 0, "metadata": null },
+            { "arn": "arn:akton:hr:acme:root/a", "registered_at": 0, "metadata": null }
+        ] }"#;
+        assert!(matches!(
+            registry.import_json(duplicate),
+            Err(ArnError::AlreadyRegistered(_))
+        ));
+        assert_eq!(registry.len(), 1);
+    }
+}
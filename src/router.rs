@@ -0,0 +1,365 @@
+use crate::errors::ArnError;
+use crate::format::ArnFormat;
+use crate::model::Arn;
+use std::collections::HashMap;
+
+/// Maps Arn patterns to handlers and resolves the most specific route for an Arn.
+///
+/// Route patterns use the Arn layout, `arn:<domain>:<category>:<account>:<root>[/<parts>]`,
+/// where each segment is one of:
+///
+/// - a literal, matching only that value;
+/// - `*`, matching any single value;
+/// - `{name}`, matching any single value and capturing it as `name`;
+/// - `**`, only as the last part, matching any number of remaining parts, including none.
+///
+/// Routes are stored in a trie keyed by segment, so resolution walks the Arn once. At each
+/// segment a literal beats `*` or `{name}`, which beats `**`; when a more specific branch
+/// fails further down, the next one is tried.
+///
+/// ```
+/// use akton_arn::{ArnParser, ArnRouter};
+///
+/// let mut router = ArnRouter::new();
+/// router.insert("arn:akton:shop:{account}:store/orders/{order_id}", "order").unwrap();
+/// router.insert("arn:akton:shop:*:store/**", "store").unwrap();
+///
+/// let arn = ArnParser::new("arn:akton:shop:acme:store/orders/42").parse().unwrap();
+/// let route = router.resolve(&arn).unwrap();
+/// assert_eq!(*route.handler, "order");
+/// assert_eq!(route.captures["order_id"], "42");
+///
+/// let arn = ArnParser::new("arn:akton:shop:acme:store/customers").parse().unwrap();
+/// assert_eq!(*router.resolve(&arn).unwrap().handler, "store");
+/// ```
+///
+/// Patterns are read in the canonical grammar unless [`ArnRouter::with_format`] sets another.
+#[derive(Debug, Clone)]
+pub struct ArnRouter<H> {
+    root: Node<H>,
+    len: usize,
+    /// The grammar route patterns are written in.
+    format: ArnFormat,
+}
+
+/// The handler and captured values of a resolved route.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteMatch<'r, H> {
+    /// The pattern the route was registered with.
+    pub pattern: &'r str,
+    pub handler: &'r H,
+    /// Values of the route's `{name}` segments.
+    pub captures: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone)]
+struct Node<H> {
+    literals: HashMap<String, Node<H>>,
+    /// The child for `*` and `{name}` segments.
+    wildcard: Option<Box<Node<H>>>,
+    /// A route ending at this segment.
+    route: Option<Route<H>>,
+    /// A route ending in `**` after this segment.
+    rest: Option<Route<H>>,
+}
+
+#[derive(Debug, Clone)]
+struct Route<H> {
+    pattern: String,
+    handler: H,
+    /// Segment positions of `{name}` captures.
+    captures: Vec<(usize, String)>,
+}
+
+/// A parsed route segment.
+enum Segment<'p> {
+    Literal(&'p str),
+    Wildcard(Option<&'p str>),
+    Rest,
+}
+
+impl<H> Default for Node<H> {
+    fn default() -> Self {
+        Node {
+            literals: HashMap::new(),
+            wildcard: None,
+            route: None,
+            rest: None,
+        }
+    }
+}
+
+impl<H> Default for ArnRouter<H> {
+    fn default() -> Self {
+        ArnRouter {
+            root: Node::default(),
+            len: 0,
+            format: ArnFormat::DEFAULT,
+        }
+    }
+}
+
+impl<H> ArnRouter<H> {
+    /// Creates an empty router.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads the patterns passed to [`ArnRouter::insert`] in `format` instead of the
+    /// canonical grammar. Arns are resolved the same way whichever format they were read in.
+    ///
+    /// ```
+    /// use akton_arn::{ArnFormat, ArnParser, ArnRouter};
+    ///
+    /// let legacy = ArnFormat::new().with_component_separator('|').unwrap();
+    /// let mut router = ArnRouter::new().with_format(legacy);
+    /// router.insert("arn|akton|shop|{account}|store/**", "store").unwrap();
+    /// let arn = ArnParser::new("arn:akton:shop:acme:store/orders").parse().unwrap();
+    /// assert_eq!(router.resolve(&arn).unwrap().captures["account"], "acme");
+    /// ```
+    pub fn with_format(mut self, format: ArnFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Returns the number of registered routes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Registers `handler` for `pattern`.
+    ///
+    /// Fails with [`ArnError::AlreadyRegistered`] if a route with the same shape exists, even
+    /// when its captures are named differently, since the two could never be told apart.
+    pub fn insert(&mut self, pattern: &str, handler: H) -> Result<(), ArnError> {
+        let segments = parse_route(pattern, &self.format)?;
+        let mut node = &mut self.root;
+        let mut captures = Vec::new();
+        let mut rest = false;
+        for (index, segment) in segments.into_iter().enumerate() {
+            node = match segment {
+                Segment::Literal(value) => node.literals.entry(value.to_string()).or_default(),
+                Segment::Wildcard(name) => {
+                    if let Some(name) = name {
+                        captures.push((index, name.to_string()));
+                    }
+                    node.wildcard.get_or_insert_with(Default::default)
+                }
+                Segment::Rest => {
+                    rest = true;
+                    break;
+                }
+            };
+        }
+        let slot = if rest {
+            &mut node.rest
+        } else {
+            &mut node.route
+        };
+        if slot.is_some() {
+            return Err(ArnError::AlreadyRegistered(pattern.to_string()));
+        }
+        *slot = Some(Route {
+            pattern: pattern.to_string(),
+            handler,
+            captures,
+        });
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Returns the most specific route matching `arn`, if any.
+    pub fn resolve(&self, arn: &Arn) -> Option<RouteMatch<'_, H>> {
+        let segments: Vec<&str> = [
+            arn.domain.as_str(),
+            arn.category.as_str(),
+            arn.account.as_str(),
+            arn.root.as_str(),
+        ]
+        .into_iter()
+        .chain(arn.parts.0.iter().map(|part| part.as_str()))
+        .collect();
+        let route = find(&self.root, &segments)?;
+        Some(RouteMatch {
+            pattern: &route.pattern,
+            handler: &route.handler,
+            captures: route
+                .captures
+                .iter()
+                .map(|(index, name)| (name.clone(), segments[*index].to_string()))
+                .collect(),
+        })
+    }
+}
+
+/// Walks the trie, preferring literal, then wildcard, then `**` branches.
+fn find<'r, H>(node: &'r Node<H>, segments: &[&str]) -> Option<&'r Route<H>> {
+    let Some((first, rest)) = segments.split_first() else {
+        return node.route.as_ref().or(node.rest.as_ref());
+    };
+    node.literals
+        .get(*first)
+        .and_then(|child| find(child, rest))
+        .or_else(|| node.wildcard.as_deref().and_then(|child| find(child, rest)))
+        .or(node.rest.as_ref())
+}
+
+fn parse_route<'p>(pattern: &'p str, format: &ArnFormat) -> Result<Vec<Segment<'p>>, ArnError> {
+    let invalid =
+        |reason: &str| ArnError::ParseFailure("ArnRouter", format!("{reason} in {pattern:?}"));
+    let raw = format.split_segments(pattern)?;
+    if raw.len() < 4 {
+        return Err(ArnError::InvalidFormat);
+    }
+    let last = raw.len() - 1;
+    raw.iter()
+        .enumerate()
+        .map(|(index, segment)| match *segment {
+            "**" if index == last && index > 3 => Ok(Segment::Rest),
+            "**" => Err(invalid("'**' is only allowed as the last part")),
+            "*" => Ok(Segment::Wildcard(None)),
+            "" => Err(invalid("empty segment")),
+            segment => match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                Some(name)
+                    if !name.is_empty()
+                        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') =>
+                {
+                    Ok(Segment::Wildcard(Some(name)))
+                }
+                _ if segment.contains(['{', '}', '*']) => Err(invalid(
+                    "captures must be a whole segment named [A-Za-z0-9_]",
+                )),
+                _ => Ok(Segment::Literal(segment)),
+            },
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arn(s: &str) -> Arn<'static> {
+        s.parse().unwrap()
+    }
+
+    fn router(patterns: &[&'static str]) -> ArnRouter<&'static str> {
+        let mut router = ArnRouter::new();
+        for pattern in patterns {
+            router.insert(pattern, *pattern).unwrap();
+        }
+        router
+    }
+
+    fn resolve(router: &ArnRouter<&'static str>, input: &str) -> Option<&'static str> {
+        router.resolve(&arn(input)).map(|route| *route.handler)
+    }
+
+    #[test]
+    fn test_most_specific_route_wins() {
+        let router = router(&[
+            "arn:akton:hr:acme:root/teams/lead",
+            "arn:akton:hr:acme:root/teams/{team}",
+            "arn:akton:hr:acme:root/**",
+            "arn:*:*:*:*/**",
+            "arn:akton:hr:acme:root",
+        ]);
+        assert_eq!(router.len(), 5);
+        let cases = [
+            (
+                "arn:akton:hr:acme:root/teams/lead",
+                "arn:akton:hr:acme:root/teams/lead",
+            ),
+            (
+                "arn:akton:hr:acme:root/teams/ops",
+                "arn:akton:hr:acme:root/teams/{team}",
+            ),
+            (
+                "arn:akton:hr:acme:root/teams/ops/x",
+                "arn:akton:hr:acme:root/**",
+            ),
+            ("arn:akton:hr:acme:root/teams", "arn:akton:hr:acme:root/**"),
+            ("arn:akton:hr:acme:root", "arn:akton:hr:acme:root"),
+            ("arn:akton:hr:other:root/a", "arn:*:*:*:*/**"),
+            ("arn:x:y:z:w", "arn:*:*:*:*/**"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(resolve(&router, input), Some(expected), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_backtracks_from_failed_literal_branch() {
+        let router = router(&["arn:akton:hr:acme:root/a/b", "arn:akton:hr:*:root/a/c"]);
+        assert_eq!(
+            resolve(&router, "arn:akton:hr:acme:root/a/c"),
+            Some("arn:akton:hr:*:root/a/c")
+        );
+        assert_eq!(resolve(&router, "arn:akton:hr:acme:root/a/d"), None);
+        assert_eq!(resolve(&router, "arn:akton:hr:acme:root/a"), None);
+    }
+
+    #[test]
+    fn test_captures() {
+        let mut router = ArnRouter::new();
+        router
+            .insert("arn:{domain}:hr:*:{root}/{team}/**", ())
+            .unwrap();
+        let route = router
+            .resolve(&arn("arn:akton:hr:acme:root/ops/a/b"))
+            .unwrap();
+        assert_eq!(route.pattern, "arn:{domain}:hr:*:{root}/{team}/**");
+        assert_eq!(
+            route.captures,
+            HashMap::from([
+                ("domain".to_string(), "akton".to_string()),
+                ("root".to_string(), "root".to_string()),
+                ("team".to_string(), "ops".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_insert_errors() {
+        let mut router = router(&["arn:akton:hr:{account}:root"]);
+        assert_eq!(
+            router.insert("arn:akton:hr:{other}:root", "dup"),
+            Err(ArnError::AlreadyRegistered(
+                "arn:akton:hr:{other}:root".to_string()
+            ))
+        );
+        for pattern in [
+            "arn:akton:hr",
+            "arn:**:hr:acme:root",
+            "arn:akton:hr:acme:root/**/a",
+            "arn:akton:hr:acme:root/a-{b}",
+            "arn:akton:hr:acme:root/{}",
+            "arn:akton:hr:acme:root//a",
+        ] {
+            assert!(router.insert(pattern, "bad").is_err(), "{pattern}");
+        }
+        assert_eq!(router.len(), 1);
+    }
+
+    #[test]
+    fn test_patterns_follow_the_format() {
+        let mut router = router(&["ern:akton:hr:{account}:root"]);
+        let route = router.resolve(&arn("arn:akton:hr:acme:root")).unwrap();
+        assert_eq!(route.captures["account"], "acme");
+        assert!(router.insert("urn:akton:hr:acme:root", "bad").is_err());
+
+        let legacy = ArnFormat::new().with_part_separator('.').unwrap();
+        let mut router = ArnRouter::new().with_format(legacy);
+        router
+            .insert("arn:akton:hr:acme:root.teams.{team}", "team")
+            .unwrap();
+        let route = router
+            .resolve(&arn("arn:akton:hr:acme:root/teams/ops"))
+            .unwrap();
+        assert_eq!(route.captures["team"], "ops");
+    }
+}
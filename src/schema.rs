@@ -0,0 +1,86 @@
+//! `schemars` support, enabled with the `schemars` feature.
+//!
+//! Arns and their components are described as strings with a documented regex pattern, so
+//! OpenAPI documents generated from the schemas show their format instead of opaque objects.
+
+use crate::model::{Account, Arn, Category, Domain, Part, Root};
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{InstanceType, Metadata, Schema, SchemaObject, StringValidation};
+use schemars::JsonSchema;
+use std::borrow::Cow;
+
+/// Pattern accepted by [`ArnParser`](crate::ArnParser).
+const ARN_PATTERN: &str =
+    "^arn:[^:?]+:[^:?]*:[^:?]*:[^/?@]*(/[^:/?@]+)*(@v[0-9]+)?(\\?[^&=]+=[^&]*(&[^&=]+=[^&]*)*)?$";
+
+fn string_schema(description: &str, pattern: &str) -> Schema {
+    SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        metadata: Some(Box::new(Metadata {
+            description: Some(description.to_string()),
+            ..Default::default()
+        })),
+        string: Some(Box::new(StringValidation {
+            pattern: Some(pattern.to_string()),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+    .into()
+}
+
+macro_rules! impl_json_schema {
+    ($type:ident, $description:expr, $pattern:expr) => {
+        impl JsonSchema for $type<'_> {
+            fn schema_name() -> String {
+                stringify!($type).to_string()
+            }
+
+            fn schema_id() -> Cow<'static, str> {
+                Cow::Borrowed(concat!("akton_arn::", stringify!($type)))
+            }
+
+            fn json_schema(_: &mut SchemaGenerator) -> Schema {
+                string_schema($description, $pattern)
+            }
+        }
+    };
+}
+
+impl_json_schema!(
+    Arn,
+    "Akton Resource Name: `arn:<domain>:<category>:<account>:<root>[/<part>...]`.",
+    ARN_PATTERN
+);
+impl_json_schema!(Domain, "Domain component of an Arn.", "^[^:]+$");
+impl_json_schema!(Category, "Category component of an Arn.", "^[^:]*$");
+impl_json_schema!(Account, "Account component of an Arn.", "^[^:]*$");
+impl_json_schema!(Root, "Root component of an Arn.", "^[^/]*$");
+impl_json_schema!(Part, "A single path part of an Arn.", "^[^:/]+$");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use schemars::schema_for;
+
+    #[test]
+    fn test_arn_schema_is_patterned_string() {
+        let schema = serde_json::to_value(schema_for!(Arn)).unwrap();
+        assert_eq!(schema["type"], "string");
+        assert_eq!(schema["pattern"], ARN_PATTERN);
+        assert_eq!(schema["title"], "Arn");
+    }
+
+    #[test]
+    fn test_arn_schema_is_referenced_from_containers() {
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        struct Resource {
+            arn: Arn<'static>,
+            parts: Vec<Part<'static>>,
+        }
+        let schema = serde_json::to_value(schema_for!(Resource)).unwrap();
+        assert_eq!(schema["properties"]["arn"]["$ref"], "#/definitions/Arn");
+        assert_eq!(schema["definitions"]["Part"]["pattern"], "^[^:/]+$");
+    }
+}
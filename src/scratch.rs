@@ -0,0 +1,100 @@
+#[cfg(feature = "scratch")]
+use std::cell::RefCell;
+
+/// Most buffers kept per thread; nested [`with_scratch`] calls beyond this allocate and drop.
+#[cfg(feature = "scratch")]
+const MAX_POOLED: usize = 4;
+/// Buffers that grew beyond this capacity are dropped instead of pooled, bounding idle memory.
+#[cfg(feature = "scratch")]
+const MAX_POOLED_CAPACITY: usize = 16 * 1024;
+
+#[cfg(feature = "scratch")]
+thread_local! {
+    static POOL: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Runs `f` with an empty scratch `String`, for formatting Arns without a fresh allocation each time.
+///
+/// With the `scratch` feature, buffers come from a small thread-local pool and are returned to
+/// it afterwards, so formatting thousands of Arns reuses the same few allocations. Without it,
+/// every call gets a new `String`. Calls may nest; the buffer is empty on entry either way.
+///
+/// ```
+/// use std::fmt::Write;
+/// use akton_arn::{with_scratch, ArnParser};
+///
+/// let arn = ArnParser::new("arn:akton:hr:acme:root/team").parse().unwrap();
+/// let len = with_scratch(|buf| {
+///     write!(buf, "{arn}").unwrap();
+///     buf.len()
+/// });
+/// assert_eq!(len, 27);
+/// ```
+pub fn with_scratch<R>(f: impl FnOnce(&mut String) -> R) -> R {
+    #[cfg(feature = "scratch")]
+    {
+        let mut buf = POOL
+            .try_with(|pool| pool.borrow_mut().pop())
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        let result = f(&mut buf);
+        if buf.capacity() <= MAX_POOLED_CAPACITY {
+            buf.clear();
+            let _ = POOL.try_with(|pool| {
+                let mut pool = pool.borrow_mut();
+                if pool.len() < MAX_POOLED {
+                    pool.push(buf);
+                }
+            });
+        }
+        result
+    }
+    #[cfg(not(feature = "scratch"))]
+    {
+        f(&mut String::new())
+    }
+}
+
+/// Runs `f` with the canonical string form of `arn` rendered into a scratch buffer.
+pub(crate) fn with_canonical<R>(arn: &impl std::fmt::Display, f: impl FnOnce(&str) -> R) -> R {
+    with_scratch(|buf| {
+        use std::fmt::Write;
+        write!(buf, "{arn}").expect("formatting into a String cannot fail");
+        f(buf)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scratch_is_empty_and_nests() {
+        let outer = with_scratch(|outer| {
+            outer.push_str("outer");
+            let inner = with_scratch(|inner| {
+                assert!(inner.is_empty());
+                inner.push_str("inner");
+                inner.clone()
+            });
+            format!("{outer}+{inner}")
+        });
+        assert_eq!(outer, "outer+inner");
+        with_scratch(|buf| assert!(buf.is_empty()));
+    }
+
+    #[cfg(feature = "scratch")]
+    #[test]
+    fn test_scratch_reuses_buffers() {
+        let first = with_scratch(|buf| {
+            buf.reserve(64);
+            buf.as_ptr()
+        });
+        let second = with_scratch(|buf| {
+            assert!(buf.capacity() >= 64);
+            buf.as_ptr()
+        });
+        assert_eq!(first, second);
+    }
+}
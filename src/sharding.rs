@@ -0,0 +1,220 @@
+use crate::format::{ArnFormat, CANONICAL_SCHEME};
+use crate::model::Arn;
+use crate::scratch::with_scratch;
+use crate::stable_hash::stable_hash_bytes;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+/// Selects which components of an Arn decide its placement.
+///
+/// Coarser keys keep related resources together: with [`ShardKey::Account`] every resource of
+/// an account lands on the same shard, with [`ShardKey::Root`] every resource under a root does.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum ShardKey {
+    /// The full canonical Arn, spreading individual resources across shards.
+    #[default]
+    Full,
+    /// Domain, category, account and root.
+    Root,
+    /// Domain, category and account.
+    Account,
+}
+
+impl ShardKey {
+    /// Returns the stable hash of the components of `arn` selected by this key.
+    ///
+    /// The components are hashed in their canonical form, whatever scheme or [`ArnFormat`] the
+    /// Arn was read with, so the same resource always lands on the same shard.
+    pub fn hash(&self, arn: &Arn) -> u64 {
+        let components = match self {
+            ShardKey::Full => return arn.stable_hash(),
+            ShardKey::Root => &[
+                arn.domain.as_str(),
+                arn.category.as_str(),
+                arn.account.as_str(),
+                arn.root.as_str(),
+            ][..],
+            ShardKey::Account => &[
+                arn.domain.as_str(),
+                arn.category.as_str(),
+                arn.account.as_str(),
+            ][..],
+        };
+        with_scratch(|key| {
+            let separator = ArnFormat::DEFAULT.component_separator();
+            key.push_str(CANONICAL_SCHEME);
+            for component in components {
+                key.push(separator);
+                key.push_str(component);
+            }
+            stable_hash_bytes(key.as_bytes())
+        })
+    }
+}
+
+impl Arn<'_> {
+    /// Assigns the Arn to one of `num_shards` shards using jump consistent hashing.
+    ///
+    /// The result is stable across processes, and growing the shard count only moves the
+    /// minimum number of Arns. Returns `0` when `num_shards` is `0`.
+    pub fn shard(&self, num_shards: u32) -> u32 {
+        jump_consistent_hash(self.stable_hash(), num_shards)
+    }
+}
+
+/// Jump consistent hash (Lamping & Veach, 2014).
+fn jump_consistent_hash(mut key: u64, num_buckets: u32) -> u32 {
+    let mut bucket: i64 = -1;
+    let mut next: i64 = 0;
+    while next < i64::from(num_buckets) {
+        bucket = next;
+        key = key.wrapping_mul(2_862_933_555_777_941_757).wrapping_add(1);
+        next = ((bucket + 1) as f64 * ((1u64 << 31) as f64 / ((key >> 33) + 1) as f64)) as i64;
+    }
+    bucket.max(0) as u32
+}
+
+/// Number of virtual nodes placed on the ring per unit of node weight.
+const REPLICAS_PER_WEIGHT: u32 = 64;
+
+/// A consistent-hash ring placing Arns on weighted nodes.
+///
+/// Each node occupies a number of virtual points proportional to its weight. Points are
+/// derived from the node's `Display` form with the stable hash, so every process building the
+/// same ring computes the same placement.
+#[derive(Debug, Clone)]
+pub struct ShardRing<N> {
+    key: ShardKey,
+    ring: BTreeMap<u64, N>,
+}
+
+impl<N: Clone + Eq + Display> ShardRing<N> {
+    /// Creates an empty ring placing Arns by the given key.
+    pub fn new(key: ShardKey) -> Self {
+        ShardRing {
+            key,
+            ring: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a node with the given relative weight, replacing any previous weight for it.
+    pub fn add_node(&mut self, node: N, weight: u32) {
+        self.remove_node(&node);
+        for replica in 0..weight.saturating_mul(REPLICAS_PER_WEIGHT) {
+            let point = stable_hash_bytes(format!("{node}#{replica}").as_bytes());
+            self.ring.entry(point).or_insert_with(|| node.clone());
+        }
+    }
+
+    /// Removes a node and all of its virtual points.
+    pub fn remove_node(&mut self, node: &N) {
+        self.ring.retain(|_, candidate| candidate != node);
+    }
+
+    /// Returns the node responsible for `arn`, or `None` if the ring is empty.
+    pub fn node_for(&self, arn: &Arn) -> Option<&N> {
+        let point = self.key.hash(arn);
+        self.ring
+            .range(point..)
+            .next()
+            .or_else(|| self.ring.iter().next())
+            .map(|(_, node)| node)
+    }
+
+    /// Returns `true` if no nodes are on the ring.
+    pub fn is_empty(&self) -> bool {
+        self.ring.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArnParser;
+    use std::collections::HashMap;
+
+    fn arn(s: &str) -> Arn<'static> {
+        ArnParser::new(s.to_string()).parse().unwrap()
+    }
+
+    fn sample(n: usize) -> Vec<Arn<'static>> {
+        (0..n)
+            .map(|i| arn(&format!("arn:akton:hr:acct{}:root/item{}", i % 7, i)))
+            .collect()
+    }
+
+    #[test]
+    fn test_shard_is_stable_and_in_range() {
+        for arn in sample(200) {
+            let shard = arn.shard(16);
+            assert!(shard < 16);
+            assert_eq!(shard, arn.clone().shard(16));
+        }
+        assert_eq!(arn("arn:akton:hr:acme:root").shard(0), 0);
+    }
+
+    #[test]
+    fn test_shard_growth_moves_few_arns() {
+        let arns = sample(1000);
+        let moved = arns.iter().filter(|a| a.shard(10) != a.shard(11)).count();
+        // Ideal movement is 1/11 of the keys; allow generous slack.
+        assert!(moved < 200, "moved {moved} of 1000");
+    }
+
+    #[test]
+    fn test_ring_respects_account_key() {
+        let mut ring = ShardRing::new(ShardKey::Account);
+        ring.add_node("node-a", 1);
+        ring.add_node("node-b", 1);
+        ring.add_node("node-c", 1);
+        let first = ring.node_for(&arn("arn:akton:hr:acme:root/a"));
+        let second = ring.node_for(&arn("arn:akton:hr:acme:other/b/c"));
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_keys_ignore_scheme_and_format() {
+        let legacy = crate::ArnFormat::new()
+            .with_scheme("res")
+            .unwrap()
+            .with_component_separator('|')
+            .unwrap();
+        let read = [
+            arn("arn:akton:hr:acme:root/a"),
+            arn("ern:akton:hr:acme:root/a"),
+            ArnParser::new("res|akton|hr|acme|root/a")
+                .with_format(legacy)
+                .parse()
+                .unwrap(),
+        ];
+        for key in [ShardKey::Full, ShardKey::Root, ShardKey::Account] {
+            assert!(read.iter().all(|arn| key.hash(arn) == key.hash(&read[0])));
+        }
+        assert_eq!(
+            ShardKey::Root.hash(&read[2]),
+            stable_hash_bytes(b"arn:akton:hr:acme:root")
+        );
+        assert_eq!(
+            ShardKey::Account.hash(&read[1]),
+            stable_hash_bytes(b"arn:akton:hr:acme")
+        );
+    }
+
+    #[test]
+    fn test_ring_weights_and_removal() {
+        let mut ring = ShardRing::new(ShardKey::Full);
+        assert!(ring.node_for(&arn("arn:akton:hr:acme:root")).is_none());
+        ring.add_node("light", 1);
+        ring.add_node("heavy", 4);
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for arn in sample(2000) {
+            *counts.entry(ring.node_for(&arn).unwrap()).or_default() += 1;
+        }
+        assert!(counts["heavy"] > counts["light"] * 2);
+
+        ring.remove_node(&"heavy");
+        for arn in sample(50) {
+            assert_eq!(ring.node_for(&arn), Some(&"light"));
+        }
+    }
+}
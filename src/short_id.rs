@@ -0,0 +1,126 @@
+use crate::errors::ArnError;
+use crate::model::Arn;
+
+/// Crockford's base32 alphabet: digits and upper-case letters without I, L, O and U.
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+impl Arn<'_> {
+    /// Returns a 26-character Crockford base32 digest of the Arn, safe to embed in URLs and QR codes.
+    ///
+    /// The digest is the 128-bit [`Arn::stable_hash128`], so it is stable across releases but
+    /// cannot be turned back into the Arn. Use [`Arn::to_reversible_short_id`] when the Arn must
+    /// be recovered from the id.
+    pub fn to_short_id(&self) -> String {
+        encode(&self.stable_hash128().to_be_bytes())
+    }
+
+    /// Returns a Crockford base32 encoding of the compact binary form, decodable with [`Arn::try_from_short_id`].
+    pub fn to_reversible_short_id(&self) -> String {
+        encode(&self.to_bytes())
+    }
+
+    /// Recovers an Arn from an id produced by [`Arn::to_reversible_short_id`].
+    ///
+    /// Decoding is case-insensitive, treats `I`/`L` as `1` and `O` as `0`, and ignores hyphens,
+    /// as Crockford's scheme prescribes. Digests from [`Arn::to_short_id`] are not reversible
+    /// and are rejected.
+    pub fn try_from_short_id(id: &str) -> Result<Arn<'static>, ArnError> {
+        let bytes = decode(id)?;
+        Arn::from_bytes(&bytes).map(Arn::into_owned)
+    }
+}
+
+pub(crate) fn encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity((bytes.len() * 8).div_ceil(5));
+    let mut buffer = 0u16;
+    let mut bits = 0;
+    for &byte in bytes {
+        buffer = (buffer << 8) | u16::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(ALPHABET[usize::from((buffer >> bits) & 0x1f)] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(ALPHABET[usize::from((buffer << (5 - bits)) & 0x1f)] as char);
+    }
+    out
+}
+
+pub(crate) fn decode(id: &str) -> Result<Vec<u8>, ArnError> {
+    let mut out = Vec::with_capacity(id.len() * 5 / 8);
+    let mut buffer = 0u16;
+    let mut bits = 0;
+    for c in id.chars().filter(|c| *c != '-') {
+        let value = match c.to_ascii_uppercase() {
+            'O' => 0,
+            'I' | 'L' => 1,
+            c => ALPHABET
+                .iter()
+                .position(|a| *a as char == c)
+                .ok_or_else(|| ArnError::DecodeFailure(format!("invalid base32 character {c:?}")))?
+                as u16,
+        };
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    // `encode` pads the last character with zero bits and never adds a character holding
+    // none of the input, so any other tail would give one id several spellings.
+    if bits >= 5 || buffer & ((1 << bits) - 1) != 0 {
+        return Err(ArnError::DecodeFailure(
+            "base32 id has trailing bits set".to_string(),
+        ));
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArnParser;
+
+    fn sample() -> Arn<'static> {
+        ArnParser::new("arn:akton-internal:hr:company123:root/departmentA/team1")
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_short_id_is_url_safe_digest() {
+        let id = sample().to_short_id();
+        assert_eq!(id.len(), 26);
+        assert!(id.chars().all(|c| ALPHABET.contains(&(c as u8))));
+        assert_eq!(id, sample().to_short_id());
+        assert!(Arn::try_from_short_id(&id).is_err());
+    }
+
+    #[test]
+    fn test_reversible_short_id_round_trip() {
+        let arn = sample();
+        let id = arn.to_reversible_short_id();
+        assert!(!id.contains([':', '/']));
+        assert_eq!(Arn::try_from_short_id(&id).unwrap(), arn);
+        assert_eq!(
+            Arn::try_from_short_id(&id.to_lowercase()).unwrap(),
+            arn,
+            "decoding is case-insensitive"
+        );
+    }
+
+    #[test]
+    fn test_crockford_aliases_and_errors() {
+        assert_eq!(encode(&[0xff, 0x00]), "ZW00");
+        assert_eq!(decode("zw-oo").unwrap(), vec![0xff, 0x00]);
+        assert_eq!(decode("10").unwrap(), vec![0x08]);
+        assert_eq!(decode("l0").unwrap(), decode("10").unwrap());
+        assert!(decode("U").is_err());
+        assert!(decode("11").is_err(), "nonzero padding bits");
+        assert!(decode("1").is_err(), "a character holding no whole byte");
+        assert!(decode("").unwrap().is_empty());
+    }
+}
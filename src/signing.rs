@@ -0,0 +1,130 @@
+use crate::errors::ArnError;
+use crate::model::Arn;
+use crate::parser::ArnParser;
+use crate::short_id::{decode, encode};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::fmt;
+use std::str::FromStr;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// An Arn carrying an HMAC-SHA256 signature, so it can be handed to untrusted clients.
+///
+/// The string form appends the signature, Crockford base32 encoded, as a final
+/// colon-separated component: `arn:domain:category:account:root/parts:<signature>`. The MAC
+/// covers the Arn's binary encoding, so any change to a component invalidates it. Parsing a
+/// `SignedArn` does not check the signature; call [`SignedArn::verify`] before trusting it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedArn {
+    arn: Arn<'static>,
+    signature: [u8; 32],
+}
+
+impl SignedArn {
+    /// Signs `arn` with `key`.
+    pub fn sign(arn: &Arn, key: &[u8]) -> SignedArn {
+        SignedArn {
+            arn: arn.clone().into_owned(),
+            signature: mac(arn, key).finalize().into_bytes().into(),
+        }
+    }
+
+    /// Checks the signature against `key` in constant time and returns the Arn if it matches.
+    pub fn verify(&self, key: &[u8]) -> Result<&Arn<'static>, ArnError> {
+        mac(&self.arn, key)
+            .verify_slice(&self.signature)
+            .map_err(|_| ArnError::InvalidSignature)?;
+        Ok(&self.arn)
+    }
+
+    /// Returns the signed Arn without checking the signature.
+    pub fn arn_unverified(&self) -> &Arn<'static> {
+        &self.arn
+    }
+
+    /// Returns the raw signature bytes.
+    pub fn signature(&self) -> &[u8; 32] {
+        &self.signature
+    }
+}
+
+impl Arn<'_> {
+    /// Signs the Arn with `key`; see [`SignedArn`].
+    pub fn sign(&self, key: &[u8]) -> SignedArn {
+        SignedArn::sign(self, key)
+    }
+}
+
+fn mac(arn: &Arn, key: &[u8]) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(&arn.to_bytes());
+    mac
+}
+
+impl fmt::Display for SignedArn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.arn, encode(&self.signature))
+    }
+}
+
+impl FromStr for SignedArn {
+    type Err = ArnError;
+
+    /// Parses the string form produced by `Display`. The signature is not verified.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (arn, signature) = s.rsplit_once(':').ok_or(ArnError::InvalidFormat)?;
+        let signature = decode(signature)?.try_into().map_err(|bytes: Vec<u8>| {
+            ArnError::DecodeFailure(format!("signature is {} bytes, expected 32", bytes.len()))
+        })?;
+        Ok(SignedArn {
+            arn: ArnParser::new(arn.to_string()).parse()?,
+            signature,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &[u8] = b"test-signing-key";
+
+    fn sample() -> Arn<'static> {
+        ArnParser::new("arn:akton-internal:hr:company123:root/departmentA/team1")
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_sign_and_verify_round_trip() {
+        let signed = sample().sign(KEY);
+        let text = signed.to_string();
+        assert!(text.starts_with("arn:akton-internal:hr:company123:root/departmentA/team1:"));
+        let parsed: SignedArn = text.parse().unwrap();
+        assert_eq!(parsed, signed);
+        assert_eq!(parsed.verify(KEY).unwrap(), &sample());
+    }
+
+    #[test]
+    fn test_wrong_key_is_rejected() {
+        let signed = sample().sign(KEY);
+        assert_eq!(signed.verify(b"other-key"), Err(ArnError::InvalidSignature));
+    }
+
+    #[test]
+    fn test_tampered_arn_is_rejected() {
+        let text = sample().sign(KEY).to_string();
+        let tampered = text.replacen("company123", "company124", 1);
+        let parsed: SignedArn = tampered.parse().unwrap();
+        assert_eq!(parsed.verify(KEY), Err(ArnError::InvalidSignature));
+    }
+
+    #[test]
+    fn test_malformed_signature_is_rejected() {
+        let arn = sample().to_string();
+        assert!(format!("{arn}:ABC").parse::<SignedArn>().is_err());
+        assert!(format!("{arn}:U!").parse::<SignedArn>().is_err());
+        assert!("no-signature".parse::<SignedArn>().is_err());
+    }
+}
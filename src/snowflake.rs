@@ -0,0 +1,265 @@
+use crate::errors::ArnError;
+use crate::id_generator::{IdGenerator, IdScope};
+use crate::model::Root;
+use crate::time_source::{SystemClock, TimeSource};
+use std::borrow::Cow;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use uuid::{Uuid, Version};
+
+/// Start of the Snowflake timestamp, 2024-01-01T00:00:00Z, as a duration since the Unix epoch.
+pub const SNOWFLAKE_EPOCH: Duration = Duration::from_millis(1_704_067_200_000);
+
+/// Number of distinct node ids, which must be below this bound.
+pub const SNOWFLAKE_NODES: u16 = 1 << NODE_BITS;
+
+const NODE_BITS: u32 = 10;
+const SEQUENCE_BITS: u32 = 12;
+const SEQUENCE_MASK: u64 = (1 << SEQUENCE_BITS) - 1;
+
+/// Mints 64-bit ids that are unique across a cluster and increase on every node.
+///
+/// Each id packs, from the most significant bit down, a 41-bit millisecond timestamp since
+/// [`SNOWFLAKE_EPOCH`], the 10-bit node id, and a 12-bit sequence counting ids minted within
+/// the same millisecond. Two nodes never share a node id, so they never mint the same id, even
+/// when their clocks agree to the millisecond.
+///
+/// A node never goes back in time: if its clock does, or if it mints more than 4096 ids in a
+/// millisecond, the timestamp runs ahead of the clock until the clock catches up.
+///
+/// [`Root::new_with_snowflake`] embeds the id in a root, from which [`Root::node_id`] and
+/// [`Root::sequence`] read it back:
+///
+/// ```
+/// use akton_arn::{Root, Snowflake};
+///
+/// let snowflake = Snowflake::new(42).unwrap();
+/// let first = Root::new_with_snowflake("order", &snowflake).unwrap();
+/// let second = Root::new_with_snowflake("order", &snowflake).unwrap();
+/// assert!(first < second);
+/// assert_eq!(first.node_id(), Some(42));
+/// ```
+pub struct Snowflake {
+    node_id: u16,
+    time_source: Arc<dyn TimeSource>,
+    /// The timestamp and sequence of the last id minted.
+    last: Mutex<(u64, u64)>,
+}
+
+impl Snowflake {
+    /// Creates a generator for `node_id` reading the wall clock.
+    ///
+    /// Fails if `node_id` is not below [`SNOWFLAKE_NODES`].
+    pub fn new(node_id: u16) -> Result<Self, ArnError> {
+        Self::with_time_source(node_id, SystemClock)
+    }
+
+    /// Creates a generator for `node_id` reading `time_source`, for reproducible simulations.
+    pub fn with_time_source(
+        node_id: u16,
+        time_source: impl TimeSource + 'static,
+    ) -> Result<Self, ArnError> {
+        if node_id >= SNOWFLAKE_NODES {
+            return Err(ArnError::ParseFailure(
+                "Snowflake",
+                format!("node id {node_id} must be below {SNOWFLAKE_NODES}"),
+            ));
+        }
+        Ok(Snowflake {
+            node_id,
+            time_source: Arc::new(time_source),
+            last: Mutex::new((0, SEQUENCE_MASK)),
+        })
+    }
+
+    /// Returns the node id stamped into every id.
+    pub fn node_id(&self) -> u16 {
+        self.node_id
+    }
+
+    /// Mints the next id.
+    pub fn next_id(&self) -> u64 {
+        let now = self
+            .time_source
+            .now()
+            .saturating_sub(SNOWFLAKE_EPOCH)
+            .as_millis() as u64;
+        let mut last = self.last.lock().unwrap();
+        let (last_millis, last_sequence) = *last;
+        *last = if now > last_millis {
+            (now, 0)
+        } else if last_sequence < SEQUENCE_MASK {
+            (last_millis, last_sequence + 1)
+        } else {
+            (last_millis + 1, 0)
+        };
+        let (millis, sequence) = *last;
+        (millis << (NODE_BITS + SEQUENCE_BITS))
+            | (u64::from(self.node_id) << SEQUENCE_BITS)
+            | sequence
+    }
+}
+
+impl IdGenerator for Snowflake {
+    fn generate(&self, _scope: &IdScope<'_>) -> Uuid {
+        to_uuid(self.next_id())
+    }
+
+    /// The 64-bit id ends at bit 58 of the UUID, and the bits below are zero.
+    fn fixed_low_bits(&self) -> u32 {
+        58
+    }
+}
+
+impl fmt::Debug for Snowflake {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Snowflake")
+            .field("node_id", &self.node_id)
+            .finish()
+    }
+}
+
+/// Returns when a Snowflake id was minted, as a duration since the Unix epoch.
+pub(crate) fn timestamp(id: u64) -> Duration {
+    SNOWFLAKE_EPOCH + Duration::from_millis(id >> (NODE_BITS + SEQUENCE_BITS))
+}
+
+/// Stores a Snowflake id in a version 8 UUID, keeping its bits in order so UUIDs sort as ids do.
+///
+/// The top 48 bits fill the first custom field, the next 12 the second, and the last 4 lead
+/// the third; the version and variant bits in between are constant.
+fn to_uuid(id: u64) -> Uuid {
+    let id = u128::from(id);
+    let bits = ((id >> 16) << 80)
+        | (0x8 << 76)
+        | (((id >> 4) & 0xfff) << 64)
+        | (0b10 << 62)
+        | ((id & 0xf) << 58);
+    Uuid::from_u128(bits)
+}
+
+fn from_uuid(uuid: Uuid) -> Option<u64> {
+    if uuid.get_version() != Some(Version::Custom) {
+        return None;
+    }
+    let bits = uuid.as_u128();
+    Some((((bits >> 80) << 16) | (((bits >> 64) & 0xfff) << 4) | ((bits >> 58) & 0xf)) as u64)
+}
+
+impl<'a> Root<'a> {
+    /// Mints a root named `value` (or `akton` when empty) carrying the next id of `snowflake`.
+    ///
+    /// The root is a TypeID like any other, but sorts by Snowflake id, so roots minted on one
+    /// node are strictly increasing and roots from different nodes never collide.
+    pub fn new_with_snowflake(
+        value: impl Into<Cow<'a, str>>,
+        snowflake: &Snowflake,
+    ) -> Result<Self, ArnError> {
+        Root::generate(&IdScope::named(&value.into()), snowflake)
+    }
+
+    /// Returns the Snowflake id of a root minted by [`Root::new_with_snowflake`].
+    pub fn snowflake_id(&self) -> Option<u64> {
+        from_uuid(self.uuid()?)
+    }
+
+    /// Returns the node that minted a Snowflake root.
+    pub fn node_id(&self) -> Option<u16> {
+        self.snowflake_id()
+            .map(|id| ((id >> SEQUENCE_BITS) & (u64::from(SNOWFLAKE_NODES) - 1)) as u16)
+    }
+
+    /// Returns the sequence of a Snowflake root within the millisecond it was minted.
+    pub fn sequence(&self) -> Option<u16> {
+        self.snowflake_id().map(|id| (id & SEQUENCE_MASK) as u16)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SimulatedClock;
+
+    fn clock_at(millis: u64) -> SimulatedClock {
+        SimulatedClock::new(SNOWFLAKE_EPOCH + Duration::from_millis(millis))
+    }
+
+    #[test]
+    fn test_ids_pack_time_node_and_sequence() {
+        let snowflake = Snowflake::with_time_source(5, clock_at(7)).unwrap();
+        assert_eq!(snowflake.next_id(), (7 << 22) | (5 << 12));
+        assert_eq!(snowflake.next_id(), (7 << 22) | (5 << 12) | 1);
+        let root = Root::new_with_snowflake("order", &snowflake).unwrap();
+        assert!(root.as_str().starts_with("order_"));
+        assert_eq!(root.snowflake_id(), Some((7 << 22) | (5 << 12) | 2));
+        assert_eq!(root.node_id(), Some(5));
+        assert_eq!(root.sequence(), Some(2));
+        assert_eq!(
+            root.created_at(),
+            Some(std::time::UNIX_EPOCH + SNOWFLAKE_EPOCH + Duration::from_millis(7))
+        );
+        assert!(Snowflake::new(SNOWFLAKE_NODES).is_err());
+    }
+
+    #[test]
+    fn test_ids_stay_monotonic_when_clock_stalls_or_rewinds() {
+        let clock = Arc::new(clock_at(100));
+        let snowflake = Snowflake::with_time_source(1, ArcClock(clock.clone())).unwrap();
+        let mut previous = snowflake.next_id();
+        for _ in 0..5000 {
+            let id = snowflake.next_id();
+            assert!(id > previous);
+            previous = id;
+        }
+        // 5001 ids overflowed the sequence, so the timestamp ran one millisecond ahead.
+        assert_eq!(previous >> 22, 101);
+        clock.set(SNOWFLAKE_EPOCH);
+        assert!(snowflake.next_id() > previous);
+    }
+
+    #[test]
+    fn test_roots_sort_by_id_and_nodes_never_collide() {
+        let a = Snowflake::with_time_source(1, clock_at(1)).unwrap();
+        let b = Snowflake::with_time_source(2, clock_at(1)).unwrap();
+        let roots: Vec<Root> = (0..100)
+            .map(|i| Root::new_with_snowflake("x", if i % 2 == 0 { &a } else { &b }).unwrap())
+            .collect();
+        let mut sorted = roots.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(sorted.len(), roots.len());
+        let ids: Vec<u64> = sorted.iter().map(|r| r.snowflake_id().unwrap()).collect();
+        assert!(ids.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(Root::new("x").unwrap().node_id(), None);
+    }
+
+    #[test]
+    fn test_truncated_suffixes_keep_the_sequence() {
+        use crate::{RootConfig, RootSuffix};
+        let snowflake = Snowflake::with_time_source(3, clock_at(1)).unwrap();
+        let config = RootConfig::new()
+            .with_suffix(RootSuffix::Truncated(4))
+            .unwrap();
+        let mut suffixes: Vec<String> = (0..1000)
+            .map(|_| {
+                Root::generate_with_config(&IdScope::named("x"), &snowflake, &config)
+                    .unwrap()
+                    .to_string()
+            })
+            .collect();
+        assert!(suffixes.iter().all(|root| root.len() == "x_".len() + 4));
+        suffixes.sort();
+        suffixes.dedup();
+        assert_eq!(suffixes.len(), 1000);
+    }
+
+    /// Lets a test keep moving a clock after handing it to a generator.
+    struct ArcClock(Arc<SimulatedClock>);
+
+    impl TimeSource for ArcClock {
+        fn now(&self) -> Duration {
+            self.0.now()
+        }
+    }
+}
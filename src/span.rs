@@ -0,0 +1,167 @@
+//! `tracing` support, enabled with the `tracing` feature.
+//!
+//! Recording an Arn as separate span fields lets observability pipelines filter on a single
+//! component, such as every span for one account, instead of substring-matching the display
+//! string. Spans must declare the fields up front; [`arn_span!`](crate::arn_span) does so.
+
+use crate::model::{Arn, ComponentKind};
+use tracing::Span;
+
+/// Names of the span fields written by [`ArnSpanExt::record_arn`].
+pub const ARN_SPAN_FIELDS: [&str; 5] = [
+    "arn.domain",
+    "arn.category",
+    "arn.account",
+    "arn.root",
+    "arn.parts",
+];
+
+/// Value recorded in place of a redacted component.
+pub const REDACTED: &str = "<redacted>";
+
+/// Records Arns on [`tracing::Span`]s as one field per component.
+pub trait ArnSpanExt {
+    /// Records every component of `arn` into the span's `arn.*` fields.
+    fn record_arn(&self, arn: &Arn) -> &Self;
+
+    /// Records `arn`, replacing the components listed in `redact` with [`REDACTED`].
+    fn record_arn_redacted(&self, arn: &Arn, redact: &[ComponentKind]) -> &Self;
+}
+
+impl ArnSpanExt for Span {
+    fn record_arn(&self, arn: &Arn) -> &Self {
+        self.record_arn_redacted(arn, &[])
+    }
+
+    fn record_arn_redacted(&self, arn: &Arn, redact: &[ComponentKind]) -> &Self {
+        let parts = arn.parts.to_string();
+        let values = [
+            (ComponentKind::Domain, arn.domain.as_str()),
+            (ComponentKind::Category, arn.category.as_str()),
+            (ComponentKind::Account, arn.account.as_str()),
+            (ComponentKind::Root, arn.root.as_str()),
+            (ComponentKind::Part, parts.as_str()),
+        ];
+        for (field, (kind, value)) in ARN_SPAN_FIELDS.iter().zip(values) {
+            let value = if redact.contains(&kind) {
+                REDACTED
+            } else {
+                value
+            };
+            self.record(*field, value);
+        }
+        self
+    }
+}
+
+/// Creates a span with the `arn.*` fields declared and, optionally, recorded.
+///
+/// ```
+/// use akton_arn::{arn_span, ArnParser, ComponentKind};
+///
+/// let arn = ArnParser::new("arn:akton:hr:acme:root/team").parse().unwrap();
+/// let span = arn_span!(tracing::Level::INFO, "handle_request", &arn);
+/// let redacted = arn_span!(tracing::Level::INFO, "handle_request", &arn, redact = [ComponentKind::Account]);
+/// let empty = arn_span!(tracing::Level::INFO, "handle_request");
+/// ```
+#[macro_export]
+macro_rules! arn_span {
+    ($level:expr, $name:expr) => {
+        $crate::__tracing::span!(
+            $level,
+            $name,
+            "arn.domain" = $crate::__tracing::field::Empty,
+            "arn.category" = $crate::__tracing::field::Empty,
+            "arn.account" = $crate::__tracing::field::Empty,
+            "arn.root" = $crate::__tracing::field::Empty,
+            "arn.parts" = $crate::__tracing::field::Empty,
+        )
+    };
+    ($level:expr, $name:expr, $arn:expr) => {{
+        let span = $crate::arn_span!($level, $name);
+        $crate::ArnSpanExt::record_arn(&span, $arn);
+        span
+    }};
+    ($level:expr, $name:expr, $arn:expr, redact = [$($kind:expr),* $(,)?]) => {{
+        let span = $crate::arn_span!($level, $name);
+        $crate::ArnSpanExt::record_arn_redacted(&span, $arn, &[$($kind),*]);
+        span
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArnParser;
+    use std::collections::BTreeMap;
+    use std::fmt::Debug;
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Level, Subscriber};
+    use tracing_subscriber::layer::{Context, SubscriberExt};
+    use tracing_subscriber::Layer;
+
+    /// Collects every recorded span field.
+    #[derive(Clone, Default)]
+    struct Fields(Arc<Mutex<BTreeMap<String, String>>>);
+
+    impl Visit for Fields {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0
+                .lock()
+                .unwrap()
+                .insert(field.name().to_string(), value.to_string());
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            self.record_str(field, &format!("{value:?}"));
+        }
+    }
+
+    impl<S: Subscriber> Layer<S> for Fields {
+        fn on_new_span(&self, attrs: &Attributes<'_>, _: &Id, _: Context<'_, S>) {
+            attrs.record(&mut self.clone());
+        }
+
+        fn on_record(&self, _: &Id, values: &Record<'_>, _: Context<'_, S>) {
+            values.record(&mut self.clone());
+        }
+    }
+
+    fn capture(f: impl FnOnce()) -> BTreeMap<String, String> {
+        let fields = Fields::default();
+        let subscriber = tracing_subscriber::registry().with(fields.clone());
+        tracing::subscriber::with_default(subscriber, f);
+        let captured = fields.0.lock().unwrap().clone();
+        captured
+    }
+
+    fn arn() -> Arn<'static> {
+        ArnParser::new("arn:akton:hr:acme:root/team/alice")
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_components_are_recorded_as_fields() {
+        let fields = capture(|| {
+            arn_span!(Level::INFO, "request", &arn());
+        });
+        assert_eq!(fields["arn.domain"], "akton");
+        assert_eq!(fields["arn.account"], "acme");
+        assert_eq!(fields["arn.root"], "root");
+        assert_eq!(fields["arn.parts"], "team/alice");
+    }
+
+    #[test]
+    fn test_redacted_components() {
+        let fields = capture(|| {
+            let span = arn_span!(Level::INFO, "request");
+            span.record_arn_redacted(&arn(), &[ComponentKind::Account, ComponentKind::Part]);
+        });
+        assert_eq!(fields["arn.account"], REDACTED);
+        assert_eq!(fields["arn.parts"], REDACTED);
+        assert_eq!(fields["arn.category"], "hr");
+    }
+}
@@ -0,0 +1,100 @@
+//! `sqlx` support, enabled with the `sqlx` feature.
+//!
+//! Arns are stored in their canonical string form, so they bind to and decode from any text
+//! column (`TEXT` on Postgres and SQLite, `VARCHAR`/`TEXT` on MySQL). Decoding validates the
+//! value with [`ArnParser`].
+
+use crate::model::Arn;
+use crate::parser::ArnParser;
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::{Database, Decode, Encode, Type};
+
+impl<DB: Database> Type<DB> for Arn<'_>
+where
+    String: Type<DB>,
+{
+    fn type_info() -> DB::TypeInfo {
+        <String as Type<DB>>::type_info()
+    }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool {
+        <String as Type<DB>>::compatible(ty)
+    }
+}
+
+impl<'q, DB: Database> Encode<'q, DB> for Arn<'_>
+where
+    String: Encode<'q, DB>,
+{
+    fn encode_by_ref(
+        &self,
+        buf: &mut <DB as Database>::ArgumentBuffer<'q>,
+    ) -> Result<IsNull, BoxDynError> {
+        self.to_string().encode_by_ref(buf)
+    }
+}
+
+impl<'r, DB: Database> Decode<'r, DB> for Arn<'_>
+where
+    &'r str: Decode<'r, DB>,
+{
+    fn decode(value: <DB as Database>::ValueRef<'r>) -> Result<Self, BoxDynError> {
+        let value = <&str as Decode<DB>>::decode(value)?;
+        Ok(ArnParser::new(value.to_string()).parse()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use sqlx::{Row, SqlitePool};
+
+    async fn pool() -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query("CREATE TABLE resources (arn TEXT PRIMARY KEY)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_bind_and_fetch_round_trip() {
+        let pool = pool().await;
+        let arn = ArnParser::new("arn:akton:hr:acme:root/team1")
+            .parse()
+            .unwrap();
+        sqlx::query("INSERT INTO resources (arn) VALUES (?)")
+            .bind(&arn)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let fetched: Arn = sqlx::query_scalar("SELECT arn FROM resources WHERE arn = ?")
+            .bind(&arn)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(fetched, arn);
+    }
+
+    #[tokio::test]
+    async fn test_decoding_invalid_text_fails() {
+        let pool = pool().await;
+        sqlx::query("INSERT INTO resources (arn) VALUES ('not-an-arn')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let row = sqlx::query("SELECT arn FROM resources")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!(row.try_get::<Arn, _>("arn").is_err());
+    }
+}
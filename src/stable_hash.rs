@@ -0,0 +1,68 @@
+use crate::model::Arn;
+use crate::scratch::with_canonical;
+use siphasher::sip::SipHasher13;
+use siphasher::sip128::{Hasher128, SipHasher13 as SipHasher13_128};
+use std::hash::Hasher;
+
+/// Version of the stable hash algorithm produced by [`Arn::stable_hash`] and [`Arn::stable_hash128`].
+///
+/// Version 1 is SipHash-1-3 keyed with [`STABLE_HASH_KEY`] over the UTF-8 bytes of the
+/// canonical (`Display`) form of the Arn. The output for a given Arn never changes within a
+/// version; any change to the algorithm, key or input encoding bumps this number.
+pub const STABLE_HASH_VERSION: u32 = 1;
+
+/// The fixed SipHash key used by version 1 of the stable hash.
+pub const STABLE_HASH_KEY: (u64, u64) = (0x616b_746f_6e2d_6172, 0x6e2d_7374_6162_6c65);
+
+/// Hashes arbitrary bytes with the version 1 stable hash.
+pub(crate) fn stable_hash_bytes(bytes: &[u8]) -> u64 {
+    let mut hasher = SipHasher13::new_with_keys(STABLE_HASH_KEY.0, STABLE_HASH_KEY.1);
+    hasher.write(bytes);
+    hasher.finish()
+}
+
+impl Arn<'_> {
+    /// Returns a 64-bit hash of the canonical form that is stable across processes and releases.
+    ///
+    /// Unlike [`std::hash::Hash`], the value is suitable for shard keys and persisted dedup keys.
+    /// See [`STABLE_HASH_VERSION`] for the algorithm.
+    pub fn stable_hash(&self) -> u64 {
+        with_canonical(self, |canonical| stable_hash_bytes(canonical.as_bytes()))
+    }
+
+    /// Returns a 128-bit variant of [`Arn::stable_hash`] for use where collisions must be negligible.
+    pub fn stable_hash128(&self) -> u128 {
+        with_canonical(self, |canonical| {
+            let mut hasher = SipHasher13_128::new_with_keys(STABLE_HASH_KEY.0, STABLE_HASH_KEY.1);
+            hasher.write(canonical.as_bytes());
+            hasher.finish128().as_u128()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ArnParser;
+
+    #[test]
+    fn test_stable_hash_is_frozen() {
+        // These values are part of the version 1 contract and must never change.
+        let arn = ArnParser::new("arn:akton-internal:hr:company123:root/departmentA/team1")
+            .parse()
+            .unwrap();
+        assert_eq!(arn.stable_hash(), 0x8be9_5517_4584_7d8f);
+        assert_eq!(
+            arn.stable_hash128(),
+            0x016b_6fc5_c774_8907_8029_dc65_2479_bc47
+        );
+    }
+
+    #[test]
+    fn test_stable_hash_distinguishes_arns() {
+        let a = ArnParser::new("arn:akton:hr:acme:root/a").parse().unwrap();
+        let b = ArnParser::new("arn:akton:hr:acme:root/b").parse().unwrap();
+        assert_eq!(a.stable_hash(), a.clone().stable_hash());
+        assert_ne!(a.stable_hash(), b.stable_hash());
+        assert_ne!(a.stable_hash128(), b.stable_hash128());
+    }
+}
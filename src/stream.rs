@@ -0,0 +1,429 @@
+//! Reading Arns from newline-delimited feeds, such as audit logs.
+//!
+//! [`ArnStreamParser`] reads one Arn per line from an [`io::BufRead`], or from a tokio
+//! `AsyncBufRead` with the `tokio` feature. Blank lines are ignored, and a line that fails to
+//! parse, is not UTF-8 or is longer than the line limit is reported with its line number and
+//! text, or skipped and counted in tolerant mode.
+
+use crate::errors::ArnError;
+use crate::format::ArnFormat;
+use crate::model::OwnedArn;
+use crate::parser::{parse_str, ComponentRules};
+use crate::traits::ArnValidator;
+use crate::{ArnLimits, CaseFold, ValidationMode};
+use std::io::{self, BufRead, Read};
+use std::ops::ControlFlow;
+use std::sync::Arc;
+
+/// Longest line, in bytes without its line ending, that [`ArnStreamParser`] reads by default.
+pub const DEFAULT_MAX_LINE_LEN: usize = 64 * 1024;
+
+/// Error returned while reading a feed.
+#[derive(Debug, thiserror::Error)]
+pub enum StreamError {
+    /// Reading from the underlying reader failed; the stream ends after this error.
+    #[error("failed to read line {line}: {source}")]
+    Io {
+        /// The line being read, counting from 1.
+        line: usize,
+        /// The error the reader returned.
+        source: io::Error,
+    },
+    /// A line is not a valid Arn, is not UTF-8 or is too long.
+    #[error("line {line} ({input:?}): {error}")]
+    InvalidLine {
+        /// The line, counting from 1.
+        line: usize,
+        /// The text of the line, without its line ending, with invalid UTF-8 replaced by
+        /// U+FFFD. Only the start of a line over the length limit is kept.
+        input: String,
+        /// Why the line was rejected.
+        error: ArnError,
+    },
+}
+
+/// Parses one Arn per line from a reader, yielding each with its error context.
+///
+/// ```
+/// use akton_arn::stream::{ArnStreamParser, StreamError};
+///
+/// let feed = "arn:akton:hr:acme:root/a\n\nnot-an-arn\r\narn:akton:hr:acme:root/b\n";
+/// let results: Vec<_> = ArnStreamParser::new(feed.as_bytes()).collect();
+/// assert_eq!(results.len(), 3);
+/// assert!(matches!(results[1], Err(StreamError::InvalidLine { line: 3, .. })));
+///
+/// let mut tolerant = ArnStreamParser::new(feed.as_bytes()).skip_invalid(true);
+/// assert_eq!(tolerant.by_ref().filter_map(Result::ok).count(), 2);
+/// assert_eq!(tolerant.skipped(), 1);
+/// ```
+pub struct ArnStreamParser<R> {
+    reader: R,
+    /// Custom rules run against each component as it is parsed.
+    validators: Vec<Arc<dyn ArnValidator>>,
+    /// The grammar the lines are written in.
+    format: ArnFormat,
+    /// How components are normalized and checked.
+    rules: ComponentRules,
+    /// Whether invalid lines are skipped instead of yielded as errors.
+    skip_invalid: bool,
+    /// The longest line read, in bytes without its line ending.
+    max_line_len: usize,
+    /// The number of lines read so far.
+    line: usize,
+    /// The number of invalid lines skipped.
+    skipped: usize,
+    /// Set once the reader is exhausted or has failed.
+    done: bool,
+    /// While the rest of an overlong line is discarded, its length so far and its start.
+    overlong: Option<(usize, String)>,
+    buffer: Vec<u8>,
+}
+
+impl<R> ArnStreamParser<R> {
+    /// Creates a parser reading lines from `reader`.
+    pub fn new(reader: R) -> Self {
+        ArnStreamParser {
+            reader,
+            validators: Vec::new(),
+            format: ArnFormat::DEFAULT,
+            rules: ComponentRules::default(),
+            skip_invalid: false,
+            max_line_len: DEFAULT_MAX_LINE_LEN,
+            line: 0,
+            skipped: 0,
+            done: false,
+            overlong: None,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Skips lines that fail to parse instead of yielding an error for each.
+    ///
+    /// Read errors are still yielded.
+    pub fn skip_invalid(mut self, yes: bool) -> Self {
+        self.skip_invalid = yes;
+        self
+    }
+
+    /// Sets the longest line, in bytes without its line ending (default
+    /// [`DEFAULT_MAX_LINE_LEN`]).
+    ///
+    /// At most this much of a line is buffered. A longer line is an invalid line failing with
+    /// [`ArnError::TooLong`], and the rest of it is read and discarded.
+    pub fn with_max_line_len(mut self, max_line_len: usize) -> Self {
+        self.max_line_len = max_line_len;
+        self
+    }
+
+    /// Registers a validator that is run against each component, as
+    /// [`ArnParser::with_validator`](crate::ArnParser::with_validator) does.
+    pub fn with_validator(mut self, validator: impl ArnValidator + 'static) -> Self {
+        self.validators.push(Arc::new(validator));
+        self
+    }
+
+    /// Applies `limits` instead of [`ArnLimits::default`].
+    pub fn with_limits(mut self, limits: ArnLimits) -> Self {
+        self.rules.limits = limits;
+        self
+    }
+
+    /// Checks and normalizes component characters as `mode` says.
+    pub fn with_validation_mode(mut self, mode: ValidationMode) -> Self {
+        self.rules.mode = mode;
+        self
+    }
+
+    /// Lowercases the components `fold` selects while parsing.
+    pub fn with_case_fold(mut self, fold: CaseFold) -> Self {
+        self.rules.case_fold = fold;
+        self
+    }
+
+    /// Reads the lines in `format` instead of the canonical `arn:` grammar.
+    pub fn with_format(mut self, format: ArnFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Returns the number of lines read so far, including blank and skipped ones.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the number of invalid lines skipped.
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Returns how many bytes to read for one line: enough for the longest line and its line
+    /// ending, so a read that fills it without a newline has found an overlong line.
+    fn read_limit(&self) -> u64 {
+        (self.max_line_len as u64).saturating_add(2)
+    }
+
+    /// Handles the line just read into the buffer, returning `None` if it yields nothing.
+    fn take_line(&mut self) -> Option<Result<OwnedArn, StreamError>> {
+        self.line += 1;
+        let line = trim_line_ending(&self.buffer);
+        let parsed = match std::str::from_utf8(line) {
+            Ok(input) if input.trim().is_empty() => return None,
+            Ok(input) if input.len() > self.max_line_len => {
+                Err(ArnError::TooLong(input.len(), self.max_line_len))
+            }
+            Ok(input) => parse_str(input, &self.validators, &self.format, &self.rules)
+                .map(|arn| arn.into_owned()),
+            Err(e) => Err(ArnError::DecodeFailure(format!(
+                "line is not valid UTF-8: {e}"
+            ))),
+        };
+        match parsed {
+            Ok(arn) => Some(Ok(arn)),
+            Err(_) if self.skip_invalid => {
+                self.skipped += 1;
+                None
+            }
+            Err(error) => Some(Err(StreamError::InvalidLine {
+                line: self.line,
+                input: String::from_utf8_lossy(line).into_owned(),
+                error,
+            })),
+        }
+    }
+
+    /// Handles the end of an overlong line, `len` bytes long without its line ending.
+    fn take_overlong_line(
+        &mut self,
+        len: usize,
+        start: String,
+    ) -> Option<Result<OwnedArn, StreamError>> {
+        self.line += 1;
+        if self.skip_invalid {
+            self.skipped += 1;
+            return None;
+        }
+        Some(Err(StreamError::InvalidLine {
+            line: self.line,
+            input: start,
+            error: ArnError::TooLong(len, self.max_line_len),
+        }))
+    }
+
+    /// Handles the result of reading up to [`read_limit`](Self::read_limit) bytes of a line
+    /// into the buffer, breaking with what the stream yields next or continuing to read.
+    fn after_read(
+        &mut self,
+        read: io::Result<usize>,
+    ) -> ControlFlow<Option<Result<OwnedArn, StreamError>>> {
+        let complete = self.buffer.ends_with(b"\n");
+        match read {
+            Ok(0) => {
+                self.done = true;
+                let overlong = self.overlong.take();
+                ControlFlow::Break(
+                    overlong.and_then(|(len, start)| self.take_overlong_line(len, start)),
+                )
+            }
+            Ok(read) => {
+                let item = if let Some((len, start)) = self.overlong.take() {
+                    if !complete {
+                        self.overlong = Some((len + read, start));
+                        return ControlFlow::Continue(());
+                    }
+                    let len = len + trim_line_ending(&self.buffer).len();
+                    self.take_overlong_line(len, start)
+                } else if !complete && read as u64 == self.read_limit() {
+                    let start = &self.buffer[..self.max_line_len.min(read)];
+                    self.overlong = Some((read, String::from_utf8_lossy(start).into_owned()));
+                    return ControlFlow::Continue(());
+                } else {
+                    self.take_line()
+                };
+                match item {
+                    Some(item) => ControlFlow::Break(Some(item)),
+                    None => ControlFlow::Continue(()),
+                }
+            }
+            Err(source) => {
+                self.done = true;
+                ControlFlow::Break(Some(Err(StreamError::Io {
+                    line: self.line + 1,
+                    source,
+                })))
+            }
+        }
+    }
+}
+
+/// Strips a trailing `\n` or `\r\n`.
+fn trim_line_ending(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+impl<R: BufRead> Iterator for ArnStreamParser<R> {
+    type Item = Result<OwnedArn, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            self.buffer.clear();
+            let limit = self.read_limit();
+            let read = Read::take(&mut self.reader, limit).read_until(b'\n', &mut self.buffer);
+            if let ControlFlow::Break(item) = self.after_read(read) {
+                return item;
+            }
+        }
+        None
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<R: tokio::io::AsyncBufRead + Unpin> ArnStreamParser<R> {
+    /// Reads the next Arn from an async reader, as [`Iterator::next`] does for a blocking one.
+    ///
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// use akton_arn::stream::ArnStreamParser;
+    ///
+    /// let mut feed = ArnStreamParser::new(&b"arn:akton:hr:acme:root/a\n"[..]);
+    /// assert!(feed.next_async().await.unwrap().is_ok());
+    /// assert!(feed.next_async().await.is_none());
+    /// # });
+    /// ```
+    pub async fn next_async(&mut self) -> Option<Result<OwnedArn, StreamError>> {
+        use tokio::io::{AsyncBufReadExt, AsyncReadExt};
+        while !self.done {
+            self.buffer.clear();
+            let limit = self.read_limit();
+            let read = (&mut self.reader)
+                .take(limit)
+                .read_until(b'\n', &mut self.buffer)
+                .await;
+            if let ControlFlow::Break(item) = self.after_read(read) {
+                return item;
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_line_context_and_tolerance() {
+        let feed = "arn:akton:hr:acme:root/a\n  \narn:akton:hr:acme\narn:akton:hr:acme:root/b";
+        let results: Vec<_> = ArnStreamParser::new(feed.as_bytes()).collect();
+        assert_eq!(results.len(), 3);
+        assert_eq!(
+            results[2].as_ref().unwrap().to_string(),
+            "arn:akton:hr:acme:root/b"
+        );
+        match &results[1] {
+            Err(StreamError::InvalidLine { line, input, error }) => {
+                assert_eq!((*line, input.as_str()), (3, "arn:akton:hr:acme"));
+                assert_eq!(error, &ArnError::InvalidFormat);
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+
+        let mut tolerant = ArnStreamParser::new(feed.as_bytes())
+            .skip_invalid(true)
+            .with_case_fold(CaseFold::all());
+        assert_eq!(tolerant.by_ref().count(), 2);
+        assert_eq!((tolerant.line(), tolerant.skipped()), (4, 1));
+    }
+
+    #[test]
+    fn test_read_errors_end_the_stream() {
+        struct Failing;
+        impl Read for Failing {
+            fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::other("disconnected"))
+            }
+        }
+        let mut feed = ArnStreamParser::new(io::BufReader::new(Failing)).skip_invalid(true);
+        assert!(matches!(
+            feed.next(),
+            Some(Err(StreamError::Io { line: 1, .. }))
+        ));
+        assert!(feed.next().is_none());
+    }
+
+    #[test]
+    fn test_corrupt_and_overlong_lines_do_not_end_the_stream() {
+        let mut feed = b"arn:akton:hr:acme:root/a\n".to_vec();
+        feed.extend_from_slice(b"arn:akton:hr:acme:root/\xff\n");
+        feed.extend_from_slice(
+            format!("arn:akton:hr:acme:root/{}\r\n", "x".repeat(100)).as_bytes(),
+        );
+        feed.extend_from_slice(b"arn:akton:hr:acme:root/b\n");
+        feed.extend_from_slice("y".repeat(50).as_bytes());
+        let reader = || io::BufReader::with_capacity(8, feed.as_slice());
+
+        let results: Vec<_> = ArnStreamParser::new(reader())
+            .with_max_line_len(40)
+            .collect();
+        assert_eq!(results.len(), 5);
+        match &results[1] {
+            Err(StreamError::InvalidLine { line, input, error }) => {
+                assert_eq!(
+                    (*line, input.as_str()),
+                    (2, "arn:akton:hr:acme:root/\u{fffd}")
+                );
+                assert!(matches!(error, ArnError::DecodeFailure(_)));
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+        match &results[2] {
+            Err(StreamError::InvalidLine { line, input, error }) => {
+                assert_eq!(*line, 3);
+                assert_eq!(input.len(), 40);
+                assert_eq!(error, &ArnError::TooLong(123, 40));
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+        assert_eq!(
+            results[3].as_ref().unwrap().to_string(),
+            "arn:akton:hr:acme:root/b"
+        );
+        assert!(matches!(
+            results[4],
+            Err(StreamError::InvalidLine {
+                line: 5,
+                error: ArnError::TooLong(50, 40),
+                ..
+            })
+        ));
+
+        let mut tolerant = ArnStreamParser::new(reader())
+            .with_max_line_len(40)
+            .skip_invalid(true);
+        assert_eq!(tolerant.by_ref().count(), 2);
+        assert_eq!((tolerant.line(), tolerant.skipped()), (5, 3));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_async_matches_blocking() {
+        let feed: &[u8] = b"arn:akton:hr:acme:root/a\nbad\xff\narn:akton:hr:acme:root/b/long\n";
+        let blocking: Vec<String> = ArnStreamParser::new(feed)
+            .with_max_line_len(26)
+            .map(|result| format!("{result:?}"))
+            .collect();
+        assert_eq!(blocking.len(), 3);
+        let mut reader = ArnStreamParser::new(feed).with_max_line_len(26);
+        let mut read = Vec::new();
+        while let Some(result) = reader.next_async().await {
+            read.push(format!("{result:?}"));
+        }
+        assert_eq!(read, blocking);
+    }
+}
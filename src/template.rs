@@ -0,0 +1,488 @@
+use crate::errors::ArnError;
+use crate::format::ArnFormat;
+use crate::model::{Arn, ComponentKind};
+use crate::parser::ArnParser;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::str::FromStr;
+
+/// A pattern for generating Arns from named values, and for recovering those values again.
+///
+/// The string form mirrors an Arn, with `{name}` placeholders standing in for all or part of
+/// any component: `arn:{domain}:hr:{account}:{root}/orders/order-{order_id}`. A name may appear
+/// more than once, in which case every occurrence takes the same value. Two placeholders may
+/// not be adjacent within a component, since the boundary between them would be ambiguous.
+///
+/// ```
+/// use akton_arn::{ArnTemplate, TemplateContext};
+///
+/// let template: ArnTemplate = "arn:akton:hr:{account}:{root}/orders/{order_id}".parse().unwrap();
+/// let context = TemplateContext::new()
+///     .with("account", "acme")
+///     .with("root", "store")
+///     .with("order_id", 42);
+/// let arn = template.render(&context).unwrap();
+/// assert_eq!(arn.to_string(), "arn:akton:hr:acme:store/orders/42");
+///
+/// let extracted = template.extract(&arn).unwrap();
+/// assert_eq!(extracted.parse::<u32>("order_id").unwrap(), 42);
+/// ```
+///
+/// Templates are read in the canonical grammar with `parse`, or in another with
+/// [`ArnTemplate::parse_with`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ArnTemplate {
+    source: String,
+    /// The grammar the template is written in, and rendered through.
+    format: ArnFormat,
+    /// Domain, category, account and root, followed by one segment per part.
+    segments: Vec<Vec<Piece>>,
+}
+
+/// A run of literal text or a placeholder within one component.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Piece {
+    Literal(String),
+    Placeholder(String),
+}
+
+/// Named values for rendering an [`ArnTemplate`], or extracted from an Arn by one.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TemplateContext(BTreeMap<String, String>);
+
+impl TemplateContext {
+    /// Creates an empty context.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a value, rendered with its `Display` implementation.
+    pub fn with(mut self, name: impl Into<String>, value: impl fmt::Display) -> Self {
+        self.insert(name, value);
+        self
+    }
+
+    /// Sets a value, rendered with its `Display` implementation, replacing any previous one.
+    pub fn insert(&mut self, name: impl Into<String>, value: impl fmt::Display) {
+        self.0.insert(name.into(), value.to_string());
+    }
+
+    /// Returns the value for `name`, if present.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0.get(name).map(String::as_str)
+    }
+
+    /// Parses the value for `name` into `T`.
+    pub fn parse<T>(&self, name: &str) -> Result<T, ArnError>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        let value = self
+            .get(name)
+            .ok_or_else(|| ArnError::MissingPlaceholder(name.to_string()))?;
+        value
+            .parse()
+            .map_err(|e| ArnError::DecodeFailure(format!("placeholder {name} = {value:?}: {e}")))
+    }
+
+    /// Iterates over the values in name order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl<K: Into<String>, V: fmt::Display> FromIterator<(K, V)> for TemplateContext {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut context = TemplateContext::new();
+        for (name, value) in iter {
+            context.insert(name, value);
+        }
+        context
+    }
+}
+
+impl ArnTemplate {
+    /// Reads a template written in `format`, whose scheme and separators it also renders with.
+    ///
+    /// ```
+    /// use akton_arn::{ArnFormat, ArnTemplate, TemplateContext};
+    ///
+    /// let legacy = ArnFormat::new().with_scheme("res").unwrap().with_part_separator('.').unwrap();
+    /// let template = ArnTemplate::parse_with("res:akton:hr:acme:root.{team}", &legacy).unwrap();
+    /// let arn = template.render(&TemplateContext::new().with("team", "eng")).unwrap();
+    /// assert_eq!(arn.to_string(), "arn:akton:hr:acme:root/eng");
+    /// assert!(template.render(&TemplateContext::new().with("team", "a.b")).is_err());
+    /// ```
+    pub fn parse_with(template: &str, format: &ArnFormat) -> Result<Self, ArnError> {
+        let segments = format.split_segments(template)?;
+        if segments.len() < 4 {
+            return Err(ArnError::InvalidFormat);
+        }
+        let segments = segments
+            .into_iter()
+            .map(parse_segment)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(ArnTemplate {
+            source: template.to_string(),
+            format: format.clone(),
+            segments,
+        })
+    }
+
+    /// Returns the distinct placeholder names in order of first appearance.
+    pub fn placeholders(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        for piece in self.segments.iter().flatten() {
+            if let Piece::Placeholder(name) = piece {
+                if !names.contains(&name.as_str()) {
+                    names.push(name);
+                }
+            }
+        }
+        names
+    }
+
+    /// Substitutes every placeholder with its value from `context` and parses the result.
+    ///
+    /// Fails with [`ArnError::MissingPlaceholder`] if a value is absent, and with
+    /// [`ArnError::ValidationFailure`] if a value would change the Arn's structure by containing
+    /// the component separator (`:`), or the part separator (`/`) in the root or a part.
+    pub fn render(&self, context: &TemplateContext) -> Result<Arn<'static>, ArnError> {
+        let (component, part) = (
+            self.format.component_separator(),
+            self.format.part_separator(),
+        );
+        let mut rendered = String::from(self.format.scheme());
+        for (index, segment) in self.segments.iter().enumerate() {
+            rendered.push(if index < 4 { component } else { part });
+            let kind = component_kind(index);
+            for piece in segment {
+                match piece {
+                    Piece::Literal(text) => rendered.push_str(text),
+                    Piece::Placeholder(name) => {
+                        let value = context
+                            .get(name)
+                            .ok_or_else(|| ArnError::MissingPlaceholder(name.clone()))?;
+                        let reserved: &[char] = if index < 3 {
+                            &[component]
+                        } else {
+                            &[component, part]
+                        };
+                        if value.contains(reserved) {
+                            return Err(ArnError::ValidationFailure(
+                                kind,
+                                format!("placeholder {name} = {value:?} contains a separator"),
+                            ));
+                        }
+                        rendered.push_str(value);
+                    }
+                }
+            }
+        }
+        ArnParser::new(rendered)
+            .with_format(self.format.clone())
+            .parse()
+    }
+
+    /// Matches `arn` against the template and returns the value of every placeholder.
+    ///
+    /// The Arn must have exactly as many parts as the template. Fails with
+    /// [`ArnError::TemplateMismatch`] naming the first component that does not match.
+    pub fn extract(&self, arn: &Arn) -> Result<TemplateContext, ArnError> {
+        let mismatch = |what: String| {
+            ArnError::TemplateMismatch(format!("{arn} does not match {}: {what}", self.source))
+        };
+        let expected_parts = self.segments.len() - 4;
+        if arn.parts.0.len() != expected_parts {
+            return Err(mismatch(format!(
+                "expected {expected_parts} parts, found {}",
+                arn.parts.0.len()
+            )));
+        }
+        let values = [
+            arn.domain.as_str(),
+            arn.category.as_str(),
+            arn.account.as_str(),
+            arn.root.as_str(),
+        ]
+        .into_iter()
+        .chain(arn.parts.0.iter().map(|part| part.as_str()));
+        let mut bindings = Vec::new();
+        for (index, (segment, value)) in self.segments.iter().zip(values).enumerate() {
+            if !match_pieces(segment, value, &mut bindings) {
+                return Err(mismatch(format!("{} {value:?}", component_kind(index))));
+            }
+        }
+        Ok(bindings.into_iter().collect())
+    }
+
+    /// Returns the placeholder values if `arn` matches the template, like a URL router's path parameters.
+    ///
+    /// This is [`ArnTemplate::extract`] for dispatching, where a mismatch is expected rather
+    /// than an error.
+    ///
+    /// ```
+    /// use akton_arn::{ArnParser, ArnTemplate};
+    ///
+    /// let template: ArnTemplate = "arn:akton:shop:{account}:store/orders/{order_id}".parse().unwrap();
+    /// let arn = ArnParser::new("arn:akton:shop:acme:store/orders/42").parse().unwrap();
+    /// let captures = template.capture(&arn).unwrap();
+    /// assert_eq!(captures["order_id"], "42");
+    /// ```
+    pub fn capture(&self, arn: &Arn) -> Option<HashMap<String, String>> {
+        self.extract(arn)
+            .ok()
+            .map(|context| context.0.into_iter().collect())
+    }
+}
+
+fn component_kind(index: usize) -> ComponentKind {
+    match index {
+        0 => ComponentKind::Domain,
+        1 => ComponentKind::Category,
+        2 => ComponentKind::Account,
+        3 => ComponentKind::Root,
+        _ => ComponentKind::Part,
+    }
+}
+
+/// Matches `value` against `pieces`, binding placeholders to non-empty substrings.
+///
+/// Placeholders are tried shortest first and backtracked on failure; a name bound earlier must
+/// match its existing value. Bindings added by a failed attempt are removed again.
+fn match_pieces<'v>(
+    pieces: &[Piece],
+    value: &'v str,
+    bindings: &mut Vec<(String, &'v str)>,
+) -> bool {
+    match pieces.split_first() {
+        None => value.is_empty(),
+        Some((Piece::Literal(text), rest)) => value
+            .strip_prefix(text.as_str())
+            .is_some_and(|tail| match_pieces(rest, tail, bindings)),
+        Some((Piece::Placeholder(name), rest)) => {
+            if let Some((_, bound)) = bindings.iter().find(|(n, _)| n == name) {
+                let bound = *bound;
+                return value
+                    .strip_prefix(bound)
+                    .is_some_and(|tail| !bound.is_empty() && match_pieces(rest, tail, bindings));
+            }
+            let mark = bindings.len();
+            let ends = value.char_indices().map(|(i, _)| i).chain([value.len()]);
+            for end in ends.filter(|&end| end > 0) {
+                bindings.push((name.clone(), &value[..end]));
+                if match_pieces(rest, &value[end..], bindings) {
+                    return true;
+                }
+                bindings.truncate(mark);
+            }
+            false
+        }
+    }
+}
+
+/// Splits one component of a template into literal and placeholder pieces.
+fn parse_segment(segment: &str) -> Result<Vec<Piece>, ArnError> {
+    let invalid =
+        |reason: &str| ArnError::ParseFailure("ArnTemplate", format!("{reason} in {segment:?}"));
+    let mut pieces = Vec::new();
+    let mut rest = segment;
+    while !rest.is_empty() {
+        match rest.find(['{', '}']) {
+            Some(0) if rest.starts_with('{') => {
+                let close = rest.find('}').ok_or_else(|| invalid("unclosed '{'"))?;
+                let name = &rest[1..close];
+                if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                    return Err(invalid("placeholder names must be non-empty [A-Za-z0-9_]"));
+                }
+                if matches!(pieces.last(), Some(Piece::Placeholder(_))) {
+                    return Err(invalid("adjacent placeholders"));
+                }
+                pieces.push(Piece::Placeholder(name.to_string()));
+                rest = &rest[close + 1..];
+            }
+            Some(0) => return Err(invalid("unmatched '}'")),
+            Some(at) => {
+                pieces.push(Piece::Literal(rest[..at].to_string()));
+                rest = &rest[at..];
+            }
+            None => {
+                pieces.push(Piece::Literal(rest.to_string()));
+                rest = "";
+            }
+        }
+    }
+    Ok(pieces)
+}
+
+impl FromStr for ArnTemplate {
+    type Err = ArnError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ArnTemplate::parse_with(s, &ArnFormat::DEFAULT)
+    }
+}
+
+impl fmt::Display for ArnTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(s: &str) -> ArnTemplate {
+        s.parse().unwrap()
+    }
+
+    fn arn(s: &str) -> Arn<'static> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_render_and_extract_round_trip() {
+        let template = template("arn:{domain}:{category}:{account}:{root}/orders/order-{id}");
+        assert_eq!(
+            template.placeholders(),
+            ["domain", "category", "account", "root", "id"]
+        );
+        let context: TemplateContext = [
+            ("domain", "akton"),
+            ("category", "shop"),
+            ("account", "acme"),
+            ("root", "store"),
+            ("id", "7"),
+        ]
+        .into_iter()
+        .collect();
+        let rendered = template.render(&context).unwrap();
+        assert_eq!(rendered, arn("arn:akton:shop:acme:store/orders/order-7"));
+        assert_eq!(template.extract(&rendered).unwrap(), context);
+        assert_eq!(
+            template.to_string(),
+            "arn:{domain}:{category}:{account}:{root}/orders/order-{id}"
+        );
+    }
+
+    #[test]
+    fn test_extract_mismatches() {
+        let template = template("arn:akton:hr:{account}:root/{team}/v{version}");
+        assert!(template
+            .extract(&arn("arn:akton:hr:acme:root/a/v1"))
+            .is_ok());
+        for input in [
+            "arn:akton:hr:acme:root/a",
+            "arn:akton:hr:acme:root/a/v1/b",
+            "arn:other:hr:acme:root/a/v1",
+            "arn:akton:hr:acme:root/a/x1",
+            "arn:akton:hr:acme:root/a/v",
+        ] {
+            assert!(
+                matches!(
+                    template.extract(&arn(input)),
+                    Err(ArnError::TemplateMismatch(_))
+                ),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_repeated_placeholders_and_backtracking() {
+        let template = template("arn:akton:hr:{a}:root/{a}-{b}-x");
+        let context = template
+            .extract(&arn("arn:akton:hr:p-q:root/p-q-r-s-x"))
+            .unwrap();
+        assert_eq!(context.get("a"), Some("p-q"));
+        assert_eq!(context.get("b"), Some("r-s"));
+        assert!(template.extract(&arn("arn:akton:hr:p:root/q-r-x")).is_err());
+    }
+
+    #[test]
+    fn test_capture_dispatches_by_template() {
+        let routes = [
+            template("arn:akton:shop:{account}:store/orders/{order_id}"),
+            template("arn:akton:shop:{account}:store/customers/{customer_id}"),
+        ];
+        let incoming = arn("arn:akton:shop:acme:store/customers/c9");
+        let (index, captures) = routes
+            .iter()
+            .enumerate()
+            .find_map(|(i, route)| route.capture(&incoming).map(|c| (i, c)))
+            .unwrap();
+        assert_eq!(index, 1);
+        assert_eq!(captures.len(), 2);
+        assert_eq!(captures["account"], "acme");
+        assert_eq!(captures["customer_id"], "c9");
+        assert_eq!(routes[0].capture(&incoming), None);
+    }
+
+    #[test]
+    fn test_render_errors() {
+        let template = template("arn:akton:hr:acme:root/{team}");
+        assert_eq!(
+            template.render(&TemplateContext::new()),
+            Err(ArnError::MissingPlaceholder("team".to_string()))
+        );
+        assert!(matches!(
+            template.render(&TemplateContext::new().with("team", "a/b")),
+            Err(ArnError::ValidationFailure(ComponentKind::Part, _))
+        ));
+        assert!(matches!(
+            TemplateContext::new().with("n", "x").parse::<u8>("n"),
+            Err(ArnError::DecodeFailure(_))
+        ));
+    }
+
+    #[test]
+    fn test_templates_follow_the_format() {
+        let ern = template("ern:akton:hr:{account}:root");
+        assert_eq!(
+            ern.extract(&arn("arn:akton:hr:acme:root"))
+                .unwrap()
+                .get("account"),
+            Some("acme")
+        );
+        let legacy = ArnFormat::new()
+            .with_scheme("res")
+            .unwrap()
+            .with_component_separator('|')
+            .unwrap();
+        let template =
+            ArnTemplate::parse_with("res|akton|hr|{account}|root/{id}", &legacy).unwrap();
+        let context = TemplateContext::new().with("account", "a:b").with("id", 7);
+        let rendered = template.render(&context).unwrap();
+        assert_eq!(rendered.to_string(), "arn:akton:hr:a:b:root/7");
+        assert_eq!(template.extract(&rendered).unwrap(), context);
+        assert!(template
+            .render(&TemplateContext::new().with("account", "a|b").with("id", 7))
+            .is_err());
+        assert!(ArnTemplate::parse_with("arn:akton:hr:acme:root", &legacy).is_err());
+    }
+
+    #[test]
+    fn test_invalid_templates() {
+        for input in [
+            "arn:akton:hr:acme",
+            "urn:akton:hr:acme:root",
+            "arn:akton:hr:acme:root/{a}{b}",
+            "arn:akton:hr:acme:root/{a",
+            "arn:akton:hr:acme:root/a}",
+            "arn:akton:hr:acme:root/{}",
+            "arn:akton:hr:acme:root/{a-b}",
+        ] {
+            assert!(input.parse::<ArnTemplate>().is_err(), "{input}");
+        }
+    }
+}
@@ -0,0 +1,203 @@
+//! Property and fuzz testing support, enabled with the `testing` feature.
+//!
+//! This module implements [`proptest::arbitrary::Arbitrary`] and [`arbitrary::Arbitrary`] for
+//! [`Arn`] and its components, and exposes strategies producing both well-formed Arns and
+//! adversarial strings so downstream code can be property-tested against every shape of input.
+//!
+//! Generated well-formed Arns always survive a round-trip through [`ArnParser`](crate::ArnParser).
+
+use crate::model::{Account, Arn, Category, Domain, Part, Parts, Root};
+use proptest::prelude::*;
+use unicode_normalization::UnicodeNormalization;
+
+/// Regex used for generated domain, category and account values.
+const COMPONENT_REGEX: &str = "[a-z][a-z0-9-]{0,15}";
+/// Regex used for generated part values.
+const PART_REGEX: &str = "[A-Za-z0-9_.-]{1,16}";
+/// Regex mirroring the `name_<base32 id>` shape produced by [`Root::new`].
+const ROOT_REGEX: &str = "[a-z]{1,10}_[0-7][0-9a-hjkmnp-tv-z]{25}";
+/// Maximum number of parts in a generated Arn.
+const MAX_PARTS: usize = 6;
+/// Longest fuzzed component in bytes, keeping whole Arns within the default [`ArnLimits`].
+///
+/// [`ArnLimits`]: crate::ArnLimits
+const MAX_FUZZ_SEGMENT_LEN: usize = 128;
+
+/// Strategy producing valid [`Domain`]s.
+pub fn domain() -> impl Strategy<Value = Domain<'static>> {
+    COMPONENT_REGEX.prop_map(|s| Domain(s.into()))
+}
+
+/// Strategy producing valid [`Category`]s.
+pub fn category() -> impl Strategy<Value = Category<'static>> {
+    COMPONENT_REGEX.prop_map(|s| Category(s.into()))
+}
+
+/// Strategy producing valid [`Account`]s.
+pub fn account() -> impl Strategy<Value = Account<'static>> {
+    COMPONENT_REGEX.prop_map(|s| Account(s.into()))
+}
+
+/// Strategy producing valid [`Root`]s shaped like generated ids.
+pub fn root() -> impl Strategy<Value = Root<'static>> {
+    ROOT_REGEX.prop_map(|s| Root(s.into()))
+}
+
+/// Strategy producing valid [`Part`]s.
+pub fn part() -> impl Strategy<Value = Part<'static>> {
+    PART_REGEX.prop_map(|s| Part::from_shared(s.into()))
+}
+
+/// Strategy producing [`Parts`] with up to `MAX_PARTS` segments.
+pub fn parts() -> impl Strategy<Value = Parts<'static>> {
+    prop::collection::vec(part(), 0..=MAX_PARTS).prop_map(Parts::new)
+}
+
+/// Strategy producing well-formed [`Arn`]s.
+pub fn arn() -> impl Strategy<Value = Arn<'static>> {
+    (domain(), category(), account(), root(), parts()).prop_map(
+        |(domain, category, account, root, parts)| Arn::new(domain, category, account, root, parts),
+    )
+}
+
+/// Strategy producing strings that look like Arns but are frequently malformed.
+///
+/// The output mixes well-formed Arns, Arns with injected separators, control characters and
+/// non-ASCII text, truncated or empty components, and completely unstructured strings.
+pub fn adversarial_arn_string() -> impl Strategy<Value = String> {
+    let noise = "[:/ \\\\%*?\\x00-\\x1f\\x7f\u{00e9}\u{202e}\u{1f600}]{0,8}";
+    prop_oneof![
+        arn().prop_map(|arn| arn.to_string()),
+        (arn(), any::<prop::sample::Index>(), noise).prop_map(|(arn, index, noise)| {
+            let mut s = arn.to_string();
+            let at = index.index(s.len() + 1);
+            let at = (0..=at).rev().find(|i| s.is_char_boundary(*i)).unwrap_or(0);
+            s.insert_str(at, &noise);
+            s
+        }),
+        "arn(:[^:]{0,12}){0,6}(/[^/]{0,12}){0,4}",
+        ".{0,64}",
+    ]
+}
+
+impl Arbitrary for Arn<'static> {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        arn().boxed()
+    }
+}
+
+macro_rules! impl_proptest_arbitrary {
+    ($type:ty, $strategy:ident) => {
+        impl Arbitrary for $type {
+            type Parameters = ();
+            type Strategy = BoxedStrategy<Self>;
+
+            fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+                $strategy().boxed()
+            }
+        }
+    };
+}
+
+impl_proptest_arbitrary!(Domain<'static>, domain);
+impl_proptest_arbitrary!(Category<'static>, category);
+impl_proptest_arbitrary!(Account<'static>, account);
+impl_proptest_arbitrary!(Root<'static>, root);
+impl_proptest_arbitrary!(Part<'static>, part);
+impl_proptest_arbitrary!(Parts<'static>, parts);
+
+/// Builds a non-empty string from fuzzer input using only characters allowed by `allowed`,
+/// never control characters, about [`MAX_FUZZ_SEGMENT_LEN`] bytes long at most, and in NFC
+/// as the parser normalizes it.
+fn fuzz_segment(
+    u: &mut arbitrary::Unstructured<'_>,
+    allowed: impl Fn(char) -> bool,
+) -> arbitrary::Result<String> {
+    let raw: String = u.arbitrary()?;
+    let mut cleaned = String::new();
+    for c in raw.chars().filter(|c| !c.is_control() && allowed(*c)) {
+        if cleaned.len() + c.len_utf8() > MAX_FUZZ_SEGMENT_LEN {
+            break;
+        }
+        cleaned.push(c);
+    }
+    let cleaned: String = cleaned.nfc().collect();
+    Ok(if cleaned.is_empty() {
+        "x".to_string()
+    } else {
+        cleaned
+    })
+}
+
+macro_rules! impl_fuzz_arbitrary {
+    ($type:ident, $allowed:expr) => {
+        impl<'a> arbitrary::Arbitrary<'a> for $type<'static> {
+            fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+                Ok($type::from(std::borrow::Cow::Owned(fuzz_segment(
+                    u, $allowed,
+                )?)))
+            }
+        }
+    };
+}
+
+impl_fuzz_arbitrary!(Domain, |c| c != ':');
+impl_fuzz_arbitrary!(Category, |c| c != ':');
+impl_fuzz_arbitrary!(Account, |c| c != ':');
+impl_fuzz_arbitrary!(Root, |c| c != ':' && c != '/' && c != '?' && c != '@');
+impl_fuzz_arbitrary!(Part, |c| c != ':' && c != '/' && c != '?' && c != '@');
+
+impl<'a> arbitrary::Arbitrary<'a> for Parts<'static> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let len = u.int_in_range(0..=MAX_PARTS)?;
+        (0..len)
+            .map(|_| u.arbitrary::<Part<'static>>())
+            .collect::<arbitrary::Result<Vec<_>>>()
+            .map(Parts::new)
+    }
+}
+
+impl<'a> arbitrary::Arbitrary<'a> for Arn<'static> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Arn::new(
+            u.arbitrary()?,
+            u.arbitrary()?,
+            u.arbitrary()?,
+            u.arbitrary()?,
+            u.arbitrary()?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArnParser;
+
+    proptest! {
+        #[test]
+        fn test_generated_arns_round_trip(arn in any::<Arn<'static>>()) {
+            let parsed = ArnParser::new(arn.to_string()).parse().unwrap();
+            prop_assert_eq!(parsed, arn);
+        }
+
+        #[test]
+        fn test_parser_never_panics_on_adversarial_input(input in adversarial_arn_string()) {
+            let _ = ArnParser::new(input).parse();
+        }
+    }
+
+    #[test]
+    fn test_fuzz_arbitrary_arn_round_trips() {
+        let data: Vec<u8> = (0..=255u8).cycle().take(2048).collect();
+        let mut u = arbitrary::Unstructured::new(&data);
+        for _ in 0..16 {
+            let arn: Arn<'static> = u.arbitrary().unwrap();
+            let parsed = ArnParser::new(arn.to_string()).parse().unwrap();
+            assert_eq!(parsed, arn);
+        }
+    }
+}
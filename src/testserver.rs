@@ -0,0 +1,282 @@
+//! An embedded HTTP registry for integration tests, enabled with the `testserver` feature.
+//!
+//! [`TestServer`] serves an [`ArnRegistry`] holding JSON metadata on an ephemeral local port,
+//! so services that resolve Arns over the network can be tested without a hand-written mock.
+//!
+//! | Method   | Path                  | Behaviour                                                       |
+//! |----------|-----------------------|-----------------------------------------------------------------|
+//! | `GET`    | `/arns`               | Lists every entry, or only those matching `?pattern=<ArnPattern>` |
+//! | `POST`   | `/arns`               | Registers `{"arn": .., "metadata": ..}`; `409` if already present |
+//! | `GET`    | `/arns/<arn>`         | Returns the entry, or `404`                                     |
+//! | `PUT`    | `/arns/<arn>`         | Registers or replaces the entry with the JSON body as metadata  |
+//! | `DELETE` | `/arns/<arn>`         | Removes the entry; `404` if absent                              |
+//!
+//! Entries are rendered as `{"arn": "<arn>", "metadata": <json>}`. Malformed Arns and patterns
+//! are answered with `400` and the error message.
+
+use crate::errors::ArnError;
+use crate::model::Arn;
+use crate::parser::ArnParser;
+use crate::pattern::ArnPattern;
+use crate::registry::ArnRegistry;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+/// Registry shared between a [`TestServer`] and its request handlers.
+pub type SharedRegistry = Arc<RwLock<ArnRegistry<Value>>>;
+
+/// A running HTTP server over an in-memory [`ArnRegistry`]; shut down when dropped.
+#[derive(Debug)]
+pub struct TestServer {
+    addr: SocketAddr,
+    registry: SharedRegistry,
+    task: JoinHandle<()>,
+}
+
+impl TestServer {
+    /// Starts serving `registry` on an ephemeral port of `127.0.0.1`.
+    ///
+    /// Must be called from within a Tokio runtime.
+    pub async fn start(registry: ArnRegistry<Value>) -> io::Result<TestServer> {
+        let registry = Arc::new(RwLock::new(registry));
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let addr = listener.local_addr()?;
+        let app = router(registry.clone());
+        let task = tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+        Ok(TestServer {
+            addr,
+            registry,
+            task,
+        })
+    }
+
+    /// Returns the address the server listens on.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Returns the base URL of the server, without a trailing slash.
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Returns the registry behind the server, for seeding and asserting from the test itself.
+    pub fn registry(&self) -> &SharedRegistry {
+        &self.registry
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Builds the HTTP routes over `registry`, for embedding in a custom server or test client.
+pub fn router(registry: SharedRegistry) -> Router {
+    Router::new()
+        .route("/arns", get(list).post(create))
+        .route("/arns/*arn", get(fetch).put(replace).delete(remove))
+        .with_state(registry)
+}
+
+/// Maps crate errors to `400 Bad Request`.
+struct BadRequest(ArnError);
+
+impl IntoResponse for BadRequest {
+    fn into_response(self) -> Response {
+        (StatusCode::BAD_REQUEST, self.0.to_string()).into_response()
+    }
+}
+
+impl From<ArnError> for BadRequest {
+    fn from(e: ArnError) -> Self {
+        BadRequest(e)
+    }
+}
+
+fn parse(arn: &str) -> Result<Arn<'static>, BadRequest> {
+    Ok(ArnParser::new(arn.to_string()).parse()?)
+}
+
+fn entry(arn: &Arn, metadata: &Value) -> Value {
+    json!({ "arn": arn.to_string(), "metadata": metadata })
+}
+
+async fn list(
+    State(registry): State<SharedRegistry>,
+    Query(query): Query<HashMap<String, String>>,
+) -> Result<Json<Vec<Value>>, BadRequest> {
+    let pattern = query
+        .get("pattern")
+        .map(|p| p.parse::<ArnPattern>())
+        .transpose()?;
+    let registry = registry.read().unwrap();
+    Ok(Json(
+        registry
+            .iter()
+            .filter(|(arn, _)| pattern.as_ref().is_none_or(|p| p.matches(arn)))
+            .map(|(arn, metadata)| entry(arn, metadata))
+            .collect(),
+    ))
+}
+
+async fn create(
+    State(registry): State<SharedRegistry>,
+    Json(body): Json<Value>,
+) -> Result<Response, BadRequest> {
+    let arn = body
+        .get("arn")
+        .and_then(Value::as_str)
+        .ok_or(ArnError::MissingPart("arn".to_string()))?;
+    let arn = parse(arn)?;
+    let metadata = body.get("metadata").cloned().unwrap_or(Value::Null);
+    let response = entry(&arn, &metadata);
+    Ok(match registry.write().unwrap().register(arn, metadata) {
+        Ok(()) => (StatusCode::CREATED, Json(response)).into_response(),
+        Err(e) => (StatusCode::CONFLICT, e.to_string()).into_response(),
+    })
+}
+
+async fn fetch(
+    State(registry): State<SharedRegistry>,
+    Path(arn): Path<String>,
+) -> Result<Response, BadRequest> {
+    let arn = parse(&arn)?;
+    Ok(match registry.read().unwrap().get(&arn) {
+        Some(metadata) => Json(entry(&arn, metadata)).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    })
+}
+
+async fn replace(
+    State(registry): State<SharedRegistry>,
+    Path(arn): Path<String>,
+    Json(metadata): Json<Value>,
+) -> Result<Response, BadRequest> {
+    let arn = parse(&arn)?;
+    let response = entry(&arn, &metadata);
+    let mut registry = registry.write().unwrap();
+    let status = match registry.unregister(&arn) {
+        Some(_) => StatusCode::OK,
+        None => StatusCode::CREATED,
+    };
+    registry.register(arn, metadata)?;
+    Ok((status, Json(response)).into_response())
+}
+
+async fn remove(
+    State(registry): State<SharedRegistry>,
+    Path(arn): Path<String>,
+) -> Result<StatusCode, BadRequest> {
+    let arn = parse(&arn)?;
+    Ok(match registry.write().unwrap().unregister(&arn) {
+        Some(_) => StatusCode::NO_CONTENT,
+        None => StatusCode::NOT_FOUND,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+
+    /// Sends a single HTTP/1.1 request and returns the status code and body.
+    async fn request(server: &TestServer, method: &str, path: &str, body: &str) -> (u16, String) {
+        let mut stream = TcpStream::connect(server.addr()).await.unwrap();
+        let request = format!(
+            "{method} {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\
+             Content-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        let status = response[9..12].parse().unwrap();
+        let body = response
+            .split_once("\r\n\r\n")
+            .map(|(_, body)| body.to_string())
+            .unwrap_or_default();
+        (status, body)
+    }
+
+    #[tokio::test]
+    async fn test_crud_round_trip() {
+        let server = TestServer::start(ArnRegistry::new()).await.unwrap();
+        let arn = "arn:akton:hr:acme:root/team1";
+        let path = format!("/arns/{arn}");
+
+        let created = r#"{"arn":"arn:akton:hr:acme:root/team1","metadata":{"owner":"alice"}}"#;
+        assert_eq!(request(&server, "POST", "/arns", created).await.0, 201);
+        assert_eq!(request(&server, "POST", "/arns", created).await.0, 409);
+
+        let (status, body) = request(&server, "GET", &path, "").await;
+        assert_eq!(status, 200);
+        let body: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["metadata"]["owner"], "alice");
+
+        assert_eq!(
+            request(&server, "PUT", &path, r#"{"owner":"bob"}"#).await.0,
+            200
+        );
+        let parsed = ArnParser::new(arn).parse().unwrap();
+        assert_eq!(
+            server.registry().read().unwrap().get(&parsed),
+            Some(&json!({"owner": "bob"}))
+        );
+
+        assert_eq!(request(&server, "DELETE", &path, "").await.0, 204);
+        assert_eq!(request(&server, "GET", &path, "").await.0, 404);
+        assert_eq!(request(&server, "DELETE", &path, "").await.0, 404);
+    }
+
+    #[tokio::test]
+    async fn test_pattern_search_and_bad_requests() {
+        let mut registry = ArnRegistry::new();
+        for arn in [
+            "arn:akton:hr:acme:root/teams/a",
+            "arn:akton:hr:acme:root/teams/b/c",
+            "arn:akton:it:acme:root/teams/a",
+        ] {
+            registry
+                .register(ArnParser::new(arn).parse().unwrap(), Value::Null)
+                .unwrap();
+        }
+        let server = TestServer::start(registry).await.unwrap();
+
+        let (status, body) =
+            request(&server, "GET", "/arns?pattern=arn:*:hr:*:root/teams/**", "").await;
+        assert_eq!(status, 200);
+        let body: Vec<Value> = serde_json::from_str(&body).unwrap();
+        assert_eq!(body.len(), 2);
+
+        let (status, body) = request(&server, "GET", "/arns", "").await;
+        assert_eq!(status, 200);
+        assert_eq!(serde_json::from_str::<Vec<Value>>(&body).unwrap().len(), 3);
+
+        assert_eq!(
+            request(&server, "GET", "/arns?pattern=nope", "").await.0,
+            400
+        );
+        assert_eq!(request(&server, "GET", "/arns/not-an-arn", "").await.0, 400);
+        assert_eq!(
+            request(&server, "POST", "/arns", r#"{"metadata":1}"#)
+                .await
+                .0,
+            400
+        );
+    }
+}
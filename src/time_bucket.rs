@@ -0,0 +1,287 @@
+use crate::model::{Arn, Part};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// How finely [`Arn::with_time_bucket`] divides time, and so how many parts it appends.
+///
+/// Buckets are in UTC, with every field zero-padded so buckets sort in time order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Granularity {
+    /// One part: `2024`.
+    Year,
+    /// Two parts: `2024/06`.
+    Month,
+    /// Three parts: `2024/06/15`.
+    Day,
+    /// Four parts: `2024/06/15/13`.
+    Hour,
+}
+
+/// The calendar fields of a bucket, finer ones left at their first value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Bucket {
+    year: i64,
+    month: u32,
+    day: u32,
+    hour: u32,
+}
+
+const SECS_PER_DAY: i64 = 86_400;
+
+impl Bucket {
+    /// Returns the bucket of `granularity` holding `secs` seconds after the Unix epoch.
+    fn containing(secs: i64, granularity: Granularity) -> Bucket {
+        let (year, month, day) = civil_from_days(secs.div_euclid(SECS_PER_DAY));
+        let hour = (secs.rem_euclid(SECS_PER_DAY) / 3_600) as u32;
+        match granularity {
+            Granularity::Year => Bucket::new(year, 1, 1, 0),
+            Granularity::Month => Bucket::new(year, month, 1, 0),
+            Granularity::Day => Bucket::new(year, month, day, 0),
+            Granularity::Hour => Bucket::new(year, month, day, hour),
+        }
+    }
+
+    fn new(year: i64, month: u32, day: u32, hour: u32) -> Bucket {
+        Bucket {
+            year,
+            month,
+            day,
+            hour,
+        }
+    }
+
+    /// Returns the seconds after the Unix epoch at which the bucket starts.
+    fn start(&self) -> i64 {
+        days_from_civil(self.year, self.month, self.day) * SECS_PER_DAY
+            + i64::from(self.hour) * 3_600
+    }
+
+    /// Returns the bucket of `granularity` that follows this one.
+    fn next(&self, granularity: Granularity) -> Bucket {
+        match granularity {
+            Granularity::Year => Bucket::new(self.year + 1, 1, 1, 0),
+            Granularity::Month if self.month == 12 => Bucket::new(self.year + 1, 1, 1, 0),
+            Granularity::Month => Bucket::new(self.year, self.month + 1, 1, 0),
+            Granularity::Day => Bucket::containing(self.start() + SECS_PER_DAY, granularity),
+            Granularity::Hour => Bucket::containing(self.start() + 3_600, granularity),
+        }
+    }
+
+    fn parts(&self, granularity: Granularity) -> Vec<String> {
+        let mut parts = vec![format!("{:04}", self.year)];
+        if granularity >= Granularity::Month {
+            parts.push(format!("{:02}", self.month));
+        }
+        if granularity >= Granularity::Day {
+            parts.push(format!("{:02}", self.day));
+        }
+        if granularity >= Granularity::Hour {
+            parts.push(format!("{:02}", self.hour));
+        }
+        parts
+    }
+}
+
+impl<'a> Arn<'a> {
+    /// Returns the child of this Arn for the time bucket holding `at`, such as
+    /// `.../logs/2024/06/15` at [`Granularity::Day`].
+    ///
+    /// Like [`Arn::add_part`], the result does not keep this Arn's version or extensions.
+    ///
+    /// ```
+    /// use akton_arn::{ArnParser, Granularity};
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// let logs = ArnParser::new("arn:akton:ops:acme:root/logs").parse().unwrap();
+    /// let at = UNIX_EPOCH + Duration::from_secs(1_718_460_000);
+    /// assert_eq!(
+    ///     logs.with_time_bucket(at, Granularity::Hour).to_string(),
+    ///     "arn:akton:ops:acme:root/logs/2024/06/15/14"
+    /// );
+    /// ```
+    pub fn with_time_bucket(&self, at: SystemTime, granularity: Granularity) -> Arn<'a> {
+        self.bucket_child(Bucket::containing(unix_secs(at), granularity), granularity)
+    }
+
+    /// Returns the children of this Arn for every time bucket overlapping `start..end`, in
+    /// time order, so a query over a time range can name each partition it reads.
+    ///
+    /// ```
+    /// use akton_arn::{ArnParser, Granularity};
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// let logs = ArnParser::new("arn:akton:ops:acme:root/logs").parse().unwrap();
+    /// let start = UNIX_EPOCH + Duration::from_secs(1_703_980_800); // 2023-12-31
+    /// let months: Vec<String> = logs
+    ///     .time_buckets(start, start + Duration::from_secs(40 * 86_400), Granularity::Month)
+    ///     .map(|arn| arn.to_string())
+    ///     .collect();
+    /// assert_eq!(
+    ///     months,
+    ///     [
+    ///         "arn:akton:ops:acme:root/logs/2023/12",
+    ///         "arn:akton:ops:acme:root/logs/2024/01",
+    ///         "arn:akton:ops:acme:root/logs/2024/02",
+    ///     ]
+    /// );
+    /// ```
+    pub fn time_buckets(
+        &self,
+        start: SystemTime,
+        end: SystemTime,
+        granularity: Granularity,
+    ) -> impl Iterator<Item = Arn<'a>> + '_ {
+        let end = unix_secs(end);
+        let mut bucket = Some(Bucket::containing(unix_secs(start), granularity))
+            .filter(|_| unix_secs(start) < end);
+        std::iter::from_fn(move || {
+            let current = bucket?;
+            let next = current.next(granularity);
+            bucket = Some(next).filter(|next| next.start() < end);
+            Some(self.bucket_child(current, granularity))
+        })
+    }
+
+    fn bucket_child(&self, bucket: Bucket, granularity: Granularity) -> Arn<'a> {
+        let mut child = self.truncated(self.depth());
+        for part in bucket.parts(granularity) {
+            child.parts.0.push(Part::new_unchecked(part));
+        }
+        child
+    }
+}
+
+/// Returns the whole seconds from the Unix epoch to `at`, negative before it.
+fn unix_secs(at: SystemTime) -> i64 {
+    match at.duration_since(UNIX_EPOCH) {
+        Ok(after) => after.as_secs() as i64,
+        Err(before) => {
+            let before = before.duration();
+            -(before.as_secs() as i64) - i64::from(before.subsec_nanos() > 0)
+        }
+    }
+}
+
+/// Converts days since the Unix epoch to a proleptic Gregorian date.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Converts a proleptic Gregorian date to days since the Unix epoch.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = year - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let month = i64::from(month);
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArnParser;
+    use std::time::Duration;
+
+    fn logs() -> Arn<'static> {
+        ArnParser::new("arn:akton:ops:acme:root/logs@v2")
+            .parse()
+            .unwrap()
+    }
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn test_calendar_round_trips() {
+        for days in [-719_468, -1, 0, 59, 11_016, 19_782, 2_932_896] {
+            let (year, month, day) = civil_from_days(days);
+            assert_eq!(days_from_civil(year, month, day), days, "{days}");
+        }
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+    }
+
+    #[test]
+    fn test_with_time_bucket() {
+        // 2024-02-29T23:59:59Z
+        let leap = at(1_709_251_199);
+        let bucket = |granularity| logs().with_time_bucket(leap, granularity).to_string();
+        assert_eq!(
+            bucket(Granularity::Year),
+            "arn:akton:ops:acme:root/logs/2024"
+        );
+        assert_eq!(
+            bucket(Granularity::Month),
+            "arn:akton:ops:acme:root/logs/2024/02"
+        );
+        assert_eq!(
+            bucket(Granularity::Day),
+            "arn:akton:ops:acme:root/logs/2024/02/29"
+        );
+        assert_eq!(
+            bucket(Granularity::Hour),
+            "arn:akton:ops:acme:root/logs/2024/02/29/23"
+        );
+        assert_eq!(
+            logs()
+                .with_time_bucket(UNIX_EPOCH - Duration::from_secs(1), Granularity::Hour)
+                .to_string(),
+            "arn:akton:ops:acme:root/logs/1969/12/31/23"
+        );
+    }
+
+    #[test]
+    fn test_time_buckets_cover_the_range() {
+        let hours: Vec<String> = logs()
+            .time_buckets(
+                at(1_709_251_199),
+                at(1_709_251_199 + 3_602),
+                Granularity::Hour,
+            )
+            .map(|arn| {
+                arn.parts
+                    .iter()
+                    .map(|p| p.to_string())
+                    .collect::<Vec<_>>()
+                    .join("/")
+            })
+            .collect();
+        assert_eq!(
+            hours,
+            [
+                "logs/2024/02/29/23",
+                "logs/2024/03/01/00",
+                "logs/2024/03/01/01"
+            ]
+        );
+        let years = logs().time_buckets(at(0), at(1), Granularity::Year).count();
+        assert_eq!(years, 1);
+        assert_eq!(
+            logs()
+                .time_buckets(at(10), at(10), Granularity::Day)
+                .count(),
+            0
+        );
+        assert_eq!(
+            logs()
+                .time_buckets(at(86_400), at(0), Granularity::Day)
+                .count(),
+            0
+        );
+        let logs = logs();
+        let days = logs.time_buckets(at(0), at(365 * 86_400), Granularity::Day);
+        assert_eq!(days.count(), 365);
+    }
+}
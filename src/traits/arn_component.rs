@@ -19,7 +19,7 @@ macro_rules! impl_arn_component {
             }
             type NextState = $next;
             fn as_cow(&self) -> Cow<'a, str> {
-                self.0.clone()
+                self.0.clone().into()
             }
         }
     };
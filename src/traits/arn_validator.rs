@@ -0,0 +1,43 @@
+use crate::errors::ArnError;
+use crate::ComponentKind;
+use std::sync::Arc;
+
+/// A custom validation rule applied to each component while an Arn is built or parsed.
+///
+/// Validators are registered on [`ArnBuilder`](crate::ArnBuilder) and [`ArnParser`](crate::ArnParser)
+/// and run as soon as a component is constructed, so domain-specific rules fail fast.
+/// Any closure of the form `Fn(ComponentKind, &str) -> Result<(), String>` is a validator.
+pub trait ArnValidator: Send + Sync {
+    /// Checks the value of a single component, returning a message describing the violation on failure.
+    fn validate(&self, kind: ComponentKind, value: &str) -> Result<(), String>;
+
+    /// Checks a component as [`ArnValidator::validate`] does, returning the error to report.
+    ///
+    /// The default reports [`ArnError::ValidationFailure`] with the message; validators with
+    /// a more specific error, such as [`Denylist`](crate::Denylist), override it.
+    fn check(&self, kind: ComponentKind, value: &str) -> Result<(), ArnError> {
+        self.validate(kind, value)
+            .map_err(|message| ArnError::ValidationFailure(kind, message))
+    }
+}
+
+impl<F> ArnValidator for F
+where
+    F: Fn(ComponentKind, &str) -> Result<(), String> + Send + Sync,
+{
+    fn validate(&self, kind: ComponentKind, value: &str) -> Result<(), String> {
+        self(kind, value)
+    }
+}
+
+/// Runs every validator against a component value, stopping at the first failure.
+pub(crate) fn run_validators(
+    validators: &[Arc<dyn ArnValidator>],
+    kind: ComponentKind,
+    value: &str,
+) -> Result<(), ArnError> {
+    for validator in validators {
+        validator.check(kind, value)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use crate::model::Extensions;
+use std::borrow::Cow;
+use std::ops::ControlFlow;
+
+/// Receives the components of an Arn one at a time as
+/// [`ArnParser::parse_with`](crate::ArnParser::parse_with) reads them, without an
+/// [`Arn`](crate::Arn) being built.
+///
+/// Components arrive in the order they are written: the scheme, domain, category, account and
+/// root, each part, then the version and extensions if present. Each has been checked,
+/// normalized and validated as [`ArnParser::parse`](crate::ArnParser::parse) would, and borrows
+/// from the input unless normalizing or folding rewrote it. Every method does nothing by
+/// default; return [`ControlFlow::Break`] to stop reading once you have what you need.
+///
+/// ```
+/// use akton_arn::{ArnParser, ArnVisitor};
+/// use std::borrow::Cow;
+/// use std::ops::ControlFlow;
+///
+/// #[derive(Default)]
+/// struct AccountOf<'i>(Option<Cow<'i, str>>);
+///
+/// impl<'i> ArnVisitor<'i> for AccountOf<'i> {
+///     fn visit_account(&mut self, account: Cow<'i, str>) -> ControlFlow<()> {
+///         self.0 = Some(account);
+///         ControlFlow::Break(())
+///     }
+/// }
+///
+/// let parser = ArnParser::new("arn:akton:hr:acme:root/a/b/c");
+/// let mut visitor = AccountOf::default();
+/// parser.parse_with(&mut visitor).unwrap();
+/// assert_eq!(visitor.0.as_deref(), Some("acme"));
+/// ```
+pub trait ArnVisitor<'i> {
+    /// Receives the scheme, such as `arn`.
+    fn visit_scheme(&mut self, scheme: Cow<'static, str>) -> ControlFlow<()> {
+        let _ = scheme;
+        ControlFlow::Continue(())
+    }
+
+    /// Receives the domain.
+    fn visit_domain(&mut self, domain: Cow<'i, str>) -> ControlFlow<()> {
+        let _ = domain;
+        ControlFlow::Continue(())
+    }
+
+    /// Receives the category.
+    fn visit_category(&mut self, category: Cow<'i, str>) -> ControlFlow<()> {
+        let _ = category;
+        ControlFlow::Continue(())
+    }
+
+    /// Receives the account.
+    fn visit_account(&mut self, account: Cow<'i, str>) -> ControlFlow<()> {
+        let _ = account;
+        ControlFlow::Continue(())
+    }
+
+    /// Receives the root.
+    fn visit_root(&mut self, root: Cow<'i, str>) -> ControlFlow<()> {
+        let _ = root;
+        ControlFlow::Continue(())
+    }
+
+    /// Receives each part below the root, in order.
+    fn visit_part(&mut self, part: Cow<'i, str>) -> ControlFlow<()> {
+        let _ = part;
+        ControlFlow::Continue(())
+    }
+
+    /// Receives the version of an Arn ending in `@v<n>`.
+    fn visit_version(&mut self, version: u64) -> ControlFlow<()> {
+        let _ = version;
+        ControlFlow::Continue(())
+    }
+
+    /// Receives the extensions of an Arn with a `?` query.
+    fn visit_extensions(&mut self, extensions: Extensions) -> ControlFlow<()> {
+        let _ = extensions;
+        ControlFlow::Continue(())
+    }
+}
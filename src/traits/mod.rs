@@ -1,2 +1,7 @@
-mod arn_component;
-pub use arn_component::ArnComponent;
+mod arn_component;
+mod arn_validator;
+mod arn_visitor;
+pub use arn_component::ArnComponent;
+pub(crate) use arn_validator::run_validators;
+pub use arn_validator::ArnValidator;
+pub use arn_visitor::ArnVisitor;
@@ -0,0 +1,471 @@
+use crate::model::Arn;
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write;
+
+/// An in-memory tree of the part hierarchy spanned by a set of Arns.
+///
+/// Each resource root (an Arn with no parts) heads a tree whose nodes are its parts, so
+/// `.../root/teams/eng` sits under `.../root/teams`. Ancestors that were never inserted are
+/// filled in, and report `false` from [`ArnNode::is_inserted`]. Nodes are keyed by their
+/// position in the hierarchy alone: inserting another version of an Arn replaces the one
+/// held at its node. Roots and children are kept in sorted order, so traversal and rendering
+/// are deterministic. Every node holds its own Arn, so a single chain `depth` parts deep
+/// stores on the order of `depth²` parts.
+///
+/// ```
+/// use akton_arn::{ArnParser, ArnTree};
+///
+/// let tree: ArnTree = [
+///     "arn:akton:hr:acme:root/teams/eng",
+///     "arn:akton:hr:acme:root/users",
+///     "arn:akton:hr:acme:root/teams",
+/// ]
+/// .into_iter()
+/// .map(|arn| ArnParser::new(arn).parse().unwrap())
+/// .collect();
+///
+/// assert_eq!(
+///     tree.render_ascii(),
+///     "arn:akton:hr:acme:root\n├── teams\n│   └── eng\n└── users\n"
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ArnTree<'a> {
+    roots: BTreeMap<Arn<'a>, ArnNode<'a>>,
+    len: usize,
+}
+
+/// A node of an [`ArnTree`]: one Arn and the nodes one part below it.
+#[derive(Debug, Clone)]
+pub struct ArnNode<'a> {
+    arn: Arn<'a>,
+    inserted: bool,
+    children: BTreeMap<String, ArnNode<'a>>,
+}
+
+impl<'a> ArnTree<'a> {
+    /// Creates an empty tree.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `arn` to the tree, filling in any missing ancestors.
+    pub fn insert(&mut self, arn: Arn<'a>) {
+        // Extended one part per level, and cloned only for the ancestors filled in.
+        let mut ancestor = arn.truncated(0);
+        let mut node = self
+            .roots
+            .entry(ancestor.clone())
+            .or_insert_with(|| ArnNode::implied(ancestor.clone()));
+        for part in arn.parts.0.iter() {
+            ancestor.parts.0.push(part.clone());
+            node = node
+                .children
+                .entry(part.as_str().to_owned())
+                .or_insert_with(|| ArnNode::implied(ancestor.clone()));
+        }
+        if !node.inserted {
+            self.len += 1;
+        }
+        node.inserted = true;
+        node.arn = arn;
+    }
+
+    /// Returns the number of Arns inserted, not counting filled-in ancestors.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no Arn has been inserted.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterates over the resource roots heading each tree, in sorted order.
+    pub fn roots(&self) -> impl Iterator<Item = &ArnNode<'a>> {
+        self.roots.values()
+    }
+
+    /// Returns the node at `arn`'s place in the hierarchy, or `None` if the tree has none.
+    ///
+    /// The node can be traversed and rendered like the tree itself. The version and
+    /// extensions of `arn` are ignored.
+    pub fn subtree(&self, arn: &Arn) -> Option<&ArnNode<'a>> {
+        let top: Arn<'a> = arn.truncated(0).into_owned();
+        let mut node = self.roots.get(&top)?;
+        for part in arn.parts.0.iter() {
+            node = node.child(part.as_str())?;
+        }
+        Some(node)
+    }
+
+    /// Iterates over every node depth first, each root followed by its descendants.
+    pub fn dfs(&self) -> impl Iterator<Item = &ArnNode<'a>> {
+        self.roots.values().flat_map(ArnNode::dfs)
+    }
+
+    /// Iterates over every node breadth first: all roots, then all nodes one part below
+    /// them, and so on.
+    pub fn bfs(&self) -> impl Iterator<Item = &ArnNode<'a>> {
+        breadth_first(self.roots.values().collect())
+    }
+
+    /// Draws the tree with box-drawing characters, one line per node.
+    ///
+    /// Each root is written as its full Arn, and the nodes below it as their last part.
+    pub fn render_ascii(&self) -> String {
+        let mut out = String::new();
+        for root in self.roots.values() {
+            root.render_into(&mut out);
+        }
+        out
+    }
+
+    /// Writes the tree as a Graphviz `digraph`, with an edge from each node to its children.
+    ///
+    /// Roots are labelled with their full Arn and other nodes with their last part; nodes
+    /// filled in as ancestors are drawn dashed.
+    pub fn to_dot(&self) -> String {
+        to_dot(self.roots.values())
+    }
+
+    /// Writes the tree as a Mermaid `graph TD` flowchart, labelled as in [`ArnTree::to_dot`].
+    pub fn to_mermaid(&self) -> String {
+        to_mermaid(self.roots.values())
+    }
+}
+
+impl<'a> ArnNode<'a> {
+    fn implied(arn: Arn<'a>) -> Self {
+        ArnNode {
+            arn,
+            inserted: false,
+            children: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the Arn at this node: the one inserted, or the filled-in ancestor.
+    pub fn arn(&self) -> &Arn<'a> {
+        &self.arn
+    }
+
+    /// Returns `true` if this node's Arn was inserted rather than filled in as an ancestor.
+    pub fn is_inserted(&self) -> bool {
+        self.inserted
+    }
+
+    /// Returns the child whose last part is `part`.
+    pub fn child(&self, part: &str) -> Option<&ArnNode<'a>> {
+        self.children.get(part)
+    }
+
+    /// Iterates over the nodes one part below this one, in sorted order.
+    pub fn children(&self) -> impl Iterator<Item = &ArnNode<'a>> {
+        self.children.values()
+    }
+
+    /// Iterates over this node and its descendants depth first, in pre-order.
+    pub fn dfs(&self) -> impl Iterator<Item = &ArnNode<'a>> {
+        let mut stack = vec![self];
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            stack.extend(node.children.values().rev());
+            Some(node)
+        })
+    }
+
+    /// Iterates over this node and its descendants breadth first.
+    pub fn bfs(&self) -> impl Iterator<Item = &ArnNode<'a>> {
+        breadth_first(VecDeque::from([self]))
+    }
+
+    /// Draws this node and its descendants as [`ArnTree::render_ascii`] does.
+    pub fn render_ascii(&self) -> String {
+        let mut out = String::new();
+        self.render_into(&mut out);
+        out
+    }
+
+    /// Writes this node and its descendants as [`ArnTree::to_dot`] does.
+    pub fn to_dot(&self) -> String {
+        to_dot([self])
+    }
+
+    /// Writes this node and its descendants as [`ArnTree::to_mermaid`] does.
+    pub fn to_mermaid(&self) -> String {
+        to_mermaid([self])
+    }
+
+    fn render_into(&self, out: &mut String) {
+        out.push_str(&self.arn.to_string());
+        out.push('\n');
+        // The children left to draw at each level, with the prefix length to restore once
+        // they are done, kept on a stack so deep hierarchies cannot overflow the call stack.
+        let mut prefix = String::new();
+        let mut stack = vec![(self.children.iter().peekable(), 0)];
+        while let Some((children, _)) = stack.last_mut() {
+            let Some((part, child)) = children.next() else {
+                let (_, len) = stack.pop().expect("the stack is not empty");
+                prefix.truncate(len);
+                continue;
+            };
+            let last = children.peek().is_none();
+            out.push_str(&prefix);
+            out.push_str(if last { "└── " } else { "├── " });
+            out.push_str(part);
+            out.push('\n');
+            let len = prefix.len();
+            prefix.push_str(if last { "    " } else { "│   " });
+            stack.push((child.children.iter().peekable(), len));
+        }
+    }
+}
+
+fn breadth_first<'t, 'a>(
+    mut queue: VecDeque<&'t ArnNode<'a>>,
+) -> impl Iterator<Item = &'t ArnNode<'a>> {
+    std::iter::from_fn(move || {
+        let node = queue.pop_front()?;
+        queue.extend(node.children.values());
+        Some(node)
+    })
+}
+
+/// A node to draw: its id, its parent's id, and its label.
+struct Drawn<'t, 'a> {
+    id: usize,
+    parent: Option<usize>,
+    label: String,
+    node: &'t ArnNode<'a>,
+}
+
+/// Numbers the nodes below `roots` depth first, labelling roots with their full Arn.
+fn numbered<'t, 'a>(roots: impl IntoIterator<Item = &'t ArnNode<'a>>) -> Vec<Drawn<'t, 'a>> {
+    let mut drawn = Vec::new();
+    for root in roots {
+        let mut stack = vec![(root, None, root.arn.to_string())];
+        while let Some((node, parent, label)) = stack.pop() {
+            let id = drawn.len();
+            stack.extend(
+                node.children
+                    .iter()
+                    .rev()
+                    .map(|(part, child)| (child, Some(id), part.clone())),
+            );
+            drawn.push(Drawn {
+                id,
+                parent,
+                label,
+                node,
+            });
+        }
+    }
+    drawn
+}
+
+fn to_dot<'t, 'a: 't>(roots: impl IntoIterator<Item = &'t ArnNode<'a>>) -> String {
+    let drawn = numbered(roots);
+    let mut out = String::from("digraph arns {\n");
+    for Drawn {
+        id, label, node, ..
+    } in &drawn
+    {
+        let label = label.replace('\\', "\\\\").replace('"', "\\\"");
+        let style = if node.inserted { "" } else { ", style=dashed" };
+        writeln!(out, "    n{id} [label=\"{label}\"{style}];").expect("writing to a String");
+    }
+    for Drawn { id, parent, .. } in &drawn {
+        if let Some(parent) = parent {
+            writeln!(out, "    n{parent} -> n{id};").expect("writing to a String");
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn to_mermaid<'t, 'a: 't>(roots: impl IntoIterator<Item = &'t ArnNode<'a>>) -> String {
+    let drawn = numbered(roots);
+    let mut out = String::from("graph TD\n");
+    for Drawn { id, label, .. } in &drawn {
+        let label = label.replace('"', "#quot;");
+        writeln!(out, "    n{id}[\"{label}\"]").expect("writing to a String");
+    }
+    for Drawn { id, parent, .. } in &drawn {
+        if let Some(parent) = parent {
+            writeln!(out, "    n{parent} --> n{id}").expect("writing to a String");
+        }
+    }
+    let implied: Vec<String> = drawn
+        .iter()
+        .filter(|drawn| !drawn.node.inserted)
+        .map(|drawn| format!("n{}", drawn.id))
+        .collect();
+    if !implied.is_empty() {
+        out.push_str("    classDef implied stroke-dasharray: 5 5\n");
+        writeln!(out, "    class {} implied", implied.join(",")).expect("writing to a String");
+    }
+    out
+}
+
+impl<'a> FromIterator<Arn<'a>> for ArnTree<'a> {
+    fn from_iter<I: IntoIterator<Item = Arn<'a>>>(iter: I) -> Self {
+        let mut tree = ArnTree::new();
+        tree.extend(iter);
+        tree
+    }
+}
+
+impl<'a> From<&[Arn<'a>]> for ArnTree<'a> {
+    fn from(arns: &[Arn<'a>]) -> Self {
+        arns.iter().cloned().collect()
+    }
+}
+
+impl<'a> Extend<Arn<'a>> for ArnTree<'a> {
+    fn extend<I: IntoIterator<Item = Arn<'a>>>(&mut self, iter: I) {
+        for arn in iter {
+            self.insert(arn);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ArnParser;
+
+    fn tree(arns: &[&'static str]) -> ArnTree<'static> {
+        arns.iter()
+            .map(|arn| ArnParser::new(*arn).parse().unwrap())
+            .collect()
+    }
+
+    fn names<'t>(nodes: impl Iterator<Item = &'t ArnNode<'static>>) -> Vec<String> {
+        nodes.map(|node| node.arn().to_string()).collect()
+    }
+
+    #[test]
+    fn test_fills_in_ancestors_and_traverses() {
+        let tree = tree(&[
+            "arn:akton:hr:acme:root/b/x",
+            "arn:akton:hr:acme:root/a",
+            "arn:akton:hr:beta:root",
+            "arn:akton:hr:acme:root/a@v2",
+        ]);
+        assert_eq!(tree.len(), 3);
+        assert_eq!(
+            names(tree.dfs()),
+            [
+                "arn:akton:hr:acme:root",
+                "arn:akton:hr:acme:root/a@v2",
+                "arn:akton:hr:acme:root/b",
+                "arn:akton:hr:acme:root/b/x",
+                "arn:akton:hr:beta:root",
+            ]
+        );
+        assert_eq!(
+            names(tree.bfs()),
+            [
+                "arn:akton:hr:acme:root",
+                "arn:akton:hr:beta:root",
+                "arn:akton:hr:acme:root/a@v2",
+                "arn:akton:hr:acme:root/b",
+                "arn:akton:hr:acme:root/b/x",
+            ]
+        );
+        let inserted: Vec<bool> = tree.dfs().map(ArnNode::is_inserted).collect();
+        assert_eq!(inserted, [false, true, false, true, true]);
+    }
+
+    #[test]
+    fn test_subtree() {
+        let tree = tree(&[
+            "arn:akton:hr:acme:root/teams/eng/backend",
+            "arn:akton:hr:acme:root/teams/ops",
+            "arn:akton:hr:acme:root/users",
+        ]);
+        let teams = ArnParser::new("arn:akton:hr:acme:root/teams@v9")
+            .parse()
+            .unwrap();
+        let subtree = tree.subtree(&teams).unwrap();
+        assert_eq!(
+            subtree.render_ascii(),
+            "arn:akton:hr:acme:root/teams\n├── eng\n│   └── backend\n└── ops\n"
+        );
+        assert_eq!(subtree.bfs().count(), 4);
+        assert!(subtree.child("eng").unwrap().child("backend").is_some());
+
+        let missing = ArnParser::new("arn:akton:hr:acme:root/teams/qa")
+            .parse()
+            .unwrap();
+        assert!(tree.subtree(&missing).is_none());
+        assert!(ArnTree::new().render_ascii().is_empty());
+    }
+
+    #[test]
+    fn test_dot_and_mermaid() {
+        let tree = tree(&[
+            "arn:akton:hr:acme:root/teams/eng",
+            "arn:akton:hr:acme:root/users",
+        ]);
+        assert_eq!(
+            tree.to_dot(),
+            "digraph arns {
+    n0 [label=\"arn:akton:hr:acme:root\", style=dashed];
+    n1 [label=\"teams\", style=dashed];
+    n2 [label=\"eng\"];
+    n3 [label=\"users\"];
+    n0 -> n1;
+    n1 -> n2;
+    n0 -> n3;
+}
+"
+        );
+        assert_eq!(
+            tree.to_mermaid(),
+            "graph TD
+    n0[\"arn:akton:hr:acme:root\"]
+    n1[\"teams\"]
+    n2[\"eng\"]
+    n3[\"users\"]
+    n0 --> n1
+    n1 --> n2
+    n0 --> n3
+    classDef implied stroke-dasharray: 5 5
+    class n0,n1 implied
+"
+        );
+
+        let eng = ArnParser::new("arn:akton:hr:acme:root/teams/eng")
+            .parse()
+            .unwrap();
+        let subtree = tree.subtree(&eng).unwrap();
+        assert_eq!(
+            subtree.to_mermaid(),
+            "graph TD\n    n0[\"arn:akton:hr:acme:root/teams/eng\"]\n"
+        );
+        assert!(subtree
+            .to_dot()
+            .contains("n0 [label=\"arn:akton:hr:acme:root/teams/eng\"];"));
+    }
+
+    #[test]
+    fn test_deep_hierarchy_renders() {
+        const DEPTH: usize = 1_000;
+        let path: Vec<String> = (0..DEPTH).map(|i| format!("p{i}")).collect();
+        let arn = ArnParser::new(format!("arn:akton:hr:acme:root/{}", path.join("/")))
+            .with_limits(crate::ArnLimits::unlimited())
+            .parse()
+            .unwrap();
+        let tree: ArnTree = [arn.clone()].into_iter().collect();
+        let rendered = tree.render_ascii();
+        assert_eq!(rendered.lines().count(), DEPTH + 1);
+        assert_eq!(
+            rendered.lines().last(),
+            Some(format!("{}└── p{}", "    ".repeat(DEPTH - 1), DEPTH - 1).as_str())
+        );
+        let deepest = tree.dfs().last().unwrap();
+        assert_eq!(deepest.arn(), &arn);
+        assert_eq!(tree.dfs().nth(DEPTH / 2).unwrap().arn().depth(), DEPTH / 2);
+    }
+}
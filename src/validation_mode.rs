@@ -0,0 +1,125 @@
+use crate::errors::ArnError;
+use crate::ComponentKind;
+use std::borrow::Cow;
+use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+
+/// Which characters [`ArnParser`](crate::ArnParser) and [`ArnBuilder`](crate::ArnBuilder)
+/// accept in a component, beyond the separators no component may contain.
+///
+/// The Unicode modes normalize every component to NFC before checking it, so a tenant name
+/// typed with a precomposed `é` and one typed as `e` plus a combining accent produce equal
+/// Arns. Input that is already NFC, which includes all ASCII, is borrowed as it is.
+///
+/// ```
+/// use akton_arn::{ArnParser, ValidationMode};
+///
+/// let composed = ArnParser::new("arn:akton:hr:caf\u{e9}:root").parse().unwrap();
+/// let decomposed = ArnParser::new("arn:akton:hr:cafe\u{301}:root").parse().unwrap();
+/// assert_eq!(composed, decomposed);
+///
+/// let ascii = ArnParser::new("arn:akton:hr:caf\u{e9}:root").with_validation_mode(ValidationMode::Ascii);
+/// assert!(ascii.parse().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValidationMode {
+    /// Only ASCII characters, left as they are.
+    Ascii,
+    /// Identifier characters after NFC normalization.
+    ///
+    /// Follows the UAX #31 default identifier syntax, with the profile common in resource
+    /// names: any character with the `XID_Continue` property, so names may start with a
+    /// digit, plus `-`, `.`, `~` and `=` as medial punctuation.
+    UnicodeIdentifier,
+    /// Any character after NFC normalization.
+    #[default]
+    Permissive,
+}
+
+impl ValidationMode {
+    /// Returns `value` normalized as the mode requires, or the first character it rejects.
+    pub(crate) fn apply<'v>(
+        &self,
+        kind: ComponentKind,
+        value: Cow<'v, str>,
+    ) -> Result<Cow<'v, str>, ArnError> {
+        if *self == ValidationMode::Ascii {
+            return match value.chars().find(|c| !c.is_ascii()) {
+                Some(c) => Err(rejected(kind, c, "non-ASCII character")),
+                None => Ok(value),
+            };
+        }
+        let value = if is_nfc_quick(value.chars()) == IsNormalized::Yes {
+            value
+        } else {
+            Cow::Owned(value.nfc().collect())
+        };
+        if *self == ValidationMode::UnicodeIdentifier {
+            if let Some(c) = value.chars().find(|&c| {
+                !unicode_ident::is_xid_continue(c) && !matches!(c, '-' | '.' | '~' | '=')
+            }) {
+                return Err(rejected(kind, c, "character outside identifiers"));
+            }
+        }
+        Ok(value)
+    }
+}
+
+fn rejected(kind: ComponentKind, c: char, what: &str) -> ArnError {
+    ArnError::ValidationFailure(kind, format!("contains {what} {c:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Account, ArnBuilder, ArnParser, Category, Domain, Root};
+
+    fn parse(input: &str, mode: ValidationMode) -> Result<crate::Arn<'_>, ArnError> {
+        ArnParser::new(input).with_validation_mode(mode).parse()
+    }
+
+    #[test]
+    fn test_modes() {
+        let international = "arn:akton:hr:m\u{fc}nchen-gmbh:root/\u{6771}\u{4eac}/v1.2";
+        assert!(parse(international, ValidationMode::Ascii).is_err());
+        assert!(parse(international, ValidationMode::UnicodeIdentifier).is_ok());
+        assert!(parse(international, ValidationMode::Permissive).is_ok());
+
+        let spaced = "arn:akton:hr:acme:root/annual report";
+        assert!(parse(spaced, ValidationMode::Ascii).is_ok());
+        assert_eq!(
+            parse(spaced, ValidationMode::UnicodeIdentifier).unwrap_err(),
+            ArnError::ValidationFailure(
+                ComponentKind::Part,
+                "contains character outside identifiers ' '".to_string()
+            )
+        );
+        assert!(parse(spaced, ValidationMode::Permissive).is_ok());
+    }
+
+    #[test]
+    fn test_unicode_modes_normalize() {
+        let decomposed = "arn:akton:hr:cafe\u{301}:root/a\u{308}";
+        for mode in [
+            ValidationMode::UnicodeIdentifier,
+            ValidationMode::Permissive,
+        ] {
+            let arn = parse(decomposed, mode).unwrap();
+            assert_eq!(arn.account.as_str(), "caf\u{e9}");
+            assert_eq!(arn.parts.to_string(), "\u{e4}");
+        }
+
+        let built = ArnBuilder::new()
+            .with_validation_mode(ValidationMode::UnicodeIdentifier)
+            .with::<Domain>("akton")
+            .unwrap()
+            .with::<Category>("hr")
+            .unwrap()
+            .with::<Account>("cafe\u{301}")
+            .unwrap()
+            .with::<Root>("root")
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(built.account.as_str(), "caf\u{e9}");
+    }
+}
@@ -0,0 +1,186 @@
+//! `clap` support, enabled with the `clap` feature.
+//!
+//! [`Arn`] and [`ArnPattern`] implement `ValueParserFactory`, so command-line tools can take
+//! them as arguments with `value_parser!` or a `#[derive(Parser)]` field. A malformed argument
+//! is reported like any other clap error, naming the argument and value, with the reason and
+//! the expected shape as tips.
+
+use crate::errors::ArnError;
+use crate::model::Arn;
+use crate::parser::ArnParser;
+use crate::pattern::ArnPattern;
+use clap::builder::{StyledStr, TypedValueParser, ValueParserFactory};
+use clap::error::{ContextKind, ContextValue, ErrorKind};
+use clap::{Arg, Command, Error};
+use std::ffi::OsStr;
+
+/// Parses a command-line argument into an owned [`Arn`].
+///
+/// ```
+/// use akton_arn::Arn;
+/// use clap::{value_parser, Arg, Command};
+///
+/// let cmd = Command::new("inspect").arg(Arg::new("arn").value_parser(value_parser!(Arn)));
+/// let matches = cmd.try_get_matches_from(["inspect", "arn:akton:hr:acme:root"]).unwrap();
+/// assert_eq!(matches.get_one::<Arn>("arn").unwrap().account().as_str(), "acme");
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ArnValueParser;
+
+impl TypedValueParser for ArnValueParser {
+    type Value = Arn<'static>;
+
+    fn parse_ref(
+        &self,
+        cmd: &Command,
+        arg: Option<&Arg>,
+        value: &OsStr,
+    ) -> Result<Self::Value, Error> {
+        let value = utf8(cmd, value)?;
+        ArnParser::new(value)
+            .parse()
+            .map(Arn::into_owned)
+            .map_err(|e| {
+                invalid(
+                    cmd,
+                    arg,
+                    value,
+                    e,
+                    "arn:<domain>:<category>:<account>:<root>[/<part>...]",
+                )
+            })
+    }
+}
+
+impl ValueParserFactory for Arn<'static> {
+    type Parser = ArnValueParser;
+
+    fn value_parser() -> Self::Parser {
+        ArnValueParser
+    }
+}
+
+/// Parses a command-line argument into an [`ArnPattern`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ArnPatternValueParser;
+
+impl TypedValueParser for ArnPatternValueParser {
+    type Value = ArnPattern;
+
+    fn parse_ref(
+        &self,
+        cmd: &Command,
+        arg: Option<&Arg>,
+        value: &OsStr,
+    ) -> Result<Self::Value, Error> {
+        let value = utf8(cmd, value)?;
+        value.parse().map_err(|e| {
+            invalid(
+                cmd,
+                arg,
+                value,
+                e,
+                "arn:<domain>:<category>:<account>:<root>[/<part>...], with `*` for any one \
+                 component or part and `**` for any number of parts",
+            )
+        })
+    }
+}
+
+impl ValueParserFactory for ArnPattern {
+    type Parser = ArnPatternValueParser;
+
+    fn value_parser() -> Self::Parser {
+        ArnPatternValueParser
+    }
+}
+
+fn utf8<'v>(cmd: &Command, value: &'v OsStr) -> Result<&'v str, Error> {
+    value
+        .to_str()
+        .ok_or_else(|| Error::new(ErrorKind::InvalidUtf8).with_cmd(cmd))
+}
+
+/// Reports `value` as invalid for `arg`, with why it failed and what was expected as tips.
+fn invalid(
+    cmd: &Command,
+    arg: Option<&Arg>,
+    value: &str,
+    error: ArnError,
+    expected: &str,
+) -> Error {
+    let mut err = Error::new(ErrorKind::ValueValidation).with_cmd(cmd);
+    let arg = arg.map_or_else(|| "...".to_string(), |arg| arg.to_string());
+    err.insert(ContextKind::InvalidArg, ContextValue::String(arg));
+    err.insert(
+        ContextKind::InvalidValue,
+        ContextValue::String(value.to_string()),
+    );
+    err.insert(
+        ContextKind::Suggested,
+        ContextValue::StyledStrs(vec![
+            StyledStr::from(error.to_string()),
+            StyledStr::from(format!("expected {expected}")),
+        ]),
+    );
+    err
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::value_parser;
+
+    fn command() -> Command {
+        Command::new("ops")
+            .arg(
+                Arg::new("target")
+                    .long("target")
+                    .value_parser(value_parser!(Arn)),
+            )
+            .arg(
+                Arg::new("pattern")
+                    .long("pattern")
+                    .value_parser(value_parser!(ArnPattern)),
+            )
+    }
+
+    #[test]
+    fn test_arguments_parse_into_arns_and_patterns() {
+        let matches = command()
+            .try_get_matches_from([
+                "ops",
+                "--target",
+                "arn:akton:hr:acme:root/teams/a",
+                "--pattern",
+                "arn:akton:hr:*:*/teams/**",
+            ])
+            .unwrap();
+        let target = matches.get_one::<Arn>("target").unwrap();
+        assert_eq!(target.parts.to_string(), "teams/a");
+        assert!(matches
+            .get_one::<ArnPattern>("pattern")
+            .unwrap()
+            .matches(target));
+    }
+
+    #[test]
+    fn test_errors_name_the_argument_and_reason() {
+        let err = command()
+            .try_get_matches_from(["ops", "--target", "arn:akton:hr"])
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ValueValidation);
+        let rendered = err.render().to_string();
+        assert!(rendered.contains("invalid value 'arn:akton:hr' for '--target <target>'"));
+        assert!(rendered.contains("tip: ARN has invalid format"));
+        assert!(rendered.contains("tip: expected arn:<domain>"));
+
+        let err = command()
+            .try_get_matches_from(["ops", "--pattern", "urn:*"])
+            .unwrap_err();
+        assert!(err
+            .render()
+            .to_string()
+            .contains("`**` for any number of parts"));
+    }
+}
@@ -0,0 +1,95 @@
+#![cfg(feature = "cli")]
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+/// Runs the `arn` binary with `args`, feeding it `stdin`.
+fn arn(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_arn"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("the arn binary runs");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn test_build_then_explain() {
+    let built = arn(
+        &[
+            "build",
+            "--domain",
+            "akton",
+            "--category",
+            "hr",
+            "--account",
+            "acme",
+            "--root",
+            "payroll",
+            "--part",
+            "2024",
+            "--part",
+            "q1",
+        ],
+        "",
+    );
+    assert!(built.status.success());
+    let built = stdout(&built);
+    assert!(built.starts_with("arn:akton:hr:acme:payroll_"));
+    assert!(built.trim_end().ends_with("/2024/q1"));
+
+    let explained = stdout(&arn(&["explain"], &built));
+    assert!(explained.contains("account:    acme"));
+    assert!(explained.contains("(generated for payroll"));
+    assert!(explained.contains("part 2:     q1"));
+}
+
+#[test]
+fn test_match_filters_stdin() {
+    let input = "arn:akton:hr:acme:root/teams/a\narn:akton:it:acme:root/teams/b\n";
+    let output = arn(&["match", "arn:akton:hr:*:*/teams/*"], input);
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "arn:akton:hr:acme:root/teams/a\n");
+    let none = arn(&["match", "arn:akton:ops:*:*"], input);
+    assert!(!none.status.success());
+    assert_eq!(stdout(&none), "");
+}
+
+#[test]
+fn test_parse_reports_invalid_arns() {
+    let output = arn(&["parse", "arn:akton:hr:acme:root", "not-an-arn"], "");
+    assert!(!output.status.success());
+    assert_eq!(stdout(&output), "arn:akton:hr:acme:root\n");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("not-an-arn"));
+}
+
+#[test]
+fn test_diff_and_convert() {
+    let diff = arn(
+        &["diff"],
+        "arn:akton:hr:acme:root/teams\narn:akton:hr:globex:root/teams\n",
+    );
+    assert_eq!(stdout(&diff), "Account changed from acme to globex\n");
+
+    let target = "arn:akton:hr:acme:root/teams/a";
+    let aws = arn(&["convert", "--to", "aws-arn", target], "");
+    assert_eq!(stdout(&aws), "arn:akton:hr::acme:root/teams/a\n");
+    let urn = arn(&["convert", "--to", "urn", target], "");
+    assert_eq!(stdout(&urn), "urn:akton:hr:acme:root/teams/a\n");
+    let json: serde_json::Value =
+        serde_json::from_str(&stdout(&arn(&["convert", "--to", "json", target], ""))).unwrap();
+    assert_eq!(json["parts"], serde_json::json!(["teams", "a"]));
+    assert_eq!(json["account"], "acme");
+}
@@ -0,0 +1,47 @@
+#![cfg(feature = "perf")]
+
+use akton_arn::perf::CountingAllocator;
+use akton_arn::*;
+use std::alloc::System;
+use std::hint::black_box;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator::new(System);
+
+/// Runs `f` once to warm up lazily initialized state, then fails if running it again
+/// allocates more than `limit` times.
+fn assert_allocations<R>(operation: &str, limit: u64, mut f: impl FnMut() -> R) {
+    black_box(f());
+    let (result, stats) = ALLOCATOR.measure(&mut f);
+    black_box(result);
+    assert!(
+        stats.allocations <= limit,
+        "{operation} made {} allocations, over its target of {limit}: {stats:?}",
+        stats.allocations
+    );
+}
+
+// The targets live in one test so that no other test allocates while they are measured.
+#[test]
+fn test_hot_paths_meet_their_allocation_targets() {
+    let input = "arn:akton:hr:acme:root/department_a/team1/42/x";
+    let arn = ArnParser::new(input).parse().unwrap();
+    let parent = arn.parent().unwrap();
+    let pattern: ArnPattern = "arn:akton:hr:*:root/**".parse().unwrap();
+    let miss: ArnPattern = "arn:akton:it:*:root/**".parse().unwrap();
+
+    assert_allocations("parse", 0, || ArnParser::new(input).parse().unwrap());
+    assert_allocations("extract_account", 0, || extract_account(input).unwrap());
+    assert_allocations("to_string", 1, || arn.to_string());
+    assert_allocations("pattern match", 0, || pattern.matches(&arn));
+    assert_allocations("pattern miss", 0, || miss.matches(&arn));
+    assert_allocations("parent", 0, || arn.parent());
+    assert_allocations("is_child_of", 0, || arn.is_child_of(&parent));
+    assert_allocations("ancestors", 0, || arn.ancestors().count());
+
+    #[cfg(feature = "arena")]
+    {
+        let arena = ArnArena::with_capacity(64 * 1024);
+        assert_allocations("arena parse", 0, || arena.parse(input).unwrap());
+    }
+}
@@ -42,18 +42,18 @@ fn test_parser() -> anyhow::Result<()> {
 
     // Verify each component matches the expected value
     assert_eq!(
-        arn.domain.to_string(),
+        arn.domain().to_string(),
         "akton-internal",
         "Domain should be 'akton-internal'"
     );
-    assert_eq!(arn.category.to_string(), "hr", "Category should be 'hr'");
+    assert_eq!(arn.category().to_string(), "hr", "Category should be 'hr'");
     assert_eq!(
-        arn.account.to_string(),
+        arn.account().to_string(),
         "company123",
         "Account should be 'company123'"
     );
     assert_eq!(
-        arn.parts.to_string(),
+        arn.parts().to_string(),
         "departmentA/team1",
         "Parts should match expected values"
     );